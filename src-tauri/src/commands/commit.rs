@@ -1,18 +1,122 @@
 //! コミットメッセージの解析・シリアライズ用 Tauri コマンド。
 
+use std::path::Path;
+
+use tokio::fs;
+use tokio::process::Command;
+
+use super::rebase::{parent_dir, read_git_config, read_git_config_all};
+use super::staging::resolve_git_root;
 use crate::error::AppError;
-use crate::parser::commit::{self, CommitMessage};
+use crate::parser::commit::{
+    self, CleanupMode, CommentChar, CommitMessage, CommitMsgFormat, LengthUnit,
+};
+use crate::parser::commit_lint::{self, CommitLintDiagnostic, LintRules};
+use crate::parser::conventional::{
+    self, ConventionalCommit, ConventionalRules, ConventionalViolation,
+};
+use crate::parser::rebase_lint::DiagnosticSeverity;
+
+/// Conventional Commits で許可する type を設定する git config のキー（複数指定可）。
+const COMMIT_TYPE_CONFIG_KEY: &str = "gui-git-editor.commitType";
+/// Conventional Commits で許可する scope を設定する git config のキー（複数指定可）。
+const COMMIT_SCOPE_CONFIG_KEY: &str = "gui-git-editor.commitScope";
+
+/// メッセージの後処理を決める git config のキー。
+const CLEANUP_CONFIG_KEY: &str = "commit.cleanup";
+/// コメント文字の git config のキー。`core.commentString` は新しい Git での別名。
+const COMMENT_CHAR_CONFIG_KEYS: [&str; 2] = ["core.commentChar", "core.commentString"];
+/// コミットメッセージのテンプレートファイルの git config のキー。
+const TEMPLATE_CONFIG_KEY: &str = "commit.template";
+
+/// lint のルールを書くプロジェクトファイル（リポジトリ直下）。
+const LINT_CONFIG_FILE: &str = ".gitmessage-lint.toml";
+/// 一部のルールを読み取る commitlint の設定ファイル。`LINT_CONFIG_FILE` を優先する。
+const COMMITLINT_CONFIG_FILE: &str = ".commitlintrc.json";
+
+/// ファイルを所有するリポジトリの `commit.cleanup` と `core.commentChar` を読む。
+pub(crate) async fn read_commit_msg_format(file_path: &str) -> Result<CommitMsgFormat, AppError> {
+    let dir = parent_dir(file_path)?;
+    let cleanup = match read_git_config(&dir, CLEANUP_CONFIG_KEY).await {
+        Some(value) => CleanupMode::from_config(&value).ok_or_else(|| AppError::CommandError {
+            message: format!("Invalid {} value: {}", CLEANUP_CONFIG_KEY, value),
+        })?,
+        None => CleanupMode::default(),
+    };
+    let mut comment_char = CommentChar::default();
+    for key in COMMENT_CHAR_CONFIG_KEYS {
+        if let Some(value) = read_git_config(&dir, key).await {
+            comment_char = CommentChar::from_config(&value);
+            break;
+        }
+    }
+    Ok(CommitMsgFormat {
+        cleanup,
+        comment_char,
+    })
+}
+
+/// `file_path` があればそのリポジトリの書式を、なければ既定の書式を返す。
+async fn commit_msg_format(file_path: Option<&str>) -> Result<CommitMsgFormat, AppError> {
+    match file_path {
+        Some(file_path) => read_commit_msg_format(file_path).await,
+        None => Ok(CommitMsgFormat::default()),
+    }
+}
+
+/// `commit.template` のファイルを読む。未設定か、ファイルがなければ None。
+///
+/// 相対パスはリポジトリのルートから、`~/` はホームディレクトリから解決する。
+async fn read_commit_template(file_path: &str) -> Result<Option<String>, AppError> {
+    let git_root = resolve_git_root(file_path).await?;
+    let output = Command::new("git")
+        .args([
+            "-C",
+            &git_root,
+            "config",
+            "--path",
+            "--get",
+            TEMPLATE_CONFIG_KEY,
+        ])
+        .output()
+        .await?;
+    let template = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\n', '\r'])
+        .to_string();
+    if !output.status.success() || template.is_empty() {
+        return Ok(None);
+    }
+
+    let path = Path::new(&git_root).join(template);
+    match fs::read_to_string(&path).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::from_io_with_path(path.to_string_lossy(), e)),
+    }
+}
 
 /// コミットメッセージ本文を構造化された CommitMessage に解析する。
+///
+/// `file_path` を渡すと、そのリポジトリの `commit.cleanup` と `core.commentChar` に従う。
 #[tauri::command]
-pub fn parse_commit_msg(content: String) -> Result<CommitMessage, AppError> {
-    commit::parse_commit_msg(&content)
+pub async fn parse_commit_msg(
+    content: String,
+    file_path: Option<String>,
+) -> Result<CommitMessage, AppError> {
+    let format = commit_msg_format(file_path.as_deref()).await?;
+    commit::parse_commit_msg_with(&content, &format)
 }
 
 /// CommitMessage をファイルへ保存する文字列に戻す。
+///
+/// `file_path` を渡すと、scissors 行をそのリポジトリのコメント文字で書く。
 #[tauri::command]
-pub fn serialize_commit_msg(message: CommitMessage) -> String {
-    commit::serialize_commit_msg(&message)
+pub async fn serialize_commit_msg(
+    message: CommitMessage,
+    file_path: Option<String>,
+) -> Result<String, AppError> {
+    let format = commit_msg_format(file_path.as_deref()).await?;
+    Ok(commit::serialize_commit_msg_with(&message, &format))
 }
 
 /// コミットメッセージ検証結果。
@@ -20,20 +124,349 @@ pub fn serialize_commit_msg(message: CommitMessage) -> String {
 pub struct CommitValidation {
     pub is_valid: bool,
     pub subject_too_long: bool,
+    /// subject の文字数。
     pub subject_length: usize,
+    /// subject の表示幅（全角を 2 桁とする桁数）。
+    pub subject_width: usize,
+    /// `subject_too_long` と `long_body_lines` の長さの数え方。
+    pub length_unit: LengthUnit,
     pub long_body_lines: Vec<(usize, usize)>,
+    /// subject を Conventional Commits として解析した結果。形式が違えば None。
+    pub conventional: Option<ConventionalCommit>,
+    pub conventional_violations: Vec<ConventionalViolation>,
+    /// リポジトリの lint ルールによる診断。行・列はコメントを除いたメッセージでの位置。
+    pub diagnostics: Vec<CommitLintDiagnostic>,
+    /// `commit.template` から編集されていない。Git はこのメッセージでのコミットを中止する。
+    pub matches_template: bool,
+}
+
+/// ファイルを所有するリポジトリの git config から、許可する type と scope を読む。
+async fn read_conventional_rules(file_path: &str) -> Result<ConventionalRules, AppError> {
+    let dir = parent_dir(file_path)?;
+    let types = read_git_config_all(&dir, COMMIT_TYPE_CONFIG_KEY).await;
+    let scopes = read_git_config_all(&dir, COMMIT_SCOPE_CONFIG_KEY).await;
+    Ok(ConventionalRules {
+        types: conventional::parse_rule_values(types.iter().map(String::as_str)),
+        scopes: conventional::parse_rule_values(scopes.iter().map(String::as_str)),
+    })
+}
+
+/// 設定ファイルの内容を lint のルールにする関数。
+type LintConfigParser = fn(&str) -> Result<LintRules, AppError>;
+
+/// リポジトリ直下の設定ファイルから lint のルールを読む。どちらもなければ既定のルール。
+async fn read_lint_rules(file_path: &str) -> Result<LintRules, AppError> {
+    let git_root = resolve_git_root(file_path).await?;
+    let parsers: [(&str, LintConfigParser); 2] = [
+        (LINT_CONFIG_FILE, commit_lint::parse_lint_toml),
+        (COMMITLINT_CONFIG_FILE, commit_lint::parse_commitlint_json),
+    ];
+    for (name, parse) in parsers {
+        let path = Path::new(&git_root).join(name);
+        match fs::read_to_string(&path).await {
+            Ok(content) => return parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(AppError::from_io_with_path(path.to_string_lossy(), e)),
+        }
+    }
+    Ok(LintRules::defaults())
 }
 
 /// コミットメッセージを検証し、警告情報を返す。
+///
+/// `file_path` を渡すと、そのリポジトリの `.gitmessage-lint.toml`（なければ
+/// `.commitlintrc.json`）のルールで lint し、`gui-git-editor.commitType` と
+/// `gui-git-editor.commitScope` で Conventional Commits の type と scope を検証する。
+/// `commit.template` から編集されていない場合も検出する。エラーの診断、
+/// Conventional Commits の違反、未編集のテンプレートのいずれかがあれば `is_valid` は false になる。
 #[tauri::command]
-pub fn validate_commit_msg(message: CommitMessage) -> CommitValidation {
-    let long_body_lines = message.get_long_body_lines();
-    let subject_too_long = message.is_subject_too_long();
+pub async fn validate_commit_msg(
+    message: CommitMessage,
+    file_path: Option<String>,
+) -> Result<CommitValidation, AppError> {
+    let (lint_rules, conventional_rules, template) = match &file_path {
+        Some(file_path) => (
+            read_lint_rules(file_path).await?,
+            read_conventional_rules(file_path).await?,
+            read_commit_template(file_path).await?,
+        ),
+        None => (LintRules::defaults(), ConventionalRules::default(), None),
+    };
+    let format = commit_msg_format(file_path.as_deref()).await?;
+    let length_unit = lint_rules.length_unit;
+    let long_body_lines = lint_rules
+        .body_line_limit()
+        .map(|limit| message.get_long_body_lines(limit, length_unit))
+        .unwrap_or_default();
+    let subject_too_long = lint_rules
+        .subject_limit()
+        .is_some_and(|limit| message.is_subject_too_long(limit, length_unit));
+    let conventional = message.conventional_commit();
+    let conventional_violations =
+        conventional::check_conventional_commit(conventional.as_ref(), &conventional_rules);
+
+    let content = commit::serialize_commit_msg_with(
+        &CommitMessage {
+            comments: Vec::new(),
+            diff_content: None,
+            ..message.clone()
+        },
+        &format,
+    );
+    let diagnostics = commit_lint::lint_commit_message(&content, &lint_rules);
+    let matches_template = match template {
+        Some(template) => {
+            let template = commit::parse_commit_msg_with(&template, &format)?;
+            !template.subject.is_empty()
+                && (&template.subject, &template.body, &template.trailers)
+                    == (&message.subject, &message.body, &message.trailers)
+        }
+        None => false,
+    };
 
-    CommitValidation {
-        is_valid: !subject_too_long && long_body_lines.is_empty(),
+    Ok(CommitValidation {
+        is_valid: conventional_violations.is_empty()
+            && !matches_template
+            && !diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error),
         subject_too_long,
         subject_length: message.subject_length(),
+        subject_width: message.subject_width(),
+        length_unit,
         long_body_lines,
+        conventional,
+        conventional_violations,
+        diagnostics,
+        matches_template,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::commit_lint::LintRuleName;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-commit-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn test_validate_commit_msg_reads_allowed_types_from_git_config() {
+        let repo = create_test_repo();
+        run_git(
+            &repo,
+            &["config", "--add", COMMIT_TYPE_CONFIG_KEY, "feat fix"],
+        );
+        run_git(&repo, &["config", "--add", COMMIT_TYPE_CONFIG_KEY, "docs"]);
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+        let validate = |subject: &str| {
+            let message = commit::parse_commit_msg(subject).unwrap();
+            tauri::async_runtime::block_on(validate_commit_msg(message, Some(file_path.clone())))
+                .unwrap()
+        };
+
+        let allowed = validate("docs: explain plans");
+        let disallowed = validate("chore: bump deps");
+        let not_conventional = validate("Bump deps");
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(allowed.is_valid);
+        assert_eq!(
+            disallowed.conventional_violations,
+            vec![ConventionalViolation::TypeNotAllowed {
+                commit_type: "chore".to_string(),
+            }]
+        );
+        assert!(!disallowed.is_valid);
+        assert_eq!(
+            not_conventional.conventional_violations,
+            vec![ConventionalViolation::NotConventional]
+        );
+    }
+
+    #[test]
+    fn test_validate_commit_msg_applies_project_lint_rules() {
+        let repo = create_test_repo();
+        std_fs::write(
+            repo.join(LINT_CONFIG_FILE),
+            "[rules]\nsubject-max-length = { level = \"error\", value = 20 }\nbody-max-line-length = { level = \"off\" }\n",
+        )
+        .unwrap();
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+        let mut message = commit::parse_commit_msg("Add a subject that is too long").unwrap();
+        message.body = "x".repeat(100);
+
+        let validation =
+            tauri::async_runtime::block_on(validate_commit_msg(message.clone(), Some(file_path)))
+                .unwrap();
+        let without_repo =
+            tauri::async_runtime::block_on(validate_commit_msg(message, None)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(!validation.is_valid);
+        assert!(validation.subject_too_long);
+        assert!(validation.long_body_lines.is_empty());
+        assert_eq!(validation.diagnostics.len(), 1);
+        assert_eq!(
+            validation.diagnostics[0].rule,
+            LintRuleName::SubjectMaxLength
+        );
+        assert_eq!(
+            validation.diagnostics[0].severity,
+            DiagnosticSeverity::Error
+        );
+        assert_eq!(
+            (
+                validation.diagnostics[0].line,
+                validation.diagnostics[0].column
+            ),
+            (1, 21)
+        );
+        // 既定のルールは警告のため、メッセージ自体は有効とする。
+        assert!(without_repo.is_valid);
+        assert_eq!(without_repo.long_body_lines, vec![(1, 100)]);
+    }
+
+    #[test]
+    fn test_parse_commit_msg_follows_repository_comment_char_and_cleanup() {
+        let repo = create_test_repo();
+        run_git(&repo, &["config", "core.commentChar", ";"]);
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+        let content = "Fix crash\n\n#123 is fixed.\n\n; Please enter the commit message\n; ------------------------ >8 ------------------------\ndiff --git a/a b/a";
+        let parse = |content: &str| {
+            tauri::async_runtime::block_on(parse_commit_msg(
+                content.to_string(),
+                Some(file_path.clone()),
+            ))
+        };
+
+        let stripped = parse(content).unwrap();
+        let serialized = tauri::async_runtime::block_on(serialize_commit_msg(
+            stripped.clone(),
+            Some(file_path.clone()),
+        ))
+        .unwrap();
+        run_git(&repo, &["config", "commit.cleanup", "verbatim"]);
+        let verbatim = parse("Fix crash\n\n; kept as written\n").unwrap();
+        run_git(&repo, &["config", "commit.cleanup", "everything"]);
+        let invalid = parse(content);
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(stripped.body, "#123 is fixed.");
+        assert_eq!(stripped.comments, vec!["; Please enter the commit message"]);
+        assert_eq!(serialized, content);
+        assert_eq!(verbatim.body, "; kept as written");
+        assert!(verbatim.comments.is_empty());
+        assert!(matches!(invalid, Err(AppError::CommandError { .. })));
+    }
+
+    #[test]
+    fn test_validate_commit_msg_detects_unedited_template() {
+        let repo = create_test_repo();
+        std_fs::write(
+            repo.join("message-template.txt"),
+            "Summarize the change\n\n# Explain why.\n",
+        )
+        .unwrap();
+        run_git(
+            &repo,
+            &["config", TEMPLATE_CONFIG_KEY, "message-template.txt"],
+        );
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+        let validate = |content: &str| {
+            let message = commit::parse_commit_msg(content).unwrap();
+            tauri::async_runtime::block_on(validate_commit_msg(message, Some(file_path.clone())))
+                .unwrap()
+        };
+
+        let unedited = validate("Summarize the change\n\n# Please enter the commit message\n");
+        let edited = validate("Fix the parser\n");
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(unedited.matches_template);
+        assert!(!unedited.is_valid);
+        assert!(!edited.matches_template);
+        assert!(edited.is_valid);
+    }
+
+    #[test]
+    fn test_validate_commit_msg_measures_display_width_when_configured() {
+        let repo = create_test_repo();
+        std_fs::write(
+            repo.join(LINT_CONFIG_FILE),
+            "length-unit = \"display-width\"\n",
+        )
+        .unwrap();
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+        let mut message = commit::parse_commit_msg(&"変更".repeat(15)).unwrap();
+        message.body = "説明".repeat(20);
+
+        let validation =
+            tauri::async_runtime::block_on(validate_commit_msg(message.clone(), Some(file_path)))
+                .unwrap();
+        let without_repo =
+            tauri::async_runtime::block_on(validate_commit_msg(message, None)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(validation.length_unit, LengthUnit::DisplayWidth);
+        assert_eq!(
+            (validation.subject_length, validation.subject_width),
+            (30, 60)
+        );
+        assert!(validation.subject_too_long);
+        assert_eq!(validation.long_body_lines, vec![(1, 80)]);
+        assert!(!without_repo.subject_too_long);
+        assert!(without_repo.long_body_lines.is_empty());
     }
 }
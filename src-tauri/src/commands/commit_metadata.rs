@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::staging::resolve_git_root;
+use crate::error::AppError;
+use crate::parser::rebase_lint::hashes_match;
+use crate::parser::RebaseTodoFile;
+
+/// `%G?` が示す署名の検証結果。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// 有効な署名（G）。
+    Good,
+    /// 不正な署名（B）。
+    Bad,
+    /// 有効だが信頼度が不明な鍵による署名（U）。
+    Untrusted,
+    /// 期限切れの署名（X）。
+    Expired,
+    /// 期限切れの鍵による署名（Y）。
+    ExpiredKey,
+    /// 失効した鍵による署名（R）。
+    Revoked,
+    /// 公開鍵がないなどの理由で検証できない署名（E）。
+    Unverifiable,
+    /// 署名なし（N）。
+    None,
+}
+
+impl SignatureStatus {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "G" => SignatureStatus::Good,
+            "B" => SignatureStatus::Bad,
+            "U" => SignatureStatus::Untrusted,
+            "X" => SignatureStatus::Expired,
+            "Y" => SignatureStatus::ExpiredKey,
+            "R" => SignatureStatus::Revoked,
+            "E" => SignatureStatus::Unverifiable,
+            _ => SignatureStatus::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMetadata {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// ISO 8601 形式の author date。
+    pub author_date: String,
+    /// ISO 8601 形式の committer date。
+    pub committer_date: String,
+    /// subject を含むメッセージ全体。
+    pub message: String,
+    pub parent_count: usize,
+    pub signature: SignatureStatus,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files_changed: usize,
+}
+
+/// レコード区切り（RS）とフィールド区切り（US）。メッセージ本文に現れない制御文字を使う。
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+const LOG_FORMAT: &str = "%x1e%H%x1f%an%x1f%ae%x1f%aI%x1f%cI%x1f%P%x1f%G?%x1f%B%x1f";
+
+/// `LOG_FORMAT` と `--numstat` を指定した `git log` の出力を解析する。
+/// 各レコードのメタデータの後ろに `追加\t削除\tパス` 形式の行が続く（バイナリは `-`）。
+pub fn parse_metadata_log(output: &str) -> Vec<CommitMetadata> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let fields: Vec<&str> = record.splitn(9, FIELD_SEPARATOR).collect();
+            if fields.len() < 9 {
+                return None;
+            }
+
+            let mut insertions = 0;
+            let mut deletions = 0;
+            let mut files_changed = 0;
+            for line in fields[8].lines() {
+                let mut columns = line.splitn(3, '\t');
+                let (Some(added), Some(deleted), Some(_)) =
+                    (columns.next(), columns.next(), columns.next())
+                else {
+                    continue;
+                };
+                files_changed += 1;
+                insertions += added.parse::<usize>().unwrap_or(0);
+                deletions += deleted.parse::<usize>().unwrap_or(0);
+            }
+
+            Some(CommitMetadata {
+                hash: fields[0].trim().to_string(),
+                author_name: fields[1].to_string(),
+                author_email: fields[2].to_string(),
+                author_date: fields[3].to_string(),
+                committer_date: fields[4].to_string(),
+                parent_count: fields[5].split_whitespace().count(),
+                signature: SignatureStatus::from_code(fields[6].trim()),
+                message: fields[7].trim_end_matches('\n').to_string(),
+                insertions,
+                deletions,
+                files_changed,
+            })
+        })
+        .collect()
+}
+
+/// todo の各エントリが参照するコミットのメタデータを、エントリ id をキーにして返す。
+///
+/// コミット数によらず `git log --no-walk` を 1 回だけ実行する。解決できないハッシュは
+/// `--ignore-missing` で読み飛ばし、そのエントリは結果に含めない。
+/// マージコミットは差分を出さないため、変更行数は 0 になる。
+#[tauri::command]
+pub async fn git_commit_metadata(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<HashMap<String, CommitMetadata>, AppError> {
+    let mut hashes: Vec<&str> = Vec::new();
+    for hash in file
+        .entries
+        .iter()
+        .filter_map(|entry| entry.referenced_commit())
+    {
+        // オプションとして解釈されないよう、16 進のハッシュだけを渡す。
+        if hash.chars().all(|c| c.is_ascii_hexdigit()) && !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let git_root = resolve_git_root(&file_path).await?;
+    let format = format!("--format={}", LOG_FORMAT);
+    let output = Command::new("git")
+        .args([
+            "-C",
+            &git_root,
+            "log",
+            "--no-walk=unsorted",
+            "--ignore-missing",
+            "--numstat",
+            "--no-color",
+            &format,
+        ])
+        .args(&hashes)
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git log: {}", e),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::CommandError {
+            message: format!("git log failed: {}", stderr),
+        });
+    }
+
+    let commits = parse_metadata_log(&String::from_utf8_lossy(&output.stdout));
+    Ok(file
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let hash = entry.referenced_commit()?;
+            commits
+                .iter()
+                .find(|commit| hashes_match(&commit.hash, hash))
+                .map(|commit| (entry.id.clone(), commit.clone()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-commit-metadata-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_parse_metadata_log() {
+        let output = "\u{1e}abc123\u{1f}Alice\u{1f}alice@example.com\u{1f}2024-01-02T03:04:05+09:00\u{1f}2024-01-03T00:00:00+00:00\u{1f}p1 p2\u{1f}G\u{1f}Subject\n\nBody line\n\u{1f}\n\n3\t1\tsrc/a.rs\n-\t-\timage.png\n";
+        let commits = parse_metadata_log(output);
+
+        assert_eq!(commits.len(), 1);
+        let commit = &commits[0];
+        assert_eq!(commit.hash, "abc123");
+        assert_eq!(commit.author_name, "Alice");
+        assert_eq!(commit.author_email, "alice@example.com");
+        assert_eq!(commit.author_date, "2024-01-02T03:04:05+09:00");
+        assert_eq!(commit.message, "Subject\n\nBody line");
+        assert_eq!(commit.parent_count, 2);
+        assert_eq!(commit.signature, SignatureStatus::Good);
+        assert_eq!(commit.insertions, 3);
+        assert_eq!(commit.deletions, 1);
+        assert_eq!(commit.files_changed, 2);
+    }
+
+    #[test]
+    fn test_git_commit_metadata_keys_by_entry_id() {
+        let repo = create_test_repo();
+        fs::write(repo.join("a.txt"), "one\n").unwrap();
+        run_git(&repo, &["add", "a.txt"]);
+        run_git(&repo, &["commit", "-m", "first\n\nbody"]);
+        fs::write(repo.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        run_git(&repo, &["commit", "-am", "second"]);
+        let first = run_git(&repo, &["rev-parse", "--short", "HEAD~1"]);
+        let second = run_git(&repo, &["rev-parse", "--short", "HEAD"]);
+
+        let todo = format!(
+            "pick {} first\npick {} second\npick deadbeef missing\nexec true\n",
+            first, second
+        );
+        let file = parse_rebase_todo(&todo).unwrap();
+        let ids: Vec<String> = file.entries.iter().map(|entry| entry.id.clone()).collect();
+        let file_path = repo.join("a.txt").to_string_lossy().to_string();
+
+        let metadata =
+            tauri::async_runtime::block_on(git_commit_metadata(file_path, file)).unwrap();
+
+        let _ = fs::remove_dir_all(&repo);
+
+        assert_eq!(metadata.len(), 2);
+        let first_meta = &metadata[&ids[0]];
+        assert_eq!(first_meta.author_name, "Test User");
+        assert_eq!(first_meta.message, "first\n\nbody");
+        assert_eq!(first_meta.parent_count, 0);
+        assert_eq!(first_meta.signature, SignatureStatus::None);
+        let second_meta = &metadata[&ids[1]];
+        assert_eq!(second_meta.insertions, 2);
+        assert_eq!(second_meta.deletions, 0);
+        assert_eq!(second_meta.parent_count, 1);
+        assert!(!metadata.contains_key(&ids[2]));
+    }
+}
@@ -142,7 +142,7 @@ async fn detect_branch_names(merged_path: &str) -> (String, String) {
 }
 
 /// 通常リポジトリと linked worktree の両方で実体の Git directory を解決する。
-async fn resolve_git_dir(git_root: &str) -> Result<PathBuf, AppError> {
+pub(crate) async fn resolve_git_dir(git_root: &str) -> Result<PathBuf, AppError> {
     let output = Command::new("git")
         .args(["-C", git_root, "rev-parse", "--git-dir"])
         .output()
@@ -171,7 +171,7 @@ async fn path_exists(path: &Path) -> bool {
     fs::try_exists(path).await.unwrap_or(false)
 }
 
-async fn path_is_dir(path: &Path) -> bool {
+pub(crate) async fn path_is_dir(path: &Path) -> bool {
     match fs::metadata(path).await {
         Ok(meta) => meta.is_dir(),
         Err(_) => false,
@@ -1,21 +1,45 @@
 pub mod codex;
 pub mod commit;
 pub mod commit_diff;
+pub mod commit_metadata;
 pub mod file;
 pub mod merge;
 pub mod rebase;
+pub mod rebase_deps;
+pub mod rebase_plan;
+pub mod rebase_progress;
+pub mod rebase_recovery;
+pub mod rebase_refs;
+pub mod rebase_simulate;
+pub mod rebase_upstream;
 pub mod staging;
+pub mod standalone;
+pub mod trailer;
 
 pub use codex::{check_codex_available, open_codex_terminal};
 pub use commit::{parse_commit_msg, serialize_commit_msg, validate_commit_msg};
 pub use commit_diff::{git_commit_diff, git_commit_files};
+pub use commit_metadata::git_commit_metadata;
 pub use file::{
     check_backup_exists, create_backup, delete_backup, exit_app, read_file, restore_backup,
     write_file,
 };
 pub use merge::{git_blame_for_merge, parse_conflicts, read_merge_files};
 pub use rebase::{
-    check_git_sc_available, generate_commit_message, generate_commit_message_from_staged,
-    parse_rebase_todo, serialize_rebase_todo,
+    apply_message_replace, autosquash_rebase_todo, build_rebase_graph, check_git_sc_available,
+    compose_squash_messages, detect_reword_mode, generate_commit_message,
+    generate_commit_message_from_staged, get_exec_templates, insert_exec_steps, lint_rebase_todo,
+    move_rebase_commit, parse_rebase_todo, preview_message_replace, queue_squash_messages,
+    queued_reword_message, regenerate_todo_help, remove_exec_steps, replace_squash_with_reword,
+    serialize_rebase_todo, serialize_rebase_todo_lossless,
 };
+pub use rebase_deps::analyze_rebase_dependencies;
+pub use rebase_plan::{apply_rebase_plan, load_rebase_plan, save_rebase_plan};
+pub use rebase_progress::get_rebase_progress;
+pub use rebase_recovery::{list_rebase_sessions, restore_rebase_session};
+pub use rebase_refs::{add_update_ref, list_stack_branches, remove_update_ref};
+pub use rebase_simulate::simulate_rebase;
+pub use rebase_upstream::detect_upstream_commits;
 pub use staging::{git_diff_file, git_stage_all, git_stage_file, git_status, git_unstage_file};
+pub use standalone::{finish_editor_request, list_base_commits, start_standalone_rebase};
+pub use trailer::apply_trailer_operations;
@@ -1,8 +1,28 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tokio::fs;
 use tokio::process::Command;
+use tokio::sync::OnceCell;
 
+use super::commit_metadata::git_commit_metadata;
+use super::staging::resolve_git_root;
+use super::standalone;
 use crate::error::AppError;
+use crate::parser::commit::{parse_commit_msg, serialize_commit_msg, CommitMessage};
+use crate::parser::message_replace::{self, MessageReplace, MessageReplacement};
+use crate::parser::rebase::{
+    parse_git_version, plan_autosquash, planned_rewords, AutosquashPlan, GitVersion, RebaseCommand,
+    RewordMode, SerializeOptions, FIXUP_MESSAGE_OPTION_VERSION,
+};
+use crate::parser::rebase_exec::{self, ExecTemplate, ExecTemplateSource};
+use crate::parser::rebase_graph::{self, RebaseGraph};
+use crate::parser::rebase_header::{self, DEFAULT_COMMENT_CHAR};
+use crate::parser::rebase_lint::{self, LintOptions, MissingCommitsCheck, RebaseDiagnostic};
+use crate::parser::squash_message::{self, ComposedSquash};
 use crate::parser::{
-    parse_rebase_todo as parse_todo, serialize_rebase_todo as serialize_todo, RebaseTodoFile,
+    parse_rebase_todo as parse_todo, serialize_rebase_todo as serialize_todo,
+    serialize_rebase_todo_lossless as serialize_todo_lossless, RebaseTodoFile,
 };
 
 /// git-rebase-todo の内容を解析する。
@@ -17,6 +37,583 @@ pub fn serialize_rebase_todo(file: RebaseTodoFile) -> String {
     serialize_todo(&file)
 }
 
+/// fixup!/squash!/amend! のコミットを対象コミットの直後へ並べ替える。
+///
+/// インストールされている Git が `fixup -C` に対応しない場合は、amend! の完全なメッセージを
+/// 読み、対象の reword として適用する。
+#[tauri::command]
+pub async fn autosquash_rebase_todo(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<AutosquashPlan, AppError> {
+    let git_version = read_git_version().await;
+    let messages: HashMap<String, String> =
+        if git_version.is_some_and(|version| version < FIXUP_MESSAGE_OPTION_VERSION) {
+            git_commit_metadata(file_path, file.clone())
+                .await?
+                .into_iter()
+                .map(|(entry_id, metadata)| (entry_id, metadata.message))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+    Ok(plan_autosquash(&file, git_version, &messages))
+}
+
+/// `--rebase-merges` の todo をセクションとラベルの関係に分解する。
+#[tauri::command]
+pub fn build_rebase_graph(file: RebaseTodoFile) -> RebaseGraph {
+    rebase_graph::build_rebase_graph(&file)
+}
+
+/// コミットを別のセクション（ブランチ）の先端へ移動した todo を返す。
+#[tauri::command]
+pub fn move_rebase_commit(
+    file: RebaseTodoFile,
+    entry_id: String,
+    target_section: usize,
+) -> Result<RebaseTodoFile, AppError> {
+    rebase_graph::move_commit_to_section(&file, &entry_id, target_section)
+}
+
+/// コミットを作る各エントリ（squash/fixup の連鎖は最後）の後ろに `exec <command>` を挿入する。
+#[tauri::command]
+pub fn insert_exec_steps(
+    file: RebaseTodoFile,
+    command: String,
+) -> Result<RebaseTodoFile, AppError> {
+    rebase_exec::insert_exec_steps(&file, &command)
+}
+
+/// `command` を実行する exec エントリをすべて取り除く。
+#[tauri::command]
+pub fn remove_exec_steps(file: RebaseTodoFile, command: String) -> RebaseTodoFile {
+    rebase_exec::remove_exec_steps(&file, &command)
+}
+
+/// exec テンプレートを git config から読むキー。
+const EXEC_TEMPLATE_CONFIG_KEY: &str = "gui-git-editor.execTemplate";
+
+/// exec テンプレートを置くプロジェクトファイル（リポジトリ直下）。
+const EXEC_TEMPLATE_FILE: &str = ".gui-git-editor-exec";
+
+/// 保存済みの exec コマンドを、git config、プロジェクトファイルの順に重複なく返す。
+#[tauri::command]
+pub async fn get_exec_templates(file_path: String) -> Result<Vec<ExecTemplate>, AppError> {
+    let git_root = resolve_git_root(&file_path).await?;
+    let mut templates: Vec<ExecTemplate> = Vec::new();
+    let mut push = |command: String, source: ExecTemplateSource| {
+        if !command.is_empty() && !templates.iter().any(|t| t.command == command) {
+            templates.push(ExecTemplate { command, source });
+        }
+    };
+
+    for value in read_git_config_all(&git_root, EXEC_TEMPLATE_CONFIG_KEY).await {
+        for command in rebase_exec::parse_exec_template_file(&value) {
+            push(command, ExecTemplateSource::GitConfig);
+        }
+    }
+
+    let project_file = Path::new(&git_root).join(EXEC_TEMPLATE_FILE);
+    match fs::read_to_string(&project_file).await {
+        Ok(content) => {
+            for command in rebase_exec::parse_exec_template_file(&content) {
+                push(command, ExecTemplateSource::ProjectFile);
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(AppError::from_io_with_path(
+                project_file.to_string_lossy(),
+                e,
+            ))
+        }
+    }
+
+    Ok(templates)
+}
+
+/// ネイティブ reword の新しいメッセージを置くディレクトリ名（rebase-merge 配下）。
+/// rebase の完了・中止時に Git が rebase-merge ごと削除するため、後始末は不要。
+const REWORD_QUEUE_DIR: &str = "gui-git-editor-reword";
+
+/// squash の連鎖ごとに合成したメッセージを置くディレクトリ名（rebase-merge 配下）。
+const SQUASH_QUEUE_DIR: &str = "gui-git-editor-squash";
+
+/// RebaseTodoFile を元の書式を保ったまま git-rebase-todo 形式へ変換する。
+/// 変更されたエントリの綴りは todo を所有するリポジトリの `rebase.abbreviateCommands` に従う。
+///
+/// `reword_mode` が `native` の場合は `reword` 行をそのまま出力し、編集済みメッセージを
+/// todo と同じディレクトリのキューへ保存する。
+#[tauri::command]
+pub async fn serialize_rebase_todo_lossless(
+    file_path: String,
+    file: RebaseTodoFile,
+    reword_mode: Option<RewordMode>,
+) -> Result<String, AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+    let abbreviate_commands = read_git_config(&todo_dir, "rebase.abbreviateCommands")
+        .await
+        .map(|value| is_config_true(&value))
+        .unwrap_or(false);
+    let reword_mode = reword_mode.unwrap_or_default();
+
+    if reword_mode == RewordMode::Native {
+        write_message_queue(
+            &Path::new(&todo_dir).join(REWORD_QUEUE_DIR),
+            &planned_rewords(&file),
+        )
+        .await?;
+    }
+
+    Ok(serialize_todo_lossless(
+        &file,
+        &SerializeOptions {
+            abbreviate_commands,
+            reword_mode,
+        },
+    ))
+}
+
+/// reword をネイティブ方式で保存してよいかを判定し、使う方式を返す。
+///
+/// キューに保存したメッセージは、Git が開く COMMIT_EDITMSG をこのアプリが開いたときにしか
+/// 補完されない。そのため `git var GIT_EDITOR`（`GIT_EDITOR`、`core.editor`、`VISUAL`、
+/// `EDITOR` の順に解決）がこのアプリを指す場合と、スタンドアロンの rebase 中だけ `native` を返す。
+#[tauri::command]
+pub async fn detect_reword_mode(file_path: String) -> Result<RewordMode, AppError> {
+    if standalone::is_running() {
+        return Ok(RewordMode::Native);
+    }
+
+    let todo_dir = parent_dir(&file_path)?;
+    let Ok(output) = Command::new("git")
+        .args(["-C", &todo_dir, "var", "GIT_EDITOR"])
+        .output()
+        .await
+    else {
+        return Ok(RewordMode::ExecAmend);
+    };
+    if !output.status.success() {
+        return Ok(RewordMode::ExecAmend);
+    }
+
+    let editor = String::from_utf8_lossy(&output.stdout);
+    let is_app = std::env::current_exe()
+        .is_ok_and(|exe| editor_is_executable(editor.trim_end_matches(['\n', '\r']), &exe));
+    Ok(if is_app {
+        RewordMode::Native
+    } else {
+        RewordMode::ExecAmend
+    })
+}
+
+/// エディタのコマンドラインの先頭の語（実行するプログラム）を、シェルと同じく引用符を外して返す。
+fn editor_program(editor: &str) -> Option<String> {
+    let mut program = String::new();
+    let mut chars = editor.trim_start().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => program.extend(chars.by_ref().take_while(|&c| c != '\'')),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => program.extend(chars.next()),
+                        c => program.push(c),
+                    }
+                }
+            }
+            '\\' => program.extend(chars.next()),
+            c if c.is_whitespace() => break,
+            c => program.push(c),
+        }
+    }
+    (!program.is_empty()).then_some(program)
+}
+
+/// エディタのコマンドラインが `exe` を起動するかを返す。
+/// パスを含まないプログラム名は PATH から探されるため、ファイル名だけを比べる。
+fn editor_is_executable(editor: &str, exe: &Path) -> bool {
+    let Some(program) = editor_program(editor) else {
+        return false;
+    };
+    let program = Path::new(&program);
+    if program.components().count() == 1 {
+        return exe.file_name() == Some(program.as_os_str());
+    }
+    match (program.canonicalize(), exe.canonicalize()) {
+        (Ok(program), Ok(exe)) => program == exe,
+        _ => program == exe,
+    }
+}
+
+/// メッセージをコミットハッシュ名のファイルとしてキューへ書き出す。
+/// 前回保存時の古いメッセージが残らないよう、キューは毎回作り直す。
+async fn write_message_queue(
+    queue_dir: &Path,
+    messages: &[(String, String)],
+) -> Result<(), AppError> {
+    let queue_dir_str = queue_dir.to_string_lossy().to_string();
+
+    match fs::remove_dir_all(queue_dir).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(AppError::from_io_with_path(queue_dir_str, e)),
+    }
+
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(queue_dir)
+        .await
+        .map_err(|e| AppError::from_io_with_path(queue_dir_str.clone(), e))?;
+
+    for (hash, message) in messages {
+        // ハッシュはファイル名に使うため、パス区切りなどを含まないことを確認する。
+        if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AppError::CommandError {
+                message: format!("Invalid commit hash for reword: {}", hash),
+            });
+        }
+        let path = queue_dir.join(hash);
+        fs::write(&path, message)
+            .await
+            .map_err(|e| AppError::from_io_with_path(path.to_string_lossy().to_string(), e))?;
+    }
+
+    Ok(())
+}
+
+/// reword や squash の途中で開かれた COMMIT_EDITMSG に対し、キューに保存済みのメッセージを返す。
+///
+/// `rebase-merge/done` の最後のコマンドが reword ならネイティブ reword のキューを、
+/// squash・fixup なら連鎖の最後のコミットをキーにした squash のキューを探す。
+/// 該当するメッセージがある場合だけ Some を返す。
+#[tauri::command]
+pub async fn queued_reword_message(file_path: String) -> Result<Option<CommitMessage>, AppError> {
+    let rebase_dir = Path::new(&parent_dir(&file_path)?).join("rebase-merge");
+
+    let Ok(done) = fs::read_to_string(rebase_dir.join("done")).await else {
+        return Ok(None);
+    };
+    let (current_hash, queue_dir) = match parse_todo(&done)?.entries.last() {
+        Some(entry) if entry.command == RebaseCommand::Reword => {
+            (entry.commit_hash.clone(), REWORD_QUEUE_DIR)
+        }
+        Some(entry) if matches!(entry.command, RebaseCommand::Squash | RebaseCommand::Fixup) => {
+            (entry.commit_hash.clone(), SQUASH_QUEUE_DIR)
+        }
+        _ => return Ok(None),
+    };
+
+    let Ok(mut queue) = fs::read_dir(rebase_dir.join(queue_dir)).await else {
+        return Ok(None);
+    };
+    while let Some(queued) = queue.next_entry().await? {
+        let name = queued.file_name().to_string_lossy().to_string();
+        if rebase_lint::hashes_match(&name, &current_hash) {
+            let message = fs::read_to_string(queued.path())
+                .await
+                .map_err(|e| AppError::from_io_with_path(queued.path().to_string_lossy(), e))?;
+            return parse_commit_msg(&message).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// squash を含む連鎖ごとに、Git が SQUASH_MSG として提示するメッセージを先に合成する。
+///
+/// 各コミットの完全なメッセージを `git log` で読み、subject と本文をまとめて
+/// fixup!/squash! の接頭辞と重複する trailer を取り除く。
+#[tauri::command]
+pub async fn compose_squash_messages(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<Vec<ComposedSquash>, AppError> {
+    let chains = squash_message::find_squash_chains(&file);
+    if chains.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let messages: HashMap<String, String> = git_commit_metadata(file_path, file.clone())
+        .await?
+        .into_iter()
+        .map(|(entry_id, metadata)| (entry_id, metadata.message))
+        .collect();
+
+    Ok(chains
+        .into_iter()
+        .map(|chain| {
+            let entries: Vec<_> = file
+                .entries
+                .iter()
+                .filter(|entry| chain.entry_ids.contains(&entry.id))
+                .cloned()
+                .collect();
+            let message = squash_message::compose_squash_message(&entries, &messages);
+            ComposedSquash {
+                chain,
+                message: serialize_commit_msg(&message),
+            }
+        })
+        .collect())
+}
+
+/// 合成した squash メッセージを、連鎖の最後のコミット名でキューへ保存する。
+///
+/// `messages` は連鎖の先頭エントリ id ごとのメッセージ。保存時点の todo で連鎖を求め直すため、
+/// 並べ替えで連鎖でなくなったエントリのメッセージは保存しない。
+#[tauri::command]
+pub async fn queue_squash_messages(
+    file_path: String,
+    file: RebaseTodoFile,
+    messages: HashMap<String, String>,
+) -> Result<(), AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+    let queued: Vec<(String, String)> = squash_message::find_squash_chains(&file)
+        .into_iter()
+        .filter_map(|chain| {
+            messages
+                .get(&chain.root_id)
+                .map(|message| (chain.final_hash, message.clone()))
+        })
+        .collect();
+
+    write_message_queue(&Path::new(&todo_dir).join(SQUASH_QUEUE_DIR), &queued).await
+}
+
+/// squash の連鎖を、合成したメッセージを持つ reword と fixup に置き換える。
+#[tauri::command]
+pub fn replace_squash_with_reword(
+    file: RebaseTodoFile,
+    root_id: String,
+    message: String,
+) -> Result<RebaseTodoFile, AppError> {
+    squash_message::replace_chain_with_reword(&file, &root_id, &message)
+}
+
+/// 選んだエントリのメッセージに検索・置換を適用した場合の、変更前後のメッセージを返す。
+///
+/// 未編集のエントリは `git log` で完全なメッセージを読み、本文や trailer も置換の対象にする。
+/// todo は変更しないため、結果を確認してから `apply_message_replace` で適用する。
+#[tauri::command]
+pub async fn preview_message_replace(
+    file_path: String,
+    file: RebaseTodoFile,
+    entry_ids: Vec<String>,
+    spec: MessageReplace,
+) -> Result<Vec<MessageReplacement>, AppError> {
+    let selected = RebaseTodoFile {
+        entries: file
+            .entries
+            .iter()
+            .filter(|entry| entry_ids.contains(&entry.id))
+            .cloned()
+            .collect(),
+        comments: Vec::new(),
+        trailing_lines: Vec::new(),
+        header: Default::default(),
+    };
+    let messages: HashMap<String, String> = git_commit_metadata(file_path, selected)
+        .await?
+        .into_iter()
+        .map(|(entry_id, metadata)| (entry_id, metadata.message))
+        .collect();
+
+    message_replace::plan_message_replacements(&file, &entry_ids, &messages, &spec)
+}
+
+/// プレビューした置換を適用し、対象のエントリを新しいメッセージの reword にする。
+#[tauri::command]
+pub fn apply_message_replace(
+    file: RebaseTodoFile,
+    replacements: Vec<MessageReplacement>,
+) -> RebaseTodoFile {
+    message_replace::apply_message_replacements(&file, &replacements)
+}
+
+/// 末尾にある Git の説明を、todo を所有するリポジトリの `core.commentChar` で作り直す。
+///
+/// 古いコメント文字のまま残った説明や、コマンド数が変わった説明を直すために使う。
+/// `core.commentChar` が `auto` の場合、Git は todo に `#` を使う。
+#[tauri::command]
+pub async fn regenerate_todo_help(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<RebaseTodoFile, AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+    let comment_char = read_git_config(&todo_dir, "core.commentChar")
+        .await
+        .filter(|value| !value.is_empty() && value != "auto")
+        .unwrap_or_else(|| DEFAULT_COMMENT_CHAR.to_string());
+    let missing_commits_check = read_git_config(&todo_dir, "rebase.missingCommitsCheck")
+        .await
+        .map(|value| MissingCommitsCheck::from_config(&value))
+        .unwrap_or_default();
+
+    Ok(rebase_header::regenerate_todo_help(
+        &file,
+        &comment_char,
+        missing_commits_check == MissingCommitsCheck::Error,
+        read_git_version().await,
+    ))
+}
+
+/// todo や COMMIT_EDITMSG のパスから、git コマンドを実行する親ディレクトリを求める。
+pub(crate) fn parent_dir(file_path: &str) -> Result<String, AppError> {
+    Path::new(file_path)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::CommandError {
+            message: "Cannot determine parent directory".to_string(),
+        })
+}
+
+/// git config の真偽値表記を解釈する。
+pub(crate) fn is_config_true(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "true" | "yes" | "on" | "1"
+    )
+}
+
+/// 編集中の todo を検証し、エントリ id 付きの診断を返す。
+///
+/// `rebase.missingCommitsCheck` が有効な場合は、Git が同じディレクトリに残す
+/// `git-rebase-todo.backup` と比較して、行ごと削除されたコミットも報告する。
+#[tauri::command]
+pub async fn lint_rebase_todo(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<Vec<RebaseDiagnostic>, AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+
+    let missing_commits_check = read_git_config(&todo_dir, "rebase.missingCommitsCheck")
+        .await
+        .map(|value| MissingCommitsCheck::from_config(&value))
+        .unwrap_or_default();
+
+    let original_commits = if missing_commits_check == MissingCommitsCheck::Ignore {
+        Vec::new()
+    } else {
+        read_original_commits(&Path::new(&todo_dir).join("git-rebase-todo.backup")).await
+    };
+
+    // Git は label が見つからない reset / merge の引数を任意のリビジョンとして解決する。
+    let mut resolvable_revisions = Vec::new();
+    for reference in rebase_lint::undefined_label_references(&file) {
+        if is_commit(&todo_dir, reference).await {
+            resolvable_revisions.push(reference.to_string());
+        }
+    }
+
+    Ok(rebase_lint::lint_rebase_todo(
+        &file,
+        &LintOptions {
+            missing_commits_check,
+            original_commits,
+            git_version: read_git_version().await,
+            resolvable_revisions,
+        },
+    ))
+}
+
+/// `rev` がコミットに解決できるかを返す。
+async fn is_commit(dir: &str, rev: &str) -> bool {
+    // オプションとして解釈されないよう、`-` で始まる名前は解決を試みない。
+    if rev.starts_with('-') {
+        return false;
+    }
+    Command::new("git")
+        .args(["-C", dir, "rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", rev))
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// `read_git_version` の結果。todo の検証は編集のたびに実行されるため、起動中は 1 度だけ読む。
+static GIT_VERSION: OnceCell<Option<GitVersion>> = OnceCell::const_new();
+
+/// インストールされている Git のバージョンを読む。実行できない場合は None。
+pub(crate) async fn read_git_version() -> Option<GitVersion> {
+    *GIT_VERSION
+        .get_or_init(|| async {
+            let output = Command::new("git").arg("--version").output().await.ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            parse_git_version(&String::from_utf8_lossy(&output.stdout))
+        })
+        .await
+}
+
+/// Git が rebase 開始時に保存した todo からコミットと subject の一覧を読む。
+/// バックアップがない、または解析できない場合は空として扱う。
+async fn read_original_commits(backup_path: &Path) -> Vec<(String, String)> {
+    let Ok(content) = fs::read_to_string(backup_path).await else {
+        return Vec::new();
+    };
+    let Ok(original) = parse_todo(&content) else {
+        return Vec::new();
+    };
+
+    original
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .referenced_commit()
+                .map(|hash| (hash.to_string(), entry.message.clone()))
+        })
+        .collect()
+}
+
+/// `git config --get` で設定値を読む。未設定や git の失敗時は None を返す。
+///
+/// `.git` 配下のディレクトリでも git はリポジトリを解決できるため、
+/// todo や COMMIT_EDITMSG の親ディレクトリをそのまま渡してよい。
+pub(crate) async fn read_git_config(dir: &str, key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["-C", dir, "config", "--get", key])
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches(['\n', '\r'])
+            .to_string(),
+    )
+}
+
+/// `git config --get-all` で複数値の設定を読む。未設定や git の失敗時は空を返す。
+pub(crate) async fn read_git_config_all(dir: &str, key: &str) -> Vec<String> {
+    let Ok(output) = Command::new("git")
+        .args(["-C", dir, "config", "--get-all", key])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
 /// システム上で git-sc が利用可能か確認する。
 #[tauri::command]
 pub async fn check_git_sc_available() -> Result<bool, AppError> {
@@ -144,6 +741,336 @@ async fn run_git_sc(args: &[String]) -> Result<String, AppError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// `.git/rebase-merge/` に todo とバックアップを置き、todo のパスを返す。
+    fn write_rebase_todo(repo: &Path, todo: &str, backup: &str) -> String {
+        let rebase_dir = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(rebase_dir.join("git-rebase-todo.backup"), backup).unwrap();
+        let todo_path = rebase_dir.join("git-rebase-todo");
+        std_fs::write(&todo_path, todo).unwrap();
+        todo_path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_get_exec_templates_merges_config_and_project_file() {
+        let repo = create_test_repo();
+        run_git(
+            &repo,
+            &["config", "--add", EXEC_TEMPLATE_CONFIG_KEY, "cargo test"],
+        );
+        run_git(
+            &repo,
+            &["config", "--add", EXEC_TEMPLATE_CONFIG_KEY, "cargo clippy"],
+        );
+        std_fs::write(
+            repo.join(EXEC_TEMPLATE_FILE),
+            "# プロジェクト共通\ncargo test\nnpm run lint\n",
+        )
+        .unwrap();
+        let todo_path = write_rebase_todo(&repo, "", "");
+
+        let templates = tauri::async_runtime::block_on(get_exec_templates(todo_path)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(
+            templates,
+            vec![
+                ExecTemplate {
+                    command: "cargo test".to_string(),
+                    source: ExecTemplateSource::GitConfig,
+                },
+                ExecTemplate {
+                    command: "cargo clippy".to_string(),
+                    source: ExecTemplateSource::GitConfig,
+                },
+                ExecTemplate {
+                    command: "npm run lint".to_string(),
+                    source: ExecTemplateSource::ProjectFile,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_rebase_todo_reports_deleted_commit_when_check_enabled() {
+        let repo = create_test_repo();
+        run_git(&repo, &["config", "rebase.missingCommitsCheck", "error"]);
+        let todo = "pick abc1234 First\n";
+        let todo_path = write_rebase_todo(&repo, todo, "pick abc1234 First\npick def5678 Second\n");
+
+        let diagnostics =
+            tauri::async_runtime::block_on(lint_rebase_todo(todo_path, parse_todo(todo).unwrap()))
+                .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].commit_hash.as_deref(), Some("def5678"));
+        assert_eq!(
+            diagnostics[0].severity,
+            rebase_lint::DiagnosticSeverity::Error
+        );
+    }
+
+    #[test]
+    fn test_lint_rebase_todo_accepts_reset_to_existing_branch() {
+        let repo = create_test_repo();
+        std_fs::write(repo.join("a.txt"), "a").unwrap();
+        run_git(&repo, &["add", "a.txt"]);
+        run_git(&repo, &["commit", "-m", "First"]);
+        run_git(&repo, &["branch", "topic"]);
+        let todo = "reset topic\npick abc1234 First\nreset missing\n";
+        let todo_path = write_rebase_todo(&repo, todo, todo);
+
+        let diagnostics =
+            tauri::async_runtime::block_on(lint_rebase_todo(todo_path, parse_todo(todo).unwrap()))
+                .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].kind,
+            rebase_lint::DiagnosticKind::UndefinedLabel
+        );
+        assert_eq!(diagnostics[0].message, "Label 'missing' is never defined");
+    }
+
+    #[test]
+    fn test_regenerate_todo_help_uses_core_comment_char() {
+        let repo = create_test_repo();
+        run_git(&repo, &["config", "core.commentChar", ";"]);
+        let todo = "pick abc1234 First\n\n# Rebase 1a2b3c4..abc1234 onto 1a2b3c4 (1 command)\n#\n";
+        let todo_path = write_rebase_todo(&repo, todo, todo);
+
+        let file = tauri::async_runtime::block_on(regenerate_todo_help(
+            todo_path,
+            parse_todo(todo).unwrap(),
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(file.header.comment_char, ";");
+        assert_eq!(
+            file.trailing_lines[1],
+            "; Rebase 1a2b3c4..abc1234 onto 1a2b3c4 (1 command)"
+        );
+        assert!(file.comments.iter().all(|line| line.starts_with(';')));
+    }
+
+    #[test]
+    fn test_serialize_rebase_todo_lossless_follows_abbreviate_commands() {
+        let repo = create_test_repo();
+        run_git(&repo, &["config", "rebase.abbreviateCommands", "true"]);
+        let todo = "pick abc1234 First\n# note\npick def5678 Second\n";
+        let todo_path = write_rebase_todo(&repo, todo, todo);
+
+        let mut file = parse_todo(todo).unwrap();
+        file.entries[1].command = RebaseCommand::Squash;
+        let output =
+            tauri::async_runtime::block_on(serialize_rebase_todo_lossless(todo_path, file, None))
+                .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(output, "pick abc1234 First\n# note\ns def5678 Second\n");
+    }
+
+    #[test]
+    fn test_native_reword_queue_fills_commit_editmsg() {
+        let repo = create_test_repo();
+        let todo = "pick abc1234 Old subject\n";
+        let todo_path = write_rebase_todo(&repo, todo, todo);
+
+        let mut file = parse_todo(todo).unwrap();
+        file.entries[0].command = RebaseCommand::Reword;
+        file.entries[0].message = "New subject\n\nNew body".to_string();
+        let output = tauri::async_runtime::block_on(serialize_rebase_todo_lossless(
+            todo_path,
+            file,
+            Some(RewordMode::Native),
+        ))
+        .unwrap();
+        assert_eq!(output, "reword abc1234 New subject\n");
+
+        // Git が reword を実行し、COMMIT_EDITMSG を開いた状態を再現する。
+        let git_dir = repo.join(".git");
+        std_fs::write(
+            git_dir.join("rebase-merge").join("done"),
+            "reword abc1234def New subject\n",
+        )
+        .unwrap();
+        let editmsg = git_dir.join("COMMIT_EDITMSG").to_string_lossy().to_string();
+        let message = tauri::async_runtime::block_on(queued_reword_message(editmsg)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let message = message.expect("キューのメッセージが返る");
+        assert_eq!(message.subject, "New subject");
+        assert_eq!(message.body, "New body");
+    }
+
+    #[test]
+    fn test_queued_reword_message_is_none_outside_reword() {
+        let repo = create_test_repo();
+        let todo = "pick abc1234 Subject\n";
+        write_rebase_todo(&repo, todo, todo);
+        let git_dir = repo.join(".git");
+        std_fs::write(git_dir.join("rebase-merge").join("done"), todo).unwrap();
+
+        let editmsg = git_dir.join("COMMIT_EDITMSG").to_string_lossy().to_string();
+        let message = tauri::async_runtime::block_on(queued_reword_message(editmsg)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(message.is_none());
+    }
+
+    #[test]
+    fn test_composed_squash_message_fills_commit_editmsg() {
+        let repo = create_test_repo();
+        std_fs::write(repo.join("a.txt"), "a\n").unwrap();
+        run_git(&repo, &["add", "a.txt"]);
+        run_git(&repo, &["commit", "-m", "Add a\n\nFirst body\n\nRefs: #1"]);
+        std_fs::write(repo.join("a.txt"), "a\nb\n").unwrap();
+        run_git(
+            &repo,
+            &["commit", "-am", "squash! Add a\n\nSecond body\n\nRefs: #1"],
+        );
+        let first = run_git(&repo, &["rev-parse", "--short", "HEAD~1"]);
+        let second = run_git(&repo, &["rev-parse", "--short", "HEAD"]);
+
+        let todo = format!("pick {} Add a\nsquash {} squash! Add a\n", first, second);
+        let todo_path = write_rebase_todo(&repo, &todo, &todo);
+        let file = parse_todo(&todo).unwrap();
+
+        let composed = tauri::async_runtime::block_on(compose_squash_messages(
+            todo_path.clone(),
+            file.clone(),
+        ))
+        .unwrap();
+        assert_eq!(composed.len(), 1);
+        let message = composed[0].message.clone();
+        assert_eq!(message, "Add a\n\nFirst body\n\nSecond body\n\nRefs: #1");
+
+        let messages = HashMap::from([(composed[0].chain.root_id.clone(), message)]);
+        tauri::async_runtime::block_on(queue_squash_messages(todo_path, file, messages)).unwrap();
+
+        // Git が連鎖の最後の squash を適用し、COMMIT_EDITMSG を開いた状態を再現する。
+        let git_dir = repo.join(".git");
+        std_fs::write(git_dir.join("rebase-merge").join("done"), &todo).unwrap();
+        let editmsg = git_dir.join("COMMIT_EDITMSG").to_string_lossy().to_string();
+        let queued = tauri::async_runtime::block_on(queued_reword_message(editmsg)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let queued = queued.expect("合成したメッセージが返る");
+        assert_eq!(queued.subject, "Add a");
+        assert_eq!(queued.body, "First body\n\nSecond body");
+    }
+
+    #[test]
+    fn test_write_message_queue_rejects_non_hex_hash() {
+        let repo = create_test_repo();
+        let result = tauri::async_runtime::block_on(write_message_queue(
+            &repo,
+            &[("../escape".to_string(), "msg".to_string())],
+        ));
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+
+    #[test]
+    fn test_editor_program_unquotes_first_word() {
+        assert_eq!(
+            editor_program(r#""/Applications/gui-git-editor.app/Contents/MacOS/gui-git-editor""#),
+            Some("/Applications/gui-git-editor.app/Contents/MacOS/gui-git-editor".to_string())
+        );
+        assert_eq!(
+            editor_program("'/opt/my apps/gui-git-editor' --wait"),
+            Some("/opt/my apps/gui-git-editor".to_string())
+        );
+        assert_eq!(
+            editor_program(r"/opt/my\ apps/vim -f"),
+            Some("/opt/my apps/vim".to_string())
+        );
+        assert_eq!(editor_program("  "), None);
+    }
+
+    #[test]
+    fn test_editor_is_executable_compares_with_app() {
+        let exe = Path::new("/Applications/gui-git-editor.app/Contents/MacOS/gui-git-editor");
+
+        assert!(editor_is_executable(
+            r#""/Applications/gui-git-editor.app/Contents/MacOS/gui-git-editor""#,
+            exe
+        ));
+        assert!(editor_is_executable("gui-git-editor", exe));
+        assert!(!editor_is_executable("code --wait", exe));
+        assert!(!editor_is_executable("/usr/bin/vim", exe));
+    }
+
+    #[test]
+    fn test_is_config_true() {
+        assert!(is_config_true("true"));
+        assert!(is_config_true("Yes"));
+        assert!(is_config_true("1\n"));
+        assert!(!is_config_true("false"));
+        assert!(!is_config_true(""));
+    }
+
+    #[test]
+    fn test_lint_rebase_todo_ignores_deleted_commit_by_default() {
+        let repo = create_test_repo();
+        let todo = "pick abc1234 First\n";
+        let todo_path = write_rebase_todo(&repo, todo, "pick abc1234 First\npick def5678 Second\n");
+
+        let diagnostics =
+            tauri::async_runtime::block_on(lint_rebase_todo(todo_path, parse_todo(todo).unwrap()))
+                .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(diagnostics.is_empty());
+    }
 
     #[test]
     fn test_parse_dry_run_subject_only() {
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::staging::resolve_git_root;
+use crate::error::AppError;
+use crate::parser::rebase::RebaseCommand;
+use crate::parser::rebase_lint::hashes_match;
+use crate::parser::RebaseTodoFile;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntryDependencies {
+    pub entry_id: String,
+    /// このコミットが変更する行（またはその隣接行）を書いた、先行するエントリ。todo の順に並ぶ。
+    pub depends_on: Vec<String>,
+}
+
+/// `-U0` の差分の 1 ハンク。行番号は 1 始まり。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub path: String,
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_len: usize,
+}
+
+/// `git log -p -U0 --format="commit %H"` の出力を、コミットごとのハンクに分ける。
+pub fn parse_zero_context_patches(output: &str) -> Vec<(String, Vec<Hunk>)> {
+    let mut patches: Vec<(String, Vec<Hunk>)> = Vec::new();
+    let mut old_path = String::new();
+    let mut path = String::new();
+    // ハンク本体の残り行数。`--- ` で始まる削除行をファイルヘッダと取り違えないよう数える。
+    let mut remaining = 0usize;
+
+    for line in output.lines() {
+        if remaining > 0 {
+            if line.starts_with('-') || line.starts_with('+') {
+                remaining -= 1;
+            }
+            continue;
+        }
+
+        if let Some(commit) = line.strip_prefix("commit ") {
+            patches.push((commit.trim().to_string(), Vec::new()));
+        } else if let Some(name) = line.strip_prefix("--- ") {
+            old_path = name.strip_prefix("a/").unwrap_or(name).to_string();
+        } else if let Some(name) = line.strip_prefix("+++ ") {
+            // 削除されたファイルは新しい側が /dev/null になるため、元のパスで追跡する。
+            path = match name.strip_prefix("b/") {
+                Some(name) => name.to_string(),
+                None => old_path.clone(),
+            };
+        } else if line.starts_with("@@ ") {
+            let Some((old_start, old_len, new_len)) = parse_hunk_header(line) else {
+                continue;
+            };
+            remaining = old_len + new_len;
+            if let Some((_, hunks)) = patches.last_mut() {
+                hunks.push(Hunk {
+                    path: path.clone(),
+                    old_start,
+                    old_len,
+                    new_len,
+                });
+            }
+        }
+    }
+    patches
+}
+
+/// `@@ -a,b +c,d @@` から (a, b, d) を取り出す。長さが省略されていれば 1。
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = line.split_whitespace().skip(1);
+    let (old_start, old_len) = parse_range(parts.next()?.strip_prefix('-')?)?;
+    let (_, new_len) = parse_range(parts.next()?.strip_prefix('+')?)?;
+    Some((old_start, old_len, new_len))
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// コミットを順に適用しながら行ごとの作者（エントリの位置）を追跡し、依存関係を求める。
+///
+/// 変更・削除する行と、その直前・直後の行を先行するコミットが書いていれば依存とみなす。
+/// 隣接する行の変更も 3-way マージでは競合になるため、並べ替えると適用できなくなる。
+pub fn compute_dependencies(
+    entries: &[(String, String)],
+    patches: &[(String, Vec<Hunk>)],
+) -> Vec<EntryDependencies> {
+    // ファイルごとの、現在の各行を書いたエントリの位置。末尾より先は誰も書いていない行。
+    let mut owners: HashMap<&str, Vec<Option<usize>>> = HashMap::new();
+    let mut result = Vec::with_capacity(entries.len());
+
+    for (position, (entry_id, hash)) in entries.iter().enumerate() {
+        let hunks = patches
+            .iter()
+            .find(|(commit, _)| hashes_match(commit, hash))
+            .map(|(_, hunks)| hunks.as_slice())
+            .unwrap_or(&[]);
+
+        let mut depends_on: Vec<usize> = Vec::new();
+        // 後ろのハンクから適用し、前のハンクの行番号がずれないようにする。
+        for hunk in hunks.iter().rev() {
+            let lines = owners.entry(hunk.path.as_str()).or_default();
+            // 削除を伴わない挿入では old_start は挿入位置の直前の行を指す。
+            let at = if hunk.old_len == 0 {
+                hunk.old_start
+            } else {
+                hunk.old_start - 1
+            };
+
+            let first = at.saturating_sub(1);
+            let last = at + hunk.old_len;
+            for owner in lines.iter().take(last + 1).skip(first).flatten().copied() {
+                if !depends_on.contains(&owner) {
+                    depends_on.push(owner);
+                }
+            }
+
+            if lines.len() < at + hunk.old_len {
+                lines.resize(at + hunk.old_len, None);
+            }
+            lines.splice(
+                at..at + hunk.old_len,
+                std::iter::repeat_n(Some(position), hunk.new_len),
+            );
+        }
+
+        depends_on.sort_unstable();
+        result.push(EntryDependencies {
+            entry_id: entry_id.clone(),
+            depends_on: depends_on
+                .into_iter()
+                .map(|index| entries[index].0.clone())
+                .collect(),
+        });
+    }
+    result
+}
+
+/// todo のコミット間の行単位の依存関係を求める。
+///
+/// 並べ替える前の todo を渡す。コミットの差分は元の親を基準にしているため、
+/// todo の順がそのまま履歴の順として扱われる。
+#[tauri::command]
+pub async fn analyze_rebase_dependencies(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<Vec<EntryDependencies>, AppError> {
+    let entries: Vec<(String, String)> = file
+        .entries
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry.command,
+                RebaseCommand::Pick
+                    | RebaseCommand::Reword
+                    | RebaseCommand::Edit
+                    | RebaseCommand::Squash
+                    | RebaseCommand::Fixup
+                    | RebaseCommand::Drop
+            ) && !entry.commit_hash.is_empty()
+                && entry.commit_hash.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .map(|entry| (entry.id.clone(), entry.commit_hash.clone()))
+        .collect();
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let git_root = resolve_git_root(&file_path).await?;
+    let output = Command::new("git")
+        .args([
+            "-C",
+            &git_root,
+            "log",
+            "--no-walk=unsorted",
+            "--ignore-missing",
+            "-p",
+            "-U0",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--format=commit %H",
+        ])
+        .args(entries.iter().map(|(_, hash)| hash.as_str()))
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git log: {}", e),
+        })?;
+    if !output.status.success() {
+        return Err(AppError::CommandError {
+            message: format!(
+                "git log failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        });
+    }
+
+    let patches = parse_zero_context_patches(&String::from_utf8_lossy(&output.stdout));
+    Ok(compute_dependencies(&entries, &patches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use std::fs as std_fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-deps-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, content: &str, message: &str) -> String {
+        std_fs::write(repo.join(name), content).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", message]);
+        run_git(repo, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_parse_zero_context_patches_counts_hunk_lines() {
+        let output = "commit aaaa\n\ndiff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -2 +2,2 @@\n--- old\n+++ new\n+more\n@@ -9,0 +11 @@ ctx\n+added\ncommit bbbb\ndiff --git a/y b/y\ndeleted file mode 100644\n--- a/y\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-a\n-b\n";
+
+        let patches = parse_zero_context_patches(output);
+
+        assert_eq!(patches.len(), 2);
+        assert_eq!(
+            patches[0].1,
+            vec![
+                Hunk {
+                    path: "x".to_string(),
+                    old_start: 2,
+                    old_len: 1,
+                    new_len: 2
+                },
+                Hunk {
+                    path: "x".to_string(),
+                    old_start: 9,
+                    old_len: 0,
+                    new_len: 1
+                },
+            ]
+        );
+        assert_eq!(patches[1].1[0].path, "y");
+        assert_eq!(patches[1].1[0].old_len, 2);
+    }
+
+    #[test]
+    fn test_compute_dependencies_tracks_shifted_and_adjacent_lines() {
+        let entries: Vec<(String, String)> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| (format!("id-{}", name), name.repeat(7)))
+            .collect();
+        let hunk = |old_start, old_len, new_len| Hunk {
+            path: "f".to_string(),
+            old_start,
+            old_len,
+            new_len,
+        };
+        let patches = vec![
+            // a: 10 行のファイルを作る。
+            ("a".repeat(40), vec![hunk(0, 0, 10)]),
+            // b: 先頭に 2 行挿入する（a の 1 行目の直前なので隣接）。
+            ("b".repeat(40), vec![hunk(0, 0, 2)]),
+            // c: 3 行目（a の 1 行目）を書き換える。隣接する b の 2 行目にも依存する。
+            ("c".repeat(40), vec![hunk(3, 1, 1)]),
+            // d: 末尾より後ろに追加する。a の最後の行に隣接する。
+            ("d".repeat(40), vec![hunk(12, 0, 1)]),
+        ];
+
+        let deps = compute_dependencies(&entries, &patches);
+
+        assert_eq!(deps[0].depends_on, Vec::<String>::new());
+        assert_eq!(deps[1].depends_on, vec!["id-a"]);
+        assert_eq!(deps[2].depends_on, vec!["id-a", "id-b"]);
+        assert_eq!(deps[3].depends_on, vec!["id-a"]);
+    }
+
+    #[test]
+    fn test_analyze_rebase_dependencies() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n", "base");
+        let lines: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let create = commit_file(&repo, "a.txt", &lines, "create a");
+        let other = commit_file(&repo, "b.txt", "b\n", "add b");
+        let edit = commit_file(
+            &repo,
+            "a.txt",
+            &lines.replace("line 15\n", "line fifteen\n"),
+            "edit a",
+        );
+
+        let todo = format!(
+            "pick {} create a\npick {} add b\npick {} edit a\n",
+            create, other, edit
+        );
+        let file = parse_rebase_todo(&todo).unwrap();
+        let ids: Vec<String> = file.entries.iter().map(|entry| entry.id.clone()).collect();
+        let file_path = repo.join("base.txt").to_string_lossy().to_string();
+
+        let deps =
+            tauri::async_runtime::block_on(analyze_rebase_dependencies(file_path, file)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(deps.len(), 3);
+        assert!(deps[0].depends_on.is_empty());
+        assert!(deps[1].depends_on.is_empty());
+        assert_eq!(deps[2].depends_on, vec![ids[0].clone()]);
+    }
+}
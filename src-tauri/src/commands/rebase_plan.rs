@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use tokio::fs;
+
+use super::merge::resolve_git_dir;
+use super::rebase_upstream::patch_ids;
+use super::staging::resolve_git_root;
+use crate::error::AppError;
+use crate::parser::rebase_plan::{self, AppliedRebasePlan, RebasePlan};
+use crate::parser::RebaseTodoFile;
+
+/// 計画を保存するファイル。rebase を中止しても消えないよう、rebase-merge の外に置く。
+const REBASE_PLAN_FILE: &str = "gui-git-editor-rebase-plan.json";
+
+async fn plan_path(git_root: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_git_dir(git_root).await?.join(REBASE_PLAN_FILE))
+}
+
+/// todo のコミットの (commit, patch-id) を返す。
+async fn todo_patch_ids(
+    git_root: &str,
+    file: &RebaseTodoFile,
+) -> Result<Vec<(String, String)>, AppError> {
+    let mut log_args = vec!["log", "--no-walk=unsorted", "--ignore-missing"];
+    // オプションとして解釈されないよう、16 進のハッシュだけを渡す。
+    log_args.extend(
+        file.entries
+            .iter()
+            .map(|entry| entry.commit_hash.as_str())
+            .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit())),
+    );
+    if log_args.len() == 3 {
+        return Ok(Vec::new());
+    }
+    patch_ids(git_root, &log_args).await
+}
+
+/// 編集中の todo を、reword のメッセージや patch-id とともに JSON の計画として保存し、
+/// 保存先のパスを返す。
+#[tauri::command]
+pub async fn save_rebase_plan(file_path: String, file: RebaseTodoFile) -> Result<String, AppError> {
+    let git_root = resolve_git_root(&file_path).await?;
+    let plan = rebase_plan::create_rebase_plan(&file, &todo_patch_ids(&git_root, &file).await?);
+    let json = serde_json::to_string_pretty(&plan).map_err(|e| AppError::CommandError {
+        message: format!("Failed to serialize rebase plan: {}", e),
+    })?;
+
+    let path = plan_path(&git_root).await?;
+    fs::write(&path, json)
+        .await
+        .map_err(|e| AppError::from_io_with_path(path.to_string_lossy(), e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 保存済みの計画を読む。保存されていなければ None。
+#[tauri::command]
+pub async fn load_rebase_plan(file_path: String) -> Result<Option<RebasePlan>, AppError> {
+    let git_root = resolve_git_root(&file_path).await?;
+    let path = plan_path(&git_root).await?;
+    let json = match fs::read_to_string(&path).await {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::from_io_with_path(path.to_string_lossy(), e)),
+    };
+
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| AppError::CommandError {
+            message: format!("Invalid rebase plan: {}", e),
+        })
+}
+
+/// 計画を todo に当てはめる。ハッシュが変わったコミットは patch-id で対応付け、
+/// 対応付けられなかったエントリを結果に含めて返す。
+#[tauri::command]
+pub async fn apply_rebase_plan(
+    file_path: String,
+    file: RebaseTodoFile,
+    plan: RebasePlan,
+) -> Result<AppliedRebasePlan, AppError> {
+    let git_root = resolve_git_root(&file_path).await?;
+    // すべてハッシュで対応付くなら patch-id は求めない。
+    let needs_patch_ids = plan.entries.iter().any(|planned| {
+        planned.patch_id.is_some()
+            && !file.entries.iter().any(|entry| {
+                crate::parser::rebase_lint::hashes_match(&entry.commit_hash, &planned.commit_hash)
+            })
+    });
+    let patch_ids = if needs_patch_ids {
+        todo_patch_ids(&git_root, &file).await?
+    } else {
+        Vec::new()
+    };
+
+    rebase_plan::apply_rebase_plan(&file, &plan, &patch_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use crate::parser::rebase::RebaseCommand;
+    use std::fs as std_fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-plan-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, content: &str) -> String {
+        std_fs::write(repo.join(name), content).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", name]);
+        run_git(repo, &["rev-parse", "--short", "HEAD"])
+    }
+
+    #[test]
+    fn test_saved_plan_is_applied_to_recreated_commits_by_patch_id() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n");
+        let first = commit_file(&repo, "a.txt", "a\n");
+        let todo_path = repo
+            .join(".git")
+            .join("rebase-merge")
+            .join("git-rebase-todo")
+            .to_string_lossy()
+            .to_string();
+
+        let mut file = parse_rebase_todo(&format!("pick {} a.txt\n", first)).unwrap();
+        file.entries[0].command = RebaseCommand::Reword;
+        file.entries[0].message = "Add a".to_string();
+        let saved_to =
+            tauri::async_runtime::block_on(save_rebase_plan(todo_path.clone(), file)).unwrap();
+
+        // 同じ変更のコミットを作り直し、ハッシュだけを変える。
+        run_git(&repo, &["reset", "--hard", "HEAD~1"]);
+        std_fs::write(repo.join("unrelated.txt"), "x\n").unwrap();
+        run_git(&repo, &["add", "unrelated.txt"]);
+        run_git(&repo, &["commit", "-m", "unrelated"]);
+        let recreated = commit_file(&repo, "a.txt", "a\n");
+        let fresh = parse_rebase_todo(&format!("pick {} a.txt\n", recreated)).unwrap();
+
+        let applied = tauri::async_runtime::block_on(async {
+            let plan = load_rebase_plan(todo_path.clone()).await.unwrap().unwrap();
+            apply_rebase_plan(todo_path.clone(), fresh.clone(), plan).await
+        })
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(saved_to.ends_with(REBASE_PLAN_FILE));
+        assert_ne!(first, recreated);
+        assert_eq!(applied.file.entries[0].command, RebaseCommand::Reword);
+        assert_eq!(applied.file.entries[0].message, "Add a");
+        assert_eq!(
+            applied.matched_by_patch_id,
+            vec![fresh.entries[0].id.clone()]
+        );
+    }
+
+    #[test]
+    fn test_load_rebase_plan_none_when_not_saved() {
+        let repo = create_test_repo();
+        let file_path = repo.join("file.txt").to_string_lossy().to_string();
+
+        let plan = tauri::async_runtime::block_on(load_rebase_plan(file_path)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(plan.is_none());
+    }
+}
@@ -0,0 +1,244 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use super::merge::{path_is_dir, resolve_git_dir};
+use super::staging::resolve_git_root;
+use crate::error::AppError;
+use crate::parser::parse_rebase_todo;
+use crate::parser::rebase::{RebaseCommand, RebaseEntry};
+
+/// rebase が停止している理由。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// `edit` で停止した。
+    Edit,
+    /// `break` で停止した。
+    Break,
+    /// `exec` のコマンドが失敗した。
+    Exec,
+    /// コミットの適用で競合した。
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseProgress {
+    /// 現在のステップ番号（1 始まり、`msgnum`）。
+    pub step: usize,
+    /// 全ステップ数（`end`）。
+    pub total: usize,
+    pub done: Vec<RebaseEntry>,
+    pub remaining: Vec<RebaseEntry>,
+    /// 適用中のエントリ（`done` の最後）。
+    pub current: Option<RebaseEntry>,
+    /// 停止したコミット（`stopped-sha`）。
+    pub stopped_sha: Option<String>,
+    pub stop_reason: Option<StopReason>,
+    pub onto: String,
+    pub orig_head: String,
+    /// rebase 元のブランチ名。detached HEAD から始めた場合は None。
+    pub head_name: Option<String>,
+}
+
+/// 進行中の対話的 rebase の進み具合を返す。rebase 中でなければ None。
+///
+/// mergetool やコミットエディタとして起動されたときに、どのステップで止まっているかを
+/// 示すために使う。`file_path` はリポジトリ内の任意のファイルでよい。
+#[tauri::command]
+pub async fn get_rebase_progress(file_path: String) -> Result<Option<RebaseProgress>, AppError> {
+    let git_root = resolve_git_root(&file_path).await?;
+    let git_dir = resolve_git_dir(&git_root).await?;
+    let rebase_merge = git_dir.join("rebase-merge");
+    if !path_is_dir(&rebase_merge).await {
+        return Ok(None);
+    }
+
+    let done = match read_optional(&rebase_merge, "done").await? {
+        Some(content) => parse_rebase_todo(&content)?.entries,
+        None => Vec::new(),
+    };
+    let remaining = match read_optional(&rebase_merge, "git-rebase-todo").await? {
+        Some(content) => parse_rebase_todo(&content)?.entries,
+        None => Vec::new(),
+    };
+    let step = read_optional(&rebase_merge, "msgnum")
+        .await?
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(done.len());
+    let total = read_optional(&rebase_merge, "end")
+        .await?
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(done.len() + remaining.len());
+    let stopped_sha = read_trimmed(&rebase_merge, "stopped-sha").await?;
+    let head_name = read_trimmed(&rebase_merge, "head-name")
+        .await?
+        .filter(|name| name != "detached HEAD")
+        .map(|name| {
+            name.strip_prefix("refs/heads/")
+                .unwrap_or(&name)
+                .to_string()
+        });
+
+    let current = done.last().cloned();
+    let stop_reason = stop_reason(current.as_ref(), stopped_sha.is_some());
+
+    Ok(Some(RebaseProgress {
+        step,
+        total,
+        done,
+        remaining,
+        current,
+        stopped_sha,
+        stop_reason,
+        onto: read_trimmed(&rebase_merge, "onto")
+            .await?
+            .unwrap_or_default(),
+        orig_head: read_trimmed(&rebase_merge, "orig-head")
+            .await?
+            .unwrap_or_default(),
+        head_name,
+    }))
+}
+
+/// 最後に実行したコマンドから停止理由を推定する。
+fn stop_reason(current: Option<&RebaseEntry>, stopped: bool) -> Option<StopReason> {
+    match current.map(|entry| &entry.command) {
+        Some(RebaseCommand::Edit) => Some(StopReason::Edit),
+        Some(RebaseCommand::Break) => Some(StopReason::Break),
+        Some(RebaseCommand::Exec(_)) => Some(StopReason::Exec),
+        _ if stopped => Some(StopReason::Conflict),
+        _ => None,
+    }
+}
+
+/// rebase-merge 配下の状態ファイルを読む。存在しなければ None。
+async fn read_optional(dir: &Path, name: &str) -> Result<Option<String>, AppError> {
+    let path = dir.join(name);
+    match fs::read_to_string(&path).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::from_io_with_path(path.to_string_lossy(), e)),
+    }
+}
+
+async fn read_trimmed(dir: &Path, name: &str) -> Result<Option<String>, AppError> {
+    Ok(read_optional(dir, name)
+        .await?
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-progress-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_get_rebase_progress_reads_state_files() {
+        let repo = create_test_repo();
+        let rebase_dir = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(
+            rebase_dir.join("done"),
+            "pick aaa1111 First\nedit bbb2222 Add parser\n",
+        )
+        .unwrap();
+        std_fs::write(rebase_dir.join("git-rebase-todo"), "pick ccc3333 Third\n").unwrap();
+        std_fs::write(rebase_dir.join("msgnum"), "2\n").unwrap();
+        std_fs::write(rebase_dir.join("end"), "3\n").unwrap();
+        std_fs::write(rebase_dir.join("onto"), "1234567\n").unwrap();
+        std_fs::write(rebase_dir.join("orig-head"), "89abcde\n").unwrap();
+        std_fs::write(rebase_dir.join("head-name"), "refs/heads/feature\n").unwrap();
+        std_fs::write(rebase_dir.join("stopped-sha"), "bbb2222\n").unwrap();
+        let file_path = repo
+            .join(".git")
+            .join("COMMIT_EDITMSG")
+            .to_string_lossy()
+            .to_string();
+
+        let progress = tauri::async_runtime::block_on(get_rebase_progress(file_path))
+            .unwrap()
+            .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(progress.step, 2);
+        assert_eq!(progress.total, 3);
+        assert_eq!(progress.done.len(), 2);
+        assert_eq!(progress.remaining.len(), 1);
+        assert_eq!(progress.current.unwrap().commit_hash, "bbb2222");
+        assert_eq!(progress.stopped_sha.as_deref(), Some("bbb2222"));
+        assert_eq!(progress.stop_reason, Some(StopReason::Edit));
+        assert_eq!(progress.onto, "1234567");
+        assert_eq!(progress.orig_head, "89abcde");
+        assert_eq!(progress.head_name.as_deref(), Some("feature"));
+    }
+
+    #[test]
+    fn test_get_rebase_progress_reports_conflict_on_pick() {
+        let repo = create_test_repo();
+        let rebase_dir = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(rebase_dir.join("done"), "pick aaa1111 First\n").unwrap();
+        std_fs::write(rebase_dir.join("stopped-sha"), "aaa1111\n").unwrap();
+        std_fs::write(rebase_dir.join("head-name"), "detached HEAD\n").unwrap();
+        let file_path = repo.join("file.txt").to_string_lossy().to_string();
+
+        let progress = tauri::async_runtime::block_on(get_rebase_progress(file_path))
+            .unwrap()
+            .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(progress.step, 1);
+        assert_eq!(progress.total, 1);
+        assert_eq!(progress.stop_reason, Some(StopReason::Conflict));
+        assert_eq!(progress.head_name, None);
+    }
+
+    #[test]
+    fn test_get_rebase_progress_none_outside_rebase() {
+        let repo = create_test_repo();
+        let file_path = repo.join("file.txt").to_string_lossy().to_string();
+
+        let progress = tauri::async_runtime::block_on(get_rebase_progress(file_path)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(progress.is_none());
+    }
+}
@@ -0,0 +1,308 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::merge::{path_is_dir, resolve_git_dir};
+use super::rebase_refs::git_stdout;
+use crate::error::AppError;
+use crate::parser::rebase_reflog::{self, RebaseSession, REFLOG_FORMAT};
+
+/// 復元前の HEAD を残すバックアップ ref の置き場所。
+const BACKUP_REF_PREFIX: &str = "refs/gui-git-editor/backup";
+
+/// rebase 前の状態へ戻した結果。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RestoredRebase {
+    /// 戻す前の HEAD を保存した ref。
+    pub backup_ref: String,
+    /// 戻す前の HEAD。
+    pub previous_commit: String,
+    pub restored_commit: String,
+}
+
+/// 現在チェックアウトしているブランチ（`refs/heads/...`）。detached HEAD なら None。
+async fn current_branch(repo_path: &str) -> Option<String> {
+    git_stdout(repo_path, &["symbolic-ref", "-q", "HEAD"])
+        .await
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+async fn read_reflog(
+    repo_path: &str,
+    ref_name: &str,
+) -> Result<Vec<rebase_reflog::ReflogEntry>, AppError> {
+    let format = format!("--format={}", REFLOG_FORMAT);
+    let output = git_stdout(
+        repo_path,
+        &["log", "-g", "--date=iso-strict", &format, ref_name, "--"],
+    )
+    .await?;
+    Ok(rebase_reflog::parse_reflog(&output))
+}
+
+/// HEAD と現在のブランチの reflog から rebase のセッションを新しい順に `limit` 件（既定 20 件）返す。
+///
+/// 各セッションには、rebase 前のコミットと rebase したブランチの現在の状態との差分の規模を付ける。
+#[tauri::command]
+pub async fn list_rebase_sessions(
+    repo_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<RebaseSession>, AppError> {
+    let head_log = read_reflog(&repo_path, "HEAD").await?;
+    let branch = current_branch(&repo_path).await;
+    let branch_log = match &branch {
+        // ブランチの reflog が無効（core.logAllRefUpdates=false）でも HEAD だけで求める。
+        Some(branch) => read_reflog(&repo_path, branch).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let mut sessions =
+        rebase_reflog::group_rebase_sessions(&head_log, branch.as_deref(), &branch_log);
+    sessions.truncate(limit.unwrap_or(20));
+
+    for session in &mut sessions {
+        let current = session.head_name.as_deref().unwrap_or("HEAD");
+        session.diff = git_stdout(
+            &repo_path,
+            &["diff", "--numstat", &session.before_commit, current, "--"],
+        )
+        .await
+        .ok()
+        .map(|output| rebase_reflog::summarize_numstat(&output));
+    }
+
+    Ok(sessions)
+}
+
+/// 現在のブランチを rebase 前のコミット `before_commit` に戻す。
+///
+/// 戻す前の HEAD は `refs/gui-git-editor/backup/` 配下の ref に保存する。
+/// rebase の途中、未コミットの変更がある場合、`head_name` と異なるブランチに
+/// いる場合は何もせずエラーにする。ブランチの分からないセッション（中断したものや
+/// detached HEAD での rebase）は、どのブランチを戻すか決められないためエラーにする。
+#[tauri::command]
+pub async fn restore_rebase_session(
+    repo_path: String,
+    before_commit: String,
+    head_name: Option<String>,
+) -> Result<RestoredRebase, AppError> {
+    // オプションとして解釈されないよう、16 進のハッシュだけを受け付ける。
+    if before_commit.is_empty() || !before_commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::CommandError {
+            message: format!("Invalid commit: {}", before_commit),
+        });
+    }
+
+    let Some(head_name) = head_name else {
+        return Err(AppError::CommandError {
+            message: "Cannot restore a rebase session whose branch is unknown".to_string(),
+        });
+    };
+    let branch = current_branch(&repo_path).await;
+    if branch.as_ref() != Some(&head_name) {
+        return Err(AppError::CommandError {
+            message: format!("Check out {} before restoring it", head_name),
+        });
+    }
+
+    let git_dir = resolve_git_dir(&repo_path).await?;
+    if path_is_dir(&git_dir.join("rebase-merge")).await
+        || path_is_dir(&git_dir.join("rebase-apply")).await
+    {
+        return Err(AppError::CommandError {
+            message: "A rebase is in progress".to_string(),
+        });
+    }
+    let status = git_stdout(
+        &repo_path,
+        &["status", "--porcelain", "--untracked-files=no"],
+    )
+    .await?;
+    if !status.trim().is_empty() {
+        return Err(AppError::CommandError {
+            message: "The working tree has uncommitted changes".to_string(),
+        });
+    }
+
+    let previous_commit = git_stdout(&repo_path, &["rev-parse", "HEAD"])
+        .await?
+        .trim()
+        .to_string();
+    let restored_commit = git_stdout(
+        &repo_path,
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("{}^{{commit}}", before_commit),
+        ],
+    )
+    .await?
+    .trim()
+    .to_string();
+
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let branch_name = branch
+        .as_deref()
+        .and_then(|name| name.strip_prefix("refs/heads/"))
+        .unwrap_or("detached");
+    let backup_ref = format!("{}/{}/{}", BACKUP_REF_PREFIX, branch_name, seconds);
+    // 空の旧値を渡し、既存の ref を上書きしない。
+    git_stdout(
+        &repo_path,
+        &[
+            "update-ref",
+            "-m",
+            "gui-git-editor: backup before restoring a rebase",
+            &backup_ref,
+            &previous_commit,
+            "",
+        ],
+    )
+    .await?;
+    git_stdout(&repo_path, &["reset", "--hard", &restored_commit]).await?;
+
+    Ok(RestoredRebase {
+        backup_ref,
+        previous_commit,
+        restored_commit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase_reflog::RebaseSessionStatus;
+    use std::fs as std_fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-recovery-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-b", "main"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, content: &str) {
+        std_fs::write(repo.join(name), content).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", name]);
+    }
+
+    /// topic を main の上に rebase したリポジトリと、rebase 前の topic を返す。
+    fn create_rebased_repo() -> (std::path::PathBuf, String) {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n");
+        run_git(&repo, &["checkout", "-b", "topic"]);
+        commit_file(&repo, "topic.txt", "topic\n");
+        let before = run_git(&repo, &["rev-parse", "HEAD"]);
+        run_git(&repo, &["checkout", "main"]);
+        commit_file(&repo, "main.txt", "one\ntwo\n");
+        run_git(&repo, &["rebase", "main", "topic"]);
+        (repo, before)
+    }
+
+    #[test]
+    fn test_list_rebase_sessions_reports_before_commit_and_diff() {
+        let (repo, before) = create_rebased_repo();
+        let repo_path = repo.to_string_lossy().to_string();
+
+        let sessions =
+            tauri::async_runtime::block_on(list_rebase_sessions(repo_path, None)).unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.status, RebaseSessionStatus::Finished);
+        assert_eq!(session.before_commit, before);
+        assert_eq!(session.head_name.as_deref(), Some("refs/heads/topic"));
+        let diff = session.diff.as_ref().unwrap();
+        assert_eq!(diff.files_changed, 1);
+        assert_eq!(diff.insertions, 2);
+    }
+
+    #[test]
+    fn test_restore_rebase_session_saves_backup_ref() {
+        let (repo, before) = create_rebased_repo();
+        let rebased = run_git(&repo, &["rev-parse", "HEAD"]);
+        let repo_path = repo.to_string_lossy().to_string();
+
+        let restored = tauri::async_runtime::block_on(restore_rebase_session(
+            repo_path,
+            before.clone(),
+            Some("refs/heads/topic".to_string()),
+        ))
+        .unwrap();
+        let head = run_git(&repo, &["rev-parse", "HEAD"]);
+        let backup = run_git(&repo, &["rev-parse", &restored.backup_ref]);
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(restored
+            .backup_ref
+            .starts_with("refs/gui-git-editor/backup/topic/"));
+        assert_eq!(restored.previous_commit, rebased);
+        assert_eq!(head, before);
+        assert_eq!(backup, rebased);
+    }
+
+    #[test]
+    fn test_restore_rebase_session_rejects_unsafe_states() {
+        let (repo, before) = create_rebased_repo();
+        let repo_path = repo.to_string_lossy().to_string();
+        let restore = |head_name: Option<&str>| {
+            tauri::async_runtime::block_on(restore_rebase_session(
+                repo_path.clone(),
+                before.clone(),
+                head_name.map(str::to_string),
+            ))
+        };
+
+        let unknown_branch = restore(None);
+        let other_branch = restore(Some("refs/heads/main"));
+        run_git(&repo, &["checkout", "--detach"]);
+        let detached = restore(None);
+        run_git(&repo, &["checkout", "topic"]);
+        std_fs::write(repo.join("base.txt"), "changed\n").unwrap();
+        let dirty = restore(Some("refs/heads/topic"));
+        let head = run_git(&repo, &["rev-parse", "HEAD"]);
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(unknown_branch.is_err());
+        assert!(other_branch.is_err());
+        assert!(detached.is_err());
+        assert!(dirty.is_err());
+        assert_ne!(head, before);
+    }
+}
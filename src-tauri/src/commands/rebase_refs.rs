@@ -0,0 +1,241 @@
+use std::path::Path;
+
+use tokio::fs;
+use tokio::process::Command;
+
+use super::rebase::parent_dir;
+use super::staging::resolve_git_root;
+use crate::error::AppError;
+use crate::parser::rebase_update_refs::{self, StackBranch};
+use crate::parser::RebaseTodoFile;
+
+/// git コマンドを実行し、失敗時は stderr を含むエラーにする。
+pub(crate) async fn git_stdout(dir: &str, args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new("git")
+        .args(["-C", dir])
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git {}: {}", args[0], e),
+        })?;
+    if !output.status.success() {
+        return Err(AppError::CommandError {
+            message: format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 現在のワークツリー以外でチェックアウトされているブランチを (パス, ref) で返す。
+async fn other_worktree_branches(git_root: &str) -> Result<Vec<(String, String)>, AppError> {
+    let output = git_stdout(git_root, &["worktree", "list", "--porcelain"]).await?;
+    Ok(rebase_update_refs::parse_worktree_branches(&output)
+        .into_iter()
+        .filter(|(path, _)| Path::new(path) != Path::new(git_root))
+        .collect())
+}
+
+/// rebase 中のブランチ（todo と同じディレクトリの `head-name`）。detached HEAD なら None。
+async fn read_head_name(todo_dir: &str) -> Option<String> {
+    let content = fs::read_to_string(Path::new(todo_dir).join("head-name"))
+        .await
+        .ok()?;
+    let head_name = content.trim();
+    head_name
+        .starts_with("refs/")
+        .then(|| head_name.to_string())
+}
+
+/// todo のコミットを指すローカルブランチと、rebase 後に移動するブランチを返す。
+///
+/// `update-ref` 行があるブランチと rebase 中のブランチが移動する。別のワークツリーで
+/// チェックアウトされているブランチは Git が更新しないため、そのパスも返す。
+#[tauri::command]
+pub async fn list_stack_branches(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<Vec<StackBranch>, AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+    let git_root = resolve_git_root(&file_path).await?;
+
+    let refs = git_stdout(
+        &git_root,
+        &[
+            "for-each-ref",
+            "--format=%(refname) %(objectname)",
+            "refs/heads",
+        ],
+    )
+    .await?;
+    let branches: Vec<(String, String)> = refs
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(ref_name, commit)| (ref_name.to_string(), commit.to_string()))
+        .collect();
+    let checked_out = other_worktree_branches(&git_root).await?;
+    let head_name = read_head_name(&todo_dir).await;
+
+    Ok(rebase_update_refs::plan_stack_branches(
+        &file,
+        &branches,
+        &checked_out,
+        head_name.as_deref(),
+    ))
+}
+
+/// `entry_id` のコミットの後ろに `update-ref <ref_name>` を追加する。
+///
+/// 別のワークツリーでチェックアウトされているブランチと rebase 中のブランチは、
+/// Git が update-ref で更新できないためエラーにする。
+#[tauri::command]
+pub async fn add_update_ref(
+    file_path: String,
+    file: RebaseTodoFile,
+    entry_id: String,
+    ref_name: String,
+) -> Result<RebaseTodoFile, AppError> {
+    let todo_dir = parent_dir(&file_path)?;
+    let git_root = resolve_git_root(&file_path).await?;
+
+    if read_head_name(&todo_dir).await.as_deref() == Some(ref_name.as_str()) {
+        return Err(AppError::CommandError {
+            message: format!("{} is the branch being rebased", ref_name),
+        });
+    }
+    if let Some((path, _)) = other_worktree_branches(&git_root)
+        .await?
+        .into_iter()
+        .find(|(_, checked_out)| *checked_out == ref_name)
+    {
+        return Err(AppError::CommandError {
+            message: format!("{} is checked out at '{}'", ref_name, path),
+        });
+    }
+
+    rebase_update_refs::add_update_ref(&file, &entry_id, &ref_name)
+}
+
+/// `ref_name` の update-ref 行を取り除く。
+#[tauri::command]
+pub fn remove_update_ref(file: RebaseTodoFile, ref_name: String) -> RebaseTodoFile {
+    rebase_update_refs::remove_update_ref(&file, &ref_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-refs-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, message: &str) -> String {
+        std_fs::write(repo.join(name), message).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", message]);
+        run_git(repo, &["rev-parse", "--short", "HEAD"])
+    }
+
+    #[test]
+    fn test_stack_branches_and_checked_out_worktree() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "Base");
+        let part1 = commit_file(&repo, "a.txt", "Part 1");
+        run_git(&repo, &["branch", "part1"]);
+        let part2 = commit_file(&repo, "b.txt", "Part 2");
+        let worktree = repo.with_extension("part2");
+        run_git(
+            &repo,
+            &["worktree", "add", "-b", "part2", worktree.to_str().unwrap()],
+        );
+
+        let rebase_merge = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_merge).unwrap();
+        std_fs::write(rebase_merge.join("head-name"), "refs/heads/topic\n").unwrap();
+        let todo_path = rebase_merge.join("git-rebase-todo");
+        let file =
+            parse_rebase_todo(&format!("pick {} Part 1\npick {} Part 2\n", part1, part2)).unwrap();
+        let first = file.entries[0].id.clone();
+        let second = file.entries[1].id.clone();
+        let todo_path = todo_path.to_string_lossy().to_string();
+
+        let branches =
+            tauri::async_runtime::block_on(list_stack_branches(todo_path.clone(), file.clone()))
+                .unwrap();
+        let added = tauri::async_runtime::block_on(add_update_ref(
+            todo_path.clone(),
+            file.clone(),
+            first.clone(),
+            "refs/heads/part1".to_string(),
+        ));
+        let checked_out = tauri::async_runtime::block_on(add_update_ref(
+            todo_path.clone(),
+            file.clone(),
+            second,
+            "refs/heads/part2".to_string(),
+        ));
+        let rebased = tauri::async_runtime::block_on(add_update_ref(
+            todo_path,
+            file,
+            first.clone(),
+            "refs/heads/topic".to_string(),
+        ));
+
+        let _ = std_fs::remove_dir_all(&worktree);
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let part1_branch = branches
+            .iter()
+            .find(|b| b.ref_name == "refs/heads/part1")
+            .unwrap();
+        assert_eq!(part1_branch.entry_id.as_deref(), Some(first.as_str()));
+        assert_eq!(part1_branch.update_ref_entry_id, None);
+        let part2_branch = branches
+            .iter()
+            .find(|b| b.ref_name == "refs/heads/part2")
+            .unwrap();
+        assert!(part2_branch.checked_out_at.is_some());
+
+        assert!(matches!(
+            &added.unwrap().entries[1].command,
+            crate::parser::rebase::RebaseCommand::UpdateRef(r) if r == "refs/heads/part1"
+        ));
+        assert!(checked_out.is_err());
+        assert!(rebased.is_err());
+    }
+}
@@ -0,0 +1,591 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::process::Command;
+
+use super::rebase::{parent_dir, read_git_version};
+use crate::error::AppError;
+use crate::parser::rebase::{GitVersion, RebaseCommand};
+use crate::parser::rebase_lint::format_version;
+use crate::parser::RebaseTodoFile;
+
+/// `reset [new root]` の引数。todo の解析では空白で分割されるため先頭の語だけが残る。
+const NEW_ROOT_TARGETS: &[&str] = &["[new", "[new root]"];
+
+/// `git merge-tree --write-tree` が追加された Git のバージョン。
+const MERGE_TREE_WRITE_TREE_VERSION: GitVersion = (2, 38, 0);
+
+/// `installed` の Git が `merge-tree --write-tree` に対応しなければエラーを返す。
+/// バージョンが分からない場合は実行して確かめるため、エラーにしない。
+fn check_merge_tree_support(installed: Option<GitVersion>) -> Result<(), AppError> {
+    match installed {
+        Some(installed) if installed < MERGE_TREE_WRITE_TREE_VERSION => {
+            Err(AppError::CommandError {
+                message: format!(
+                    "Predicting conflicts requires git {} or later, but git {} is installed",
+                    format_version(MERGE_TREE_WRITE_TREE_VERSION),
+                    format_version(installed)
+                ),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// インストールされている Git で `Simulator` のマージを使えるかを確かめる。
+pub(crate) async fn ensure_merge_tree_supported() -> Result<(), AppError> {
+    check_merge_tree_support(read_git_version().await)
+}
+
+/// シミュレーション用の一時コミットに使う固定の作者情報。
+/// ユーザーの identity が未設定でも commit-tree が失敗しないようにする。
+const SIMULATION_IDENT: (&str, &str) = ("gui-git-editor", "gui-git-editor@localhost");
+
+/// 1 エントリを適用した結果の予測。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationStatus {
+    Clean,
+    Conflict,
+    /// 適用元が分からないなどの理由で予測できなかった。
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedStep {
+    pub entry_id: String,
+    pub status: SimulationStatus,
+    /// 競合が予測されるファイルのパス。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+    /// 先行するエントリの競合を、競合マーカーを含んだまま進めた状態から予測した結果か。
+    #[serde(default)]
+    pub after_conflict: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseSimulation {
+    pub onto: String,
+    /// コミットを適用するエントリ（pick 系と merge）ごとの予測結果。todo の順に並ぶ。
+    pub steps: Vec<SimulatedStep>,
+}
+
+/// `git merge-tree --write-tree --name-only -z` の出力を解析する。
+/// 先頭が結果のツリー、続く空フィールドまでが競合したパス（ステージごとの重複は除く）。
+pub fn parse_merge_tree_output(output: &[u8]) -> (String, Vec<String>) {
+    let mut fields = output.split(|byte| *byte == 0);
+    let tree = fields
+        .next()
+        .map(|field| String::from_utf8_lossy(field).trim().to_string())
+        .unwrap_or_default();
+
+    let mut conflicts: Vec<String> = Vec::new();
+    for field in fields {
+        if field.is_empty() {
+            break;
+        }
+        let path = String::from_utf8_lossy(field).to_string();
+        if !conflicts.contains(&path) {
+            conflicts.push(path);
+        }
+    }
+
+    (tree, conflicts)
+}
+
+/// 編集中の todo を `onto` から順に適用した場合の競合を予測する。
+///
+/// 各コミットは `git merge-tree --write-tree` で 3-way マージし、ツリーとオブジェクトだけを
+/// 作るため、作業ツリー・インデックス・ref は変更しない。`git replay` は ref 単位で
+/// 動作し、並べ替えや squash を含む任意の todo を表せないため使わない。
+#[tauri::command]
+pub async fn simulate_rebase(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<RebaseSimulation, AppError> {
+    // 古い Git ではすべてのエントリが理由なく Skipped になるため、先にエラーにする。
+    ensure_merge_tree_supported().await?;
+    let todo_dir = parent_dir(&file_path)?;
+    let onto_path = Path::new(&todo_dir).join("onto");
+    let onto = fs::read_to_string(&onto_path)
+        .await
+        .map_err(|e| AppError::from_io_with_path(onto_path.to_string_lossy(), e))?
+        .trim()
+        .to_string();
+
+    let git = Simulator::new(&todo_dir);
+    // 現在の HEAD に相当する一時コミット。merge の共通祖先を求められるよう、ツリーではなく
+    // 履歴を持つコミットで追跡する。reset 先が解決できない間は None とする。
+    let mut head = Some(git.commit_of(&onto).await?);
+    let mut labels: HashMap<String, Option<String>> = HashMap::new();
+    labels.insert("onto".to_string(), head.clone());
+    let mut after_conflict = false;
+    let mut steps = Vec::new();
+
+    for entry in &file.entries {
+        let applied = match &entry.command {
+            RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Squash
+            | RebaseCommand::Fixup => match &head {
+                Some(ours) => git.pick_onto(ours, &entry.commit_hash).await,
+                None => None,
+            },
+            // オクトパスマージは予測しない。
+            RebaseCommand::Merge { labels: merged, .. } => match (&head, merged.as_slice()) {
+                (Some(ours), [label]) => match labels.get(label).cloned().flatten() {
+                    Some(theirs) => git.merge(ours, &theirs).await,
+                    None => None,
+                },
+                _ => None,
+            },
+            RebaseCommand::Label(label) => {
+                labels.insert(label.clone(), head.clone());
+                continue;
+            }
+            RebaseCommand::Reset(target) => {
+                head = match labels.get(target) {
+                    Some(commit) => commit.clone(),
+                    None if NEW_ROOT_TARGETS.contains(&target.as_str()) => {
+                        git.empty_root().await.ok()
+                    }
+                    None => git.commit_of(target).await.ok(),
+                };
+                continue;
+            }
+            _ => continue,
+        };
+
+        let step = match applied {
+            Some((commit, conflicts)) => {
+                head = Some(commit);
+                let status = if conflicts.is_empty() {
+                    SimulationStatus::Clean
+                } else {
+                    SimulationStatus::Conflict
+                };
+                SimulatedStep {
+                    entry_id: entry.id.clone(),
+                    status,
+                    conflicts,
+                    after_conflict,
+                }
+            }
+            None => {
+                // 適用結果が分からないため、以降の予測の前提も失われる。
+                head = None;
+                SimulatedStep {
+                    entry_id: entry.id.clone(),
+                    status: SimulationStatus::Skipped,
+                    conflicts: Vec::new(),
+                    after_conflict,
+                }
+            }
+        };
+        after_conflict |= step.status == SimulationStatus::Conflict;
+        steps.push(step);
+    }
+
+    Ok(RebaseSimulation { onto, steps })
+}
+
+/// 一時コミットと merge-tree でマージ結果を求める。
+pub(crate) struct Simulator<'a> {
+    dir: &'a str,
+}
+
+impl<'a> Simulator<'a> {
+    pub(crate) fn new(dir: &'a str) -> Self {
+        Self { dir }
+    }
+
+    async fn run(&self, args: &[&str]) -> Result<std::process::Output, AppError> {
+        Command::new("git")
+            .arg("-C")
+            .arg(self.dir)
+            .args(args)
+            .env("GIT_AUTHOR_NAME", SIMULATION_IDENT.0)
+            .env("GIT_AUTHOR_EMAIL", SIMULATION_IDENT.1)
+            .env("GIT_COMMITTER_NAME", SIMULATION_IDENT.0)
+            .env("GIT_COMMITTER_EMAIL", SIMULATION_IDENT.1)
+            // `mktree` は標準入力を読むため、端末から起動された場合に待ち続けないようにする。
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| AppError::CommandError {
+                message: format!("Failed to run git {}: {}", args[0], e),
+            })
+    }
+
+    pub(crate) async fn stdout(&self, args: &[&str]) -> Result<String, AppError> {
+        let output = self.run(args).await?;
+        if !output.status.success() {
+            return Err(AppError::CommandError {
+                message: format!(
+                    "git {} failed: {}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    async fn commit_of(&self, rev: &str) -> Result<String, AppError> {
+        self.stdout(&["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+            .await
+    }
+
+    pub(crate) async fn tree_of(&self, rev: &str) -> Result<String, AppError> {
+        self.stdout(&["rev-parse", "--verify", &format!("{}^{{tree}}", rev)])
+            .await
+    }
+
+    /// 親のツリー。ルートコミットなら空ツリーを返す。
+    async fn parent_tree_of(&self, commit: &str) -> Result<String, AppError> {
+        match self.tree_of(&format!("{}^", commit)).await {
+            Ok(tree) => Ok(tree),
+            Err(_) => {
+                self.tree_of(commit).await?;
+                self.stdout(&["mktree"]).await
+            }
+        }
+    }
+
+    async fn commit_tree(&self, tree: &str, parents: &[&str]) -> Result<String, AppError> {
+        let mut args = vec!["commit-tree", tree, "-m", "simulate"];
+        for parent in parents {
+            args.extend(["-p", parent]);
+        }
+        self.stdout(&args).await
+    }
+
+    /// `reset [new root]` 後の HEAD。親を持たない空ツリーの一時コミット。
+    async fn empty_root(&self) -> Result<String, AppError> {
+        let tree = self.stdout(&["mktree"]).await?;
+        self.commit_tree(&tree, &[]).await
+    }
+
+    /// base を共通祖先に持つ 2 つの一時コミットを作り、merge-tree で 3-way マージする。
+    /// Git 2.40 未満では `--merge-base` を指定できないため、履歴側で共通祖先を固定する。
+    async fn merge_trees(
+        &self,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<(String, Vec<String>), AppError> {
+        let base_commit = self.commit_tree(base, &[]).await?;
+        let ours_commit = self.commit_tree(ours, &[&base_commit]).await?;
+        let theirs_commit = self.commit_tree(theirs, &[&base_commit]).await?;
+
+        let output = self
+            .run(&[
+                "merge-tree",
+                "--write-tree",
+                "--name-only",
+                "--no-messages",
+                "-z",
+                &ours_commit,
+                &theirs_commit,
+            ])
+            .await?;
+
+        // 終了コード 0 は競合なし、1 は競合あり。それ以外は実行エラー。
+        match output.status.code() {
+            Some(0) | Some(1) => Ok(parse_merge_tree_output(&output.stdout)),
+            _ => Err(AppError::CommandError {
+                message: format!(
+                    "git merge-tree failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            }),
+        }
+    }
+
+    /// コミットの変更を現在のツリーへ cherry-pick した結果。コミットが解決できなければ None。
+    pub(crate) async fn cherry_pick(
+        &self,
+        head: &str,
+        commit: &str,
+    ) -> Option<(String, Vec<String>)> {
+        let theirs = self.tree_of(commit).await.ok()?;
+        let base = self.parent_tree_of(commit).await.ok()?;
+        self.merge_trees(&base, head, &theirs).await.ok()
+    }
+
+    /// コミットを一時コミット `head` の上へ cherry-pick し、結果の一時コミットを返す。
+    async fn pick_onto(&self, head: &str, commit: &str) -> Option<(String, Vec<String>)> {
+        let head_tree = self.tree_of(head).await.ok()?;
+        let (tree, conflicts) = self.cherry_pick(&head_tree, commit).await?;
+        let commit = self.commit_tree(&tree, &[head]).await.ok()?;
+        Some((commit, conflicts))
+    }
+
+    /// merge コマンドの結果の一時コミット。Git と同じく、`-C` / `-c` の有無にかかわらず
+    /// 書き換え後の HEAD と相手の共通祖先を基準にする（元のマージコミットはメッセージにしか
+    /// 使われない）。共通祖先がない場合は空ツリーを基準にする。
+    async fn merge(&self, head: &str, theirs: &str) -> Option<(String, Vec<String>)> {
+        let base = match self.stdout(&["merge-base", head, theirs]).await.ok() {
+            Some(base_commit) => self.tree_of(&base_commit).await.ok()?,
+            None => self.stdout(&["mktree"]).await.ok()?,
+        };
+        let head_tree = self.tree_of(head).await.ok()?;
+        let theirs_tree = self.tree_of(theirs).await.ok()?;
+        let (tree, conflicts) = self
+            .merge_trees(&base, &head_tree, &theirs_tree)
+            .await
+            .ok()?;
+        let commit = self.commit_tree(&tree, &[head, theirs]).await.ok()?;
+        Some((commit, conflicts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-simulate-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, content: &str, message: &str) -> String {
+        std_fs::write(repo.join(name), content).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", message]);
+        run_git(repo, &["rev-parse", "HEAD"])
+    }
+
+    /// `.git/rebase-merge/` に onto と todo を置き、todo のパスを返す。
+    fn write_rebase_state(repo: &Path, onto: &str, todo: &str) -> String {
+        let rebase_dir = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(rebase_dir.join("onto"), format!("{}\n", onto)).unwrap();
+        let todo_path = rebase_dir.join("git-rebase-todo");
+        std_fs::write(&todo_path, todo).unwrap();
+        todo_path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_check_merge_tree_support_names_required_version() {
+        let result = check_merge_tree_support(Some((2, 37, 1)));
+        assert!(matches!(
+            result,
+            Err(AppError::CommandError { message })
+                if message == "Predicting conflicts requires git 2.38.0 or later, but git 2.37.1 is installed"
+        ));
+        assert!(check_merge_tree_support(Some((2, 38, 0))).is_ok());
+        assert!(check_merge_tree_support(None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_merge_tree_output_dedupes_paths() {
+        let output = b"abc123\0a.txt\0a.txt\0b.txt\0\0";
+        let (tree, conflicts) = parse_merge_tree_output(output);
+        assert_eq!(tree, "abc123");
+        assert_eq!(conflicts, vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_parse_merge_tree_output_clean() {
+        let (tree, conflicts) = parse_merge_tree_output(b"abc123\0");
+        assert_eq!(tree, "abc123");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_simulate_rebase_predicts_reorder_conflict() {
+        let repo = create_test_repo();
+        let base = commit_file(&repo, "a.txt", "one\n", "base");
+        let first = commit_file(&repo, "a.txt", "two\n", "first");
+        let second = commit_file(&repo, "a.txt", "three\n", "second");
+        let other = commit_file(&repo, "b.txt", "other\n", "other");
+        let head = run_git(&repo, &["rev-parse", "HEAD"]);
+
+        // second を first より前に移すと、second は "two" を前提にしているため競合する。
+        let todo = format!(
+            "pick {} second\npick {} first\npick {} other\n",
+            second, first, other
+        );
+        let todo_path = write_rebase_state(&repo, &base, &todo);
+
+        let simulation = tauri::async_runtime::block_on(simulate_rebase(
+            todo_path,
+            parse_rebase_todo(&todo).unwrap(),
+        ))
+        .unwrap();
+
+        let head_after = run_git(&repo, &["rev-parse", "HEAD"]);
+        let status = run_git(&repo, &["status", "--porcelain"]);
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(simulation.onto, base);
+        assert_eq!(simulation.steps.len(), 3);
+        assert_eq!(simulation.steps[0].status, SimulationStatus::Conflict);
+        assert_eq!(simulation.steps[0].conflicts, vec!["a.txt"]);
+        assert!(!simulation.steps[0].after_conflict);
+        assert_eq!(simulation.steps[2].status, SimulationStatus::Clean);
+        assert!(simulation.steps[2].after_conflict);
+        // ref と作業ツリーには触れない。
+        assert_eq!(head_after, head);
+        assert_eq!(status, "");
+    }
+
+    #[test]
+    fn test_simulate_rebase_original_order_is_clean() {
+        let repo = create_test_repo();
+        let base = commit_file(&repo, "a.txt", "one\n", "base");
+        let first = commit_file(&repo, "a.txt", "two\n", "first");
+        let second = commit_file(&repo, "a.txt", "three\n", "second");
+
+        let todo = format!("pick {} first\nexec true\nfixup {} second\n", first, second);
+        let todo_path = write_rebase_state(&repo, &base, &todo);
+
+        let simulation = tauri::async_runtime::block_on(simulate_rebase(
+            todo_path,
+            parse_rebase_todo(&todo).unwrap(),
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(simulation.steps.len(), 2);
+        assert!(simulation
+            .steps
+            .iter()
+            .all(|step| step.status == SimulationStatus::Clean));
+    }
+
+    #[test]
+    fn test_simulate_rebase_from_new_root_with_merge_without_original() {
+        let repo = create_test_repo();
+        let base = commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n", "base");
+        run_git(&repo, &["checkout", "-b", "side"]);
+        let side = commit_file(&repo, "a.txt", "one\n2\n3\n4\n5\n", "side");
+        run_git(&repo, &["checkout", "-"]);
+        let main = commit_file(&repo, "a.txt", "1\n2\n3\n4\nfive\n", "main");
+
+        // `git rebase -i --root --rebase-merges` の形。merge は -C なしで新しいマージを作る。
+        let todo = format!(
+            "label onto\nreset [new root]\npick {} base\nlabel branch-point\n\
+             pick {} side\nlabel side\nreset branch-point\npick {} main\nmerge side\n",
+            base, side, main
+        );
+        let todo_path = write_rebase_state(&repo, &base, &todo);
+
+        let simulation = tauri::async_runtime::block_on(simulate_rebase(
+            todo_path,
+            parse_rebase_todo(&todo).unwrap(),
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        // 共通祖先を base として求められれば、別々の行の変更は競合しない。
+        assert_eq!(simulation.steps.len(), 4);
+        assert!(simulation
+            .steps
+            .iter()
+            .all(|step| step.status == SimulationStatus::Clean));
+    }
+
+    #[test]
+    fn test_simulate_rebase_merge_with_original_uses_rewritten_merge_base() {
+        let repo = create_test_repo();
+        let base = commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n", "base");
+        let main = commit_file(&repo, "a.txt", "1\ntwo\n3\n4\n5\n", "main");
+        let fix = commit_file(&repo, "a.txt", "1\nTWO\n3\n4\n5\n", "fix");
+        run_git(&repo, &["checkout", "-b", "side", &base]);
+        let side = commit_file(&repo, "a.txt", "1\n2\n3\nfour\n5\n", "side");
+        let original = run_git(
+            &repo,
+            &[
+                "commit-tree",
+                &format!("{}^{{tree}}", side),
+                "-p",
+                &main,
+                "-p",
+                &side,
+                "-m",
+                "Merge side",
+            ],
+        );
+        run_git(&repo, &["checkout", "-b", "upstream", &base]);
+        let onto = commit_file(&repo, "b.txt", "upstream\n", "upstream");
+
+        // side を main の上へ積み直したため、書き換え後の共通祖先は main になる。
+        // 元のマージの親同士の共通祖先（base）を使うと、2 行目が両側の変更に見えて競合する。
+        let todo = format!(
+            "label onto\nreset onto\npick {} main\nlabel main\n\
+             pick {} side\npick {} fix\nlabel side\nreset main\nmerge -C {} side\n",
+            main, side, fix, original
+        );
+        let todo_path = write_rebase_state(&repo, &onto, &todo);
+
+        let simulation = tauri::async_runtime::block_on(simulate_rebase(
+            todo_path,
+            parse_rebase_todo(&todo).unwrap(),
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(simulation.steps.len(), 4);
+        assert!(simulation
+            .steps
+            .iter()
+            .all(|step| step.status == SimulationStatus::Clean));
+    }
+
+    #[test]
+    fn test_simulate_rebase_skips_unknown_commit() {
+        let repo = create_test_repo();
+        let base = commit_file(&repo, "a.txt", "one\n", "base");
+
+        let todo = "pick deadbeef missing\n";
+        let todo_path = write_rebase_state(&repo, &base, todo);
+
+        let simulation = tauri::async_runtime::block_on(simulate_rebase(
+            todo_path,
+            parse_rebase_todo(todo).unwrap(),
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(simulation.steps[0].status, SimulationStatus::Skipped);
+    }
+}
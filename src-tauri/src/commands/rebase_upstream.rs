@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::rebase::parent_dir;
+use super::rebase_simulate::{ensure_merge_tree_supported, Simulator};
+use crate::error::AppError;
+use crate::parser::rebase::RebaseCommand;
+use crate::parser::rebase_lint::hashes_match;
+use crate::parser::RebaseTodoFile;
+
+/// todo のコミットが onto 側でどう扱われるか。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamStatus {
+    /// 同じ patch-id のコミットが onto 側に取り込まれている。
+    AlreadyApplied,
+    /// patch-id は異なるが、onto に適用しても変更が残らない。
+    BecomesEmpty,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamMatch {
+    pub entry_id: String,
+    pub commit_hash: String,
+    pub status: UpstreamStatus,
+    /// patch-id が一致した onto 側のコミット。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream_commit: Option<String>,
+}
+
+/// `git patch-id --stable` の出力（`<patch-id> <commit>` の行）を (commit, patch-id) に変換する。
+pub fn parse_patch_ids(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let patch_id = parts.next()?;
+            let commit = parts.next()?;
+            Some((commit.to_string(), patch_id.to_string()))
+        })
+        .collect()
+}
+
+/// todo のコミットのうち、onto 側に取り込み済み、または適用すると空になるものを返す。
+///
+/// rebase-merge の `orig-head` と `onto` から merge-base を求め、その間の onto 側コミットと
+/// `git patch-id --stable` で比較する。UI ではこれらに drop を提案できる。
+#[tauri::command]
+pub async fn detect_upstream_commits(
+    file_path: String,
+    file: RebaseTodoFile,
+) -> Result<Vec<UpstreamMatch>, AppError> {
+    // linked worktree では rebase-merge が .git/worktrees/<name>/ の下にあるため、
+    // リポジトリのルートからではなく todo の置き場所から状態を読む。
+    let todo_dir = parent_dir(&file_path)?;
+    let rebase_merge = Path::new(&todo_dir);
+    if rebase_merge.file_name().and_then(|name| name.to_str()) != Some("rebase-merge") {
+        return Err(AppError::CommandError {
+            message: "No interactive rebase in progress".to_string(),
+        });
+    }
+
+    let onto = read_state_file(rebase_merge, "onto").await?;
+    let orig_head = read_state_file(rebase_merge, "orig-head").await?;
+
+    let git = Simulator::new(&todo_dir);
+    let merge_base = git.stdout(&["merge-base", &orig_head, &onto]).await?;
+    let upstream = patch_ids(
+        &todo_dir,
+        &["log", "--no-merges", &format!("{}..{}", merge_base, onto)],
+    )
+    .await?;
+    let upstream_by_patch: HashMap<&str, &str> = upstream
+        .iter()
+        .map(|(commit, patch_id)| (patch_id.as_str(), commit.as_str()))
+        .collect();
+
+    let picked: Vec<_> = file
+        .entries
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry.command,
+                RebaseCommand::Pick
+                    | RebaseCommand::Reword
+                    | RebaseCommand::Edit
+                    | RebaseCommand::Squash
+                    | RebaseCommand::Fixup
+            ) && !entry.commit_hash.is_empty()
+                && entry.commit_hash.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .collect();
+    if picked.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut log_args = vec!["log", "--no-walk=unsorted", "--ignore-missing"];
+    log_args.extend(picked.iter().map(|entry| entry.commit_hash.as_str()));
+    let todo_patch_ids = patch_ids(&todo_dir, &log_args).await?;
+
+    let onto_tree = git.tree_of(&onto).await?;
+    // 空になるかの判定は merge-tree を使うため、古い Git では patch-id による判定だけを行う。
+    let detects_empty = ensure_merge_tree_supported().await.is_ok();
+    let mut matches = Vec::new();
+    for entry in picked {
+        let patch_id = todo_patch_ids
+            .iter()
+            .find(|(commit, _)| hashes_match(commit, &entry.commit_hash))
+            .map(|(_, patch_id)| patch_id.as_str());
+
+        if let Some(upstream_commit) = patch_id.and_then(|id| upstream_by_patch.get(id)) {
+            matches.push(UpstreamMatch {
+                entry_id: entry.id.clone(),
+                commit_hash: entry.commit_hash.clone(),
+                status: UpstreamStatus::AlreadyApplied,
+                upstream_commit: Some(upstream_commit.to_string()),
+            });
+            continue;
+        }
+
+        // patch-id が出ない（差分のない）コミットや、内容が既に onto にあるコミットは空になる。
+        let becomes_empty = detects_empty
+            && match git.cherry_pick(&onto_tree, &entry.commit_hash).await {
+                Some((tree, conflicts)) => conflicts.is_empty() && tree == onto_tree,
+                None => false,
+            };
+        if becomes_empty {
+            matches.push(UpstreamMatch {
+                entry_id: entry.id.clone(),
+                commit_hash: entry.commit_hash.clone(),
+                status: UpstreamStatus::BecomesEmpty,
+                upstream_commit: None,
+            });
+        }
+    }
+
+    Ok(matches)
+}
+
+/// rebase-merge 配下の状態ファイルを 1 行読む。
+async fn read_state_file(rebase_merge: &Path, name: &str) -> Result<String, AppError> {
+    let path = rebase_merge.join(name);
+    fs::read_to_string(&path)
+        .await
+        .map(|content| content.trim().to_string())
+        .map_err(|e| AppError::from_io_with_path(path.to_string_lossy(), e))
+}
+
+/// `git log -p` の出力を `git patch-id --stable` に渡し、(commit, patch-id) を返す。
+pub(crate) async fn patch_ids(
+    dir: &str,
+    log_args: &[&str],
+) -> Result<Vec<(String, String)>, AppError> {
+    let log = Command::new("git")
+        .args(["-C", dir])
+        .args(log_args)
+        .args(["-p", "--no-color", "--no-ext-diff", "--format=commit %H"])
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git log: {}", e),
+        })?;
+    if !log.status.success() {
+        return Err(AppError::CommandError {
+            message: format!("git log failed: {}", String::from_utf8_lossy(&log.stderr)),
+        });
+    }
+
+    let mut child = Command::new("git")
+        .args(["-C", dir, "patch-id", "--stable"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git patch-id: {}", e),
+        })?;
+    // 書き込みを終えるまで出力を読まないと、patch-id の出力がパイプを埋めた時点で
+    // 双方が待ち続けるため、書き込みと読み取りを並行して進める。
+    // 書き終えたら stdin を閉じ、patch-id に入力の終わりを伝える。
+    let stdin = child.stdin.take();
+    let write_input = async move {
+        match stdin {
+            Some(mut stdin) => stdin.write_all(&log.stdout).await,
+            None => Ok(()),
+        }
+    };
+    let (written, output) = tokio::join!(write_input, child.wait_with_output());
+    let output = output.map_err(|e| AppError::CommandError {
+        message: format!("Failed to run git patch-id: {}", e),
+    })?;
+    if !output.status.success() {
+        return Err(AppError::CommandError {
+            message: format!(
+                "git patch-id failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        });
+    }
+    written?;
+
+    Ok(parse_patch_ids(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rebase_todo;
+    use std::fs as std_fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-rebase-upstream-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init", "-b", "main"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, content: &str, message: &str) -> String {
+        std_fs::write(repo.join(name), content).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", message]);
+        run_git(repo, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_parse_patch_ids() {
+        let output = "1111 aaaa\n2222 bbbb\n";
+        assert_eq!(
+            parse_patch_ids(output),
+            vec![
+                ("aaaa".to_string(), "1111".to_string()),
+                ("bbbb".to_string(), "2222".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_upstream_commits() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n", "base");
+        run_git(&repo, &["checkout", "-b", "feature"]);
+        let picked = commit_file(&repo, "a.txt", "a\n", "add a");
+        let partial = commit_file(&repo, "b.txt", "b\n", "add b");
+        let own = commit_file(&repo, "c.txt", "c\n", "add c");
+        run_git(&repo, &["checkout", "main"]);
+        // -x でメッセージを変え、元と同じコミットオブジェクトにならないようにする。
+        run_git(&repo, &["cherry-pick", "-x", &picked]);
+        let upstream = run_git(&repo, &["rev-parse", "HEAD"]);
+        // 同じ内容を別のコミットでまとめて入れると、patch-id は一致しないが空になる。
+        std_fs::write(repo.join("b.txt"), "b\n").unwrap();
+        run_git(&repo, &["add", "b.txt"]);
+        commit_file(&repo, "other.txt", "other\n", "add b and other");
+        let onto = run_git(&repo, &["rev-parse", "HEAD"]);
+
+        let todo = format!(
+            "pick {} add a\npick {} add b\npick {} add c\n",
+            picked, partial, own
+        );
+        let rebase_dir = repo.join(".git").join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(rebase_dir.join("onto"), format!("{}\n", onto)).unwrap();
+        std_fs::write(rebase_dir.join("orig-head"), format!("{}\n", own)).unwrap();
+        let todo_path = rebase_dir.join("git-rebase-todo");
+        std_fs::write(&todo_path, &todo).unwrap();
+        let file = parse_rebase_todo(&todo).unwrap();
+        let ids: Vec<String> = file.entries.iter().map(|entry| entry.id.clone()).collect();
+
+        let matches = tauri::async_runtime::block_on(detect_upstream_commits(
+            todo_path.to_string_lossy().to_string(),
+            file,
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].entry_id, ids[0]);
+        assert_eq!(matches[0].status, UpstreamStatus::AlreadyApplied);
+        assert_eq!(
+            matches[0].upstream_commit.as_deref(),
+            Some(upstream.as_str())
+        );
+        assert_eq!(matches[1].entry_id, ids[1]);
+        assert_eq!(matches[1].status, UpstreamStatus::BecomesEmpty);
+    }
+
+    #[test]
+    fn test_detect_upstream_commits_in_linked_worktree() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n", "base");
+        let worktree = repo.with_file_name(format!(
+            "{}-wt",
+            repo.file_name().unwrap().to_string_lossy()
+        ));
+        run_git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-b",
+                "feature",
+                &worktree.to_string_lossy(),
+            ],
+        );
+        let picked = commit_file(&worktree, "a.txt", "a\n", "add a");
+        run_git(&repo, &["cherry-pick", "-x", &picked]);
+        let onto = run_git(&repo, &["rev-parse", "HEAD"]);
+
+        let todo = format!("pick {} add a\n", picked);
+        let git_dir = run_git(&worktree, &["rev-parse", "--absolute-git-dir"]);
+        let rebase_dir = Path::new(&git_dir).join("rebase-merge");
+        std_fs::create_dir_all(&rebase_dir).unwrap();
+        std_fs::write(rebase_dir.join("onto"), format!("{}\n", onto)).unwrap();
+        std_fs::write(rebase_dir.join("orig-head"), format!("{}\n", picked)).unwrap();
+        let todo_path = rebase_dir.join("git-rebase-todo");
+        std_fs::write(&todo_path, &todo).unwrap();
+
+        let matches = tauri::async_runtime::block_on(detect_upstream_commits(
+            todo_path.to_string_lossy().to_string(),
+            parse_rebase_todo(&todo).unwrap(),
+        ));
+
+        let _ = std_fs::remove_dir_all(&worktree);
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let matches = matches.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].status, UpstreamStatus::AlreadyApplied);
+    }
+
+    #[test]
+    fn test_patch_ids_does_not_block_on_large_output() {
+        let repo = create_test_repo();
+        // patch-id の出力（1 コミット 82 バイト）がパイプの容量を超える数のコミットを作る。
+        let count = 1500;
+        let mut stream = String::new();
+        for i in 0..count {
+            let content = format!("{}\n", i);
+            stream.push_str(&format!(
+                "commit refs/heads/main\ncommitter T <t@example.com> {} +0000\n\
+                 data 1\nc\nM 644 inline f.txt\ndata {}\n{}\n",
+                i,
+                content.len(),
+                content
+            ));
+        }
+        let mut child = StdCommand::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["fast-import", "--quiet"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::io::Write::write_all(child.stdin.as_mut().unwrap(), stream.as_bytes()).unwrap();
+        drop(child.stdin.take());
+        assert!(child.wait().unwrap().success());
+
+        let ids =
+            tauri::async_runtime::block_on(patch_ids(&repo.to_string_lossy(), &["log", "main"]))
+                .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn test_detect_upstream_commits_requires_rebase_in_progress() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "base\n", "base");
+        let todo_path = repo.join("base.txt").to_string_lossy().to_string();
+
+        let result = tauri::async_runtime::block_on(detect_upstream_commits(
+            todo_path,
+            parse_rebase_todo("").unwrap(),
+        ));
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+}
@@ -0,0 +1,604 @@
+use std::io::{BufRead, BufReader as StdBufReader, Write};
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
+
+use crate::error::AppError;
+
+/// git からエディタとして起動されたことを示す引数。
+pub const EDITOR_BRIDGE_FLAG: &str = "--editor-bridge";
+
+/// エディタとして起動されたプロセスに、起動中のアプリの待ち受けアドレスとトークンを渡す環境変数。
+const EDITOR_BRIDGE_ENV: &str = "GUI_GIT_EDITOR_BRIDGE";
+
+/// スタンドアロンの rebase を実行中か。同時に 1 つだけ実行する。
+static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// UI での編集を待っているエディタ要求への応答。
+static PENDING_EDITOR: Mutex<Option<oneshot::Sender<bool>>> = Mutex::new(None);
+
+/// 待っているエディタ要求を取り出す。
+fn take_pending_editor() -> Option<oneshot::Sender<bool>> {
+    PENDING_EDITOR
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+}
+
+/// スタンドアロンの rebase の実行中を表す。タスクが途中で止まった場合も含め、
+/// 破棄されたときに実行中の状態と待っているエディタ要求を片付ける。
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        take_pending_editor();
+        IS_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// スタンドアロンの rebase を実行中かを返す。実行中は git のエディタがこのアプリになっている。
+pub(crate) fn is_running() -> bool {
+    IS_RUNNING.load(Ordering::SeqCst)
+}
+
+/// rebase の起点として選べるコミット。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BaseCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub subject: String,
+    pub author_name: String,
+    /// ISO 8601 形式の author date。
+    pub author_date: String,
+}
+
+/// スタンドアロンで開始した rebase から UI へ送るイベント。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StandaloneRebaseEvent {
+    /// git がエディタでファイルを開こうとしている。編集後に `finish_editor_request` で応答する。
+    EditorRequest { path: String },
+    /// git の標準出力・標準エラーの 1 行。
+    Output { line: String },
+    /// `git rebase` が終了した。edit や競合で停止した場合も含む。
+    Finished { success: bool, code: Option<i32> },
+}
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+const BASE_LOG_FORMAT: &str = "%H%x1f%h%x1f%s%x1f%an%x1f%aI";
+
+/// `BASE_LOG_FORMAT` を指定した `git log` の出力を解析する。
+pub fn parse_base_commits(output: &str) -> Vec<BaseCommit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(5, FIELD_SEPARATOR).collect();
+            let [hash, short_hash, subject, author_name, author_date] = fields[..] else {
+                return None;
+            };
+            Some(BaseCommit {
+                hash: hash.to_string(),
+                short_hash: short_hash.to_string(),
+                subject: subject.to_string(),
+                author_name: author_name.to_string(),
+                author_date: author_date.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// rebase の起点を選ぶため、HEAD から `limit` 件（既定 100 件）のコミットを新しい順に返す。
+#[tauri::command]
+pub async fn list_base_commits(
+    repo_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<BaseCommit>, AppError> {
+    let output = Command::new("git")
+        .args(["-C", &repo_path, "log", "--first-parent"])
+        .arg(format!("--max-count={}", limit.unwrap_or(100)))
+        .arg(format!("--format={}", BASE_LOG_FORMAT))
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git log: {}", e),
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::CommandError {
+            message: format!(
+                "git log failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        });
+    }
+
+    Ok(parse_base_commits(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// シェルのコマンドラインに埋め込めるよう単一引用符で囲む。
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// エディタ要求の 1 行（`<token>\t<path>`）を解釈する。トークンが一致しなければ None。
+fn parse_editor_request<'a>(line: &'a str, token: &str) -> Option<&'a str> {
+    let (request_token, path) = line.trim_end_matches(['\n', '\r']).split_once('\t')?;
+    (request_token == token && !path.is_empty()).then_some(path)
+}
+
+/// エディタとして起動されたプロセスからの接続を 1 件処理する。
+/// UI で保存されるまで待ち、終了コード（0 で保存、1 でキャンセル）を返す。
+///
+/// 応答を待つ間に接続が閉じた場合や git が終了した場合は、待つのをやめる。
+/// git が終了していればその終了状態を返す。
+async fn serve_editor_request(
+    stream: TcpStream,
+    token: &str,
+    child: &mut Child,
+    send: &(dyn Fn(StandaloneRebaseEvent) + Send + Sync),
+) -> Option<std::io::Result<ExitStatus>> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    let accepted = match reader.read_line(&mut line).await {
+        Ok(_) => match parse_editor_request(&line, token) {
+            Some(path) => {
+                let (tx, rx) = oneshot::channel();
+                if let Ok(mut pending) = PENDING_EDITOR.lock() {
+                    *pending = Some(tx);
+                }
+                send(StandaloneRebaseEvent::EditorRequest {
+                    path: path.to_string(),
+                });
+                // エディタ側は応答を待つ間に何も送らないため、読み取りが終わるのは接続が閉じたとき。
+                let mut rest = Vec::new();
+                tokio::select! {
+                    accepted = rx => accepted.unwrap_or(false),
+                    _ = reader.read_to_end(&mut rest) => {
+                        take_pending_editor();
+                        return None;
+                    }
+                    status = child.wait() => {
+                        take_pending_editor();
+                        return Some(status);
+                    }
+                }
+            }
+            None => false,
+        },
+        Err(_) => false,
+    };
+
+    let reply = if accepted { "0\n" } else { "1\n" };
+    if let Err(e) = writer.write_all(reply.as_bytes()).await {
+        log::warn!("Failed to reply to editor bridge: {}", e);
+    }
+    None
+}
+
+/// git の出力を 1 行ずつイベントとして送る。
+async fn forward_lines(
+    stream: impl AsyncRead + Unpin,
+    send: &(dyn Fn(StandaloneRebaseEvent) + Send + Sync),
+) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        send(StandaloneRebaseEvent::Output { line });
+    }
+}
+
+/// rebase の起点をコミットハッシュに解決する。
+/// `git rebase` のオプションとして解釈されないよう、`-` で始まる値は受け付けない。
+async fn resolve_base(repo_path: &str, base: &str) -> Result<String, AppError> {
+    let invalid = || AppError::CommandError {
+        message: format!("Invalid base commit: {}", base),
+    };
+    if base.is_empty() || base.starts_with('-') {
+        return Err(invalid());
+    }
+    let output = Command::new("git")
+        .args(["-C", repo_path, "rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", base))
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| AppError::CommandError {
+            message: format!("Failed to run git rev-parse: {}", e),
+        })?;
+    if !output.status.success() {
+        return Err(invalid());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `git rebase -i <base>` を起動し、終了するまでエディタ要求と出力を `send` へ送るタスクを返す。
+///
+/// `editor` は `GIT_SEQUENCE_EDITOR` と `GIT_EDITOR` に設定するコマンド。起動中のプロセスが
+/// 待ち受けるアドレスとトークンを環境変数で受け取り、編集対象のパスを送り返す。
+pub(crate) async fn spawn_standalone_rebase(
+    repo_path: &str,
+    base: &str,
+    editor: &str,
+    send: Arc<dyn Fn(StandaloneRebaseEvent) + Send + Sync>,
+) -> Result<tauri::async_runtime::JoinHandle<()>, AppError> {
+    let base = resolve_base(repo_path, base).await?;
+    if IS_RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err(AppError::CommandError {
+            message: "A rebase is already running".to_string(),
+        });
+    }
+    let running = RunningGuard;
+
+    let spawned = async {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let token = uuid::Uuid::new_v4().to_string();
+        let child = Command::new("git")
+            .args(["-C", repo_path, "rebase", "-i", &base])
+            .env("GIT_SEQUENCE_EDITOR", editor)
+            .env("GIT_EDITOR", editor)
+            .env(
+                EDITOR_BRIDGE_ENV,
+                format!("{} {}", listener.local_addr()?, token),
+            )
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        Ok::<_, std::io::Error>((listener, token, child))
+    }
+    .await;
+    let (listener, token, mut child) = spawned.map_err(|e| AppError::CommandError {
+        message: format!("Failed to start git rebase: {}", e),
+    })?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    Ok(tauri::async_runtime::spawn(async move {
+        let output = {
+            let send = send.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Some(stdout) = stdout {
+                    forward_lines(stdout, send.as_ref()).await;
+                }
+            })
+        };
+        let errors = {
+            let send = send.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Some(stderr) = stderr {
+                    forward_lines(stderr, send.as_ref()).await;
+                }
+            })
+        };
+
+        let status = loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        let exited =
+                            serve_editor_request(stream, &token, &mut child, send.as_ref()).await;
+                        if let Some(status) = exited {
+                            break status;
+                        }
+                    }
+                }
+                status = child.wait() => break status,
+            }
+        };
+        let _ = output.await;
+        let _ = errors.await;
+
+        // 終了を受け取った UI がすぐ次の rebase を始められるよう、先に実行中の状態を戻す。
+        drop(running);
+        send(StandaloneRebaseEvent::Finished {
+            success: status.as_ref().is_ok_and(|s| s.success()),
+            code: status.ok().and_then(|s| s.code()),
+        });
+    }))
+}
+
+/// リポジトリで `git rebase -i <base>` を開始する。
+///
+/// todo やコミットメッセージの編集は、このアプリ自身をエディタとして git に渡し、
+/// `on_event` の `editor_request` で UI へ引き渡す。git の出力と終了も `on_event` で送る。
+#[tauri::command]
+pub async fn start_standalone_rebase(
+    repo_path: String,
+    base: String,
+    on_event: Channel<StandaloneRebaseEvent>,
+) -> Result<(), AppError> {
+    let exe = std::env::current_exe().map_err(|e| AppError::CommandError {
+        message: format!("Failed to locate the application executable: {}", e),
+    })?;
+    let editor = format!(
+        "{} {}",
+        shell_quote(&exe.to_string_lossy()),
+        EDITOR_BRIDGE_FLAG
+    );
+    let send = move |event: StandaloneRebaseEvent| {
+        if let Err(e) = on_event.send(event) {
+            log::warn!("Failed to send rebase event: {}", e);
+        }
+    };
+
+    spawn_standalone_rebase(&repo_path, &base, &editor, Arc::new(send)).await?;
+    Ok(())
+}
+
+/// 編集を待っている git へ応答する。`accepted` が false なら、エディタが失敗したものとして
+/// git に中止させる。
+#[tauri::command]
+pub fn finish_editor_request(accepted: bool) -> Result<(), AppError> {
+    match take_pending_editor() {
+        Some(tx) => {
+            let _ = tx.send(accepted);
+            Ok(())
+        }
+        None => Err(AppError::CommandError {
+            message: "No editor request is waiting".to_string(),
+        }),
+    }
+}
+
+/// 起動中のアプリへ編集対象のパスを送り、応答（終了コード）を待つ。
+fn send_editor_request(bridge: &str, path: &Path) -> std::io::Result<i32> {
+    let (addr, token) = bridge.split_once(' ').ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "malformed bridge address")
+    })?;
+    let mut stream = std::net::TcpStream::connect(addr)?;
+    writeln!(stream, "{}\t{}", token, path.display())?;
+
+    let mut reply = String::new();
+    StdBufReader::new(stream).read_line(&mut reply)?;
+    Ok(if reply.trim() == "0" { 0 } else { 1 })
+}
+
+/// git からエディタ（`<exe> --editor-bridge <file>`）として起動された場合に、
+/// 起動中のアプリへ編集を依頼して終了コードを返す。通常の起動なら None。
+pub fn run_editor_bridge() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some(EDITOR_BRIDGE_FLAG) {
+        return None;
+    }
+
+    let Some(file) = args.last().filter(|_| args.len() > 1) else {
+        eprintln!("gui-git-editor: no file to edit");
+        return Some(1);
+    };
+    let Ok(bridge) = std::env::var(EDITOR_BRIDGE_ENV) else {
+        eprintln!("gui-git-editor: {} is not set", EDITOR_BRIDGE_ENV);
+        return Some(1);
+    };
+    // git は作業ツリーからの相対パスを渡すことがある。
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(file))
+        .unwrap_or_else(|_| file.into());
+
+    match send_editor_request(&bridge, &path) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            eprintln!("gui-git-editor: failed to reach the running app: {}", e);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs as std_fs;
+    use std::process::Command as StdCommand;
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-standalone-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        run_git(&repo, &["config", "user.email", "test@example.com"]);
+        run_git(&repo, &["config", "user.name", "Test User"]);
+        run_git(&repo, &["config", "commit.gpgsign", "false"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(repo: &Path, name: &str, message: &str) {
+        std_fs::write(repo.join(name), message).unwrap();
+        run_git(repo, &["add", name]);
+        run_git(repo, &["commit", "-m", message]);
+    }
+
+    #[test]
+    fn test_parse_base_commits() {
+        let output = "aaaa\u{1f}aa\u{1f}Add parser\u{1f}Test User\u{1f}2024-01-02T03:04:05+09:00\nbroken line\n";
+
+        assert_eq!(
+            parse_base_commits(output),
+            vec![BaseCommit {
+                hash: "aaaa".to_string(),
+                short_hash: "aa".to_string(),
+                subject: "Add parser".to_string(),
+                author_name: "Test User".to_string(),
+                author_date: "2024-01-02T03:04:05+09:00".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_editor_request_checks_token() {
+        assert_eq!(
+            parse_editor_request(
+                "secret\t/repo/.git/rebase-merge/git-rebase-todo\n",
+                "secret"
+            ),
+            Some("/repo/.git/rebase-merge/git-rebase-todo")
+        );
+        assert_eq!(parse_editor_request("other\t/tmp/x\n", "secret"), None);
+        assert_eq!(parse_editor_request("secret\t\n", "secret"), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/Apps/it's.app"), r"'/Apps/it'\''s.app'");
+    }
+
+    #[test]
+    fn test_resolve_base_rejects_options_and_unknown_revisions() {
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "Base");
+        let repo_path = repo.to_string_lossy().to_string();
+        let head = run_git(&repo, &["rev-parse", "HEAD"]);
+
+        let resolved = tauri::async_runtime::block_on(resolve_base(&repo_path, "HEAD"));
+        let option = tauri::async_runtime::block_on(resolve_base(&repo_path, "--exec=touch x"));
+        let unknown = tauri::async_runtime::block_on(resolve_base(&repo_path, "missing"));
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(resolved.unwrap(), head);
+        assert!(matches!(option, Err(AppError::CommandError { .. })));
+        assert!(matches!(unknown, Err(AppError::CommandError { .. })));
+    }
+
+    /// アプリの実行ファイルの代わりに、同じ手順で編集を依頼する bash スクリプトを使う。
+    const BRIDGE_SCRIPT: &str = r#"#!/bin/bash
+addr=${GUI_GIT_EDITOR_BRIDGE%% *}
+token=${GUI_GIT_EDITOR_BRIDGE#* }
+case "$1" in /*) file=$1 ;; *) file=$PWD/$1 ;; esac
+exec 3<>"/dev/tcp/${addr%:*}/${addr##*:}"
+printf '%s\t%s\n' "$token" "$file" >&3
+read -r code <&3
+exit "$code"
+"#;
+
+    /// 応答を待たずに終了するエディタ。ブリッジのプロセスが途中で落ちた場合に相当する。
+    const CRASHING_BRIDGE_SCRIPT: &str = r#"#!/bin/bash
+addr=${GUI_GIT_EDITOR_BRIDGE%% *}
+token=${GUI_GIT_EDITOR_BRIDGE#* }
+exec 3<>"/dev/tcp/${addr%:*}/${addr##*:}"
+printf '%s\t%s\n' "$token" "$1" >&3
+exit 1
+"#;
+
+    /// 実行中の rebase は 1 つに限られるため、rebase を起動するテストは順に実行する。
+    static REBASE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_standalone_rebase_round_trips_todo_through_editor_request() {
+        let _lock = REBASE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "Base");
+        commit_file(&repo, "a.txt", "Drop me");
+        commit_file(&repo, "b.txt", "Keep me");
+        let script = repo.with_extension("editor.sh");
+        std_fs::write(&script, BRIDGE_SCRIPT).unwrap();
+
+        let events: Arc<Mutex<Vec<StandaloneRebaseEvent>>> = Arc::default();
+        let send = {
+            let events = events.clone();
+            move |event: StandaloneRebaseEvent| {
+                if let StandaloneRebaseEvent::EditorRequest { path } = &event {
+                    let todo = std_fs::read_to_string(path).unwrap();
+                    std_fs::write(path, todo.replacen("pick", "drop", 1)).unwrap();
+                    finish_editor_request(true).unwrap();
+                }
+                events.lock().unwrap().push(event);
+            }
+        };
+
+        let repo_path = repo.to_string_lossy().to_string();
+        let editor = format!("bash {}", shell_quote(&script.to_string_lossy()));
+        tauri::async_runtime::block_on(async {
+            spawn_standalone_rebase(&repo_path, "HEAD~2", &editor, Arc::new(send))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        let subjects = run_git(&repo, &["log", "--format=%s"]);
+
+        let _ = std_fs::remove_file(&script);
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            &events[0],
+            StandaloneRebaseEvent::EditorRequest { path } if path.ends_with("git-rebase-todo")
+        ));
+        assert_eq!(
+            events.last(),
+            Some(&StandaloneRebaseEvent::Finished {
+                success: true,
+                code: Some(0)
+            })
+        );
+        assert_eq!(subjects, "Keep me\nBase");
+    }
+
+    #[test]
+    fn test_standalone_rebase_finishes_when_editor_exits_without_answer() {
+        let _lock = REBASE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let repo = create_test_repo();
+        commit_file(&repo, "base.txt", "Base");
+        commit_file(&repo, "a.txt", "First");
+        let script = repo.with_extension("editor.sh");
+        std_fs::write(&script, CRASHING_BRIDGE_SCRIPT).unwrap();
+
+        // UI は応答しない。接続が閉じた時点で待つのをやめ、git の終了を受け取る。
+        let events: Arc<Mutex<Vec<StandaloneRebaseEvent>>> = Arc::default();
+        let send = {
+            let events = events.clone();
+            move |event: StandaloneRebaseEvent| events.lock().unwrap().push(event)
+        };
+
+        let repo_path = repo.to_string_lossy().to_string();
+        let editor = format!("bash {}", shell_quote(&script.to_string_lossy()));
+        tauri::async_runtime::block_on(async {
+            spawn_standalone_rebase(&repo_path, "HEAD~1", &editor, Arc::new(send))
+                .await
+                .unwrap()
+                .await
+                .unwrap();
+        });
+
+        let _ = std_fs::remove_file(&script);
+        let _ = std_fs::remove_dir_all(&repo);
+
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            events.last(),
+            Some(StandaloneRebaseEvent::Finished { success: false, .. })
+        ));
+        assert!(!is_running());
+        assert!(finish_editor_request(true).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! リポジトリの `trailer.*` 設定に従って trailer を編集する Tauri コマンド。
+
+use tokio::process::Command;
+
+use super::commit::read_commit_msg_format;
+use super::rebase::parent_dir;
+use crate::error::AppError;
+use crate::parser::trailer::{self, TrailerConfig, TrailerOperation};
+
+/// `git config --get-regexp` で `trailer.*` の設定を読む。未設定や git の失敗時は既定の設定。
+async fn read_trailer_config(dir: &str) -> TrailerConfig {
+    let Ok(output) = Command::new("git")
+        .args(["-C", dir, "config", "--get-regexp", r"^trailer\."])
+        .output()
+        .await
+    else {
+        return TrailerConfig::default();
+    };
+
+    if !output.status.success() {
+        return TrailerConfig::default();
+    }
+
+    TrailerConfig::from_config_lines(String::from_utf8_lossy(&output.stdout).lines())
+}
+
+/// コミットメッセージの trailer ブロックに追加・置換・削除を順に適用する。
+///
+/// ファイルを所有するリポジトリの `trailer.separators`、`trailer.<token>.key`、
+/// `where`・`ifExists`・`ifMissing` と `core.commentChar` に従い、
+/// `git interpret-trailers` と同じ結果を返す。
+#[tauri::command]
+pub async fn apply_trailer_operations(
+    file_path: String,
+    content: String,
+    operations: Vec<TrailerOperation>,
+) -> Result<String, AppError> {
+    let dir = parent_dir(&file_path)?;
+    let config = read_trailer_config(&dir).await;
+    let comment = read_commit_msg_format(&file_path)
+        .await?
+        .comment_char
+        .text();
+    Ok(trailer::apply_trailer_operations(
+        &content,
+        &operations,
+        &config,
+        &comment,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs as std_fs;
+    use std::io::Write;
+    use std::path::Path;
+    use std::process::{Command as StdCommand, Stdio};
+
+    /// 並列実行時の衝突を避けるため uuid v4 でユニーク化したテスト用リポジトリを作成する。
+    fn create_test_repo() -> std::path::PathBuf {
+        let repo = std::env::temp_dir().join(format!(
+            "gui-git-editor-trailer-test-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std_fs::create_dir_all(&repo).unwrap();
+        run_git(&repo, &["init"]);
+        repo
+    }
+
+    fn run_git(repo: &Path, args: &[&str]) {
+        let output = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// `git interpret-trailers` に同じ操作を渡した出力。
+    fn git_interpret_trailers(
+        repo: &Path,
+        message: &str,
+        operations: &[TrailerOperation],
+    ) -> String {
+        let mut args: Vec<String> = vec!["interpret-trailers".to_string()];
+        for operation in operations {
+            match operation {
+                TrailerOperation::Add { token, value } => {
+                    args.extend(["--trailer".to_string(), format!("{}={}", token, value)]);
+                }
+                TrailerOperation::Replace { token, value } => args.extend([
+                    "--if-exists".to_string(),
+                    "replace".to_string(),
+                    "--trailer".to_string(),
+                    format!("{}={}", token, value),
+                    "--no-if-exists".to_string(),
+                ]),
+                TrailerOperation::Remove { .. } => unreachable!("git has no remove option"),
+            }
+        }
+        let mut child = StdCommand::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(message.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn add(token: &str, value: &str) -> TrailerOperation {
+        TrailerOperation::Add {
+            token: token.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn replace(token: &str, value: &str) -> TrailerOperation {
+        TrailerOperation::Replace {
+            token: token.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_trailer_operations_matches_git_interpret_trailers() {
+        let messages = [
+            "",
+            "Subject\n",
+            "Subject\n\nBody text.",
+            "Subject\nSigned-off-by: A <a@example.com>\n",
+            "Subject\n\nBody\n\nKey:x\n  continued\nother line\n# note\nSigned-off-by: A\n\n# Please enter the commit message\n# ------------------------ >8 ------------------------\ndiff --git a/a b/a\n",
+            "Subject\n\nSee: the docs\nfor details\n",
+            "Subject\n\nRefs: #1\nCc: A\nRefs: #2\nCc: B\n",
+            "Subject\n\nBody\n---\n a.txt | 1 +\n",
+            "Subject\n\nsign: A\nBug #1\nfoo : bar\n(cherry picked from commit abc)\n",
+            "Subject\n\nFoo Bar: x\n",
+            "Subject\n\n  indented: x\nA: b\n",
+            "Subject\n\nA: b\n\n\n",
+            "Subject\n\nA: b\nConflicts:\n\tfile\n",
+            "Subject\n\nA:\nB: c\n",
+            "Subject\n\n# c\nA: b\n",
+            "Subject\n\nprose one\nprose two\nprose three\nSigned-off-by: A\n",
+            "Subject\n\nprose one\nprose two\nprose three\nprose four\nSigned-off-by: A\n",
+            "Subject\n\nBug #5\nsi: x\n   \n",
+            "# only comment\n",
+            "Subject\n\nA: b\n# ------------------------ >8 ------------------------\nC: d\n",
+        ];
+        let operation_sets = [
+            vec![],
+            vec![add("Refs", "#3"), add("Cc", "A"), add("cc", "a")],
+            vec![replace("Refs", "#9"), add("sign", "B"), add("Cc", "C")],
+            vec![add("bug", "2"), add("s", "a"), add("Refs", "#1")],
+        ];
+        let configs: [&[(&str, &str)]; 3] = [
+            &[],
+            &[
+                ("trailer.separators", ":#"),
+                ("trailer.sign.key", "Signed-off-by"),
+                ("trailer.sign.ifexists", "addIfDifferent"),
+                ("trailer.bug.key", "Bug #"),
+                ("trailer.bug.where", "start"),
+            ],
+            &[
+                ("trailer.where", "start"),
+                ("trailer.ifexists", "add"),
+                ("trailer.cc.ifmissing", "doNothing"),
+                ("trailer.refs.where", "before"),
+                ("trailer.refs.ifexists", "addIfDifferentNeighbor"),
+            ],
+        ];
+
+        for config in configs {
+            let repo = create_test_repo();
+            for (key, value) in config {
+                run_git(&repo, &["config", key, value]);
+            }
+            let file_path = repo
+                .join(".git")
+                .join("COMMIT_EDITMSG")
+                .to_string_lossy()
+                .to_string();
+
+            for message in messages {
+                for operations in &operation_sets {
+                    let expected = git_interpret_trailers(&repo, message, operations);
+                    let actual = tauri::async_runtime::block_on(apply_trailer_operations(
+                        file_path.clone(),
+                        message.to_string(),
+                        operations.clone(),
+                    ))
+                    .unwrap();
+                    assert_eq!(
+                        actual, expected,
+                        "config {:?}, message {:?}, operations {:?}",
+                        config, message, operations
+                    );
+                }
+            }
+            let _ = std_fs::remove_dir_all(&repo);
+        }
+    }
+
+    #[test]
+    fn test_remove_uses_configured_alias() {
+        let repo = create_test_repo();
+        run_git(&repo, &["config", "trailer.sign.key", "Signed-off-by"]);
+        let file_path = repo.join(".git").join("COMMIT_EDITMSG");
+
+        let result = tauri::async_runtime::block_on(apply_trailer_operations(
+            file_path.to_string_lossy().to_string(),
+            "Subject\n\nSigned-off-by: A\nSigned-off-by-bot: B\nsign: C\n".to_string(),
+            vec![TrailerOperation::Remove {
+                token: "sign".to_string(),
+                value: None,
+            }],
+        ))
+        .unwrap();
+
+        let _ = std_fs::remove_dir_all(&repo);
+
+        assert_eq!(result, "Subject\n\nSigned-off-by-bot: B\n");
+    }
+}
@@ -2,13 +2,24 @@ mod commands;
 mod error;
 mod parser;
 
+pub use commands::standalone::run_editor_bridge;
+
 use commands::{
-    check_backup_exists, check_codex_available, check_git_sc_available, create_backup,
-    delete_backup, exit_app, generate_commit_message, generate_commit_message_from_staged,
-    git_blame_for_merge, git_commit_diff, git_commit_files, git_diff_file, git_stage_all,
-    git_stage_file, git_status, git_unstage_file, open_codex_terminal, parse_commit_msg,
-    parse_conflicts, parse_rebase_todo, read_file, read_merge_files, restore_backup,
-    serialize_commit_msg, serialize_rebase_todo, validate_commit_msg, write_file,
+    add_update_ref, analyze_rebase_dependencies, apply_message_replace, apply_rebase_plan,
+    apply_trailer_operations, autosquash_rebase_todo, build_rebase_graph, check_backup_exists,
+    check_codex_available, check_git_sc_available, compose_squash_messages, create_backup,
+    delete_backup, detect_reword_mode, detect_upstream_commits, exit_app, finish_editor_request,
+    generate_commit_message, generate_commit_message_from_staged, get_exec_templates,
+    get_rebase_progress, git_blame_for_merge, git_commit_diff, git_commit_files,
+    git_commit_metadata, git_diff_file, git_stage_all, git_stage_file, git_status,
+    git_unstage_file, insert_exec_steps, lint_rebase_todo, list_base_commits, list_rebase_sessions,
+    list_stack_branches, load_rebase_plan, move_rebase_commit, open_codex_terminal,
+    parse_commit_msg, parse_conflicts, parse_rebase_todo, preview_message_replace,
+    queue_squash_messages, queued_reword_message, read_file, read_merge_files,
+    regenerate_todo_help, remove_exec_steps, remove_update_ref, replace_squash_with_reword,
+    restore_backup, restore_rebase_session, save_rebase_plan, serialize_commit_msg,
+    serialize_rebase_todo, serialize_rebase_todo_lossless, simulate_rebase,
+    start_standalone_rebase, validate_commit_msg, write_file,
 };
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::Manager;
@@ -106,6 +117,35 @@ pub fn run() {
             exit_app,
             parse_rebase_todo,
             serialize_rebase_todo,
+            serialize_rebase_todo_lossless,
+            detect_reword_mode,
+            lint_rebase_todo,
+            regenerate_todo_help,
+            autosquash_rebase_todo,
+            simulate_rebase,
+            detect_upstream_commits,
+            build_rebase_graph,
+            move_rebase_commit,
+            list_stack_branches,
+            add_update_ref,
+            remove_update_ref,
+            analyze_rebase_dependencies,
+            get_rebase_progress,
+            list_rebase_sessions,
+            restore_rebase_session,
+            save_rebase_plan,
+            load_rebase_plan,
+            apply_rebase_plan,
+            apply_trailer_operations,
+            queued_reword_message,
+            compose_squash_messages,
+            queue_squash_messages,
+            replace_squash_with_reword,
+            preview_message_replace,
+            apply_message_replace,
+            insert_exec_steps,
+            remove_exec_steps,
+            get_exec_templates,
             check_git_sc_available,
             generate_commit_message,
             generate_commit_message_from_staged,
@@ -123,7 +163,11 @@ pub fn run() {
             git_stage_all,
             git_diff_file,
             git_commit_files,
+            git_commit_metadata,
             git_commit_diff,
+            list_base_commits,
+            start_standalone_rebase,
+            finish_editor_request,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // git からエディタとして起動された場合は、起動中のアプリへ編集を依頼して終了する。
+    if let Some(code) = gui_git_editor_lib::run_editor_bridge() {
+        std::process::exit(code);
+    }
     gui_git_editor_lib::run()
 }
@@ -3,6 +3,7 @@
 //! COMMIT_EDITMSG、MERGE_MSG、SQUASH_MSG、TAG_EDITMSG の解析を扱う。
 
 use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
 
 use crate::error::AppError;
 
@@ -15,12 +16,35 @@ pub struct CommitMessage {
     pub body: String,
     /// Git trailer（例: "Signed-off-by:"、"Co-authored-by:"）。
     pub trailers: Vec<Trailer>,
-    /// `#` で始まるコメント行。
+    /// コメント文字（既定は `#`）で始まるコメント行。`commit.cleanup` が strip のときだけ
+    /// 本文から分ける。
     pub comments: Vec<String>,
     /// verbose モードで scissors 行の後ろに表示される diff。
     pub diff_content: Option<String>,
 }
 
+/// 行の長さの数え方。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    /// Unicode のコードポイント数。
+    #[default]
+    Chars,
+    /// 端末や `git log --oneline` で占める桁数。East Asian Width の全角を 2 桁、
+    /// 結合文字を 0 桁とし、ZWJ でつないだ絵文字は 1 つの絵文字として数える。
+    DisplayWidth,
+}
+
+impl LengthUnit {
+    /// `text` の長さをこの単位で数える。
+    pub fn measure(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Chars => character_count(text),
+            LengthUnit::DisplayWidth => display_width(text),
+        }
+    }
+}
+
 /// Git trailer の key-value メタデータ。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Trailer {
@@ -28,8 +52,154 @@ pub struct Trailer {
     pub value: String,
 }
 
-/// コミットメッセージと diff を分離する scissors 行。
-const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+/// コミットメッセージと diff を分離する scissors 行の、コメント文字より後ろの部分。
+const SCISSORS_MARK: &str = "------------------------ >8 ------------------------";
+
+/// `core.commentChar=auto` で Git が順に試すコメント文字。
+const AUTO_COMMENT_CHARS: &[char] = &['#', ';', '@', '!', '$', '%', '^', '&', '|', ':'];
+
+/// `commit.cleanup` の指定。エディタで編集するときの `default` は `strip` と同じ。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CleanupMode {
+    /// コメント行を取り除く。
+    #[default]
+    Strip,
+    /// 空白だけを整え、コメント行もメッセージとして残す。
+    Whitespace,
+    /// メッセージをそのまま使う。
+    Verbatim,
+    /// `whitespace` と同じだが、scissors 行より後ろを取り除く。
+    Scissors,
+}
+
+impl CleanupMode {
+    /// git config の値を解釈する。不正な値なら None。
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value.trim() {
+            "" | "default" | "strip" => Some(CleanupMode::Strip),
+            "whitespace" => Some(CleanupMode::Whitespace),
+            "verbatim" => Some(CleanupMode::Verbatim),
+            "scissors" => Some(CleanupMode::Scissors),
+            _ => None,
+        }
+    }
+}
+
+/// `core.commentChar` の指定。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentChar {
+    /// 固定のコメント文字（列）。
+    Text(String),
+    /// メッセージで行頭に使われていない文字を Git が選ぶ。
+    Auto,
+}
+
+impl Default for CommentChar {
+    fn default() -> Self {
+        CommentChar::Text("#".to_string())
+    }
+}
+
+impl CommentChar {
+    /// git config の値を解釈する。空なら既定の `#`。
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "" => CommentChar::default(),
+            "auto" => CommentChar::Auto,
+            text => CommentChar::Text(text.to_string()),
+        }
+    }
+
+    /// Git が書いたファイルの内容から、使われているコメント文字を求める。
+    ///
+    /// `auto` の場合は scissors 行の文字を優先する。なければ、Git がコメントを末尾に
+    /// 書くことから最後の行の先頭の文字を先に試し、「その文字の行を除いたメッセージから
+    /// Git が選ぶ文字」と一致する候補を使う。
+    fn resolve_in_file(&self, content: &str) -> String {
+        let CommentChar::Auto = self else {
+            return self.text();
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        if let Some(c) = AUTO_COMMENT_CHARS
+            .iter()
+            .find(|c| lines.contains(&scissors_line(&c.to_string()).as_str()))
+        {
+            return c.to_string();
+        }
+
+        let last = lines
+            .iter()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .and_then(|line| line.chars().next())
+            .filter(|c| AUTO_COMMENT_CHARS.contains(c));
+        last.into_iter()
+            .chain(AUTO_COMMENT_CHARS.iter().copied())
+            .find(|&c| {
+                let message: Vec<&str> = lines
+                    .iter()
+                    .copied()
+                    .filter(|line| !line.starts_with(c))
+                    .collect();
+                auto_comment_char(&message.join("\n")) == Some(c)
+            })
+            .unwrap_or('#')
+            .to_string()
+    }
+
+    /// メッセージを書き出すときのコメント文字を求める。
+    ///
+    /// `auto` の場合は、Git が書いたコメントの文字を引き継ぐ。コメントがなければ
+    /// 本文から Git と同じ規則で選ぶ。
+    fn resolve_for_message(&self, message: &CommitMessage, content: &str) -> String {
+        let CommentChar::Auto = self else {
+            return self.text();
+        };
+        message
+            .comments
+            .first()
+            .and_then(|comment| comment.chars().next())
+            .filter(|c| AUTO_COMMENT_CHARS.contains(c))
+            .or_else(|| auto_comment_char(content))
+            .map_or_else(|| "#".to_string(), |c| c.to_string())
+    }
+
+    /// 固定のコメント文字。`auto` では Git が最初に使う `#` を返す。
+    pub(crate) fn text(&self) -> String {
+        match self {
+            CommentChar::Text(text) => text.clone(),
+            CommentChar::Auto => "#".to_string(),
+        }
+    }
+}
+
+/// Git の `core.commentChar=auto` と同じ規則で、どの行の先頭にも使われていない
+/// 最初の候補を選ぶ。`#` がメッセージのどこにもなければ `#`。すべて使われていれば None。
+fn auto_comment_char(message: &str) -> Option<char> {
+    if !message.contains(AUTO_COMMENT_CHARS[0]) {
+        return Some(AUTO_COMMENT_CHARS[0]);
+    }
+    let used: Vec<char> = message
+        .split(['\n', '\r'])
+        .filter_map(|line| line.chars().next())
+        .collect();
+    AUTO_COMMENT_CHARS
+        .iter()
+        .copied()
+        .find(|c| !used.contains(c))
+}
+
+/// コメント文字に対応する scissors 行。
+fn scissors_line(comment: &str) -> String {
+    format!("{} {}", comment, SCISSORS_MARK)
+}
+
+/// コミットメッセージファイルの書式を決める git config。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitMsgFormat {
+    pub cleanup: CleanupMode,
+    pub comment_char: CommentChar,
+}
 
 /// よく使われる trailer key。
 const KNOWN_TRAILER_KEYS: &[&str] = &[
@@ -58,9 +228,9 @@ impl CommitMessage {
         }
     }
 
-    /// subject が推奨文字数（50 文字）を超えているかを返す。
-    pub fn is_subject_too_long(&self) -> bool {
-        self.subject_length() > 50
+    /// subject が `unit` で数えて `limit` を超えているかを返す。
+    pub fn is_subject_too_long(&self, limit: usize, unit: LengthUnit) -> bool {
+        unit.measure(&self.subject) > limit
     }
 
     /// subject の文字数を返す。
@@ -68,20 +238,25 @@ impl CommitMessage {
         character_count(&self.subject)
     }
 
-    /// 本文に推奨文字数（72 文字）を超える行があるかを返す。
+    /// subject の表示幅（桁数）を返す。
+    pub fn subject_width(&self) -> usize {
+        display_width(&self.subject)
+    }
+
+    /// 本文に `unit` で数えて `limit` を超える行があるかを返す。
     #[cfg(test)]
-    pub fn has_long_body_lines(&self) -> bool {
-        self.body.lines().any(|line| character_count(line) > 72)
+    pub fn has_long_body_lines(&self, limit: usize, unit: LengthUnit) -> bool {
+        self.body.lines().any(|line| unit.measure(line) > limit)
     }
 
-    /// 本文から推奨 72 文字を超える行と文字数を返す。
-    pub fn get_long_body_lines(&self) -> Vec<(usize, usize)> {
+    /// 本文から `unit` で数えて `limit` を超える行と、その長さを返す。
+    pub fn get_long_body_lines(&self, limit: usize, unit: LengthUnit) -> Vec<(usize, usize)> {
         self.body
             .lines()
             .enumerate()
             .filter_map(|(i, line)| {
-                let length = character_count(line);
-                if length > 72 {
+                let length = unit.measure(line);
+                if length > limit {
                     Some((i + 1, length))
                 } else {
                     None
@@ -91,23 +266,41 @@ impl CommitMessage {
     }
 }
 
-fn character_count(text: &str) -> usize {
+pub(crate) fn character_count(text: &str) -> usize {
     text.chars().count()
 }
 
+pub(crate) fn display_width(text: &str) -> usize {
+    text.width()
+}
+
 impl Default for CommitMessage {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// コミットメッセージファイルの内容を CommitMessage に解析する。
+/// コミットメッセージファイルの内容を、既定の書式（`#` のコメント、strip）で解析する。
 pub fn parse_commit_msg(content: &str) -> Result<CommitMessage, AppError> {
+    parse_commit_msg_with(content, &CommitMsgFormat::default())
+}
+
+/// コミットメッセージファイルの内容を、`format` に従って CommitMessage に解析する。
+///
+/// strip 以外ではコメント文字で始まる行もメッセージとして本文に残す。verbatim では
+/// 書き戻して同じ行になる trailer だけを分け、前後の空白も削らない。
+pub fn parse_commit_msg_with(
+    content: &str,
+    format: &CommitMsgFormat,
+) -> Result<CommitMessage, AppError> {
     let mut message = CommitMessage::new();
     let mut lines: Vec<&str> = content.lines().collect();
+    let comment = format.comment_char.resolve_in_file(content);
+    let scissors = scissors_line(&comment);
+    let verbatim = format.cleanup == CleanupMode::Verbatim;
 
     // scissors 行がある場合は diff 部分を分離する。
-    if let Some(scissors_pos) = lines.iter().position(|line| *line == SCISSORS_LINE) {
+    if let Some(scissors_pos) = lines.iter().position(|line| *line == scissors) {
         // scissors 行より後ろはすべて diff として扱う。
         let diff_lines: Vec<&str> = lines.drain(scissors_pos..).skip(1).collect();
         if !diff_lines.is_empty() {
@@ -117,13 +310,23 @@ pub fn parse_commit_msg(content: &str) -> Result<CommitMessage, AppError> {
 
     // コメント行と本文行を分ける。
     let (content_lines, comment_lines): (Vec<&str>, Vec<&str>) =
-        lines.iter().partition(|line| !line.starts_with('#'));
+        if format.cleanup == CleanupMode::Strip {
+            lines
+                .iter()
+                .partition(|line| !line.starts_with(comment.as_str()))
+        } else {
+            (lines, Vec::new())
+        };
 
     message.comments = comment_lines.iter().map(|s| s.to_string()).collect();
 
     // 本文行から subject、body、trailer を解析する。
     let content_text = content_lines.join("\n");
-    let trimmed = content_text.trim();
+    let trimmed = if verbatim {
+        content_text.trim_matches('\n')
+    } else {
+        content_text.trim()
+    };
 
     if trimmed.is_empty() {
         return Ok(message);
@@ -156,7 +359,7 @@ pub fn parse_commit_msg(content: &str) -> Result<CommitMessage, AppError> {
 
     // 最後の body 段落から trailer を抽出する。
     if let Some(last_part) = body_parts.last() {
-        let (remaining_body, trailers) = extract_trailers(last_part);
+        let (remaining_body, trailers) = extract_trailers(last_part, verbatim);
 
         if !trailers.is_empty() {
             message.trailers = trailers;
@@ -167,13 +370,18 @@ pub fn parse_commit_msg(content: &str) -> Result<CommitMessage, AppError> {
         }
     }
 
-    message.body = body_parts.join("\n\n").trim().to_string();
+    let body = body_parts.join("\n\n");
+    message.body = if verbatim {
+        body.trim_matches('\n').to_string()
+    } else {
+        body.trim().to_string()
+    };
 
     Ok(message)
 }
 
-/// テキストブロックから trailer を抽出する。
-fn extract_trailers(text: &str) -> (String, Vec<Trailer>) {
+/// テキストブロックから trailer を抽出する。`exact` なら `Key: Value` と同じ行だけを扱う。
+fn extract_trailers(text: &str, exact: bool) -> (String, Vec<Trailer>) {
     let lines: Vec<&str> = text.lines().collect();
     let mut trailers = Vec::new();
     let mut non_trailer_lines = Vec::new();
@@ -181,7 +389,9 @@ fn extract_trailers(text: &str) -> (String, Vec<Trailer>) {
 
     // 末尾の trailer ブロックを見つけるために後ろから処理する。
     for line in lines.iter().rev() {
-        if let Some(trailer) = parse_trailer_line(line) {
+        if let Some(trailer) = parse_trailer_line(line)
+            .filter(|trailer| !exact || format!("{}: {}", trailer.key, trailer.value) == *line)
+        {
             trailers.push(trailer);
             in_trailer_block = true;
         } else if in_trailer_block && line.trim().is_empty() {
@@ -200,7 +410,7 @@ fn extract_trailers(text: &str) -> (String, Vec<Trailer>) {
 }
 
 /// 1 行が "Key: Value" 形式なら trailer として解析する。
-fn parse_trailer_line(line: &str) -> Option<Trailer> {
+pub(crate) fn parse_trailer_line(line: &str) -> Option<Trailer> {
     let trimmed = line.trim();
 
     // "Key: Value" 形式か確認する。
@@ -248,8 +458,13 @@ fn is_valid_trailer_key(key: &str) -> bool {
         .all(|c| c.is_ascii_alphanumeric() || *c == '-')
 }
 
-/// CommitMessage をコミットメッセージファイル形式へ戻す。
+/// CommitMessage を既定の書式でコミットメッセージファイル形式へ戻す。
 pub fn serialize_commit_msg(message: &CommitMessage) -> String {
+    serialize_commit_msg_with(message, &CommitMsgFormat::default())
+}
+
+/// CommitMessage を `format` のコメント文字でコミットメッセージファイル形式へ戻す。
+pub fn serialize_commit_msg_with(message: &CommitMessage, format: &CommitMsgFormat) -> String {
     let mut parts: Vec<String> = Vec::new();
 
     // subject 行。
@@ -277,6 +492,7 @@ pub fn serialize_commit_msg(message: &CommitMessage) -> String {
     }
 
     let mut result = parts.join("\n");
+    let comment = format.comment_char.resolve_for_message(message, &result);
 
     // コメントを追加する。
     if !message.comments.is_empty() {
@@ -289,7 +505,7 @@ pub fn serialize_commit_msg(message: &CommitMessage) -> String {
     // scissors 行の後ろに diff を追加する。
     if let Some(diff) = &message.diff_content {
         result.push('\n');
-        result.push_str(SCISSORS_LINE);
+        result.push_str(&scissors_line(&comment));
         result.push('\n');
         result.push_str(diff);
     }
@@ -297,10 +513,47 @@ pub fn serialize_commit_msg(message: &CommitMessage) -> String {
     result
 }
 
+/// key（大文字小文字は区別しない）と value が同じ trailer を、最初の出現だけ残して取り除く。
+pub fn dedup_trailers(trailers: Vec<Trailer>) -> Vec<Trailer> {
+    let mut result: Vec<Trailer> = Vec::with_capacity(trailers.len());
+    for trailer in trailers {
+        let duplicate = result
+            .iter()
+            .any(|kept| kept.key.eq_ignore_ascii_case(&trailer.key) && kept.value == trailer.value);
+        if !duplicate {
+            result.push(trailer);
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_dedup_trailers_ignores_key_case() {
+        let trailer = |key: &str, value: &str| Trailer {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let result = dedup_trailers(vec![
+            trailer("Signed-off-by", "A <a@example.com>"),
+            trailer("Refs", "#1"),
+            trailer("signed-off-by", "A <a@example.com>"),
+            trailer("Signed-off-by", "B <b@example.com>"),
+        ]);
+
+        assert_eq!(
+            result,
+            vec![
+                trailer("Signed-off-by", "A <a@example.com>"),
+                trailer("Refs", "#1"),
+                trailer("Signed-off-by", "B <b@example.com>"),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_simple_commit() {
         let content = "Add new feature\n\nThis is the body of the commit message.";
@@ -404,7 +657,7 @@ mod tests {
             diff_content: None,
         };
 
-        assert!(message.is_subject_too_long());
+        assert!(message.is_subject_too_long(50, LengthUnit::Chars));
         assert_eq!(message.subject_length(), 60);
     }
 
@@ -419,7 +672,7 @@ mod tests {
         };
 
         assert_eq!(message.subject_length(), 40);
-        assert!(!message.is_subject_too_long());
+        assert!(!message.is_subject_too_long(50, LengthUnit::Chars));
     }
 
     #[test]
@@ -432,8 +685,8 @@ mod tests {
             diff_content: None,
         };
 
-        assert!(message.has_long_body_lines());
-        let long_lines = message.get_long_body_lines();
+        assert!(message.has_long_body_lines(72, LengthUnit::Chars));
+        let long_lines = message.get_long_body_lines(72, LengthUnit::Chars);
         assert_eq!(long_lines.len(), 1);
         assert_eq!(long_lines[0], (2, 80)); // 2 行目、80 文字。
     }
@@ -448,10 +701,125 @@ mod tests {
             diff_content: None,
         };
 
-        let long_lines = message.get_long_body_lines();
+        let long_lines = message.get_long_body_lines(72, LengthUnit::Chars);
         assert_eq!(long_lines, vec![(2, 74)]);
     }
 
+    #[test]
+    fn test_display_width_counts_full_width_as_two_columns() {
+        let message = CommitMessage {
+            subject: "機能".repeat(25),
+            body: format!("{}\n{}", "説明".repeat(18), "説明".repeat(19)),
+            ..CommitMessage::new()
+        };
+
+        assert_eq!(message.subject_length(), 50);
+        assert_eq!(message.subject_width(), 100);
+        assert!(!message.is_subject_too_long(50, LengthUnit::Chars));
+        assert!(message.is_subject_too_long(50, LengthUnit::DisplayWidth));
+        assert!(!message.has_long_body_lines(72, LengthUnit::Chars));
+        assert_eq!(
+            message.get_long_body_lines(72, LengthUnit::DisplayWidth),
+            vec![(2, 76)]
+        );
+    }
+
+    #[test]
+    fn test_display_width_handles_emoji_and_combining_characters() {
+        // ZWJ でつないだ家族の絵文字、国旗、結合文字のアクセント。
+        assert_eq!(display_width("👨\u{200d}👩\u{200d}👧"), 2);
+        assert_eq!(display_width("🇯🇵"), 2);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(character_count("e\u{301}"), 2);
+    }
+
+    #[test]
+    fn test_parse_with_custom_comment_char_keeps_hash_lines() {
+        let format = CommitMsgFormat {
+            comment_char: CommentChar::from_config(";"),
+            ..CommitMsgFormat::default()
+        };
+        let content = "Fix crash\n\n#123 is fixed.\n\n; Please enter the commit message\n; ------------------------ >8 ------------------------\ndiff --git a/a b/a\n";
+
+        let message = parse_commit_msg_with(content, &format).unwrap();
+
+        assert_eq!(message.body, "#123 is fixed.");
+        assert_eq!(message.comments, vec!["; Please enter the commit message"]);
+        assert_eq!(message.diff_content.as_deref(), Some("diff --git a/a b/a"));
+        assert_eq!(
+            serialize_commit_msg_with(&message, &format),
+            content.trim_end()
+        );
+    }
+
+    #[test]
+    fn test_parse_without_strip_keeps_comment_lines_in_body() {
+        let content = "Fix crash\n\n# Not a comment\nDetails\n\n# ------------------------ >8 ------------------------\n# Please enter the commit message";
+
+        for cleanup in [CleanupMode::Whitespace, CleanupMode::Scissors] {
+            let format = CommitMsgFormat {
+                cleanup,
+                ..CommitMsgFormat::default()
+            };
+            let message = parse_commit_msg_with(content, &format).unwrap();
+
+            assert_eq!(message.body, "# Not a comment\nDetails");
+            assert!(message.comments.is_empty());
+            assert_eq!(
+                message.diff_content.as_deref(),
+                Some("# Please enter the commit message")
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_verbatim_keeps_lines_as_written() {
+        let format = CommitMsgFormat {
+            cleanup: CleanupMode::Verbatim,
+            ..CommitMsgFormat::default()
+        };
+        let content = "Fix crash\n\n    indented code\n# kept\n\nRefs:#1";
+
+        let message = parse_commit_msg_with(content, &format).unwrap();
+        let stripped = parse_commit_msg(content).unwrap();
+
+        assert_eq!(message.body, "    indented code\n# kept\n\nRefs:#1");
+        assert!(message.trailers.is_empty());
+        assert_eq!(serialize_commit_msg_with(&message, &format), content);
+        // strip では `#` の行が消え、trailer も `Refs: #1` に書き換わる。
+        assert_eq!(stripped.trailers[0].value, "#1");
+    }
+
+    #[test]
+    fn test_auto_comment_char_follows_git_selection() {
+        let format = CommitMsgFormat {
+            comment_char: CommentChar::from_config("auto"),
+            ..CommitMsgFormat::default()
+        };
+
+        let hashed = parse_commit_msg_with("Fix\n\n# Please enter", &format).unwrap();
+        let semicolon =
+            parse_commit_msg_with("Fix\n\n#123 fixed\n\n; Please enter", &format).unwrap();
+        let created = CommitMessage {
+            subject: "Fix".to_string(),
+            body: "#1 and ;2".to_string(),
+            diff_content: Some("diff".to_string()),
+            ..CommitMessage::new()
+        };
+
+        assert_eq!(hashed.comments, vec!["# Please enter"]);
+        assert_eq!(semicolon.body, "#123 fixed");
+        assert_eq!(semicolon.comments, vec!["; Please enter"]);
+        assert_eq!(
+            serialize_commit_msg_with(&semicolon, &format),
+            "Fix\n\n#123 fixed\n\n; Please enter"
+        );
+        assert!(serialize_commit_msg_with(&created, &format)
+            .ends_with("\n; ------------------------ >8 ------------------------\ndiff"));
+        assert_eq!(auto_comment_char("Fix #1"), Some('#'));
+        assert_eq!(auto_comment_char("Fix\n#1\n;2"), Some('@'));
+    }
+
     #[test]
     fn test_roundtrip() {
         let original = CommitMessage {
@@ -0,0 +1,674 @@
+//! リポジトリごとに設定できるコミットメッセージの lint。
+//!
+//! `.gitmessage-lint.toml`、または `.commitlintrc.json` の一部のルールを読み、
+//! メッセージの行・列に対応付けた診断を返す。
+
+use serde::{Deserialize, Serialize};
+
+use super::commit::{character_count, display_width, parse_trailer_line, LengthUnit};
+use super::rebase_lint::DiagnosticSeverity;
+use crate::error::AppError;
+
+/// ルールの有効・無効と、違反したときの重大度。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleLevel {
+    Off,
+    #[default]
+    Warning,
+    Error,
+}
+
+impl RuleLevel {
+    fn severity(self) -> Option<DiagnosticSeverity> {
+        match self {
+            RuleLevel::Off => None,
+            RuleLevel::Warning => Some(DiagnosticSeverity::Warning),
+            RuleLevel::Error => Some(DiagnosticSeverity::Error),
+        }
+    }
+
+    /// commitlint の 0（無効）・1（警告）・2（エラー）を解釈する。
+    fn from_commitlint(level: u64) -> Self {
+        match level {
+            0 => RuleLevel::Off,
+            1 => RuleLevel::Warning,
+            _ => RuleLevel::Error,
+        }
+    }
+}
+
+/// 1 つのルールの設定。`value` を取らないルールでは省略する。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Rule<T> {
+    #[serde(default)]
+    pub level: RuleLevel,
+    #[serde(default)]
+    pub value: T,
+}
+
+impl<T> Rule<T> {
+    fn new(level: RuleLevel, value: T) -> Self {
+        Self { level, value }
+    }
+}
+
+/// subject（Conventional Commits なら description）の先頭の文字の大文字・小文字。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LetterCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+/// lint のルール。設定ファイルに書かれたルールだけが既定値を上書きする。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LintRules {
+    pub subject_max_length: Option<Rule<usize>>,
+    pub subject_min_length: Option<Rule<usize>>,
+    pub body_max_line_length: Option<Rule<usize>>,
+    /// subject の末尾の `.`（`。`）を禁止する。
+    pub subject_full_stop: Option<Rule<()>>,
+    pub subject_case: Option<Rule<LetterCase>>,
+    /// subject の次の行を空行にする。
+    pub body_leading_blank: Option<Rule<()>>,
+    /// 必須の trailer の key（大文字小文字は区別しない）。
+    pub trailer_exists: Option<Rule<Vec<String>>>,
+    /// subject・本文で使えない単語（大文字小文字は区別しない）。
+    pub forbidden_words: Option<Rule<Vec<String>>>,
+    /// 長さのルールの数え方。設定ファイルの最上位の `length-unit` で指定する。
+    #[serde(skip)]
+    pub length_unit: LengthUnit,
+}
+
+impl LintRules {
+    /// 設定ファイルがない場合のルール。subject 50 文字・本文 72 文字を警告する。
+    pub fn defaults() -> Self {
+        Self {
+            subject_max_length: Some(Rule::new(RuleLevel::Warning, 50)),
+            body_max_line_length: Some(Rule::new(RuleLevel::Warning, 72)),
+            ..Self::default()
+        }
+    }
+
+    /// `other` に書かれたルールで上書きする。
+    fn merge(mut self, other: LintRules) -> Self {
+        macro_rules! overlay {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field;
+                })*
+            };
+        }
+        overlay!(
+            subject_max_length,
+            subject_min_length,
+            body_max_line_length,
+            subject_full_stop,
+            subject_case,
+            body_leading_blank,
+            trailer_exists,
+            forbidden_words
+        );
+        self
+    }
+
+    /// 有効な subject の最大長（`length_unit` で数える）。
+    pub fn subject_limit(&self) -> Option<usize> {
+        enabled(&self.subject_max_length).map(|rule| rule.value)
+    }
+
+    /// 有効な本文 1 行の最大長（`length_unit` で数える）。
+    pub fn body_line_limit(&self) -> Option<usize> {
+        enabled(&self.body_max_line_length).map(|rule| rule.value)
+    }
+}
+
+fn enabled<T>(rule: &Option<Rule<T>>) -> Option<&Rule<T>> {
+    rule.as_ref().filter(|rule| rule.level != RuleLevel::Off)
+}
+
+/// `.gitmessage-lint.toml` の内容。
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct LintConfigFile {
+    /// `"chars"`（既定）か `"display-width"`。
+    #[serde(default)]
+    length_unit: ConfigLengthUnit,
+    #[serde(default)]
+    rules: LintRules,
+}
+
+/// 設定ファイルに書く長さの数え方。他のキーに合わせて kebab-case で書く。
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ConfigLengthUnit {
+    #[default]
+    Chars,
+    DisplayWidth,
+}
+
+impl From<ConfigLengthUnit> for LengthUnit {
+    fn from(unit: ConfigLengthUnit) -> Self {
+        match unit {
+            ConfigLengthUnit::Chars => LengthUnit::Chars,
+            ConfigLengthUnit::DisplayWidth => LengthUnit::DisplayWidth,
+        }
+    }
+}
+
+/// 文字列中のバイト位置を 1 始まりの行番号にする。
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// `.gitmessage-lint.toml` を解析し、既定のルールに重ねる。
+pub fn parse_lint_toml(content: &str) -> Result<LintRules, AppError> {
+    let file: LintConfigFile = toml::from_str(content).map_err(|e| AppError::ParseError {
+        line: e.span().map_or(0, |span| line_of(content, span.start)),
+        message: e.message().to_string(),
+    })?;
+    Ok(LintRules {
+        length_unit: file.length_unit.into(),
+        ..LintRules::defaults().merge(file.rules)
+    })
+}
+
+/// commitlint のルール `[level, "always" | "never", value]` の一部を読む。
+fn commitlint_rule(
+    rule: &serde_json::Value,
+) -> Option<(RuleLevel, bool, Option<&serde_json::Value>)> {
+    let items = rule.as_array()?;
+    let level = RuleLevel::from_commitlint(items.first()?.as_u64()?);
+    let always = items.get(1).and_then(|when| when.as_str()) != Some("never");
+    Some((level, always, items.get(2)))
+}
+
+fn string_values(value: Option<&serde_json::Value>) -> Vec<String> {
+    match value {
+        Some(serde_json::Value::String(value)) => vec![value.clone()],
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// commitlint の subject-case を先頭の文字の大文字・小文字として解釈する。
+fn commitlint_case(always: bool, value: Option<&serde_json::Value>) -> Option<LetterCase> {
+    let cases = string_values(value);
+    let has = |name: &str| cases.iter().any(|case| case == name);
+    let upper =
+        has("sentence-case") || has("start-case") || has("pascal-case") || has("upper-case");
+    let lower = has("lower-case") || has("camel-case") || has("kebab-case") || has("snake-case");
+    match (always, upper, lower) {
+        (true, true, false) | (false, false, true) => Some(LetterCase::Upper),
+        (true, false, true) | (false, true, false) => Some(LetterCase::Lower),
+        _ => None,
+    }
+}
+
+/// `.commitlintrc.json` のうち、対応するルールだけを読んで既定のルールに重ねる。
+///
+/// 対応するのは header-max-length、header-min-length、body-max-line-length、
+/// subject-full-stop、subject-case、body-leading-blank、trailer-exists。
+/// `extends` や他のルールは無視する。
+pub fn parse_commitlint_json(content: &str) -> Result<LintRules, AppError> {
+    let config: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| AppError::ParseError {
+            line: e.line(),
+            message: e.to_string(),
+        })?;
+    let mut rules = LintRules::default();
+    let Some(entries) = config.get("rules").and_then(|rules| rules.as_object()) else {
+        return Ok(LintRules::defaults());
+    };
+
+    for (name, rule) in entries {
+        let Some((level, always, value)) = commitlint_rule(rule) else {
+            continue;
+        };
+        let length = value
+            .and_then(|value| value.as_u64())
+            .map(|value| value as usize);
+        match name.as_str() {
+            "header-max-length" => {
+                rules.subject_max_length = length.map(|length| Rule::new(level, length));
+            }
+            "header-min-length" => {
+                rules.subject_min_length = length.map(|length| Rule::new(level, length));
+            }
+            "body-max-line-length" => {
+                rules.body_max_line_length = length.map(|length| Rule::new(level, length));
+            }
+            // "never" で末尾の `.` を禁止する。"always" は対応しない。
+            "subject-full-stop" if !always => {
+                rules.subject_full_stop = Some(Rule::new(level, ()));
+            }
+            "subject-case" => {
+                rules.subject_case =
+                    commitlint_case(always, value).map(|case| Rule::new(level, case));
+            }
+            "body-leading-blank" if always => {
+                rules.body_leading_blank = Some(Rule::new(level, ()));
+            }
+            "trailer-exists" if always => {
+                let keys = string_values(value)
+                    .into_iter()
+                    .map(|key| key.trim_end_matches(':').to_string())
+                    .collect();
+                rules.trailer_exists = Some(Rule::new(level, keys));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(LintRules::defaults().merge(rules))
+}
+
+/// lint のルールの名前。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRuleName {
+    SubjectMaxLength,
+    SubjectMinLength,
+    BodyMaxLineLength,
+    SubjectFullStop,
+    SubjectCase,
+    BodyLeadingBlank,
+    TrailerExists,
+    ForbiddenWords,
+}
+
+/// 1 件分の lint の結果。行・列は 1 始まりで、列は文字単位。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommitLintDiagnostic {
+    pub rule: LintRuleName,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// `word` が `line` に単語として現れる位置（文字単位の 0 始まり）を返す。
+fn find_word(line: &str, word: &str) -> Vec<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    let lower: Vec<char> = line.to_lowercase().chars().collect();
+    let word: Vec<char> = word.to_lowercase().chars().collect();
+    // 小文字にして文字数が変わる場合は位置がずれるため、照合しない。
+    if word.is_empty() || lower.len() != chars.len() {
+        return Vec::new();
+    }
+    // 日本語の文中でも見つけられるよう、ASCII の英数字だけを単語の一部とみなす。
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    (0..=chars.len().saturating_sub(word.len()))
+        .filter(|&start| {
+            lower[start..].starts_with(&word)
+                && (start == 0 || !is_word_char(chars[start - 1]))
+                && chars
+                    .get(start + word.len())
+                    .is_none_or(|&next| !is_word_char(next))
+        })
+        .collect()
+}
+
+/// `unit` で数えて `limit` を超え始める文字の列（文字単位の 1 始まり）。
+fn overflow_column(line: &str, limit: usize, unit: LengthUnit) -> usize {
+    match unit {
+        LengthUnit::Chars => limit + 1,
+        LengthUnit::DisplayWidth => line
+            .char_indices()
+            .position(|(offset, c)| display_width(&line[..offset + c.len_utf8()]) > limit)
+            .map_or(limit + 1, |index| index + 1),
+    }
+}
+
+/// 長さを診断のメッセージに書く形にする。
+fn describe_length(length: usize, unit: LengthUnit) -> String {
+    match unit {
+        LengthUnit::Chars => format!("{} characters long", length),
+        LengthUnit::DisplayWidth => format!("{} columns wide", length),
+    }
+}
+
+/// Conventional Commits の `type(scope): ` を除いた description の開始位置（文字単位）。
+fn description_start(subject: &str) -> usize {
+    match subject.split_once(": ") {
+        Some((prefix, _))
+            if !prefix.is_empty()
+                && !prefix.contains(' ')
+                && prefix
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic()) =>
+        {
+            character_count(prefix) + 2
+        }
+        _ => 0,
+    }
+}
+
+/// コメントと diff を除いたメッセージ本文を lint する。
+pub fn lint_commit_message(content: &str, rules: &LintRules) -> Vec<CommitLintDiagnostic> {
+    let lines: Vec<&str> = content.lines().collect();
+    let subject = lines.first().copied().unwrap_or("");
+    let mut diagnostics = Vec::new();
+    let mut report = |rule: LintRuleName, level: RuleLevel, line, column, message: String| {
+        if let Some(severity) = level.severity() {
+            diagnostics.push(CommitLintDiagnostic {
+                rule,
+                severity,
+                message,
+                line,
+                column,
+            });
+        }
+    };
+    let unit = rules.length_unit;
+    let subject_length = unit.measure(subject);
+
+    if let Some(rule) = &rules.subject_max_length {
+        if subject_length > rule.value {
+            report(
+                LintRuleName::SubjectMaxLength,
+                rule.level,
+                1,
+                overflow_column(subject, rule.value, unit),
+                format!(
+                    "Subject is {} (max {})",
+                    describe_length(subject_length, unit),
+                    rule.value
+                ),
+            );
+        }
+    }
+    if let Some(rule) = &rules.subject_min_length {
+        if subject_length < rule.value {
+            report(
+                LintRuleName::SubjectMinLength,
+                rule.level,
+                1,
+                1,
+                format!(
+                    "Subject is {} (min {})",
+                    describe_length(subject_length, unit),
+                    rule.value
+                ),
+            );
+        }
+    }
+    if let Some(rule) = &rules.subject_full_stop {
+        if subject.ends_with(['.', '。']) {
+            report(
+                LintRuleName::SubjectFullStop,
+                rule.level,
+                1,
+                character_count(subject),
+                "Subject must not end with a period".to_string(),
+            );
+        }
+    }
+    if let Some(rule) = &rules.subject_case {
+        let start = description_start(subject);
+        // 大文字・小文字のない文字（漢字など）で始まる場合は検査しない。
+        if let Some(first) = subject.chars().nth(start) {
+            let wrong = match rule.value {
+                LetterCase::Upper => first.is_lowercase(),
+                LetterCase::Lower => first.is_uppercase(),
+            };
+            if wrong {
+                let expected = match rule.value {
+                    LetterCase::Upper => "an uppercase",
+                    LetterCase::Lower => "a lowercase",
+                };
+                report(
+                    LintRuleName::SubjectCase,
+                    rule.level,
+                    1,
+                    start + 1,
+                    format!("Subject must start with {} letter", expected),
+                );
+            }
+        }
+    }
+    if let Some(rule) = &rules.body_leading_blank {
+        if lines.get(1).is_some_and(|line| !line.trim().is_empty()) {
+            report(
+                LintRuleName::BodyLeadingBlank,
+                rule.level,
+                2,
+                1,
+                "Leave a blank line after the subject".to_string(),
+            );
+        }
+    }
+
+    // 末尾の段落の trailer 行は本文の行長の対象にしない。
+    let last_paragraph = lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(lines.len(), |index| index + 1);
+    let is_trailer =
+        |index: usize| index >= last_paragraph && parse_trailer_line(lines[index]).is_some();
+    if let Some(rule) = &rules.body_max_line_length {
+        for (index, line) in lines.iter().enumerate().skip(1) {
+            let length = unit.measure(line);
+            if length > rule.value && !is_trailer(index) {
+                report(
+                    LintRuleName::BodyMaxLineLength,
+                    rule.level,
+                    index + 1,
+                    overflow_column(line, rule.value, unit),
+                    format!(
+                        "Line is {} (max {})",
+                        describe_length(length, unit),
+                        rule.value
+                    ),
+                );
+            }
+        }
+    }
+    if let Some(rule) = &rules.forbidden_words {
+        for (index, line) in lines.iter().enumerate() {
+            for word in &rule.value {
+                for start in find_word(line, word) {
+                    report(
+                        LintRuleName::ForbiddenWords,
+                        rule.level,
+                        index + 1,
+                        start + 1,
+                        format!("'{}' is not allowed", word),
+                    );
+                }
+            }
+        }
+    }
+    if let Some(rule) = &rules.trailer_exists {
+        let trailer_keys: Vec<String> = (last_paragraph.max(1)..lines.len())
+            .filter_map(|index| parse_trailer_line(lines[index]))
+            .map(|trailer| trailer.key)
+            .collect();
+        for key in &rule.value {
+            if !trailer_keys
+                .iter()
+                .any(|found| found.eq_ignore_ascii_case(key))
+            {
+                report(
+                    LintRuleName::TrailerExists,
+                    rule.level,
+                    lines.len().max(1),
+                    1,
+                    format!("Missing trailer '{}'", key),
+                );
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_names(diagnostics: &[CommitLintDiagnostic]) -> Vec<(LintRuleName, usize, usize)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.line, diagnostic.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_rules_only_check_lengths() {
+        let long_line = "x".repeat(73);
+        let content = format!(
+            "{}\n\n{}\nSigned-off-by: {}",
+            "s".repeat(51),
+            long_line,
+            long_line
+        );
+
+        let diagnostics = lint_commit_message(&content, &LintRules::defaults());
+
+        assert_eq!(
+            rule_names(&diagnostics),
+            vec![
+                (LintRuleName::SubjectMaxLength, 1, 51),
+                (LintRuleName::BodyMaxLineLength, 3, 73),
+            ]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == DiagnosticSeverity::Warning));
+    }
+
+    #[test]
+    fn test_parse_lint_toml_overrides_defaults() {
+        let rules = parse_lint_toml(
+            r#"
+[rules]
+subject-max-length = { level = "error", value = 72 }
+body-max-line-length = { level = "off" }
+subject-full-stop = { level = "error" }
+subject-case = { value = "upper" }
+body-leading-blank = { level = "error" }
+trailer-exists = { level = "error", value = ["Signed-off-by"] }
+forbidden-words = { level = "error", value = ["WIP"] }
+"#,
+        )
+        .unwrap();
+
+        let diagnostics = lint_commit_message(
+            "fix: wip parser.\nWIP: details that are long enough to pass anything reasonable\n\nRefs: #1",
+            &rules,
+        );
+
+        assert_eq!(rules.subject_limit(), Some(72));
+        assert_eq!(rules.body_line_limit(), None);
+        assert_eq!(
+            rule_names(&diagnostics),
+            vec![
+                (LintRuleName::SubjectFullStop, 1, 16),
+                (LintRuleName::SubjectCase, 1, 6),
+                (LintRuleName::BodyLeadingBlank, 2, 1),
+                (LintRuleName::ForbiddenWords, 1, 6),
+                (LintRuleName::ForbiddenWords, 2, 1),
+                (LintRuleName::TrailerExists, 4, 1),
+            ]
+        );
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+    }
+
+    #[test]
+    fn test_parse_lint_toml_reports_line_of_error() {
+        let error =
+            parse_lint_toml("[rules]\nsubject-max-length = { level = \"fatal\" }\n").unwrap_err();
+
+        assert!(matches!(error, AppError::ParseError { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_parse_commitlint_json_reads_supported_subset() {
+        let rules = parse_commitlint_json(
+            r#"{
+  "extends": ["@commitlint/config-conventional"],
+  "rules": {
+    "header-max-length": [2, "always", 100],
+    "body-max-line-length": [0, "always", 100],
+    "subject-full-stop": [2, "never", "."],
+    "subject-case": [2, "never", ["sentence-case", "start-case", "pascal-case", "upper-case"]],
+    "trailer-exists": [1, "always", "Signed-off-by:"],
+    "type-enum": [2, "always", ["feat", "fix"]]
+  }
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules.subject_max_length,
+            Some(Rule::new(RuleLevel::Error, 100))
+        );
+        assert_eq!(rules.body_line_limit(), None);
+        assert_eq!(
+            rules.subject_full_stop,
+            Some(Rule::new(RuleLevel::Error, ()))
+        );
+        assert_eq!(
+            rules.subject_case,
+            Some(Rule::new(RuleLevel::Error, LetterCase::Lower))
+        );
+        assert_eq!(
+            rules.trailer_exists,
+            Some(Rule::new(
+                RuleLevel::Warning,
+                vec!["Signed-off-by".to_string()]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_length_unit_display_width_counts_columns() {
+        let rules = parse_lint_toml(
+            "length-unit = \"display-width\"\n\n[rules]\nsubject-max-length = { value = 10 }\n",
+        )
+        .unwrap();
+
+        // 全角 6 文字は 12 桁。11 桁目を含む 6 文字目から超える。
+        let diagnostics = lint_commit_message("日本語の修正\n\nabc👨\u{200d}👩\u{200d}👧", &rules);
+
+        assert_eq!(rules.length_unit, LengthUnit::DisplayWidth);
+        assert_eq!(
+            rule_names(&diagnostics),
+            vec![(LintRuleName::SubjectMaxLength, 1, 6)]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "Subject is 12 columns wide (max 10)"
+        );
+        assert_eq!(
+            parse_lint_toml("[rules]\n").unwrap().length_unit,
+            LengthUnit::Chars
+        );
+    }
+
+    #[test]
+    fn test_find_word_matches_whole_words_only() {
+        assert_eq!(find_word("WIP: wip, swipe WIPs", "wip"), vec![0, 5]);
+        assert_eq!(find_word("作業中WIPです", "WIP"), vec![3]);
+    }
+
+    #[test]
+    fn test_subject_case_skips_uncased_letters() {
+        let rules = LintRules {
+            subject_case: Some(Rule::new(RuleLevel::Error, LetterCase::Upper)),
+            ..LintRules::default()
+        };
+
+        assert!(lint_commit_message("feat: 日本語の説明", &rules).is_empty());
+        assert!(lint_commit_message("Add parser", &rules).is_empty());
+    }
+}
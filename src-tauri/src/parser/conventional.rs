@@ -0,0 +1,267 @@
+//! Conventional Commits のヘッダーと BREAKING CHANGE フッターの解析。
+
+use serde::{Deserialize, Serialize};
+
+use super::commit::CommitMessage;
+
+/// 破壊的変更を表すフッターの token。`BREAKING-CHANGE` は同義。
+const BREAKING_CHANGE_TOKENS: &[&str] = &["BREAKING CHANGE", "BREAKING-CHANGE"];
+
+/// `type(scope)!: description` 形式の subject と、破壊的変更のフッター。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConventionalCommit {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// subject の `!`、または `BREAKING CHANGE:` フッターがある。
+    pub breaking: bool,
+    pub description: String,
+    /// `BREAKING CHANGE:` フッターの説明（複数行は改行でつなぐ）。
+    pub breaking_changes: Vec<String>,
+}
+
+/// 許可する type と scope。空なら制限しない。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConventionalRules {
+    pub types: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+/// Conventional Commits の規約違反。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConventionalViolation {
+    /// type が設定されているのに、subject が `type: description` 形式でない。
+    NotConventional,
+    TypeNotAllowed {
+        commit_type: String,
+    },
+    ScopeNotAllowed {
+        scope: String,
+    },
+}
+
+/// subject を `type(scope)!: description` として解析する。形式が違えば None。
+fn parse_header(subject: &str) -> Option<(String, Option<String>, bool, String)> {
+    let (prefix, description) = subject.split_once(": ")?;
+    let description = description.trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (prefix, marker) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((commit_type, rest)) => {
+            let scope = rest.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (commit_type, Some(scope.to_string()))
+        }
+        None => (prefix, None),
+    };
+
+    let mut chars = commit_type.chars();
+    if !chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return None;
+    }
+
+    Some((
+        commit_type.to_string(),
+        scope,
+        marker,
+        description.to_string(),
+    ))
+}
+
+fn strip_breaking_token(line: &str) -> Option<&str> {
+    BREAKING_CHANGE_TOKENS.iter().find_map(|token| {
+        line.strip_prefix(token)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(str::trim)
+    })
+}
+
+/// body と trailer から `BREAKING CHANGE:` フッターの説明を集める。
+///
+/// token に空白を含む `BREAKING CHANGE` は trailer として扱われず body に残るため、
+/// body の行も調べる。説明は空行または次のフッターまで続く行を含む。
+fn breaking_changes(message: &CommitMessage) -> Vec<String> {
+    let mut notes: Vec<String> = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in message.body.lines() {
+        if let Some(first) = strip_breaking_token(line) {
+            if let Some(lines) = current.take() {
+                notes.push(lines.join("\n"));
+            }
+            current = Some(vec![first]);
+        } else if line.trim().is_empty() {
+            if let Some(lines) = current.take() {
+                notes.push(lines.join("\n"));
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line.trim());
+        }
+    }
+    if let Some(lines) = current {
+        notes.push(lines.join("\n"));
+    }
+
+    notes.extend(
+        message
+            .trailers
+            .iter()
+            .filter(|trailer| BREAKING_CHANGE_TOKENS.contains(&trailer.key.as_str()))
+            .map(|trailer| trailer.value.clone()),
+    );
+    notes
+}
+
+impl CommitMessage {
+    /// subject を Conventional Commits のヘッダーとして解析する。形式が違えば None。
+    pub fn conventional_commit(&self) -> Option<ConventionalCommit> {
+        let (commit_type, scope, marker, description) = parse_header(&self.subject)?;
+        let breaking_changes = breaking_changes(self);
+        Some(ConventionalCommit {
+            commit_type,
+            scope,
+            breaking: marker || !breaking_changes.is_empty(),
+            description,
+            breaking_changes,
+        })
+    }
+}
+
+/// 解析した Conventional Commits のヘッダーを、許可する type と scope で検証する。
+///
+/// type が設定されていなければ、形式の違う subject も違反にしない。
+pub fn check_conventional_commit(
+    commit: Option<&ConventionalCommit>,
+    rules: &ConventionalRules,
+) -> Vec<ConventionalViolation> {
+    let Some(commit) = commit else {
+        return if rules.types.is_empty() {
+            Vec::new()
+        } else {
+            vec![ConventionalViolation::NotConventional]
+        };
+    };
+
+    let mut violations = Vec::new();
+    if !rules.types.is_empty() && !rules.types.contains(&commit.commit_type) {
+        violations.push(ConventionalViolation::TypeNotAllowed {
+            commit_type: commit.commit_type.clone(),
+        });
+    }
+    if let Some(scope) = &commit.scope {
+        if !rules.scopes.is_empty() && !rules.scopes.contains(scope) {
+            violations.push(ConventionalViolation::ScopeNotAllowed {
+                scope: scope.clone(),
+            });
+        }
+    }
+    violations
+}
+
+/// git config の値（空白またはカンマ区切り）を type や scope の一覧にする。
+pub fn parse_rule_values<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for value in values
+        .into_iter()
+        .flat_map(|value| value.split([',', ' ', '\t']))
+        .filter(|value| !value.is_empty())
+    {
+        if !result.iter().any(|kept| kept == value) {
+            result.push(value.to_string());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::commit::parse_commit_msg;
+
+    #[test]
+    fn test_conventional_commit_parses_type_scope_and_marker() {
+        let message = parse_commit_msg("feat(parser)!: drop the legacy format\n").unwrap();
+
+        assert_eq!(
+            message.conventional_commit(),
+            Some(ConventionalCommit {
+                commit_type: "feat".to_string(),
+                scope: Some("parser".to_string()),
+                breaking: true,
+                description: "drop the legacy format".to_string(),
+                breaking_changes: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_conventional_commit_rejects_malformed_headers() {
+        for subject in [
+            "Add parser",
+            "feat:missing space",
+            "feat(): empty scope",
+            "feat(parser: unclosed scope",
+            "fix bug: not a type",
+            "feat: ",
+        ] {
+            let message = parse_commit_msg(subject).unwrap();
+            assert_eq!(message.conventional_commit(), None, "{}", subject);
+        }
+    }
+
+    #[test]
+    fn test_conventional_commit_collects_breaking_change_footers() {
+        let message = parse_commit_msg(
+            "fix: handle empty input\n\nDetails.\n\nBREAKING CHANGE: empty input\n  now returns an error\nRefs: #12\nBREAKING-CHANGE: the CLI flag is gone\n",
+        )
+        .unwrap();
+
+        let commit = message.conventional_commit().unwrap();
+
+        assert!(commit.breaking);
+        assert_eq!(
+            commit.breaking_changes,
+            vec![
+                "empty input\nnow returns an error".to_string(),
+                "the CLI flag is gone".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_conventional_commit_reports_disallowed_type_and_scope() {
+        let rules = ConventionalRules {
+            types: parse_rule_values(["feat, fix", "docs"]),
+            scopes: parse_rule_values(["parser ui"]),
+        };
+        let commit = parse_commit_msg("chore(build): bump deps")
+            .unwrap()
+            .conventional_commit();
+
+        assert_eq!(
+            check_conventional_commit(commit.as_ref(), &rules),
+            vec![
+                ConventionalViolation::TypeNotAllowed {
+                    commit_type: "chore".to_string(),
+                },
+                ConventionalViolation::ScopeNotAllowed {
+                    scope: "build".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            check_conventional_commit(None, &rules),
+            vec![ConventionalViolation::NotConventional]
+        );
+        assert!(check_conventional_commit(None, &ConventionalRules::default()).is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{has_edited_message, RebaseCommand, RebaseTodoFile};
+use crate::error::AppError;
+
+/// コミットメッセージに適用する置換。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageReplace {
+    pub find: String,
+    pub replace: String,
+    /// `find` を正規表現として扱う。このとき `replace` では `$1` などで参照できる。
+    #[serde(default)]
+    pub regex: bool,
+}
+
+/// 置換で変わるエントリの、変更前後のメッセージ。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageReplacement {
+    pub entry_id: String,
+    pub commit_hash: String,
+    pub old_message: String,
+    pub new_message: String,
+}
+
+/// 選んだエントリのメッセージに置換を適用した結果を返す。todo は変更しない。
+///
+/// 編集済みのメッセージはそのまま、未編集のものは `messages`（エントリ id ごとの完全な
+/// メッセージ）を置換の対象にする。メッセージが変わらないエントリは結果に含めない。
+/// pick・reword 以外のエントリを選んだ場合はエラーにする。
+pub fn plan_message_replacements(
+    file: &RebaseTodoFile,
+    entry_ids: &[String],
+    messages: &HashMap<String, String>,
+    spec: &MessageReplace,
+) -> Result<Vec<MessageReplacement>, AppError> {
+    if spec.find.is_empty() {
+        return Err(AppError::CommandError {
+            message: "Search pattern must not be empty".to_string(),
+        });
+    }
+    let pattern = if spec.regex {
+        Regex::new(&spec.find)
+    } else {
+        Regex::new(&regex::escape(&spec.find))
+    }
+    .map_err(|e| AppError::CommandError {
+        message: format!("Invalid search pattern: {}", e),
+    })?;
+
+    let mut replacements = Vec::new();
+    for entry in file
+        .entries
+        .iter()
+        .filter(|entry| entry_ids.contains(&entry.id))
+    {
+        if !matches!(entry.command, RebaseCommand::Pick | RebaseCommand::Reword) {
+            return Err(AppError::CommandError {
+                message: format!(
+                    "'{}' {} cannot be reworded",
+                    entry.command.to_long(),
+                    entry.commit_hash
+                ),
+            });
+        }
+
+        let old_message = if has_edited_message(entry) {
+            entry.message.clone()
+        } else {
+            messages
+                .get(&entry.id)
+                .map(|message| message.trim_end().to_string())
+                .unwrap_or_else(|| entry.message.clone())
+        };
+        let new_message = if spec.regex {
+            pattern.replace_all(&old_message, spec.replace.as_str())
+        } else {
+            pattern.replace_all(&old_message, NoExpand(&spec.replace))
+        }
+        .into_owned();
+
+        if new_message != old_message {
+            replacements.push(MessageReplacement {
+                entry_id: entry.id.clone(),
+                commit_hash: entry.commit_hash.clone(),
+                old_message,
+                new_message,
+            });
+        }
+    }
+    Ok(replacements)
+}
+
+/// 置換結果を適用し、対象のエントリを新しいメッセージを持つ reword にした todo を返す。
+pub fn apply_message_replacements(
+    file: &RebaseTodoFile,
+    replacements: &[MessageReplacement],
+) -> RebaseTodoFile {
+    let mut result = file.clone();
+    for entry in &mut result.entries {
+        if let Some(replacement) = replacements
+            .iter()
+            .find(|replacement| replacement.entry_id == entry.id)
+        {
+            entry.command = RebaseCommand::Reword;
+            entry.message = replacement.new_message.clone();
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    const TODO: &str = "pick aaa1111 ABC-1: Add parser\npick bbb2222 ABC-2: Fix typo\nexec make\npick ccc3333 Unrelated\n";
+
+    fn ids(file: &RebaseTodoFile) -> Vec<String> {
+        file.entries.iter().map(|entry| entry.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_plan_literal_replacement_uses_full_messages() {
+        let file = parse_rebase_todo(TODO).unwrap();
+        let messages: HashMap<String, String> = [(
+            file.entries[0].id.clone(),
+            "ABC-1: Add parser\n\nSee ABC-1.\n".to_string(),
+        )]
+        .into_iter()
+        .collect();
+        let spec = MessageReplace {
+            find: "ABC-".to_string(),
+            replace: "$XYZ-".to_string(),
+            regex: false,
+        };
+        let selected = vec![file.entries[0].id.clone(), file.entries[3].id.clone()];
+
+        let result = plan_message_replacements(&file, &selected, &messages, &spec).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].old_message, "ABC-1: Add parser\n\nSee ABC-1.");
+        assert_eq!(result[0].new_message, "$XYZ-1: Add parser\n\nSee $XYZ-1.");
+    }
+
+    #[test]
+    fn test_plan_regex_replacement_with_captures() {
+        let file = parse_rebase_todo(TODO).unwrap();
+        let spec = MessageReplace {
+            find: r"^ABC-(\d+): ".to_string(),
+            replace: "feat(ABC-$1): ".to_string(),
+            regex: true,
+        };
+        let selected = vec![file.entries[1].id.clone()];
+
+        let result = plan_message_replacements(&file, &selected, &HashMap::new(), &spec).unwrap();
+
+        assert_eq!(result[0].new_message, "feat(ABC-2): Fix typo");
+    }
+
+    #[test]
+    fn test_plan_rejects_invalid_pattern_and_non_commit_entries() {
+        let file = parse_rebase_todo(TODO).unwrap();
+        let invalid = MessageReplace {
+            find: "(".to_string(),
+            replace: String::new(),
+            regex: true,
+        };
+        let literal = MessageReplace {
+            regex: false,
+            ..invalid.clone()
+        };
+
+        assert!(matches!(
+            plan_message_replacements(&file, &ids(&file), &HashMap::new(), &invalid),
+            Err(AppError::CommandError { .. })
+        ));
+        assert!(matches!(
+            plan_message_replacements(&file, &ids(&file), &HashMap::new(), &literal),
+            Err(AppError::CommandError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_message_replacements_turns_entries_into_rewords() {
+        let file = parse_rebase_todo(TODO).unwrap();
+        let replacements = vec![MessageReplacement {
+            entry_id: file.entries[1].id.clone(),
+            commit_hash: "bbb2222".to_string(),
+            old_message: "ABC-2: Fix typo".to_string(),
+            new_message: "XYZ-2: Fix typo\n\nBody".to_string(),
+        }];
+
+        let result = apply_message_replacements(&file, &replacements);
+
+        assert_eq!(result.entries[0].command, RebaseCommand::Pick);
+        assert_eq!(result.entries[1].command, RebaseCommand::Reword);
+        assert_eq!(result.entries[1].message, "XYZ-2: Fix typo\n\nBody");
+    }
+}
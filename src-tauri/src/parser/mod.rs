@@ -1,8 +1,22 @@
 pub mod commit;
+pub mod commit_lint;
 pub mod conflict;
+pub mod conventional;
 pub mod detector;
+pub mod message_replace;
 pub mod rebase;
+pub mod rebase_exec;
+pub mod rebase_graph;
+pub mod rebase_header;
+pub mod rebase_lint;
+pub mod rebase_plan;
+pub mod rebase_reflog;
+pub mod rebase_update_refs;
+pub mod squash_message;
+pub mod trailer;
 
 pub use conflict::{parse_conflict_markers, ParseConflictsResult};
 pub use detector::{detect_file_type, GitFileType};
-pub use rebase::{parse_rebase_todo, serialize_rebase_todo, RebaseTodoFile};
+pub use rebase::{
+    parse_rebase_todo, serialize_rebase_todo, serialize_rebase_todo_lossless, RebaseTodoFile,
+};
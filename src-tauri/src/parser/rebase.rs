@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use base64::{engine::general_purpose::STANDARD, Engine};
 
+use super::rebase_header::{detect_comment_char, parse_todo_header, TodoHeader};
+use super::rebase_lint::hashes_match;
 use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -22,9 +26,65 @@ pub enum RebaseCommand {
     Merge {
         commit: Option<String>,
         edit_message: bool,
-        label: String,
+        /// マージする label。オクトパスマージでは複数になる。
+        labels: Vec<String>,
         message: Option<String>,
     },
+    /// エディタが解釈できないコマンド。新しい Git が追加した動詞でも todo 全体を
+    /// 読めなくしないよう、前後の空白を除いた行をそのまま保持して書き戻す。
+    Opaque(String),
+}
+
+/// Git のバージョン（major, minor, patch）。
+pub type GitVersion = (u32, u32, u32);
+
+/// `git --version` の出力からバージョンを読む。
+/// `2.39.5.windows.1` や `(Apple Git-143)` のような後続は無視する。
+pub fn parse_git_version(output: &str) -> Option<GitVersion> {
+    let version = output.trim().strip_prefix("git version ")?;
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Git の todo コマンドと、それが追加された Git のバージョン。
+/// 解釈できない動詞を警告するときにも、どの Git 由来かを示すために使う。
+const GIT_TODO_COMMAND_VERSIONS: &[(&str, GitVersion)] = &[
+    ("exec", (1, 7, 3)),
+    ("x", (1, 7, 3)),
+    ("drop", (2, 6, 0)),
+    ("d", (2, 6, 0)),
+    ("label", (2, 18, 0)),
+    ("l", (2, 18, 0)),
+    ("reset", (2, 18, 0)),
+    ("t", (2, 18, 0)),
+    ("merge", (2, 18, 0)),
+    ("m", (2, 18, 0)),
+    ("break", (2, 20, 0)),
+    ("b", (2, 20, 0)),
+    ("update-ref", (2, 38, 0)),
+    ("u", (2, 38, 0)),
+];
+
+/// `fixup -C` / `fixup -c` が追加された Git のバージョン。
+pub const FIXUP_MESSAGE_OPTION_VERSION: GitVersion = (2, 32, 0);
+
+/// 動詞を追加した Git のバージョンが分かれば返す。
+pub fn git_version_for_command(verb: &str) -> Option<GitVersion> {
+    let verb = verb.to_lowercase();
+    GIT_TODO_COMMAND_VERSIONS
+        .iter()
+        .find(|(known, _)| *known == verb)
+        .map(|(_, version)| *version)
+}
+
+/// 保持している行から先頭の動詞を取り出す。
+fn opaque_verb(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or("")
 }
 
 impl RebaseCommand {
@@ -42,6 +102,12 @@ impl RebaseCommand {
         }
     }
 
+    /// 何もしない `noop` か。空の todo に Git が書き出す動詞で、編集の対象ではないため
+    /// 元の行のまま保持するが、解釈できない動詞としては扱わない。
+    pub fn is_noop(&self) -> bool {
+        matches!(self, RebaseCommand::Opaque(line) if opaque_verb(line).eq_ignore_ascii_case("noop"))
+    }
+
     /// 出力用にコマンドを短縮形式へ変換する。
     pub fn to_short(&self) -> &str {
         match self {
@@ -57,6 +123,41 @@ impl RebaseCommand {
             RebaseCommand::Reset(_) => "t",
             RebaseCommand::UpdateRef(_) => "u",
             RebaseCommand::Merge { .. } => "m",
+            RebaseCommand::Opaque(line) => opaque_verb(line),
+        }
+    }
+
+    /// 出力用にコマンドをフル形式へ変換する。
+    pub fn to_long(&self) -> &str {
+        match self {
+            RebaseCommand::Pick => "pick",
+            RebaseCommand::Reword => "reword",
+            RebaseCommand::Edit => "edit",
+            RebaseCommand::Squash => "squash",
+            RebaseCommand::Fixup => "fixup",
+            RebaseCommand::Drop => "drop",
+            RebaseCommand::Exec(_) => "exec",
+            RebaseCommand::Break => "break",
+            RebaseCommand::Label(_) => "label",
+            RebaseCommand::Reset(_) => "reset",
+            RebaseCommand::UpdateRef(_) => "update-ref",
+            RebaseCommand::Merge { .. } => "merge",
+            RebaseCommand::Opaque(line) => opaque_verb(line),
+        }
+    }
+
+    /// コマンドを解釈できる最も古い Git のバージョン。古くからあるコマンドや、
+    /// 追加されたバージョンが分からない動詞は None。
+    pub fn required_git_version(&self) -> Option<GitVersion> {
+        git_version_for_command(self.to_long())
+    }
+
+    /// `rebase.abbreviateCommands` に合わせた綴りを返す。
+    fn spelling(&self, abbreviate: bool) -> &str {
+        if abbreviate {
+            self.to_short()
+        } else {
+            self.to_long()
         }
     }
 }
@@ -69,6 +170,36 @@ pub struct RebaseEntry {
     pub fixup_option: Option<String>,
     pub commit_hash: String,
     pub message: String,
+    /// 解析元の行そのもの。ロスレス出力で未変更エントリを元の綴りのまま書き戻すために使う。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_line: Option<String>,
+    /// このエントリの直前にあった空行・コメント行。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub leading_lines: Vec<String>,
+    /// コミット後に `git commit --amend` で書き換えるメタデータ。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_edit: Option<MetadataEdit>,
+}
+
+/// エントリのコミットに対する author・日時・sign-off の書き換え。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MetadataEdit {
+    /// `Name <email>` 形式の author。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// author date と committer date の両方に設定する、git が解釈できる日時。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// `Signed-off-by` を追加する。
+    #[serde(default)]
+    pub signoff: bool,
+}
+
+impl MetadataEdit {
+    /// 書き換える項目がないかを返す。
+    pub fn is_empty(&self) -> bool {
+        self.author.is_none() && self.date.is_none() && !self.signoff
+    }
 }
 
 impl RebaseEntry {
@@ -79,6 +210,29 @@ impl RebaseEntry {
             fixup_option: None,
             commit_hash,
             message,
+            raw_line: None,
+            leading_lines: Vec::new(),
+            metadata_edit: None,
+        }
+    }
+
+    /// エントリを解釈できる最も古い Git のバージョン。`fixup -C` / `-c` はコマンドより新しい。
+    pub fn required_git_version(&self) -> Option<GitVersion> {
+        if self.command == RebaseCommand::Fixup && self.fixup_option.is_some() {
+            return Some(FIXUP_MESSAGE_OPTION_VERSION);
+        }
+        self.command.required_git_version()
+    }
+
+    /// エントリが参照する元コミットを返す（merge は `-C`/`-c` のコミット）。
+    pub fn referenced_commit(&self) -> Option<&str> {
+        match &self.command {
+            RebaseCommand::Merge {
+                commit: Some(commit),
+                ..
+            } => Some(commit.as_str()),
+            _ if !self.commit_hash.is_empty() => Some(self.commit_hash.as_str()),
+            _ => None,
         }
     }
 }
@@ -87,13 +241,54 @@ impl RebaseEntry {
 pub struct RebaseTodoFile {
     pub entries: Vec<RebaseEntry>,
     pub comments: Vec<String>,
+    /// 最後のエントリより後ろにあった空行・コメント行（ロスレス出力用）。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trailing_lines: Vec<String>,
+    /// コメントから読み取った範囲・onto・コマンド数とコメント文字。
+    #[serde(default)]
+    pub header: TodoHeader,
+}
+
+impl RebaseTodoFile {
+    /// `remove` に当てはまるエントリを取り除いた todo を返す。
+    /// 取り除いた行の直前にあったコメント・空行は次のエントリへ引き継ぐ。
+    pub fn without_entries(&self, remove: impl Fn(&RebaseEntry) -> bool) -> RebaseTodoFile {
+        let mut entries: Vec<RebaseEntry> = Vec::with_capacity(self.entries.len());
+        let mut carried: Vec<String> = Vec::new();
+        for entry in &self.entries {
+            if remove(entry) {
+                carried.extend(entry.leading_lines.iter().cloned());
+                continue;
+            }
+            let mut entry = entry.clone();
+            if !carried.is_empty() {
+                carried.append(&mut entry.leading_lines);
+                entry.leading_lines = std::mem::take(&mut carried);
+            }
+            entries.push(entry);
+        }
+
+        let mut trailing_lines = carried;
+        trailing_lines.extend(self.trailing_lines.iter().cloned());
+        RebaseTodoFile {
+            entries,
+            comments: self.comments.clone(),
+            trailing_lines,
+            header: self.header.clone(),
+        }
+    }
 }
 
 /// git-rebase-todo の内容を解析する。
+///
+/// コメント行は `core.commentChar` に合わせ、内容から推定したコメント文字で判定する。
 pub fn parse_rebase_todo(content: &str) -> Result<RebaseTodoFile, AppError> {
+    let comment_char = detect_comment_char(content);
     let mut entries = Vec::new();
     let mut comments = Vec::new();
     let mut in_comments_section = false;
+    // 次のエントリの直前に置かれていた空行・コメント行。
+    let mut pending_lines = Vec::new();
 
     for (line_num, line) in content.lines().enumerate() {
         let trimmed = line.trim();
@@ -103,116 +298,127 @@ pub fn parse_rebase_todo(content: &str) -> Result<RebaseTodoFile, AppError> {
             if in_comments_section {
                 comments.push(String::new());
             }
+            pending_lines.push(line.to_string());
             continue;
         }
 
         // コメント行を扱う。
-        if trimmed.starts_with('#') {
+        if trimmed.starts_with(comment_char.as_str()) {
             in_comments_section = true;
             comments.push(line.to_string());
+            pending_lines.push(line.to_string());
             continue;
         }
 
-        // コマンド行を解析する。
-        let parts: Vec<&str> = trimmed.splitn(3, char::is_whitespace).collect();
-
-        if parts.is_empty() {
-            continue;
-        }
-
-        let command_str = parts[0];
-
-        // 特殊コマンドを扱う。
-        match command_str.to_lowercase().as_str() {
-            "exec" | "x" => {
-                let exec_command = if parts.len() > 1 {
-                    parts[1..].join(" ")
-                } else {
-                    String::new()
-                };
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::Exec(exec_command),
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            "break" | "b" => {
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::Break,
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            "label" | "l" => {
-                let label = parts.get(1).unwrap_or(&"").to_string();
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::Label(label),
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            "reset" | "t" => {
-                let label = parts.get(1).unwrap_or(&"").to_string();
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::Reset(label),
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            "update-ref" | "u" => {
-                let ref_name = parts.get(1).unwrap_or(&"").to_string();
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::UpdateRef(ref_name),
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            "merge" | "m" => {
-                // 構文: merge [-C <commit> | -c <commit>] <label> [# <oneline>]
-                let rest = if parts.len() > 1 {
-                    parts[1..].join(" ")
-                } else {
-                    String::new()
-                };
-                let (commit, edit_message, label, message) = parse_merge_args(&rest);
-                entries.push(RebaseEntry::new(
-                    RebaseCommand::Merge {
-                        commit,
-                        edit_message,
-                        label,
-                        message,
-                    },
-                    String::new(),
-                    String::new(),
-                ));
-                continue;
-            }
-            _ => {}
-        }
-
-        // 標準コマンド: <command> <hash> <message>
-        let command = RebaseCommand::from_str(command_str).ok_or_else(|| AppError::ParseError {
+        let mut entry = parse_command_line(trimmed).map_err(|message| AppError::ParseError {
             line: line_num + 1,
-            message: format!("Unknown command: {}", command_str),
+            message,
         })?;
+        entry.raw_line = Some(line.to_string());
+        entry.leading_lines = std::mem::take(&mut pending_lines);
+        entries.push(entry);
+    }
 
-        let rest = trimmed
-            .strip_prefix(command_str)
-            .unwrap_or_default()
-            .trim_start();
-        let (fixup_option, commit_hash, message) = parse_commit_args(&command, rest);
+    let header = parse_todo_header(&comments, &pending_lines, &comment_char);
+    Ok(RebaseTodoFile {
+        entries,
+        comments,
+        trailing_lines: pending_lines,
+        header,
+    })
+}
 
-        let mut entry = RebaseEntry::new(command, commit_hash, message);
-        entry.fixup_option = fixup_option;
-        entries.push(entry);
+/// 前後の空白を除いたコマンド行を 1 エントリに解析する。
+fn parse_command_line(trimmed: &str) -> Result<RebaseEntry, String> {
+    let parts: Vec<&str> = trimmed.splitn(3, char::is_whitespace).collect();
+    let command_str = parts[0];
+
+    // 特殊コマンドを扱う。
+    match command_str.to_lowercase().as_str() {
+        "exec" | "x" => {
+            let exec_command = if parts.len() > 1 {
+                parts[1..].join(" ")
+            } else {
+                String::new()
+            };
+            return Ok(RebaseEntry::new(
+                RebaseCommand::Exec(exec_command),
+                String::new(),
+                String::new(),
+            ));
+        }
+        "break" | "b" => {
+            return Ok(RebaseEntry::new(
+                RebaseCommand::Break,
+                String::new(),
+                String::new(),
+            ));
+        }
+        "label" | "l" => {
+            let label = parts.get(1).unwrap_or(&"").to_string();
+            return Ok(RebaseEntry::new(
+                RebaseCommand::Label(label),
+                String::new(),
+                String::new(),
+            ));
+        }
+        "reset" | "t" => {
+            let label = parts.get(1).unwrap_or(&"").to_string();
+            return Ok(RebaseEntry::new(
+                RebaseCommand::Reset(label),
+                String::new(),
+                String::new(),
+            ));
+        }
+        "update-ref" | "u" => {
+            let ref_name = parts.get(1).unwrap_or(&"").to_string();
+            return Ok(RebaseEntry::new(
+                RebaseCommand::UpdateRef(ref_name),
+                String::new(),
+                String::new(),
+            ));
+        }
+        "merge" | "m" => {
+            // 構文: merge [-C <commit> | -c <commit>] <label>... [# <oneline>]
+            let rest = if parts.len() > 1 {
+                parts[1..].join(" ")
+            } else {
+                String::new()
+            };
+            let (commit, edit_message, labels, message) = parse_merge_args(&rest);
+            return Ok(RebaseEntry::new(
+                RebaseCommand::Merge {
+                    commit,
+                    edit_message,
+                    labels,
+                    message,
+                },
+                String::new(),
+                String::new(),
+            ));
+        }
+        _ => {}
     }
 
-    Ok(RebaseTodoFile { entries, comments })
+    // 標準コマンド: <command> <hash> <message>
+    // 解釈できない動詞は失敗させず、行ごと保持して書き戻す。
+    let Some(command) = RebaseCommand::from_str(command_str) else {
+        return Ok(RebaseEntry::new(
+            RebaseCommand::Opaque(trimmed.to_string()),
+            String::new(),
+            String::new(),
+        ));
+    };
+
+    let rest = trimmed
+        .strip_prefix(command_str)
+        .unwrap_or_default()
+        .trim_start();
+    let (fixup_option, commit_hash, message) = parse_commit_args(&command, rest);
+
+    let mut entry = RebaseEntry::new(command, commit_hash, message);
+    entry.fixup_option = fixup_option;
+    Ok(entry)
 }
 
 /// 空白区切りの次トークンと残りを分離する。
@@ -250,10 +456,10 @@ fn parse_commit_args(command: &RebaseCommand, args: &str) -> (Option<String>, St
 }
 
 /// merge コマンドの引数を解析する。
-fn parse_merge_args(args: &str) -> (Option<String>, bool, String, Option<String>) {
+fn parse_merge_args(args: &str) -> (Option<String>, bool, Vec<String>, Option<String>) {
     let mut commit = None;
     let mut edit_message = false;
-    let mut label = String::new();
+    let mut labels = Vec::new();
     let mut message = None;
 
     let parts: Vec<&str> = args.split_whitespace().collect();
@@ -281,109 +487,490 @@ fn parse_merge_args(args: &str) -> (Option<String>, bool, String, Option<String>
             break;
         }
 
-        if label.is_empty() {
-            label = part.to_string();
-        }
-
+        labels.push(part.to_string());
         i += 1;
     }
 
-    (commit, edit_message, label, message)
+    (commit, edit_message, labels, message)
 }
 
 /// RebaseTodoFile を git-rebase-todo 形式へ戻す。
 pub fn serialize_rebase_todo(file: &RebaseTodoFile) -> String {
     let mut lines = Vec::new();
 
+    let mut pending_edits = Vec::new();
+    for entry in &file.entries {
+        if !continues_chain(entry) {
+            lines.append(&mut pending_edits);
+        }
+        lines.extend(entry_lines(entry, true, RewordMode::ExecAmend));
+        pending_edits.extend(metadata_edit_line(entry, true));
+    }
+    lines.append(&mut pending_edits);
+
+    // コメントを末尾に追加する。
+    if !file.comments.is_empty() {
+        lines.push(String::new()); // コメントの前に空行を入れる。
+        lines.extend(file.comments.clone());
+    }
+
+    lines.join("\n")
+}
+
+/// RebaseTodoFile を元の書式を保ったまま git-rebase-todo 形式へ戻す。
+///
+/// 未変更のエントリは解析元の行をそのまま出力し、空行やコメントも元の位置に戻す。
+/// 変更・追加されたエントリだけを `rebase.abbreviateCommands` に従った綴りで書き直す。
+/// 位置情報を持つ `leading_lines` / `trailing_lines` を使うため、`comments` は参照しない。
+pub fn serialize_rebase_todo_lossless(file: &RebaseTodoFile, options: &SerializeOptions) -> String {
+    let mut lines = Vec::new();
+
+    let mut pending_edits = Vec::new();
     for entry in &file.entries {
-        match &entry.command {
-            RebaseCommand::Reword => {
-                // reword はエディタを開かずにメッセージを適用するため pick + exec に変換する。
-                let subject = entry.message.lines().next().unwrap_or(&entry.message);
-                lines.push(format!("pick {} {}", entry.commit_hash, subject));
-
-                // 複数行メッセージをシェル経由で安全に渡すため base64 化する。
-                // base64 文字列は [A-Za-z0-9+/=] のみなので、改行・引用符・特殊文字は
-                // すべて encoded 側に隔離され、todo の行構造を壊さない。
-                // デコードフラグは GNU coreutils が `-d`、BSD(macOS) が `-D` と異なるため、
-                // `-d` が使えるか先に判定して両環境に対応する。stdin 供給は実装差のある
-                // echo ではなく printf '%s' を用いる。--quiet は出力抑制、--no-edit は
-                // エディタ起動を防ぐ。
-                let encoded = STANDARD.encode(&entry.message);
-                lines.push(format!(
+        if !continues_chain(entry) {
+            lines.append(&mut pending_edits);
+        }
+        lines.extend(entry.leading_lines.iter().cloned());
+        match entry.raw_line.as_deref() {
+            Some(raw_line) if is_unchanged(entry, raw_line) => lines.push(raw_line.to_string()),
+            _ => lines.extend(entry_lines(
+                entry,
+                options.abbreviate_commands,
+                options.reword_mode,
+            )),
+        }
+        pending_edits.extend(metadata_edit_line(entry, options.abbreviate_commands));
+    }
+    lines.append(&mut pending_edits);
+    lines.extend(file.trailing_lines.iter().cloned());
+
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    // Git は todo を改行終端で書くため、ロスレス出力も末尾改行を付ける。
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
+
+/// reword の出力方式。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewordMode {
+    /// `pick` と `exec ... git commit --amend` に展開し、エディタを開かずに適用する。
+    #[default]
+    ExecAmend,
+    /// `reword` 行のまま出力する。新しいメッセージは別途キューに保存し、
+    /// Git が COMMIT_EDITMSG を開いたときに補完する。
+    Native,
+}
+
+/// ロスレス出力の書式設定。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerializeOptions {
+    /// `rebase.abbreviateCommands` の値。変更・追加されたエントリの綴りに使う。
+    pub abbreviate_commands: bool,
+    pub reword_mode: RewordMode,
+}
+
+/// 解析元の行を読み直した結果と比べ、エントリがユーザーに変更されていないかを返す。
+fn is_unchanged(entry: &RebaseEntry, raw_line: &str) -> bool {
+    match parse_command_line(raw_line.trim()) {
+        Ok(original) => {
+            original.command == entry.command
+                && original.fixup_option == entry.fixup_option
+                && original.commit_hash == entry.commit_hash
+                && original.message == entry.message
+        }
+        Err(_) => false,
+    }
+}
+
+/// エントリのメッセージが解析元の todo 行から変更されているかを返す。
+/// 変更されていなければ、メッセージは todo 上の subject だけを持つ。
+pub fn has_edited_message(entry: &RebaseEntry) -> bool {
+    match entry.raw_line.as_deref() {
+        Some(raw_line) => parse_command_line(raw_line.trim())
+            .map(|original| original.message != entry.message)
+            .unwrap_or(true),
+        None => !entry.message.is_empty(),
+    }
+}
+
+/// ネイティブ reword でキューへ保存すべき (コミットハッシュ, 新しいメッセージ) を返す。
+///
+/// todo 上の subject しか持たない未編集の reword をキューに入れると本文が失われるため、
+/// メッセージが解析元から変更されたエントリだけを対象にする。
+pub fn planned_rewords(file: &RebaseTodoFile) -> Vec<(String, String)> {
+    file.entries
+        .iter()
+        .filter(|entry| entry.command == RebaseCommand::Reword && !entry.commit_hash.is_empty())
+        .filter(|entry| has_edited_message(entry))
+        .map(|entry| (entry.commit_hash.clone(), entry.message.clone()))
+        .collect()
+}
+
+/// シェルの単一引用符で囲む。todo は 1 行 1 コマンドのため、改行は空白に置き換える。
+fn shell_quote(value: &str) -> String {
+    let single_line = value.replace(['\r', '\n'], " ");
+    format!("'{}'", single_line.replace('\'', "'\\''"))
+}
+
+/// squash/fixup の連鎖の途中にあるエントリ。メタデータの書き換えは連鎖の後ろまで遅らせる。
+fn continues_chain(entry: &RebaseEntry) -> bool {
+    matches!(entry.command, RebaseCommand::Squash | RebaseCommand::Fixup)
+}
+
+/// メタデータの書き換えを適用する `exec git commit --amend` 行を返す。
+/// コミットを作らないエントリや、書き換える項目がない場合は None。
+fn metadata_edit_line(entry: &RebaseEntry, abbreviate: bool) -> Option<String> {
+    let edit = entry
+        .metadata_edit
+        .as_ref()
+        .filter(|edit| !edit.is_empty())?;
+    if !matches!(
+        entry.command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Squash
+            | RebaseCommand::Fixup
+            | RebaseCommand::Merge { .. }
+    ) {
+        return None;
+    }
+
+    let mut parts = vec![RebaseCommand::Exec(String::new())
+        .spelling(abbreviate)
+        .to_string()];
+    // --date は author date だけを変えるため、committer date は環境変数で揃える。
+    if let Some(date) = &edit.date {
+        parts.push(format!("GIT_COMMITTER_DATE={}", shell_quote(date)));
+    }
+    parts.push("git commit --amend --no-edit --quiet".to_string());
+    if let Some(author) = &edit.author {
+        parts.push(format!("--author={}", shell_quote(author)));
+    }
+    if let Some(date) = &edit.date {
+        parts.push(format!("--date={}", shell_quote(date)));
+    }
+    if edit.signoff {
+        parts.push("--signoff".to_string());
+    }
+    Some(parts.join(" "))
+}
+
+/// 1 エントリ分の todo 行を生成する。
+fn entry_lines(entry: &RebaseEntry, abbreviate: bool, reword_mode: RewordMode) -> Vec<String> {
+    let name = entry.command.spelling(abbreviate);
+
+    match &entry.command {
+        RebaseCommand::Reword if reword_mode == RewordMode::Native => {
+            // メッセージ本体はキューで渡すため、todo には subject だけを書く。
+            let subject = entry.message.lines().next().unwrap_or(&entry.message);
+            vec![format!("{} {} {}", name, entry.commit_hash, subject)]
+        }
+        RebaseCommand::Reword => {
+            // reword はエディタを開かずにメッセージを適用するため pick + exec に変換する。
+            let subject = entry.message.lines().next().unwrap_or(&entry.message);
+
+            // 複数行メッセージをシェル経由で安全に渡すため base64 化する。
+            // base64 文字列は [A-Za-z0-9+/=] のみなので、改行・引用符・特殊文字は
+            // すべて encoded 側に隔離され、todo の行構造を壊さない。
+            // デコードフラグは GNU coreutils が `-d`、BSD(macOS) が `-D` と異なるため、
+            // `-d` が使えるか先に判定して両環境に対応する。stdin 供給は実装差のある
+            // echo ではなく printf '%s' を用いる。--quiet は出力抑制、--no-edit は
+            // エディタ起動を防ぐ。
+            let encoded = STANDARD.encode(&entry.message);
+            vec![
+                format!("pick {} {}", entry.commit_hash, subject),
+                format!(
                     "exec b64='{}'; if base64 -d </dev/null >/dev/null 2>&1; then printf '%s' \"$b64\" | base64 -d; else printf '%s' \"$b64\" | base64 -D; fi | git commit --amend --quiet --no-edit -F -",
                     encoded
-                ));
+                ),
+            ]
+        }
+        RebaseCommand::Pick | RebaseCommand::Edit | RebaseCommand::Squash | RebaseCommand::Drop => {
+            // git-rebase-todo は 1 行形式なので subject 行だけを出力する。
+            let subject = entry.message.lines().next().unwrap_or(&entry.message);
+            vec![format!("{} {} {}", name, entry.commit_hash, subject)]
+        }
+        RebaseCommand::Fixup => {
+            // fixup -C/-c は元の todo の意味を変えないよう保持する。
+            let subject = entry.message.lines().next().unwrap_or(&entry.message);
+            if let Some(option) = entry
+                .fixup_option
+                .as_deref()
+                .filter(|option| *option == "-C" || *option == "-c")
+            {
+                vec![format!(
+                    "{} {} {} {}",
+                    name, option, entry.commit_hash, subject
+                )]
+            } else {
+                vec![format!("{} {} {}", name, entry.commit_hash, subject)]
+            }
+        }
+        RebaseCommand::Exec(cmd) => vec![format!("{} {}", name, cmd)],
+        RebaseCommand::Break => vec![name.to_string()],
+        RebaseCommand::Label(label) => vec![format!("{} {}", name, label)],
+        RebaseCommand::Reset(label) => vec![format!("{} {}", name, label)],
+        RebaseCommand::UpdateRef(ref_name) => vec![format!("{} {}", name, ref_name)],
+        RebaseCommand::Merge {
+            commit,
+            edit_message,
+            labels,
+            message,
+        } => {
+            let mut parts = vec![name.to_string()];
+            if let Some(c) = commit {
+                let option = if *edit_message { "-c" } else { "-C" };
+                parts.push(format!("{} {}", option, c));
+            }
+            parts.extend(labels.iter().cloned());
+            if let Some(msg) = message {
+                parts.push(format!("# {}", msg));
             }
-            RebaseCommand::Pick
+            vec![parts.join(" ")]
+        }
+        RebaseCommand::Opaque(line) => vec![line.clone()],
+    }
+}
+
+/// autosquash で移動またはコマンドを変更したエントリ。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutosquashMove {
+    pub entry_id: String,
+    pub commit_hash: String,
+    pub target_id: String,
+    pub target_hash: String,
+    pub command: RebaseCommand,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixup_option: Option<String>,
+    pub from_index: usize,
+    pub to_index: usize,
+}
+
+/// autosquash を適用した todo と、その変更内容。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutosquashPlan {
+    pub file: RebaseTodoFile,
+    pub moves: Vec<AutosquashMove>,
+    /// 対象コミットが見つからなかった fixup!/squash!/amend! エントリの ID。
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FixupKind {
+    Fixup,
+    Squash,
+    Amend,
+}
+
+const FIXUP_PREFIXES: &[(&str, FixupKind)] = &[
+    ("fixup! ", FixupKind::Fixup),
+    ("squash! ", FixupKind::Squash),
+    ("amend! ", FixupKind::Amend),
+];
+
+/// subject 先頭の `fixup! ` などを取り除き、種別と対象の subject を返す。
+/// `fixup! fixup! A` のような入れ子は、最初の接頭辞で種別を決めて A を対象にする。
+pub(crate) fn strip_fixup_prefix(subject: &str) -> Option<(FixupKind, &str)> {
+    let (kind, mut rest) = strip_one_fixup_prefix(subject)?;
+    while let Some((_, inner)) = strip_one_fixup_prefix(rest) {
+        rest = inner;
+    }
+    Some((kind, rest))
+}
+
+fn strip_one_fixup_prefix(text: &str) -> Option<(FixupKind, &str)> {
+    FIXUP_PREFIXES
+        .iter()
+        .find_map(|(prefix, kind)| text.strip_prefix(prefix).map(|rest| (*kind, rest)))
+}
+
+/// autosquash の対象になる、コミットを取り込むエントリかどうか。
+fn is_autosquash_candidate(entry: &RebaseEntry) -> bool {
+    matches!(
+        entry.command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
             | RebaseCommand::Edit
             | RebaseCommand::Squash
-            | RebaseCommand::Drop => {
-                // git-rebase-todo は 1 行形式なので subject 行だけを出力する。
-                let subject = entry.message.lines().next().unwrap_or(&entry.message);
-                lines.push(format!(
-                    "{} {} {}",
-                    entry.command.to_short(),
-                    entry.commit_hash,
-                    subject
-                ));
+            | RebaseCommand::Fixup
+    ) && !entry.commit_hash.is_empty()
+}
+
+fn entry_subject(entry: &RebaseEntry) -> &str {
+    entry.message.lines().next().unwrap_or("")
+}
+
+/// `index` より前のエントリから対象を探す。
+/// Git と同じく subject の完全一致、コミットハッシュ、subject の前方一致の順に試す。
+fn find_autosquash_target(entries: &[RebaseEntry], index: usize, target: &str) -> Option<usize> {
+    let candidates = || {
+        entries[..index]
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| is_autosquash_candidate(entry))
+    };
+
+    if let Some((found, _)) = candidates().find(|(_, entry)| entry_subject(entry) == target) {
+        return Some(found);
+    }
+
+    let looks_like_hash = target.len() >= 4 && target.chars().all(|c| c.is_ascii_hexdigit());
+    if looks_like_hash {
+        if let Some((found, _)) =
+            candidates().find(|(_, entry)| hashes_match(&entry.commit_hash, target))
+        {
+            return Some(found);
+        }
+    }
+
+    if target.is_empty() {
+        return None;
+    }
+    candidates()
+        .find(|(_, entry)| entry_subject(entry).starts_with(target))
+        .map(|(found, _)| found)
+}
+
+/// `amend! ` コミットのメッセージから、Git の `fixup -C` と同じく 1 行目の `amend! ...` と
+/// 続く空行を除いたものを返す。
+pub(crate) fn amend_message(message: &str) -> &str {
+    message
+        .split_once('\n')
+        .map(|(_, rest)| rest.trim_start_matches('\n'))
+        .unwrap_or("")
+}
+
+/// `fixup! `・`squash! `・`amend! ` で始まるコミットを対象コミットの直後へ移動し、
+/// コマンドを fixup・squash・`fixup -C` に書き換える。
+///
+/// `--autosquash` なしで開始した rebase にも適用できるよう、todo の内容だけから判断する。
+/// 対象は Git と同じく todo 上で前にあるコミットに限る。fixup の fixup は最初の対象へまとめる。
+///
+/// `git_version` が `fixup -C` に対応しない場合、amend! は素の fixup にし、対象を amend! の
+/// メッセージを持つ reword にする。`messages` はエントリ id ごとの完全なメッセージで、
+/// このときだけ使う。対象が pick / reword 以外の場合は reword にできないため `fixup -C` のままにする。
+pub fn plan_autosquash(
+    file: &RebaseTodoFile,
+    git_version: Option<GitVersion>,
+    messages: &HashMap<String, String>,
+) -> AutosquashPlan {
+    let entries = &file.entries;
+    let count = entries.len();
+    // fixup 系エントリが移動する先（連鎖をたどった最初の対象）のインデックス。
+    let mut roots: Vec<Option<(usize, FixupKind)>> = vec![None; count];
+    let mut unmatched = Vec::new();
+
+    for index in 0..count {
+        let entry = &entries[index];
+        if !is_autosquash_candidate(entry) {
+            continue;
+        }
+        let Some((kind, target)) = strip_fixup_prefix(entry_subject(entry)) else {
+            continue;
+        };
+        match find_autosquash_target(entries, index, target) {
+            Some(found) => {
+                let root = roots[found].map(|(root, _)| root).unwrap_or(found);
+                roots[index] = Some((root, kind));
             }
-            RebaseCommand::Fixup => {
-                // fixup -C/-c は元の todo の意味を変えないよう保持する。
-                let subject = entry.message.lines().next().unwrap_or(&entry.message);
-                if let Some(option) = entry
-                    .fixup_option
-                    .as_deref()
-                    .filter(|option| *option == "-C" || *option == "-c")
-                {
-                    lines.push(format!(
-                        "{} {} {} {}",
-                        entry.command.to_short(),
-                        option,
-                        entry.commit_hash,
-                        subject
-                    ));
-                } else {
-                    lines.push(format!(
-                        "{} {} {}",
-                        entry.command.to_short(),
-                        entry.commit_hash,
-                        subject
-                    ));
+            None => unmatched.push(entry.id.clone()),
+        }
+    }
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for (index, root) in roots.iter().enumerate() {
+        if let Some((root, _)) = root {
+            children[*root].push(index);
+        }
+    }
+
+    let mut order = Vec::with_capacity(count);
+    for index in 0..count {
+        if roots[index].is_none() {
+            order.push(index);
+            order.extend(&children[index]);
+        }
+    }
+
+    let supports_fixup_option =
+        git_version.is_none_or(|version| version >= FIXUP_MESSAGE_OPTION_VERSION);
+    let uses_fixup_option = |root: usize| {
+        supports_fixup_option
+            || !matches!(
+                entries[root].command,
+                RebaseCommand::Pick | RebaseCommand::Reword
+            )
+    };
+
+    // fixup -C を使えない場合に、対象へ reword で適用する amend! のメッセージ。
+    // fixup -C と同じく、後の amend! が前のものを置き換える。
+    let mut rewords: HashMap<usize, &str> = HashMap::new();
+    for &index in &order {
+        if let Some((root, FixupKind::Amend)) = roots[index] {
+            if !uses_fixup_option(root) {
+                let entry = &entries[index];
+                let message = amend_message(messages.get(&entry.id).unwrap_or(&entry.message));
+                if !message.trim().is_empty() {
+                    rewords.insert(root, message);
                 }
             }
-            RebaseCommand::Exec(cmd) => lines.push(format!("x {}", cmd)),
-            RebaseCommand::Break => lines.push("b".to_string()),
-            RebaseCommand::Label(label) => lines.push(format!("l {}", label)),
-            RebaseCommand::Reset(label) => lines.push(format!("t {}", label)),
-            RebaseCommand::UpdateRef(ref_name) => lines.push(format!("u {}", ref_name)),
-            RebaseCommand::Merge {
-                commit,
-                edit_message,
-                label,
-                message,
-            } => {
-                let mut parts = vec!["m".to_string()];
-                if let Some(c) = commit {
-                    let option = if *edit_message { "-c" } else { "-C" };
-                    parts.push(format!("{} {}", option, c));
-                }
-                parts.push(label.clone());
-                if let Some(msg) = message {
-                    parts.push(format!("# {}", msg));
+        }
+    }
+
+    let mut new_entries = Vec::with_capacity(count);
+    let mut moves = Vec::new();
+    for (to_index, &from_index) in order.iter().enumerate() {
+        let mut entry = entries[from_index].clone();
+        if let Some(message) = rewords.get(&from_index) {
+            entry.command = RebaseCommand::Reword;
+            entry.message = message.to_string();
+        }
+        if let Some((root, kind)) = roots[from_index] {
+            let (command, fixup_option) = match kind {
+                FixupKind::Fixup => (RebaseCommand::Fixup, None),
+                FixupKind::Squash => (RebaseCommand::Squash, None),
+                FixupKind::Amend if uses_fixup_option(root) => {
+                    (RebaseCommand::Fixup, Some("-C".to_string()))
                 }
-                lines.push(parts.join(" "));
+                FixupKind::Amend => (RebaseCommand::Fixup, None),
+            };
+            let changed = from_index != to_index
+                || entry.command != command
+                || entry.fixup_option != fixup_option;
+            entry.command = command;
+            entry.fixup_option = fixup_option;
+            if changed {
+                moves.push(AutosquashMove {
+                    entry_id: entry.id.clone(),
+                    commit_hash: entry.commit_hash.clone(),
+                    target_id: entries[root].id.clone(),
+                    target_hash: entries[root].commit_hash.clone(),
+                    command: entry.command.clone(),
+                    fixup_option: entry.fixup_option.clone(),
+                    from_index,
+                    to_index,
+                });
             }
         }
+        new_entries.push(entry);
     }
 
-    // コメントを末尾に追加する。
-    if !file.comments.is_empty() {
-        lines.push(String::new()); // コメントの前に空行を入れる。
-        lines.extend(file.comments.clone());
+    AutosquashPlan {
+        file: RebaseTodoFile {
+            entries: new_entries,
+            comments: file.comments.clone(),
+            trailing_lines: file.trailing_lines.clone(),
+            header: file.header.clone(),
+        },
+        moves,
+        unmatched,
     }
-
-    lines.join("\n")
 }
 
 #[cfg(test)]
@@ -450,7 +1037,7 @@ squash ghi9012 Third commit
             RebaseCommand::Merge {
                 commit: Some("abc1234".to_string()),
                 edit_message: true,
-                label: "feature-label".to_string(),
+                labels: vec!["feature-label".to_string()],
                 message: Some("merge subject".to_string()),
             },
         );
@@ -467,12 +1054,32 @@ squash ghi9012 Third commit
             RebaseCommand::Merge {
                 commit: Some("abc1234".to_string()),
                 edit_message: false,
-                label: "feature-label".to_string(),
+                labels: vec!["feature-label".to_string()],
                 message: Some("merge subject".to_string()),
             },
         );
     }
 
+    #[test]
+    fn test_parse_octopus_merge_keeps_every_label() {
+        let content = "merge -C abc1234 one two three # Merge branches\n";
+        let result = parse_rebase_todo(content).unwrap();
+
+        assert_eq!(
+            result.entries[0].command,
+            RebaseCommand::Merge {
+                commit: Some("abc1234".to_string()),
+                edit_message: false,
+                labels: vec!["one".to_string(), "two".to_string(), "three".to_string()],
+                message: Some("Merge branches".to_string()),
+            },
+        );
+        assert_eq!(
+            serialize_rebase_todo(&result),
+            "m -C abc1234 one two three # Merge branches",
+        );
+    }
+
     #[test]
     fn test_parse_fixup_commit_option_preserves_real_commit_hash() {
         let content = "fixup -C abc1234 # amend! target commit\n";
@@ -517,14 +1124,19 @@ squash ghi9012 Third commit
                 command: RebaseCommand::Merge {
                     commit: Some("abc1234".to_string()),
                     edit_message: false,
-                    label: "feature-label".to_string(),
+                    labels: vec!["feature-label".to_string()],
                     message: Some("merge subject".to_string()),
                 },
                 fixup_option: None,
                 commit_hash: String::new(),
                 message: String::new(),
+                raw_line: None,
+                leading_lines: Vec::new(),
+                metadata_edit: None,
             }],
             comments: vec![],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         assert_eq!(
@@ -541,14 +1153,19 @@ squash ghi9012 Third commit
                 command: RebaseCommand::Merge {
                     commit: Some("abc1234".to_string()),
                     edit_message: true,
-                    label: "feature-label".to_string(),
+                    labels: vec!["feature-label".to_string()],
                     message: Some("merge subject".to_string()),
                 },
                 fixup_option: None,
                 commit_hash: String::new(),
                 message: String::new(),
+                raw_line: None,
+                leading_lines: Vec::new(),
+                metadata_edit: None,
             }],
             comments: vec![],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         assert_eq!(
@@ -567,6 +1184,9 @@ squash ghi9012 Third commit
                     fixup_option: None,
                     commit_hash: "abc1234".to_string(),
                     message: "First commit".to_string(),
+                    raw_line: None,
+                    leading_lines: Vec::new(),
+                    metadata_edit: None,
                 },
                 RebaseEntry {
                     id: "2".to_string(),
@@ -574,9 +1194,14 @@ squash ghi9012 Third commit
                     fixup_option: None,
                     commit_hash: "def5678".to_string(),
                     message: "Second commit".to_string(),
+                    raw_line: None,
+                    leading_lines: Vec::new(),
+                    metadata_edit: None,
                 },
             ],
             comments: vec!["# Comment".to_string()],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         let output = serialize_rebase_todo(&file);
@@ -594,8 +1219,13 @@ squash ghi9012 Third commit
                 fixup_option: Some("-C".to_string()),
                 commit_hash: "abc1234".to_string(),
                 message: "# amend! target commit".to_string(),
+                raw_line: None,
+                leading_lines: Vec::new(),
+                metadata_edit: None,
             }],
             comments: vec![],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         assert_eq!(
@@ -613,8 +1243,13 @@ squash ghi9012 Third commit
                 fixup_option: None,
                 commit_hash: String::new(),
                 message: String::new(),
+                raw_line: None,
+                leading_lines: Vec::new(),
+                metadata_edit: None,
             }],
             comments: vec![],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         assert_eq!(serialize_rebase_todo(&file), "u refs/heads/feature");
@@ -636,8 +1271,13 @@ squash ghi9012 Third commit
                 fixup_option: None,
                 commit_hash: "abc1234".to_string(),
                 message: message.to_string(),
+                raw_line: None,
+                leading_lines: Vec::new(),
+                metadata_edit: None,
             }],
             comments: vec![],
+            trailing_lines: Vec::new(),
+            header: Default::default(),
         };
 
         let output = serialize_rebase_todo(&file);
@@ -669,4 +1309,486 @@ squash ghi9012 Third commit
         assert!(exec_line.contains("printf '%s'"));
         assert!(exec_line.contains("git commit --amend --quiet --no-edit -F -"));
     }
+
+    #[test]
+    fn test_lossless_roundtrip_keeps_spelling_spacing_and_comment_positions() {
+        let content = "\
+# Rebase 1a2b3c4..5e6f7a8 onto 1a2b3c4 (4 commands)
+pick   abc1234 First commit # keep this note
+
+p def5678 Second commit
+# group: tests
+x  cargo test
+fixup -C 0a1b2c3 # amend! First commit
+
+# Commands:
+# p, pick <commit> = use commit
+";
+        let file = parse_rebase_todo(content).unwrap();
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            content
+        );
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: true,
+                    ..Default::default()
+                }
+            ),
+            content
+        );
+    }
+
+    #[test]
+    fn test_lossless_rewrites_only_changed_entries() {
+        let content = "pick abc1234 First commit\nsquash def5678 Second commit\n";
+        let mut file = parse_rebase_todo(content).unwrap();
+        file.entries[1].command = RebaseCommand::Fixup;
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            "pick abc1234 First commit\nfixup def5678 Second commit\n"
+        );
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: true,
+                    ..Default::default()
+                }
+            ),
+            "pick abc1234 First commit\nf def5678 Second commit\n"
+        );
+    }
+
+    #[test]
+    fn test_lossless_appends_metadata_edit_after_unchanged_entry() {
+        let content = "pick abc1234 First\ndrop def5678 Second\n";
+        let mut file = parse_rebase_todo(content).unwrap();
+        file.entries[0].metadata_edit = Some(MetadataEdit {
+            author: Some("O'Brien <ob@example.com>".to_string()),
+            date: Some("2024-01-02T03:04:05+09:00".to_string()),
+            signoff: true,
+        });
+        file.entries[1].metadata_edit = Some(MetadataEdit {
+            signoff: true,
+            ..MetadataEdit::default()
+        });
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            "pick abc1234 First\nexec GIT_COMMITTER_DATE='2024-01-02T03:04:05+09:00' git commit --amend --no-edit --quiet --author='O'\\''Brien <ob@example.com>' --date='2024-01-02T03:04:05+09:00' --signoff\ndrop def5678 Second\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_skips_empty_metadata_edit() {
+        let mut file = parse_rebase_todo("pick abc1234 First\n").unwrap();
+        file.entries[0].metadata_edit = Some(MetadataEdit::default());
+
+        assert_eq!(serialize_rebase_todo(&file), "p abc1234 First");
+    }
+
+    #[test]
+    fn test_serialize_defers_metadata_edit_past_fixup_chain() {
+        let mut file = parse_rebase_todo(
+            "pick abc1234 First\nfixup def5678 fixup! First\npick 0a1b2c3 Third\n",
+        )
+        .unwrap();
+        file.entries[0].metadata_edit = Some(MetadataEdit {
+            signoff: true,
+            ..MetadataEdit::default()
+        });
+
+        assert_eq!(
+            serialize_rebase_todo(&file),
+            "p abc1234 First\nf def5678 fixup! First\nx git commit --amend --no-edit --quiet --signoff\np 0a1b2c3 Third"
+        );
+    }
+
+    #[test]
+    fn test_lossless_moves_leading_comments_with_reordered_entry() {
+        let content = "pick abc1234 First\n# about second\npick def5678 Second\n";
+        let mut file = parse_rebase_todo(content).unwrap();
+        file.entries.swap(0, 1);
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            "# about second\npick def5678 Second\npick abc1234 First\n"
+        );
+    }
+
+    #[test]
+    fn test_lossless_uses_configured_spelling_for_new_entries() {
+        let mut file = parse_rebase_todo("pick abc1234 First\n").unwrap();
+        file.entries.push(RebaseEntry::new(
+            RebaseCommand::Exec("make test".to_string()),
+            String::new(),
+            String::new(),
+        ));
+        file.entries.push(RebaseEntry::new(
+            RebaseCommand::UpdateRef("refs/heads/topic".to_string()),
+            String::new(),
+            String::new(),
+        ));
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            "pick abc1234 First\nexec make test\nupdate-ref refs/heads/topic\n"
+        );
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: true,
+                    ..Default::default()
+                }
+            ),
+            "pick abc1234 First\nx make test\nu refs/heads/topic\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_records_leading_and_trailing_lines() {
+        let content = "\npick abc1234 First\n\n# trailing\n";
+        let file = parse_rebase_todo(content).unwrap();
+
+        assert_eq!(file.entries[0].leading_lines, vec![String::new()]);
+        assert_eq!(
+            file.entries[0].raw_line.as_deref(),
+            Some("pick abc1234 First")
+        );
+        assert_eq!(
+            file.trailing_lines,
+            vec![String::new(), "# trailing".to_string()]
+        );
+        // 既存の comments はコメントセクションだけを保持する。
+        assert_eq!(file.comments, vec!["# trailing".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_unknown_command_is_kept_as_opaque() {
+        let content = "pick abc1234 First\nfrobnicate  abc1234 --flag # future verb\nnoop\n";
+        let file = parse_rebase_todo(content).unwrap();
+
+        assert_eq!(file.entries.len(), 3);
+        assert_eq!(
+            file.entries[1].command,
+            RebaseCommand::Opaque("frobnicate  abc1234 --flag # future verb".to_string())
+        );
+        assert_eq!(file.entries[1].command.to_short(), "frobnicate");
+        assert!(!file.entries[1].command.is_noop());
+        assert_eq!(
+            file.entries[2].command,
+            RebaseCommand::Opaque("noop".to_string())
+        );
+        assert!(file.entries[2].command.is_noop());
+    }
+
+    #[test]
+    fn test_serialize_opaque_command_unchanged() {
+        let content = "pick abc1234 First\nfrobnicate  abc1234 --flag\n";
+        let file = parse_rebase_todo(content).unwrap();
+
+        assert_eq!(
+            serialize_rebase_todo(&file),
+            "p abc1234 First\nfrobnicate  abc1234 --flag"
+        );
+        assert_eq!(
+            serialize_rebase_todo_lossless(
+                &file,
+                &SerializeOptions {
+                    abbreviate_commands: false,
+                    ..Default::default()
+                }
+            ),
+            content
+        );
+    }
+
+    #[test]
+    fn test_opaque_command_serializes_as_tagged_value() {
+        let json = serde_json::to_string(&RebaseCommand::Opaque("noop".to_string())).unwrap();
+        assert_eq!(json, r#"{"type":"opaque","value":"noop"}"#);
+    }
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.39.5\n"), Some((2, 39, 5)));
+        assert_eq!(
+            parse_git_version("git version 2.45.1.windows.1"),
+            Some((2, 45, 1))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)"),
+            Some((2, 39, 3))
+        );
+        assert_eq!(parse_git_version("git version 2.40"), Some((2, 40, 0)));
+        assert_eq!(parse_git_version("not git"), None);
+    }
+
+    #[test]
+    fn test_git_version_for_command() {
+        assert_eq!(git_version_for_command("update-ref"), Some((2, 38, 0)));
+        assert_eq!(git_version_for_command("B"), Some((2, 20, 0)));
+        assert_eq!(git_version_for_command("frobnicate"), None);
+    }
+
+    #[test]
+    fn test_required_git_version_for_fixup_message_option() {
+        let file =
+            parse_rebase_todo("pick abc1234 A\nfixup abc5678 B\nfixup -C abc9999 C\n").unwrap();
+
+        assert_eq!(file.entries[1].required_git_version(), None);
+        assert_eq!(file.entries[2].required_git_version(), Some((2, 32, 0)));
+    }
+
+    #[test]
+    fn test_required_git_version_for_typed_commands() {
+        let file = parse_rebase_todo("pick abc1234 A\nupdate-ref\nb\n").unwrap();
+
+        assert_eq!(file.entries[0].command.required_git_version(), None);
+        assert_eq!(
+            file.entries[1].command.required_git_version(),
+            Some((2, 38, 0))
+        );
+        assert_eq!(
+            file.entries[2].command.required_git_version(),
+            Some((2, 20, 0))
+        );
+    }
+
+    #[test]
+    fn test_native_reword_emits_reword_line_and_plans_message() {
+        let mut file = parse_rebase_todo("pick abc1234 Old subject\npick def5678 Keep\n").unwrap();
+        file.entries[0].command = RebaseCommand::Reword;
+        file.entries[0].message = "New subject\n\nNew body".to_string();
+        let options = SerializeOptions {
+            abbreviate_commands: false,
+            reword_mode: RewordMode::Native,
+        };
+
+        assert_eq!(
+            serialize_rebase_todo_lossless(&file, &options),
+            "reword abc1234 New subject\npick def5678 Keep\n"
+        );
+        assert_eq!(
+            planned_rewords(&file),
+            vec![("abc1234".to_string(), "New subject\n\nNew body".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_planned_rewords_skips_unedited_reword() {
+        // todo 上の subject だけをキューに入れると本文が消えるため対象外にする。
+        let file = parse_rebase_todo("reword abc1234 Subject only\n").unwrap();
+        assert!(planned_rewords(&file).is_empty());
+    }
+
+    #[test]
+    fn test_exec_amend_reword_mode_is_default_for_lossless() {
+        let mut file = parse_rebase_todo("pick abc1234 Old\n").unwrap();
+        file.entries[0].command = RebaseCommand::Reword;
+        file.entries[0].message = "New".to_string();
+
+        let output = serialize_rebase_todo_lossless(&file, &SerializeOptions::default());
+        assert!(output.starts_with("pick abc1234 New\nexec b64="));
+    }
+
+    fn autosquash(content: &str) -> AutosquashPlan {
+        plan_autosquash(&parse_rebase_todo(content).unwrap(), None, &HashMap::new())
+    }
+
+    fn commands(file: &RebaseTodoFile) -> Vec<(String, RebaseCommand, Option<String>)> {
+        file.entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.commit_hash.clone(),
+                    entry.command.clone(),
+                    entry.fixup_option.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_autosquash_moves_fixups_under_targets() {
+        let plan = autosquash(
+            "pick aaa1111 Add parser\n\
+             pick bbb2222 Add editor\n\
+             pick ccc3333 fixup! Add parser\n\
+             pick ddd4444 squash! Add editor\n\
+             pick eee5555 amend! Add parser\n",
+        );
+
+        assert_eq!(
+            commands(&plan.file),
+            vec![
+                ("aaa1111".to_string(), RebaseCommand::Pick, None),
+                ("ccc3333".to_string(), RebaseCommand::Fixup, None),
+                (
+                    "eee5555".to_string(),
+                    RebaseCommand::Fixup,
+                    Some("-C".to_string())
+                ),
+                ("bbb2222".to_string(), RebaseCommand::Pick, None),
+                ("ddd4444".to_string(), RebaseCommand::Squash, None),
+            ]
+        );
+        assert_eq!(plan.moves.len(), 3);
+        assert_eq!(plan.moves[0].commit_hash, "ccc3333");
+        assert_eq!(plan.moves[0].target_hash, "aaa1111");
+        assert_eq!(plan.moves[0].from_index, 2);
+        assert_eq!(plan.moves[0].to_index, 1);
+        assert!(plan.unmatched.is_empty());
+    }
+
+    const AMEND_TODO: &str = "pick aaa1111 Add parser\n\
+                              pick bbb2222 Add editor\n\
+                              pick ccc3333 amend! Add parser\n";
+
+    fn amend_messages(file: &RebaseTodoFile) -> HashMap<String, String> {
+        HashMap::from([(
+            file.entries[2].id.clone(),
+            "amend! Add parser\n\nAdd todo parser\n\nWith body".to_string(),
+        )])
+    }
+
+    #[test]
+    fn test_autosquash_amend_uses_fixup_c_on_git_2_32() {
+        let file = parse_rebase_todo(AMEND_TODO).unwrap();
+
+        let plan = plan_autosquash(&file, Some((2, 32, 0)), &amend_messages(&file));
+
+        assert_eq!(
+            commands(&plan.file),
+            vec![
+                ("aaa1111".to_string(), RebaseCommand::Pick, None),
+                (
+                    "ccc3333".to_string(),
+                    RebaseCommand::Fixup,
+                    Some("-C".to_string())
+                ),
+                ("bbb2222".to_string(), RebaseCommand::Pick, None),
+            ]
+        );
+        assert_eq!(plan.file.entries[0].message, "Add parser");
+    }
+
+    #[test]
+    fn test_autosquash_amend_falls_back_to_reword_before_git_2_32() {
+        let file = parse_rebase_todo(AMEND_TODO).unwrap();
+
+        let plan = plan_autosquash(&file, Some((2, 31, 8)), &amend_messages(&file));
+
+        assert_eq!(
+            commands(&plan.file),
+            vec![
+                ("aaa1111".to_string(), RebaseCommand::Reword, None),
+                ("ccc3333".to_string(), RebaseCommand::Fixup, None),
+                ("bbb2222".to_string(), RebaseCommand::Pick, None),
+            ]
+        );
+        assert_eq!(plan.file.entries[0].message, "Add todo parser\n\nWith body");
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].fixup_option, None);
+    }
+
+    #[test]
+    fn test_autosquash_matches_by_hash_and_prefix() {
+        let plan = autosquash(
+            "pick aaa1111 Add parser for todo files\n\
+             pick bbb2222 Add editor\n\
+             pick ccc3333 fixup! bbb2222\n\
+             pick ddd4444 fixup! Add parser\n",
+        );
+
+        let hashes: Vec<&str> = plan
+            .file
+            .entries
+            .iter()
+            .map(|entry| entry.commit_hash.as_str())
+            .collect();
+        assert_eq!(hashes, vec!["aaa1111", "ddd4444", "bbb2222", "ccc3333"]);
+    }
+
+    #[test]
+    fn test_autosquash_nested_fixup_goes_to_first_target() {
+        let plan = autosquash(
+            "pick aaa1111 Add parser\n\
+             pick bbb2222 fixup! Add parser\n\
+             pick ccc3333 Add editor\n\
+             pick ddd4444 fixup! fixup! Add parser\n",
+        );
+
+        let hashes: Vec<&str> = plan
+            .file
+            .entries
+            .iter()
+            .map(|entry| entry.commit_hash.as_str())
+            .collect();
+        assert_eq!(hashes, vec!["aaa1111", "bbb2222", "ddd4444", "ccc3333"]);
+        assert_eq!(plan.moves[1].target_hash, "aaa1111");
+    }
+
+    #[test]
+    fn test_autosquash_reports_unmatched_and_keeps_order() {
+        let plan = autosquash(
+            "pick aaa1111 fixup! Not in this rebase\n\
+             pick bbb2222 Add editor\n",
+        );
+
+        assert_eq!(plan.moves.len(), 0);
+        assert_eq!(plan.unmatched, vec![plan.file.entries[0].id.clone()]);
+        assert_eq!(plan.file.entries[0].command, RebaseCommand::Pick);
+    }
+
+    #[test]
+    fn test_autosquash_is_noop_for_already_arranged_todo() {
+        let plan = autosquash(
+            "pick aaa1111 Add parser\n\
+             fixup bbb2222 fixup! Add parser\n\
+             fixup -C ccc3333 amend! Add parser\n",
+        );
+
+        assert!(plan.moves.is_empty());
+        assert_eq!(
+            serialize_rebase_todo_lossless(&plan.file, &SerializeOptions::default()),
+            "pick aaa1111 Add parser\nfixup bbb2222 fixup! Add parser\nfixup -C ccc3333 amend! Add parser\n"
+        );
+    }
 }
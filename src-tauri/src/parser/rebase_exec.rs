@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{RebaseCommand, RebaseEntry, RebaseTodoFile};
+use crate::error::AppError;
+
+/// exec テンプレートの定義元。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecTemplateSource {
+    /// `gui-git-editor.execTemplate`（複数指定可）。
+    GitConfig,
+    /// リポジトリ直下の `.gui-git-editor-exec`。
+    ProjectFile,
+}
+
+/// 保存済みの exec コマンド。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExecTemplate {
+    pub command: String,
+    pub source: ExecTemplateSource,
+}
+
+/// テンプレートファイルを解釈する。1 行に 1 コマンドで、空行と `#` で始まる行は無視する。
+pub fn parse_exec_template_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// `git rebase --exec` と同じく、その直後に exec を挟むエントリ。
+fn produces_commit(command: &RebaseCommand) -> bool {
+    matches!(
+        command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Merge { .. }
+    )
+}
+
+/// 連続する空白を 1 つにまとめる。解析時に exec のコマンドは空白区切りで結合し直されるため、
+/// 比較はこの形で行う。引用符内の空白も潰れるので、書き込むコマンドには使わない。
+fn normalize_command(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_exec_of(entry: &RebaseEntry, command: &str) -> bool {
+    matches!(
+        &entry.command,
+        RebaseCommand::Exec(existing) if normalize_command(existing) == normalize_command(command)
+    )
+}
+
+/// コミットを作る各エントリの後ろに `exec <command>` を挿入した todo を返す。
+///
+/// squash/fixup の連鎖には途中で割り込まず、連鎖の最後のエントリの後ろに置く。
+/// 同じコマンドの exec が既にあれば取り除いてから挿入し直すため、繰り返し適用しても重複しない。
+pub fn insert_exec_steps(file: &RebaseTodoFile, command: &str) -> Result<RebaseTodoFile, AppError> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err(AppError::CommandError {
+            message: "Exec command must not be empty".to_string(),
+        });
+    }
+
+    let base = remove_exec_steps(file, &command);
+    let new_exec = || {
+        RebaseEntry::new(
+            RebaseCommand::Exec(command.clone()),
+            String::new(),
+            String::new(),
+        )
+    };
+
+    let mut entries = Vec::with_capacity(base.entries.len() * 2);
+    let mut pending = false;
+    for entry in base.entries {
+        if pending && !matches!(entry.command, RebaseCommand::Squash | RebaseCommand::Fixup) {
+            entries.push(new_exec());
+            pending = false;
+        }
+        pending |= produces_commit(&entry.command);
+        entries.push(entry);
+    }
+    if pending {
+        entries.push(new_exec());
+    }
+
+    Ok(RebaseTodoFile { entries, ..base })
+}
+
+/// `command` を実行する exec エントリをすべて取り除いた todo を返す。
+/// 取り除いた行の直前にあったコメント・空行は次のエントリへ引き継ぐ。
+pub fn remove_exec_steps(file: &RebaseTodoFile, command: &str) -> RebaseTodoFile {
+    file.without_entries(|entry| is_exec_of(entry, command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    fn commands(file: &RebaseTodoFile) -> Vec<String> {
+        file.entries
+            .iter()
+            .map(|entry| match &entry.command {
+                RebaseCommand::Exec(command) => format!("exec {}", command),
+                other => format!("{} {}", other.to_long(), entry.commit_hash),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_exec_template_file() {
+        let content = "# テスト\ncargo test\n\n  npm  run lint  \n";
+        assert_eq!(
+            parse_exec_template_file(content),
+            vec!["cargo test", "npm  run lint"]
+        );
+    }
+
+    #[test]
+    fn test_insert_exec_after_each_commit_and_fixup_chain() {
+        let todo = "pick aaa1111 One\nfixup bbb2222 fixup! One\nsquash ccc3333 squash! One\nreword ddd4444 Two\ndrop eee5555 Three\nedit fff6666 Four\n";
+        let file = parse_rebase_todo(todo).unwrap();
+
+        let result = insert_exec_steps(&file, "cargo test").unwrap();
+
+        assert_eq!(
+            commands(&result),
+            vec![
+                "pick aaa1111",
+                "fixup bbb2222",
+                "squash ccc3333",
+                "exec cargo test",
+                "reword ddd4444",
+                "exec cargo test",
+                "drop eee5555",
+                "edit fff6666",
+                "exec cargo test",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_exec_is_idempotent() {
+        let file = parse_rebase_todo("pick aaa1111 One\npick bbb2222 Two\n").unwrap();
+
+        let once = insert_exec_steps(&file, "make").unwrap();
+        let twice = insert_exec_steps(&once, "make").unwrap();
+
+        assert_eq!(commands(&once), commands(&twice));
+        assert_eq!(twice.entries.len(), 4);
+    }
+
+    #[test]
+    fn test_insert_exec_keeps_command_as_written() {
+        let file = parse_rebase_todo("pick aaa1111 One\nexec printf 'a b'\n").unwrap();
+
+        let result = insert_exec_steps(&file, "  printf 'a  b'  ").unwrap();
+
+        assert_eq!(
+            commands(&result),
+            vec!["pick aaa1111", "exec printf 'a  b'"]
+        );
+    }
+
+    #[test]
+    fn test_insert_exec_rejects_empty_command() {
+        let file = parse_rebase_todo("pick aaa1111 One\n").unwrap();
+
+        let result = insert_exec_steps(&file, "  ");
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+
+    #[test]
+    fn test_remove_exec_keeps_other_commands_and_leading_lines() {
+        let todo = "pick aaa1111 One\n# run tests\nexec cargo test\nexec make lint\n\npick bbb2222 Two\nexec cargo test\n";
+        let file = parse_rebase_todo(todo).unwrap();
+
+        let result = remove_exec_steps(&file, "cargo test");
+
+        assert_eq!(
+            commands(&result),
+            vec!["pick aaa1111", "exec make lint", "pick bbb2222"]
+        );
+        assert_eq!(result.entries[1].leading_lines, vec!["# run tests"]);
+        assert_eq!(result.entries[2].leading_lines, vec![""]);
+    }
+}
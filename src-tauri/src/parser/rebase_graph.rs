@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{RebaseCommand, RebaseEntry, RebaseTodoFile};
+use super::rebase_header::TodoHeader;
+use crate::error::AppError;
+
+/// merge が取り込むラベルと、そのラベルを定義したセクション。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MergeLink {
+    pub entry_id: String,
+    pub label: String,
+    /// ラベルを定義したセクション。todo 内で定義されていなければ None。
+    pub source_section: Option<usize>,
+}
+
+/// `reset` から次の `reset` の直前までのひと続きのエントリ（1 本のブランチに相当する）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseSection {
+    pub index: usize,
+    /// 先頭の `reset` の対象。最初のセクションは rebase 開始位置から始まるため None。
+    pub base: Option<String>,
+    /// `base` のラベルを定義したセクション。
+    pub base_section: Option<usize>,
+    pub entries: Vec<RebaseEntry>,
+    /// このセクション内で定義されたラベル。
+    pub labels: Vec<String>,
+    pub merges: Vec<MergeLink>,
+    /// このブランチの途中や先端で更新される ref。
+    pub update_refs: Vec<String>,
+}
+
+/// `--rebase-merges` の todo を、reset/label/merge の関係を持つセクションに分けたもの。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseGraph {
+    pub sections: Vec<RebaseSection>,
+    pub comments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trailing_lines: Vec<String>,
+    #[serde(default)]
+    pub header: TodoHeader,
+}
+
+impl RebaseGraph {
+    /// セクションを順に連結し、平坦な todo に戻す。
+    pub fn to_todo_file(&self) -> RebaseTodoFile {
+        RebaseTodoFile {
+            entries: self
+                .sections
+                .iter()
+                .flat_map(|section| section.entries.iter().cloned())
+                .collect(),
+            comments: self.comments.clone(),
+            trailing_lines: self.trailing_lines.clone(),
+            header: self.header.clone(),
+        }
+    }
+}
+
+/// 新しいコミットを作るエントリ。squash/fixup の統合先になれる。
+fn creates_commit(command: &RebaseCommand) -> bool {
+    matches!(
+        command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Squash
+            | RebaseCommand::Fixup
+            | RebaseCommand::Merge { .. }
+    )
+}
+
+/// セクション間で移動できる、単一コミットを扱うエントリ。
+fn is_movable(command: &RebaseCommand) -> bool {
+    matches!(
+        command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Squash
+            | RebaseCommand::Fixup
+            | RebaseCommand::Drop
+    )
+}
+
+/// 平坦な todo からセクションとラベルの関係を組み立てる。
+pub fn build_rebase_graph(file: &RebaseTodoFile) -> RebaseGraph {
+    let mut sections: Vec<RebaseSection> = Vec::new();
+    let mut current = new_section(0, None);
+
+    for entry in &file.entries {
+        if let RebaseCommand::Reset(target) = &entry.command {
+            let index = sections.len() + 1;
+            sections.push(std::mem::replace(
+                &mut current,
+                new_section(index, Some(target.clone())),
+            ));
+        }
+        current.entries.push(entry.clone());
+    }
+    sections.push(current);
+
+    // 最初のセクションが空（todo が reset で始まる）なら取り除く。
+    if sections.len() > 1 && sections[0].entries.is_empty() {
+        sections.remove(0);
+        for (index, section) in sections.iter_mut().enumerate() {
+            section.index = index;
+        }
+    }
+
+    let mut defined: HashMap<String, usize> = HashMap::new();
+    for section in &mut sections {
+        section.base_section = section
+            .base
+            .as_ref()
+            .and_then(|base| defined.get(base).copied());
+
+        for entry in &section.entries {
+            match &entry.command {
+                RebaseCommand::Label(label) => {
+                    section.labels.push(label.clone());
+                    defined.insert(label.clone(), section.index);
+                }
+                RebaseCommand::UpdateRef(ref_name) => section.update_refs.push(ref_name.clone()),
+                RebaseCommand::Merge { labels, .. } => {
+                    for label in labels {
+                        section.merges.push(MergeLink {
+                            entry_id: entry.id.clone(),
+                            label: label.clone(),
+                            source_section: defined.get(label).copied(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    RebaseGraph {
+        sections,
+        comments: file.comments.clone(),
+        trailing_lines: file.trailing_lines.clone(),
+        header: file.header.clone(),
+    }
+}
+
+fn new_section(index: usize, base: Option<String>) -> RebaseSection {
+    RebaseSection {
+        index,
+        base,
+        base_section: None,
+        entries: Vec::new(),
+        labels: Vec::new(),
+        merges: Vec::new(),
+        update_refs: Vec::new(),
+    }
+}
+
+/// コミットを別のセクション（ブランチ）の先端へ移動した todo を返す。
+///
+/// 先端とは、セクション末尾に並ぶ `label`・`update-ref` の直前を指す。移動後に
+/// 統合先のない squash/fixup が生じる場合は、todo を変更せずにエラーを返す。
+pub fn move_commit_to_section(
+    file: &RebaseTodoFile,
+    entry_id: &str,
+    target_section: usize,
+) -> Result<RebaseTodoFile, AppError> {
+    let mut graph = build_rebase_graph(file);
+    if target_section >= graph.sections.len() {
+        return Err(AppError::CommandError {
+            message: format!("Section {} does not exist", target_section),
+        });
+    }
+
+    let (source_section, position) = graph
+        .sections
+        .iter()
+        .enumerate()
+        .find_map(|(section_index, section)| {
+            section
+                .entries
+                .iter()
+                .position(|entry| entry.id == entry_id)
+                .map(|position| (section_index, position))
+        })
+        .ok_or_else(|| AppError::CommandError {
+            message: format!("Entry {} not found", entry_id),
+        })?;
+
+    if !is_movable(&graph.sections[source_section].entries[position].command) {
+        return Err(AppError::CommandError {
+            message: "Only commit entries can be moved between sections".to_string(),
+        });
+    }
+    if source_section == target_section {
+        return Ok(graph.to_todo_file());
+    }
+
+    let entry = graph.sections[source_section].entries.remove(position);
+    let target = &mut graph.sections[target_section].entries;
+    let tip = target
+        .iter()
+        .rposition(|entry| {
+            !matches!(
+                entry.command,
+                RebaseCommand::Label(_) | RebaseCommand::UpdateRef(_)
+            )
+        })
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    target.insert(tip, entry);
+
+    for section in [source_section, target_section] {
+        validate_squash_targets(&graph.sections[section])?;
+    }
+
+    Ok(graph.to_todo_file())
+}
+
+/// セクション内の squash/fixup が、同じセクション内の先行するコミットに統合されることを確かめる。
+fn validate_squash_targets(section: &RebaseSection) -> Result<(), AppError> {
+    let mut has_commit = false;
+    for entry in &section.entries {
+        if matches!(entry.command, RebaseCommand::Squash | RebaseCommand::Fixup) && !has_commit {
+            return Err(AppError::CommandError {
+                message: format!(
+                    "'{}' {} would have no commit to squash into in section {}",
+                    entry.command.to_long(),
+                    entry.commit_hash,
+                    section.index
+                ),
+            });
+        }
+        has_commit |= creates_commit(&entry.command);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    const REBASE_MERGES_TODO: &str = "label onto
+
+# Branch topic
+reset onto
+pick aaa1111 Topic one
+pick bbb2222 Topic two
+update-ref refs/heads/topic
+label topic
+
+reset onto
+pick ccc3333 Main work
+merge -C ddd4444 topic # Merge branch 'topic'
+";
+
+    fn entry_id(file: &RebaseTodoFile, hash: &str) -> String {
+        file.entries
+            .iter()
+            .find(|entry| entry.commit_hash == hash)
+            .unwrap()
+            .id
+            .clone()
+    }
+
+    #[test]
+    fn test_build_rebase_graph_sections() {
+        let file = parse_rebase_todo(REBASE_MERGES_TODO).unwrap();
+        let graph = build_rebase_graph(&file);
+
+        assert_eq!(graph.sections.len(), 3);
+        assert_eq!(graph.sections[0].base, None);
+        assert_eq!(graph.sections[0].labels, vec!["onto"]);
+
+        let topic = &graph.sections[1];
+        assert_eq!(topic.base.as_deref(), Some("onto"));
+        assert_eq!(topic.base_section, Some(0));
+        assert_eq!(topic.labels, vec!["topic"]);
+        assert_eq!(topic.update_refs, vec!["refs/heads/topic"]);
+
+        let main = &graph.sections[2];
+        assert_eq!(main.merges.len(), 1);
+        assert_eq!(main.merges[0].label, "topic");
+        assert_eq!(main.merges[0].source_section, Some(1));
+    }
+
+    #[test]
+    fn test_octopus_merge_links_every_label() {
+        let todo = "label onto
+reset onto
+pick aaa1111 One
+label one
+
+reset onto
+pick bbb2222 Two
+label two
+
+reset onto
+merge -C ccc3333 one two # Merge branches 'one' and 'two'
+";
+        let file = parse_rebase_todo(todo).unwrap();
+        let graph = build_rebase_graph(&file);
+
+        let merges = &graph.sections[3].merges;
+        let links: Vec<(&str, Option<usize>)> = merges
+            .iter()
+            .map(|link| (link.label.as_str(), link.source_section))
+            .collect();
+        assert_eq!(links, vec![("one", Some(1)), ("two", Some(2))]);
+        assert_eq!(merges[0].entry_id, merges[1].entry_id);
+    }
+
+    #[test]
+    fn test_graph_round_trips_to_flat_todo() {
+        let file = parse_rebase_todo(REBASE_MERGES_TODO).unwrap();
+        let flat = build_rebase_graph(&file).to_todo_file();
+
+        let ids: Vec<&str> = flat.entries.iter().map(|entry| entry.id.as_str()).collect();
+        let original: Vec<&str> = file.entries.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, original);
+    }
+
+    #[test]
+    fn test_move_commit_to_section_inserts_before_label_and_update_ref() {
+        let file = parse_rebase_todo(REBASE_MERGES_TODO).unwrap();
+        let moved = move_commit_to_section(&file, &entry_id(&file, "ccc3333"), 1).unwrap();
+
+        let graph = build_rebase_graph(&moved);
+        let topic: Vec<String> = graph.sections[1]
+            .entries
+            .iter()
+            .map(|entry| entry.command.to_long().to_string())
+            .collect();
+        assert_eq!(
+            topic,
+            vec!["reset", "pick", "pick", "pick", "update-ref", "label"]
+        );
+        assert_eq!(graph.sections[1].entries[3].commit_hash, "ccc3333");
+        assert_eq!(graph.sections[2].entries.len(), 2);
+    }
+
+    #[test]
+    fn test_move_commit_rejects_orphaned_fixup() {
+        let todo = "reset onto\npick aaa1111 One\nfixup bbb2222 Two\nlabel topic\n\nreset onto\npick ccc3333 Three\n";
+        let file = parse_rebase_todo(todo).unwrap();
+
+        let result = move_commit_to_section(&file, &entry_id(&file, "aaa1111"), 1);
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+
+    #[test]
+    fn test_move_commit_rejects_non_commit_entry() {
+        let file = parse_rebase_todo(REBASE_MERGES_TODO).unwrap();
+        let label_id = file.entries[0].id.clone();
+
+        let result = move_commit_to_section(&file, &label_id, 1);
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+}
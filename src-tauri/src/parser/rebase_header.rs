@@ -0,0 +1,516 @@
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{
+    git_version_for_command, GitVersion, RebaseTodoFile, FIXUP_MESSAGE_OPTION_VERSION,
+};
+
+/// `core.commentChar` が未設定のときのコメント文字。
+pub const DEFAULT_COMMENT_CHAR: &str = "#";
+
+/// `--update-refs` で、別のワークツリーにチェックアウト中のため更新されないブランチ。
+/// Git は `# Ref <ref> checked out at '<path>'` というコメントで知らせる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckedOutRef {
+    pub ref_name: String,
+    pub worktree: String,
+}
+
+/// todo のコメントから読み取った rebase の情報。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TodoHeader {
+    /// コメント行の先頭に使われている文字（`core.commentChar`）。
+    pub comment_char: String,
+    /// `Rebase <range> onto <onto>` の範囲（例: `1a2b3c4..5e6f7a8`）。
+    pub range: Option<String>,
+    pub onto: Option<String>,
+    /// rebase 開始時のコマンド数。
+    pub command_count: Option<usize>,
+    pub checked_out_refs: Vec<CheckedOutRef>,
+    /// 末尾にある、Git が生成した説明の行数。この行は `regenerate_todo_help` で作り直せる。
+    pub help_lines: usize,
+}
+
+impl Default for TodoHeader {
+    fn default() -> Self {
+        Self {
+            comment_char: DEFAULT_COMMENT_CHAR.to_string(),
+            range: None,
+            onto: None,
+            command_count: None,
+            checked_out_refs: Vec::new(),
+            help_lines: 0,
+        }
+    }
+}
+
+/// Git が説明の先頭に書く `Rebase <range> onto <onto> (<n> commands)` を解釈する。
+fn parse_rebase_summary(text: &str) -> Option<(String, String, usize)> {
+    let rest = text.strip_prefix("Rebase ")?;
+    let (range, rest) = rest.split_once(" onto ")?;
+    let (onto, count) = rest.split_once(" (")?;
+    let count = count
+        .strip_suffix(" commands)")
+        .or_else(|| count.strip_suffix(" command)"))?;
+    if range.contains(char::is_whitespace) || onto.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((range.to_string(), onto.to_string(), count.parse().ok()?))
+}
+
+/// `Ref <ref> checked out at '<path>'` を解釈する。
+fn parse_checked_out_ref(text: &str) -> Option<CheckedOutRef> {
+    let rest = text.strip_prefix("Ref ")?;
+    let (ref_name, worktree) = rest.split_once(" checked out at ")?;
+    let worktree = worktree.strip_prefix('\'')?.strip_suffix('\'')?;
+    Some(CheckedOutRef {
+        ref_name: ref_name.to_string(),
+        worktree: worktree.to_string(),
+    })
+}
+
+/// コメント文字に続く本文を返す。コメント行でなければ None。
+fn comment_text<'a>(line: &'a str, comment_char: &str) -> Option<&'a str> {
+    let text = line.trim().strip_prefix(comment_char)?;
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+/// todo で使われているコメント文字を推定する。
+///
+/// `Rebase ... onto ...` の行があればその先頭の記号を使う。見つからない場合
+/// （翻訳された説明など）は、Git が末尾に書く説明の最終行の先頭の記号を使う。
+pub fn detect_comment_char(content: &str) -> String {
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some((prefix, text)) = trimmed.split_once(' ') {
+            if !prefix.is_empty()
+                && prefix.chars().all(|c| c.is_ascii_punctuation())
+                && parse_rebase_summary(text).is_some()
+            {
+                return prefix.to_string();
+            }
+        }
+    }
+
+    content
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .and_then(|line| line.chars().next())
+        .filter(|c| c.is_ascii_punctuation())
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| DEFAULT_COMMENT_CHAR.to_string())
+}
+
+/// 解析済みのコメントから rebase の情報を読み取る。
+///
+/// 末尾のコメント行は Git が生成した説明として扱う。`Rebase ... onto ...` の行が
+/// あればそこから、なければ最初のコメント行からを説明とみなす。
+pub fn parse_todo_header(
+    comments: &[String],
+    trailing_lines: &[String],
+    comment_char: &str,
+) -> TodoHeader {
+    let mut header = TodoHeader {
+        comment_char: comment_char.to_string(),
+        ..Default::default()
+    };
+
+    for text in comments
+        .iter()
+        .filter_map(|line| comment_text(line, comment_char))
+    {
+        if let Some((range, onto, count)) = parse_rebase_summary(text) {
+            if header.range.is_none() {
+                header.range = Some(range);
+                header.onto = Some(onto);
+                header.command_count = Some(count);
+            }
+        } else if let Some(checked_out) = parse_checked_out_ref(text) {
+            header.checked_out_refs.push(checked_out);
+        }
+    }
+
+    let is_comment = |line: &String| comment_text(line, comment_char).is_some();
+    let help_start = trailing_lines
+        .iter()
+        .position(|line| {
+            comment_text(line, comment_char).is_some_and(|t| parse_rebase_summary(t).is_some())
+        })
+        .or_else(|| trailing_lines.iter().position(is_comment));
+    if let Some(start) = help_start {
+        header.help_lines = trailing_lines.len() - start;
+    }
+
+    header
+}
+
+/// 説明に載せるコマンドの行。動詞を追加した Git より古い Git では載せない。
+const TODO_HELP_COMMANDS: &[(&str, &[&str])] = &[
+    ("pick", &["p, pick <commit> = use commit"]),
+    (
+        "reword",
+        &["r, reword <commit> = use commit, but edit the commit message"],
+    ),
+    (
+        "edit",
+        &["e, edit <commit> = use commit, but stop for amending"],
+    ),
+    (
+        "squash",
+        &["s, squash <commit> = use commit, but meld into previous commit"],
+    ),
+    (
+        "fixup",
+        &[
+            "f, fixup [-C | -c] <commit> = like \"squash\" but keep only the previous",
+            "                   commit's log message, unless -C is used, in which case",
+            "                   keep only this commit's message; -c is same as -C but",
+            "                   opens the editor",
+        ],
+    ),
+    (
+        "exec",
+        &["x, exec <command> = run command (the rest of the line) using shell"],
+    ),
+    (
+        "break",
+        &["b, break = stop here (continue rebase later with 'git rebase --continue')"],
+    ),
+    ("drop", &["d, drop <commit> = remove commit"]),
+    (
+        "label",
+        &["l, label <label> = label current HEAD with a name"],
+    ),
+    ("reset", &["t, reset <label> = reset HEAD to a label"]),
+    (
+        "merge",
+        &[
+            "m, merge [-C <commit> | -c <commit>] <label> [# <oneline>]",
+            "        create a merge commit using the original merge commit's",
+            "        message (or the oneline, if no original merge commit was",
+            "        specified); use -c <commit> to reword the commit message",
+        ],
+    ),
+    (
+        "update-ref",
+        &[
+            "u, update-ref <ref> = track a placeholder for the <ref> to be updated",
+            "                      to this position in the new commits. The <ref> is",
+            "                      updated at the end of the rebase",
+        ],
+    ),
+];
+
+/// `fixup -C` / `-c` に対応しない Git での fixup の説明。
+const LEGACY_FIXUP_HELP: &[&str] =
+    &["f, fixup <commit> = like \"squash\", but discard this commit's log message"];
+
+/// Git（`append_todo_help`）と同じ説明を、指定のコメント文字で組み立てる。
+///
+/// `range` と `onto` が分からない場合は `git rebase --edit-todo` のときの説明になる。
+/// `git_version` が分かれば、その Git が解釈できないコマンドは載せない。
+pub fn render_todo_help(
+    header: &TodoHeader,
+    command_count: usize,
+    missing_commits_error: bool,
+    git_version: Option<GitVersion>,
+) -> Vec<String> {
+    let supports = |required: Option<GitVersion>| {
+        required
+            .zip(git_version)
+            .is_none_or(|(required, installed)| installed >= required)
+    };
+
+    let mut text = Vec::new();
+    let summary = header.range.as_deref().zip(header.onto.as_deref());
+    if let Some((range, onto)) = summary {
+        let noun = if command_count == 1 {
+            "command"
+        } else {
+            "commands"
+        };
+        text.push(format!(
+            "Rebase {} onto {} ({} {})",
+            range, onto, command_count, noun
+        ));
+    }
+
+    text.extend(["", "Commands:"].map(str::to_string));
+    for (verb, lines) in TODO_HELP_COMMANDS {
+        let lines = match *verb {
+            "fixup" if !supports(Some(FIXUP_MESSAGE_OPTION_VERSION)) => LEGACY_FIXUP_HELP,
+            _ if !supports(git_version_for_command(verb)) => continue,
+            _ => lines,
+        };
+        text.extend(lines.iter().map(|line| line.to_string()));
+    }
+    text.extend(
+        [
+            "",
+            "These lines can be re-ordered; they are executed from top to bottom.",
+            "",
+        ]
+        .map(str::to_string),
+    );
+
+    text.push(if missing_commits_error {
+        "Do not remove any line. Use 'drop' explicitly to remove a commit.".to_string()
+    } else {
+        "If you remove a line here THAT COMMIT WILL BE LOST.".to_string()
+    });
+    text.push(String::new());
+
+    if summary.is_some() {
+        text.push("However, if you remove everything, the rebase will be aborted.".to_string());
+    } else {
+        text.extend(
+            [
+                "You are editing the todo file of an ongoing interactive rebase.",
+                "To continue rebase after editing, run:",
+                "    git rebase --continue",
+                "",
+            ]
+            .map(str::to_string),
+        );
+    }
+    text.push(String::new());
+
+    // 空行にはコメント文字だけを置く（strbuf_add_commented_lines と同じ）。
+    text.into_iter()
+        .map(|line| {
+            if line.is_empty() {
+                header.comment_char.clone()
+            } else {
+                format!("{} {}", header.comment_char, line)
+            }
+        })
+        .collect()
+}
+
+/// コメント行の先頭の `from` を `to` に置き換える。コメント行以外はそのまま返す。
+fn recomment(line: &str, from: &str, to: &str) -> String {
+    let indent = line.len() - line.trim_start().len();
+    match line[indent..].strip_prefix(from) {
+        Some(rest) => format!("{}{}{}", &line[..indent], to, rest),
+        None => line.to_string(),
+    }
+}
+
+/// 末尾の説明を作り直した todo を返す。
+///
+/// 他のコメント行も `comment_char` に付け替えるため、`core.commentChar` を変えた後でも
+/// Git がそのまま読める。説明は、要約のコマンド数が現在のエントリ数と異なる場合だけ
+/// `git_version` に合わせて作り直す。要約を解釈できない翻訳された説明や、数が変わらない
+/// 説明は、コメント文字だけを付け替えて元の行を残す。
+pub fn regenerate_todo_help(
+    file: &RebaseTodoFile,
+    comment_char: &str,
+    missing_commits_error: bool,
+    git_version: Option<GitVersion>,
+) -> RebaseTodoFile {
+    let old = &file.header.comment_char;
+    let help_lines = file.header.help_lines;
+    let recomment_all = |lines: &[String]| -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| recomment(line, old, comment_char))
+            .collect()
+    };
+
+    let mut entries = file.entries.clone();
+    for entry in &mut entries {
+        entry.leading_lines = recomment_all(&entry.leading_lines);
+    }
+
+    let mut comments = recomment_all(&file.comments);
+    let keeps_help = help_lines > 0
+        && (file.header.range.is_none() || file.header.command_count == Some(entries.len()));
+    if keeps_help {
+        return RebaseTodoFile {
+            entries,
+            comments,
+            trailing_lines: recomment_all(&file.trailing_lines),
+            header: TodoHeader {
+                comment_char: comment_char.to_string(),
+                ..file.header.clone()
+            },
+        };
+    }
+    comments.truncate(comments.len().saturating_sub(help_lines));
+    let mut trailing_lines = recomment_all(&file.trailing_lines);
+    trailing_lines.truncate(trailing_lines.len().saturating_sub(help_lines));
+    if trailing_lines.is_empty() && !entries.is_empty() {
+        // Git は説明の前に空行を 1 行置く。
+        trailing_lines.push(String::new());
+    }
+
+    let mut header = TodoHeader {
+        comment_char: comment_char.to_string(),
+        command_count: Some(entries.len()),
+        ..file.header.clone()
+    };
+    let help = render_todo_help(&header, entries.len(), missing_commits_error, git_version);
+    header.help_lines = help.len();
+    comments.extend(help.iter().cloned());
+    trailing_lines.extend(help);
+
+    RebaseTodoFile {
+        entries,
+        comments,
+        trailing_lines,
+        header,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::{parse_rebase_todo, serialize_rebase_todo_lossless};
+
+    /// `git rebase -i` が書き出す todo と同じ内容。
+    fn git_todo(comment_char: &str) -> String {
+        let mut content = String::from(
+            "pick abc1234 First\nupdate-ref refs/heads/topic\npick def5678 Second\n\n",
+        );
+        let header = TodoHeader {
+            comment_char: comment_char.to_string(),
+            range: Some("1a2b3c4..5e6f7a8".to_string()),
+            onto: Some("9f8e7d6".to_string()),
+            ..Default::default()
+        };
+        for line in render_todo_help(&header, 3, false, None) {
+            content.push_str(&line);
+            content.push('\n');
+        }
+        content
+    }
+
+    #[test]
+    fn test_parse_header_from_git_todo() {
+        let file = parse_rebase_todo(&git_todo("#")).unwrap();
+
+        assert_eq!(file.entries.len(), 3);
+        assert_eq!(file.header.comment_char, "#");
+        assert_eq!(file.header.range.as_deref(), Some("1a2b3c4..5e6f7a8"));
+        assert_eq!(file.header.onto.as_deref(), Some("9f8e7d6"));
+        assert_eq!(file.header.command_count, Some(3));
+        assert_eq!(file.header.help_lines, file.trailing_lines.len() - 1);
+        assert_eq!(
+            file.trailing_lines[1],
+            "# Rebase 1a2b3c4..5e6f7a8 onto 9f8e7d6 (3 commands)"
+        );
+        assert_eq!(file.trailing_lines.last().map(String::as_str), Some("#"));
+    }
+
+    #[test]
+    fn test_parse_header_with_custom_comment_char() {
+        let content = git_todo(";").replacen(
+            "update-ref",
+            "; Ref refs/heads/other checked out at '/work/other'\nupdate-ref",
+            1,
+        );
+        let file = parse_rebase_todo(&content).unwrap();
+
+        assert_eq!(file.entries.len(), 3);
+        assert_eq!(file.header.comment_char, ";");
+        assert_eq!(file.header.command_count, Some(3));
+        assert_eq!(
+            file.header.checked_out_refs,
+            vec![CheckedOutRef {
+                ref_name: "refs/heads/other".to_string(),
+                worktree: "/work/other".to_string(),
+            }]
+        );
+        assert_eq!(
+            file.entries[1].leading_lines,
+            vec!["; Ref refs/heads/other checked out at '/work/other'".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_detect_comment_char_from_localized_help() {
+        let content =
+            "pick abc1234 First\n\n% Befehle:\n% p, pick <Commit> = Commit verwenden\n%\n";
+        assert_eq!(detect_comment_char(content), "%");
+        assert_eq!(detect_comment_char("pick abc1234 First\n"), "#");
+    }
+
+    #[test]
+    fn test_regenerate_help_keeps_localized_block_and_changes_comment_char() {
+        let content = "\
+# Ref refs/heads/other checked out at '/work/other'
+pick abc1234 First
+pick def5678 Second
+
+# Rebase 1a2b3c4..5e6f7a8 onto 9f8e7d6 (2 Befehle)
+# Befehle:
+#
+";
+        let mut file = parse_rebase_todo(content).unwrap();
+        file.entries.pop();
+        let regenerated = regenerate_todo_help(&file, ";", false, None);
+
+        assert_eq!(regenerated.header.comment_char, ";");
+        // 翻訳された説明は作り直せないため、コメント文字だけを付け替えて残す。
+        assert_eq!(
+            regenerated.trailing_lines,
+            [
+                "",
+                "; Rebase 1a2b3c4..5e6f7a8 onto 9f8e7d6 (2 Befehle)",
+                "; Befehle:",
+                ";",
+            ]
+            .map(str::to_string)
+        );
+        assert_eq!(regenerated.header.help_lines, file.header.help_lines);
+        assert_eq!(
+            regenerated.entries[0].leading_lines,
+            vec!["; Ref refs/heads/other checked out at '/work/other'".to_string()]
+        );
+
+        let serialized = serialize_rebase_todo_lossless(&regenerated, &Default::default());
+        let reparsed = parse_rebase_todo(&serialized).unwrap();
+        assert_eq!(reparsed.entries.len(), 1);
+        assert_eq!(reparsed.header.comment_char, ";");
+        assert_eq!(reparsed.header.help_lines, regenerated.header.help_lines);
+    }
+
+    #[test]
+    fn test_render_help_lists_only_commands_of_installed_git() {
+        let header = TodoHeader::default();
+        let help = |version| render_todo_help(&header, 1, false, Some(version)).join("\n");
+
+        let current = help((2, 38, 0));
+        assert!(current.contains("# f, fixup [-C | -c] <commit>"));
+        assert!(current.contains("# u, update-ref <ref>"));
+
+        let old = help((2, 20, 0));
+        assert!(old.contains("# f, fixup <commit> = like \"squash\", but discard"));
+        assert!(!old.contains("-C | -c] <commit> ="));
+        assert!(!old.contains("update-ref"));
+        assert!(old.contains("# b, break"));
+        assert!(old.contains("# m, merge"));
+
+        let older = help((2, 17, 0));
+        assert!(!older.contains("break"));
+        assert!(!older.contains("label"));
+        assert!(!older.contains("reset"));
+        assert!(!older.contains("merge"));
+        assert!(older.contains("# d, drop"));
+    }
+
+    #[test]
+    fn test_regenerate_help_keeps_summary_and_updates_count() {
+        let mut file = parse_rebase_todo(&git_todo("#")).unwrap();
+        file.entries.remove(1);
+        let regenerated = regenerate_todo_help(&file, "#", true, None);
+
+        let serialized = serialize_rebase_todo_lossless(&regenerated, &Default::default());
+        assert!(serialized
+            .contains("\n\n# Rebase 1a2b3c4..5e6f7a8 onto 9f8e7d6 (2 commands)\n#\n# Commands:\n"));
+        assert!(serialized.contains("# Do not remove any line."));
+        assert!(serialized
+            .ends_with("# However, if you remove everything, the rebase will be aborted.\n#\n"));
+    }
+}
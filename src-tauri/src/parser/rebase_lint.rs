@@ -0,0 +1,675 @@
+//! git-rebase-todo の静的検証。
+//!
+//! Git が todo を受け付けない、または実行途中で止まる並びを保存前に検出する。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{
+    git_version_for_command, GitVersion, MetadataEdit, RebaseCommand, RebaseEntry, RebaseTodoFile,
+};
+
+/// 診断の重大度。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// Git が todo を拒否する、または rebase が確実に失敗する。
+    Error,
+    /// 実行はできるが意図しない結果になりうる。
+    Warning,
+}
+
+/// 診断の種別。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// 前にコミットがない squash / fixup。
+    FixupWithoutCommit,
+    /// reset / merge が参照する label が定義されていない。
+    UndefinedLabel,
+    /// 同じ名前の label が複数回定義されている。
+    DuplicateLabel,
+    /// 同じ ref への update-ref が複数ある。
+    DuplicateUpdateRef,
+    /// コマンドが空の exec。
+    EmptyExec,
+    /// 行の削除で落とされたコミット（rebase.missingCommitsCheck）。
+    MissingCommit,
+    /// エディタが解釈できず、そのまま書き戻すコマンド。
+    UnknownCommand,
+    /// インストールされている Git より新しいバージョンで追加されたコマンド。
+    UnsupportedCommand,
+    /// author・日時の書き換えが `git commit --amend` に渡せない。
+    InvalidMetadataEdit,
+}
+
+/// 1 件分の診断結果。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RebaseDiagnostic {
+    /// 対象エントリの id。削除済みコミットのように対応するエントリがない場合は None。
+    pub entry_id: Option<String>,
+    /// 対象のコミットハッシュ（コミットに紐づく診断のみ）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_hash: Option<String>,
+    pub severity: DiagnosticSeverity,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+/// `rebase.missingCommitsCheck` の設定値。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingCommitsCheck {
+    #[default]
+    Ignore,
+    Warn,
+    Error,
+}
+
+impl MissingCommitsCheck {
+    /// git config の値を解釈する。未知の値は Git と同様に ignore 扱いにする。
+    pub fn from_config(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "warn" => MissingCommitsCheck::Warn,
+            "error" => MissingCommitsCheck::Error,
+            _ => MissingCommitsCheck::Ignore,
+        }
+    }
+}
+
+/// 検証に使う todo 外の情報。
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    pub missing_commits_check: MissingCommitsCheck,
+    /// Git が最初に書き出した todo（git-rebase-todo.backup）のコミット一覧。
+    pub original_commits: Vec<(String, String)>,
+    /// インストールされている Git のバージョン。不明な場合は None で、比較しない。
+    pub git_version: Option<GitVersion>,
+    /// label として定義されていないが、`git rev-parse` でコミットに解決できた reset / merge の引数。
+    pub resolvable_revisions: Vec<String>,
+}
+
+/// Git が暗黙に定義する label。
+/// `reset [new root]` は空白で分割されて "[new" になるため、その形で持つ。
+const BUILTIN_LABELS: &[&str] = &["onto", "[new"];
+
+/// todo で定義されている label。
+fn defined_labels(file: &RebaseTodoFile) -> HashSet<&str> {
+    file.entries
+        .iter()
+        .filter_map(|entry| match &entry.command {
+            RebaseCommand::Label(label) => Some(label.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// reset / merge が参照する label。引数のない reset / merge は空の label を参照するものとして扱う。
+fn referenced_labels(command: &RebaseCommand) -> Vec<&str> {
+    match command {
+        RebaseCommand::Reset(label) => vec![label.as_str()],
+        RebaseCommand::Merge { labels, .. } if labels.is_empty() => vec![""],
+        RebaseCommand::Merge { labels, .. } => labels.iter().map(String::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// label として定義されていない reset / merge の引数を、重複なく todo 上の順序で返す。
+///
+/// Git はこれらを任意のリビジョン（`reset main` など）として解決するため、
+/// 呼び出し側で `git rev-parse` に渡し、解決できたものを `LintOptions` で渡す。
+pub fn undefined_label_references(file: &RebaseTodoFile) -> Vec<&str> {
+    let defined_labels = defined_labels(file);
+    let mut references: Vec<&str> = Vec::new();
+    for label in file
+        .entries
+        .iter()
+        .flat_map(|entry| referenced_labels(&entry.command))
+    {
+        if !label.is_empty()
+            && !is_known_label(label, &defined_labels)
+            && !references.contains(&label)
+        {
+            references.push(label);
+        }
+    }
+    references
+}
+
+/// todo を検証し、問題を todo 上の順序で返す。
+pub fn lint_rebase_todo(file: &RebaseTodoFile, options: &LintOptions) -> Vec<RebaseDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let defined_labels = defined_labels(file);
+
+    let mut seen_labels: HashMap<&str, usize> = HashMap::new();
+    let mut seen_refs: HashMap<&str, usize> = HashMap::new();
+    // Git と同じく、drop 以外のコマンドが 1 つでも前にあれば fixup を受け付ける。
+    let mut fixup_okay = false;
+
+    for entry in &file.entries {
+        let unsupported = unsupported_command(entry, options.git_version);
+
+        match &entry.command {
+            RebaseCommand::Squash | RebaseCommand::Fixup if !fixup_okay => {
+                let verb = if entry.command == RebaseCommand::Squash {
+                    "squash"
+                } else {
+                    "fixup"
+                };
+                diagnostics.push(RebaseDiagnostic {
+                    entry_id: Some(entry.id.clone()),
+                    commit_hash: Some(entry.commit_hash.clone()),
+                    severity: DiagnosticSeverity::Error,
+                    kind: DiagnosticKind::FixupWithoutCommit,
+                    message: format!("Cannot '{}' without a previous commit", verb),
+                });
+            }
+            RebaseCommand::Exec(command) if command.trim().is_empty() => {
+                diagnostics.push(RebaseDiagnostic {
+                    entry_id: Some(entry.id.clone()),
+                    commit_hash: None,
+                    severity: DiagnosticSeverity::Error,
+                    kind: DiagnosticKind::EmptyExec,
+                    message: "exec has no command".to_string(),
+                });
+            }
+            RebaseCommand::Label(label) => {
+                let count = seen_labels.entry(label.as_str()).or_insert(0);
+                *count += 1;
+                if *count == 2 {
+                    diagnostics.push(RebaseDiagnostic {
+                        entry_id: Some(entry.id.clone()),
+                        commit_hash: None,
+                        severity: DiagnosticSeverity::Error,
+                        kind: DiagnosticKind::DuplicateLabel,
+                        message: format!("Label '{}' is defined more than once", label),
+                    });
+                }
+            }
+            RebaseCommand::UpdateRef(ref_name) => {
+                let count = seen_refs.entry(ref_name.as_str()).or_insert(0);
+                *count += 1;
+                if *count == 2 {
+                    diagnostics.push(RebaseDiagnostic {
+                        entry_id: Some(entry.id.clone()),
+                        commit_hash: None,
+                        severity: DiagnosticSeverity::Error,
+                        kind: DiagnosticKind::DuplicateUpdateRef,
+                        message: format!("'{}' is updated more than once", ref_name),
+                    });
+                }
+            }
+            RebaseCommand::Reset(_) | RebaseCommand::Merge { .. } => {
+                for label in referenced_labels(&entry.command) {
+                    if is_known_label(label, &defined_labels)
+                        || options.resolvable_revisions.iter().any(|rev| rev == label)
+                    {
+                        continue;
+                    }
+                    diagnostics.push(RebaseDiagnostic {
+                        entry_id: Some(entry.id.clone()),
+                        commit_hash: None,
+                        severity: DiagnosticSeverity::Error,
+                        kind: DiagnosticKind::UndefinedLabel,
+                        message: format!("Label '{}' is never defined", label),
+                    });
+                }
+            }
+            // インストールされている Git より新しい動詞は、下の UnsupportedCommand で報告する。
+            RebaseCommand::Opaque(line) if unsupported.is_none() && !entry.command.is_noop() => {
+                let verb = entry.command.to_short();
+                let message = match git_version_for_command(verb) {
+                    Some(version) => format!(
+                        "'{}' (added in git {}) is not editable here and is kept as written",
+                        verb,
+                        format_version(version)
+                    ),
+                    None => format!("Unknown command '{}' is kept as written: {}", verb, line),
+                };
+                diagnostics.push(RebaseDiagnostic {
+                    entry_id: Some(entry.id.clone()),
+                    commit_hash: None,
+                    severity: DiagnosticSeverity::Warning,
+                    kind: DiagnosticKind::UnknownCommand,
+                    message,
+                });
+            }
+            _ => {}
+        }
+
+        if let Some(message) = unsupported {
+            diagnostics.push(RebaseDiagnostic {
+                entry_id: Some(entry.id.clone()),
+                commit_hash: None,
+                severity: DiagnosticSeverity::Warning,
+                kind: DiagnosticKind::UnsupportedCommand,
+                message,
+            });
+        }
+
+        if let Some(message) = entry.metadata_edit.as_ref().and_then(metadata_edit_problem) {
+            diagnostics.push(RebaseDiagnostic {
+                entry_id: Some(entry.id.clone()),
+                commit_hash: Some(entry.commit_hash.clone()),
+                severity: DiagnosticSeverity::Error,
+                kind: DiagnosticKind::InvalidMetadataEdit,
+                message,
+            });
+        }
+
+        if !matches!(entry.command, RebaseCommand::Drop) {
+            fixup_okay = true;
+        }
+    }
+
+    diagnostics.extend(find_missing_commits(file, options));
+    diagnostics
+}
+
+/// author は `Name <email>` 形式、日時は空でないことを確かめる。
+fn metadata_edit_problem(edit: &MetadataEdit) -> Option<String> {
+    if let Some(author) = &edit.author {
+        let valid = author
+            .trim()
+            .strip_suffix('>')
+            .and_then(|rest| rest.split_once('<'))
+            .map(|(name, email)| !name.trim().is_empty() && !email.trim().is_empty())
+            .unwrap_or(false);
+        if !valid || author.contains('\n') {
+            return Some(format!("Author '{}' is not in 'Name <email>' form", author));
+        }
+    }
+    if edit.date.as_deref().map(str::trim) == Some("") {
+        return Some("Date override is empty".to_string());
+    }
+    None
+}
+
+/// label 名として解決できるかを返す。
+///
+/// reset / merge は label が見つからない場合にコミットとして解決を試みるため、
+/// 16 進のハッシュらしい引数は未定義扱いにしない。
+fn is_known_label(label: &str, defined_labels: &HashSet<&str>) -> bool {
+    if label.is_empty() {
+        return false;
+    }
+
+    defined_labels.contains(label)
+        || BUILTIN_LABELS.contains(&label)
+        || (label.len() >= 4 && label.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// 元の todo にあったが、現在の todo から行ごと消えたコミットを探す。
+fn find_missing_commits(file: &RebaseTodoFile, options: &LintOptions) -> Vec<RebaseDiagnostic> {
+    let severity = match options.missing_commits_check {
+        MissingCommitsCheck::Ignore => return Vec::new(),
+        MissingCommitsCheck::Warn => DiagnosticSeverity::Warning,
+        MissingCommitsCheck::Error => DiagnosticSeverity::Error,
+    };
+
+    let present: Vec<&str> = file
+        .entries
+        .iter()
+        .filter_map(|entry| entry.referenced_commit())
+        .collect();
+
+    options
+        .original_commits
+        .iter()
+        .filter(|(hash, _)| {
+            !present
+                .iter()
+                .any(|current| hashes_match(current, hash.as_str()))
+        })
+        .map(|(hash, subject)| RebaseDiagnostic {
+            entry_id: None,
+            commit_hash: Some(hash.clone()),
+            severity,
+            kind: DiagnosticKind::MissingCommit,
+            message: format!(
+                "Commit {} '{}' was removed; use 'drop' to discard it explicitly",
+                hash, subject
+            ),
+        })
+        .collect()
+}
+
+/// コマンドがインストールされている Git より新しければ、その旨のメッセージを返す。
+fn unsupported_command(entry: &RebaseEntry, installed: Option<GitVersion>) -> Option<String> {
+    let installed = installed?;
+    let required = entry.required_git_version()?;
+    let command = match (&entry.command, &entry.fixup_option) {
+        (RebaseCommand::Fixup, Some(option)) => format!("fixup {}", option),
+        (command, _) => command.to_long().to_string(),
+    };
+    (required > installed).then(|| {
+        format!(
+            "'{}' requires git {} or later, but git {} is installed",
+            command,
+            format_version(required),
+            format_version(installed)
+        )
+    })
+}
+
+pub(crate) fn format_version((major, minor, patch): GitVersion) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// 短縮形が混在してもよいよう、短い方を接頭辞として比較する。
+///
+/// ユーザーが入力した値も渡るため、バイト単位で比較して非 ASCII でも panic しない。
+pub(crate) fn hashes_match(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    let len = a.len().min(b.len());
+    a.as_bytes()[..len].eq_ignore_ascii_case(&b.as_bytes()[..len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    fn lint(content: &str) -> Vec<RebaseDiagnostic> {
+        let file = parse_rebase_todo(content).unwrap();
+        lint_rebase_todo(&file, &LintOptions::default())
+    }
+
+    #[test]
+    fn test_valid_todo_has_no_diagnostics() {
+        let content = "pick abc1234 First\nfixup def5678 Second\nexec make test\n";
+        assert!(lint(content).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_metadata_edit() {
+        let mut file = parse_rebase_todo("pick abc1234 First\npick def5678 Second\n").unwrap();
+        file.entries[0].metadata_edit = Some(MetadataEdit {
+            author: Some("Alice <alice@example.com>".to_string()),
+            ..MetadataEdit::default()
+        });
+        file.entries[1].metadata_edit = Some(MetadataEdit {
+            author: Some("alice@example.com".to_string()),
+            ..MetadataEdit::default()
+        });
+
+        let diagnostics = lint_rebase_todo(&file, &LintOptions::default());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::InvalidMetadataEdit);
+        assert_eq!(
+            diagnostics[0].entry_id.as_deref(),
+            Some(file.entries[1].id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_fixup_without_previous_commit() {
+        let file = parse_rebase_todo("drop abc1234 First\nsquash def5678 Second\n").unwrap();
+        let diagnostics = lint_rebase_todo(&file, &LintOptions::default());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::FixupWithoutCommit);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(
+            diagnostics[0].entry_id.as_deref(),
+            Some(file.entries[1].id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_undefined_label_for_reset_and_merge() {
+        let content = "\
+label onto
+reset onto
+pick abc1234 First
+label feature
+reset missing
+merge -C def5678 other # Merge branch 'other'
+merge -C 9876fed feature # Merge branch 'feature'
+merge -C 1234abc feature octo # Merge branches 'feature' and 'octo'
+";
+        let diagnostics = lint(content);
+        let labels: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.kind == DiagnosticKind::UndefinedLabel)
+            .map(|d| d.message.as_str())
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Label 'missing' is never defined",
+                "Label 'other' is never defined",
+                "Label 'octo' is never defined",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolvable_revision_is_not_undefined_label() {
+        let content = "\
+label onto
+reset main
+pick abc1234 First
+merge -C def5678 origin/topic # Merge branch 'topic'
+reset main
+merge -C 9876fed missing
+";
+        let file = parse_rebase_todo(content).unwrap();
+        assert_eq!(
+            undefined_label_references(&file),
+            vec!["main", "origin/topic", "missing"]
+        );
+
+        let diagnostics = lint_rebase_todo(
+            &file,
+            &LintOptions {
+                resolvable_revisions: vec!["main".to_string(), "origin/topic".to_string()],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UndefinedLabel);
+        assert_eq!(diagnostics[0].message, "Label 'missing' is never defined");
+    }
+
+    #[test]
+    fn test_reset_to_commit_hash_is_not_undefined_label() {
+        assert!(lint("reset 1a2b3c4 # upstream commit\npick abc1234 First\n").is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_label_and_update_ref() {
+        let content = "\
+pick abc1234 First
+label base
+update-ref refs/heads/feature
+pick def5678 Second
+label base
+update-ref refs/heads/feature
+";
+        let kinds: Vec<DiagnosticKind> = lint(content).into_iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiagnosticKind::DuplicateLabel,
+                DiagnosticKind::DuplicateUpdateRef
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_exec() {
+        let diagnostics = lint("pick abc1234 First\nexec\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::EmptyExec);
+    }
+
+    #[test]
+    fn test_missing_commits_follow_config_severity() {
+        let file = parse_rebase_todo("pick abc1234 First\ndrop def5678 Second\n").unwrap();
+        let original_commits = vec![
+            ("abc1234".to_string(), "First".to_string()),
+            ("def5678".to_string(), "Second".to_string()),
+            ("0123abc".to_string(), "Third".to_string()),
+        ];
+
+        let ignored = lint_rebase_todo(
+            &file,
+            &LintOptions {
+                missing_commits_check: MissingCommitsCheck::Ignore,
+                original_commits: original_commits.clone(),
+                ..Default::default()
+            },
+        );
+        assert!(ignored.is_empty());
+
+        let warned = lint_rebase_todo(
+            &file,
+            &LintOptions {
+                missing_commits_check: MissingCommitsCheck::Warn,
+                original_commits,
+                ..Default::default()
+            },
+        );
+        assert_eq!(warned.len(), 1);
+        assert_eq!(warned[0].kind, DiagnosticKind::MissingCommit);
+        assert_eq!(warned[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(warned[0].entry_id, None);
+        assert_eq!(warned[0].commit_hash.as_deref(), Some("0123abc"));
+    }
+
+    #[test]
+    fn test_missing_commits_check_from_config() {
+        assert_eq!(
+            MissingCommitsCheck::from_config("Error\n"),
+            MissingCommitsCheck::Error
+        );
+        assert_eq!(
+            MissingCommitsCheck::from_config("warn"),
+            MissingCommitsCheck::Warn
+        );
+        assert_eq!(
+            MissingCommitsCheck::from_config("bogus"),
+            MissingCommitsCheck::Ignore
+        );
+    }
+
+    #[test]
+    fn test_hashes_match_abbreviated() {
+        assert!(hashes_match("abc1234", "abc1234def"));
+        assert!(hashes_match("ABC1234", "abc1234"));
+        assert!(!hashes_match("abc1234", "abc1235"));
+        assert!(!hashes_match("", "abc1234"));
+    }
+
+    #[test]
+    fn test_hashes_match_non_ascii_does_not_panic() {
+        assert!(!hashes_match("aé", "ab"));
+        assert!(!hashes_match("ab", "aé"));
+        assert!(hashes_match("é", "é1"));
+    }
+
+    #[test]
+    fn test_command_newer_than_installed_git_is_reported() {
+        let content = "pick abc1234 First\nupdate-ref refs/heads/topic\nbreak\n";
+        let file = parse_rebase_todo(content).unwrap();
+        let options = LintOptions {
+            git_version: Some((2, 30, 1)),
+            ..Default::default()
+        };
+
+        let diagnostics = lint_rebase_todo(&file, &options);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].entry_id, Some(file.entries[1].id.clone()));
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnsupportedCommand);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "'update-ref' requires git 2.38.0 or later, but git 2.30.1 is installed"
+        );
+
+        let current = LintOptions {
+            git_version: Some((2, 38, 0)),
+            ..Default::default()
+        };
+        assert!(lint_rebase_todo(&file, &current).is_empty());
+        // バージョンが分からなければ比較しない。
+        assert!(lint(content).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_command_is_reported_as_warning() {
+        let diagnostics = lint("pick abc1234 First\nfrobnicate abc1234\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnknownCommand);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert!(diagnostics[0].message.contains("'frobnicate'"));
+    }
+
+    #[test]
+    fn test_noop_is_not_reported() {
+        // `git rebase -i HEAD` のように取り込むコミットがない場合、Git は noop だけを書き出す。
+        assert!(lint("noop\n").is_empty());
+    }
+
+    #[test]
+    fn test_fixup_message_option_requires_git_2_32() {
+        let file =
+            parse_rebase_todo("pick abc1234 First\nfixup -C def5678 amend! First\n").unwrap();
+        let options = LintOptions {
+            git_version: Some((2, 31, 0)),
+            ..Default::default()
+        };
+
+        let diagnostics = lint_rebase_todo(&file, &options);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnsupportedCommand);
+        assert_eq!(
+            diagnostics[0].message,
+            "'fixup -C' requires git 2.32.0 or later, but git 2.31.0 is installed"
+        );
+    }
+
+    #[test]
+    fn test_opaque_line_names_git_version_of_its_verb() {
+        // 解釈できずに保持した行でも、動詞が既知なら追加された Git のバージョンを示す。
+        let mut file = parse_rebase_todo("pick abc1234 First\n").unwrap();
+        file.entries.push(RebaseEntry::new(
+            RebaseCommand::Opaque("update-ref refs/heads/topic extra".to_string()),
+            String::new(),
+            String::new(),
+        ));
+
+        let unknown_version = lint_rebase_todo(&file, &LintOptions::default());
+        assert_eq!(unknown_version.len(), 1);
+        assert_eq!(unknown_version[0].kind, DiagnosticKind::UnknownCommand);
+        assert_eq!(
+            unknown_version[0].message,
+            "'update-ref' (added in git 2.38.0) is not editable here and is kept as written"
+        );
+
+        // インストールされている Git より新しければ、その旨の警告 1 件にまとめる。
+        let older = lint_rebase_todo(
+            &file,
+            &LintOptions {
+                git_version: Some((2, 37, 1)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(older.len(), 1);
+        assert_eq!(older[0].kind, DiagnosticKind::UnsupportedCommand);
+        assert_eq!(
+            older[0].message,
+            "'update-ref' requires git 2.38.0 or later, but git 2.37.1 is installed"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{MetadataEdit, RebaseCommand, RebaseEntry, RebaseTodoFile};
+use super::rebase_lint::hashes_match;
+use crate::error::AppError;
+
+/// 保存する計画の形式のバージョン。形式を変えたら上げる。
+pub const REBASE_PLAN_VERSION: u32 = 1;
+
+/// 計画に保存する 1 エントリ。コミットは元のハッシュと patch-id で対応付ける。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RebasePlanEntry {
+    pub command: RebaseCommand,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixup_option: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub commit_hash: String,
+    /// `git patch-id --stable` の値。ハッシュが変わったコミットの対応付けに使う。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch_id: Option<String>,
+    /// reword で書き換えたメッセージを含む、エントリのメッセージ。
+    #[serde(default)]
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_edit: Option<MetadataEdit>,
+}
+
+/// 並び順・コマンド・メッセージの決定を、別の `git rebase -i` で使い回すための計画。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RebasePlan {
+    pub version: u32,
+    pub entries: Vec<RebasePlanEntry>,
+}
+
+/// 計画を todo に当てはめた結果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedRebasePlan {
+    pub file: RebaseTodoFile,
+    /// 計画にあるが todo に見つからなかったコミットのエントリ。
+    pub missing: Vec<RebasePlanEntry>,
+    /// todo にあるが計画になかったコミットのエントリ id。元のコマンドのまま残す。
+    pub unplanned_entry_ids: Vec<String>,
+    /// ハッシュではなく patch-id で対応付けたエントリ id。
+    pub matched_by_patch_id: Vec<String>,
+}
+
+fn patch_id_of<'a>(patch_ids: &'a [(String, String)], hash: &str) -> Option<&'a str> {
+    patch_ids
+        .iter()
+        .find(|(commit, _)| hashes_match(commit, hash))
+        .map(|(_, patch_id)| patch_id.as_str())
+}
+
+/// todo を計画として書き出す。`patch_ids` は todo のコミットの (commit, patch-id)。
+pub fn create_rebase_plan(file: &RebaseTodoFile, patch_ids: &[(String, String)]) -> RebasePlan {
+    RebasePlan {
+        version: REBASE_PLAN_VERSION,
+        entries: file
+            .entries
+            .iter()
+            .map(|entry| RebasePlanEntry {
+                command: entry.command.clone(),
+                fixup_option: entry.fixup_option.clone(),
+                commit_hash: entry.commit_hash.clone(),
+                patch_id: patch_id_of(patch_ids, &entry.commit_hash).map(str::to_string),
+                message: entry.message.clone(),
+                metadata_edit: entry.metadata_edit.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// 計画を新しい todo に当てはめる。`patch_ids` は todo のコミットの (commit, patch-id)。
+///
+/// コミットはハッシュ、次に patch-id で対応付け、計画の並びとコマンド・メッセージにする。
+/// exec や update-ref などコミットを持たないエントリは、todo に同じものがあればそれを使い、
+/// なければ計画から追加する。計画にない todo のエントリは、todo 上で直前にあったエントリの
+/// 後ろに元のまま残し、コミットなら `unplanned_entry_ids` で報告する。
+pub fn apply_rebase_plan(
+    file: &RebaseTodoFile,
+    plan: &RebasePlan,
+    patch_ids: &[(String, String)],
+) -> Result<AppliedRebasePlan, AppError> {
+    if plan.version != REBASE_PLAN_VERSION {
+        return Err(AppError::CommandError {
+            message: format!("Unsupported rebase plan version: {}", plan.version),
+        });
+    }
+
+    let mut used = vec![false; file.entries.len()];
+    let mut missing = Vec::new();
+    let mut matched_by_patch_id = Vec::new();
+    // (todo 上の位置, エントリ)。計画から追加したエントリは位置を持たない。
+    let mut output: Vec<(Option<usize>, RebaseEntry)> = Vec::new();
+
+    for planned in &plan.entries {
+        let index = if planned.commit_hash.is_empty() {
+            (0..file.entries.len()).find(|&i| {
+                !used[i]
+                    && file.entries[i].commit_hash.is_empty()
+                    && file.entries[i].command == planned.command
+            })
+        } else {
+            let by_hash = (0..file.entries.len()).find(|&i| {
+                !used[i] && hashes_match(&file.entries[i].commit_hash, &planned.commit_hash)
+            });
+            by_hash.or_else(|| {
+                let patch_id = planned.patch_id.as_deref()?;
+                let index = (0..file.entries.len()).find(|&i| {
+                    !used[i]
+                        && !file.entries[i].commit_hash.is_empty()
+                        && patch_id_of(patch_ids, &file.entries[i].commit_hash) == Some(patch_id)
+                })?;
+                matched_by_patch_id.push(file.entries[index].id.clone());
+                Some(index)
+            })
+        };
+
+        match index {
+            Some(index) => {
+                used[index] = true;
+                let mut entry = file.entries[index].clone();
+                entry.command = planned.command.clone();
+                entry.fixup_option = planned.fixup_option.clone();
+                entry.metadata_edit = planned.metadata_edit.clone();
+                if !planned.commit_hash.is_empty() {
+                    entry.message = planned.message.clone();
+                }
+                output.push((Some(index), entry));
+            }
+            None if planned.commit_hash.is_empty() => {
+                let mut entry = RebaseEntry::new(
+                    planned.command.clone(),
+                    String::new(),
+                    planned.message.clone(),
+                );
+                entry.metadata_edit = planned.metadata_edit.clone();
+                output.push((None, entry));
+            }
+            None => missing.push(planned.clone()),
+        }
+    }
+
+    let mut unplanned_entry_ids = Vec::new();
+    for (index, entry) in file.entries.iter().enumerate() {
+        if used[index] {
+            continue;
+        }
+        if !entry.commit_hash.is_empty() {
+            unplanned_entry_ids.push(entry.id.clone());
+        }
+        let position = output
+            .iter()
+            .rposition(|(original, _)| original.is_some_and(|original| original < index))
+            .map_or(0, |position| position + 1);
+        output.insert(position, (Some(index), entry.clone()));
+    }
+
+    Ok(AppliedRebasePlan {
+        file: RebaseTodoFile {
+            entries: output.into_iter().map(|(_, entry)| entry).collect(),
+            ..file.clone()
+        },
+        missing,
+        unplanned_entry_ids,
+        matched_by_patch_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    fn lines(file: &RebaseTodoFile) -> Vec<String> {
+        file.entries
+            .iter()
+            .map(|entry| match &entry.command {
+                RebaseCommand::Exec(command) => format!("exec {}", command),
+                RebaseCommand::UpdateRef(ref_name) => format!("update-ref {}", ref_name),
+                other => format!(
+                    "{} {} {}",
+                    other.to_long(),
+                    entry.commit_hash,
+                    entry.message
+                ),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_rebase_plan_restores_order_commands_and_messages() {
+        let mut first = parse_rebase_todo(
+            "pick aaa1111 Part 1\npick bbb2222 Part 2\npick ccc3333 fixup! Part 1\n",
+        )
+        .unwrap();
+        first.entries[1].command = RebaseCommand::Reword;
+        first.entries[1].message = "Part 2, reworded\n\nWith a body".to_string();
+        first.entries[2].command = RebaseCommand::Fixup;
+        let fixup = first.entries.remove(2);
+        first.entries.insert(1, fixup);
+        first.entries.push(RebaseEntry::new(
+            RebaseCommand::Exec("make test".to_string()),
+            String::new(),
+            String::new(),
+        ));
+        let plan = create_rebase_plan(&first, &[]);
+
+        let fresh = parse_rebase_todo(
+            "pick aaa1111 Part 1\npick bbb2222 Part 2\npick ccc3333 fixup! Part 1\n",
+        )
+        .unwrap();
+        let applied = apply_rebase_plan(&fresh, &plan, &[]).unwrap();
+
+        assert_eq!(
+            lines(&applied.file),
+            vec![
+                "pick aaa1111 Part 1",
+                "fixup ccc3333 fixup! Part 1",
+                "reword bbb2222 Part 2, reworded\n\nWith a body",
+                "exec make test",
+            ]
+        );
+        assert_eq!(applied.file.entries[0].id, fresh.entries[0].id);
+        assert!(applied.missing.is_empty());
+        assert!(applied.unplanned_entry_ids.is_empty());
+    }
+
+    #[test]
+    fn test_apply_rebase_plan_matches_by_patch_id() {
+        let mut first = parse_rebase_todo("pick aaa1111 Part 1\n").unwrap();
+        first.entries[0].command = RebaseCommand::Drop;
+        let plan = create_rebase_plan(&first, &[("aaa1111000".to_string(), "patch-1".to_string())]);
+
+        // コミットを作り直したため、ハッシュだけが変わっている。
+        let fresh = parse_rebase_todo("pick ddd4444 Part 1\n").unwrap();
+        let applied = apply_rebase_plan(
+            &fresh,
+            &plan,
+            &[("ddd4444000".to_string(), "patch-1".to_string())],
+        )
+        .unwrap();
+
+        assert_eq!(lines(&applied.file), vec!["drop ddd4444 Part 1"]);
+        assert_eq!(
+            applied.matched_by_patch_id,
+            vec![fresh.entries[0].id.clone()]
+        );
+    }
+
+    #[test]
+    fn test_apply_rebase_plan_reports_unmatched_entries() {
+        let plan = create_rebase_plan(
+            &parse_rebase_todo(
+                "pick aaa1111 Part 1\nupdate-ref refs/heads/part1\npick eee5555 Gone\n",
+            )
+            .unwrap(),
+            &[],
+        );
+
+        let fresh = parse_rebase_todo(
+            "pick fff6666 New first\npick aaa1111 Part 1\npick ddd7777 New\nupdate-ref refs/heads/part1\n",
+        )
+        .unwrap();
+        let applied = apply_rebase_plan(&fresh, &plan, &[]).unwrap();
+
+        assert_eq!(
+            lines(&applied.file),
+            vec![
+                "pick fff6666 New first",
+                "pick aaa1111 Part 1",
+                "pick ddd7777 New",
+                "update-ref refs/heads/part1",
+            ]
+        );
+        assert_eq!(applied.missing.len(), 1);
+        assert_eq!(applied.missing[0].commit_hash, "eee5555");
+        assert_eq!(
+            applied.unplanned_entry_ids,
+            vec![fresh.entries[0].id.clone(), fresh.entries[2].id.clone()]
+        );
+    }
+
+    #[test]
+    fn test_apply_rebase_plan_rejects_unknown_version() {
+        let fresh = parse_rebase_todo("pick aaa1111 Part 1\n").unwrap();
+        let plan = RebasePlan {
+            version: REBASE_PLAN_VERSION + 1,
+            entries: Vec::new(),
+        };
+
+        assert!(apply_rebase_plan(&fresh, &plan, &[]).is_err());
+    }
+}
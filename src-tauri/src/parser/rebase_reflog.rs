@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+
+/// reflog の 1 件。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflogEntry {
+    /// 更新後のコミット。
+    pub hash: String,
+    /// ISO 8601 形式の更新日時。
+    pub date: String,
+    /// `rebase (finish): returning to refs/heads/topic` のような reflog メッセージ。
+    pub subject: String,
+}
+
+/// `git log -g --date=iso-strict` に指定する書式。
+pub const REFLOG_FORMAT: &str = "%H%x1f%gd%x1f%gs";
+
+/// `REFLOG_FORMAT` を指定した reflog の出力（新しい順）を解析する。
+pub fn parse_reflog(output: &str) -> Vec<ReflogEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\u{1f}');
+            let (Some(hash), Some(selector), Some(subject)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return None;
+            };
+            // `HEAD@{2024-01-02T03:04:05+09:00}` から日時を取り出す。
+            let date = selector
+                .split_once("@{")
+                .map(|(_, rest)| rest.trim_end_matches('}'))
+                .unwrap_or_default();
+            Some(ReflogEntry {
+                hash: hash.to_string(),
+                date: date.to_string(),
+                subject: subject.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// rebase が記録した reflog メッセージから、`start` などの段階と続く説明を取り出す。
+/// 古い Git の `rebase -i (start)` や `rebase -m (start)` も受け付ける。
+fn rebase_action(subject: &str) -> Option<(&str, &str)> {
+    let rest = subject.strip_prefix("rebase")?;
+    let rest = rest
+        .strip_prefix(" -i")
+        .or_else(|| rest.strip_prefix(" -m"))
+        .unwrap_or(rest);
+    let (action, detail) = rest.strip_prefix(" (")?.split_once("):")?;
+    Some((action, detail.trim()))
+}
+
+/// rebase セッションの結末。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RebaseSessionStatus {
+    Finished,
+    Aborted,
+    /// finish も abort も記録されていない（進行中、または reflog が途中で切れている）。
+    Incomplete,
+}
+
+/// rebase 前のコミットと現在の状態との差分の規模。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// HEAD の reflog の `rebase (start)` から `rebase (finish)` / `rebase (abort)` までのまとまり。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RebaseSession {
+    pub status: RebaseSessionStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// rebase を始める前に HEAD（ブランチ）が指していたコミット。
+    pub before_commit: String,
+    /// rebase の移動先（`rebase (start)` で checkout したコミット）。
+    pub onto_commit: String,
+    /// rebase が終わったときのコミット。
+    pub after_commit: Option<String>,
+    /// rebase したブランチ（`refs/heads/...`）。detached HEAD や未完了なら None。
+    pub head_name: Option<String>,
+    /// HEAD を動かしたステップ数（pick や reword など。start と finish は含まない）。
+    pub steps: usize,
+    /// rebase 前のコミットと現在のブランチとの差分。読み取れなければ None。
+    #[serde(default)]
+    pub diff: Option<DiffSummary>,
+}
+
+/// HEAD の reflog を rebase ごとのセッションに分け、新しい順に返す。
+///
+/// 現在のブランチの reflog（`branch_ref` のもの）があれば、そのブランチで終わった
+/// セッションの rebase 前のコミットは、ブランチ側の `rebase (finish)` の直前の値を使う。
+/// `git rebase <upstream> <branch>` のように別のブランチから始めた場合も正しく求まる。
+/// reflog が rebase の途中から始まっているセッションは、rebase 前が分からないため含めない。
+pub fn group_rebase_sessions(
+    head_log: &[ReflogEntry],
+    branch_ref: Option<&str>,
+    branch_log: &[ReflogEntry],
+) -> Vec<RebaseSession> {
+    let mut sessions = Vec::new();
+    let mut current: Option<RebaseSession> = None;
+    // reflog は新しい順なので、古い順にたどる。
+    let chronological: Vec<&ReflogEntry> = head_log.iter().rev().collect();
+
+    for (index, entry) in chronological.iter().enumerate() {
+        let Some((action, detail)) = rebase_action(&entry.subject) else {
+            continue;
+        };
+        match action {
+            "start" => {
+                if let Some(session) = current.take() {
+                    sessions.push(session);
+                }
+                let Some(before) = index.checked_sub(1).map(|i| chronological[i]) else {
+                    continue;
+                };
+                current = Some(RebaseSession {
+                    status: RebaseSessionStatus::Incomplete,
+                    started_at: entry.date.clone(),
+                    finished_at: None,
+                    before_commit: before.hash.clone(),
+                    onto_commit: entry.hash.clone(),
+                    after_commit: None,
+                    head_name: None,
+                    steps: 0,
+                    diff: None,
+                });
+            }
+            "finish" | "abort" => {
+                if let Some(mut session) = current.take() {
+                    session.status = if action == "finish" {
+                        RebaseSessionStatus::Finished
+                    } else {
+                        RebaseSessionStatus::Aborted
+                    };
+                    session.finished_at = Some(entry.date.clone());
+                    session.after_commit = Some(entry.hash.clone());
+                    session.head_name = detail
+                        .strip_prefix("returning to ")
+                        .filter(|name| name.starts_with("refs/"))
+                        .map(str::to_string);
+                    sessions.push(session);
+                }
+            }
+            _ => {
+                if let Some(session) = current.as_mut() {
+                    session.steps += 1;
+                }
+            }
+        }
+    }
+    if let Some(session) = current {
+        sessions.push(session);
+    }
+
+    for session in &mut sessions {
+        if session.status != RebaseSessionStatus::Finished
+            || session.head_name.as_deref() != branch_ref
+        {
+            continue;
+        }
+        let finished_on_branch = branch_log.iter().position(|entry| {
+            Some(&entry.hash) == session.after_commit.as_ref()
+                && rebase_action(&entry.subject).is_some_and(|(action, _)| action == "finish")
+        });
+        if let Some(before) = finished_on_branch.and_then(|index| branch_log.get(index + 1)) {
+            session.before_commit = before.hash.clone();
+        }
+    }
+
+    sessions.reverse();
+    sessions
+}
+
+/// `git diff --numstat` の出力を集計する。バイナリファイル（`-`）は行数に数えない。
+pub fn summarize_numstat(output: &str) -> DiffSummary {
+    let mut summary = DiffSummary::default();
+    for line in output.lines() {
+        let mut columns = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(_)) =
+            (columns.next(), columns.next(), columns.next())
+        else {
+            continue;
+        };
+        summary.files_changed += 1;
+        summary.insertions += added.parse::<usize>().unwrap_or(0);
+        summary.deletions += deleted.parse::<usize>().unwrap_or(0);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, subject: &str) -> ReflogEntry {
+        ReflogEntry {
+            hash: hash.to_string(),
+            date: format!("date-{}", hash),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_reflog_extracts_date_from_selector() {
+        let output =
+            "aaaa\u{1f}HEAD@{2024-01-02T03:04:05+09:00}\u{1f}rebase (start): checkout main\n";
+
+        assert_eq!(
+            parse_reflog(output),
+            vec![ReflogEntry {
+                hash: "aaaa".to_string(),
+                date: "2024-01-02T03:04:05+09:00".to_string(),
+                subject: "rebase (start): checkout main".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rebase_action_accepts_old_formats() {
+        assert_eq!(
+            rebase_action("rebase -i (finish): returning to refs/heads/topic"),
+            Some(("finish", "returning to refs/heads/topic"))
+        );
+        assert_eq!(
+            rebase_action("rebase (pick): Add parser"),
+            Some(("pick", "Add parser"))
+        );
+        assert_eq!(rebase_action("commit: rebase (start) notes"), None);
+    }
+
+    #[test]
+    fn test_group_rebase_sessions_splits_by_start_and_end() {
+        // 新しい順。
+        let head_log = vec![
+            entry("i1", "rebase (start): checkout main"),
+            entry("f2", "rebase (abort): returning to refs/heads/topic"),
+            entry("p2", "rebase (pick): Part 2"),
+            entry("s2", "rebase (start): checkout main"),
+            entry("f1", "rebase (finish): returning to refs/heads/topic"),
+            entry("a1", "commit (amend): Part 1"),
+            entry("p1", "rebase (pick): Part 1"),
+            entry("s1", "rebase (start): checkout main"),
+            entry("b1", "commit: Part 2"),
+            entry("s0", "rebase (pick): cut off before start"),
+        ];
+
+        let sessions = group_rebase_sessions(&head_log, None, &[]);
+
+        let summary: Vec<(RebaseSessionStatus, &str, usize)> = sessions
+            .iter()
+            .map(|s| (s.status, s.before_commit.as_str(), s.steps))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (RebaseSessionStatus::Incomplete, "f2", 0),
+                (RebaseSessionStatus::Aborted, "f1", 1),
+                (RebaseSessionStatus::Finished, "b1", 1),
+            ]
+        );
+        assert_eq!(sessions[2].onto_commit, "s1");
+        assert_eq!(sessions[2].after_commit.as_deref(), Some("f1"));
+        assert_eq!(sessions[2].head_name.as_deref(), Some("refs/heads/topic"));
+    }
+
+    #[test]
+    fn test_group_rebase_sessions_prefers_branch_reflog_for_before() {
+        // `git rebase main topic` を別のブランチから実行した場合、HEAD の直前は topic ではない。
+        let head_log = vec![
+            entry("f1", "rebase (finish): returning to refs/heads/topic"),
+            entry("p1", "rebase (pick): Part 1"),
+            entry("s1", "rebase (start): checkout main"),
+            entry("m0", "checkout: moving from topic to other"),
+        ];
+        let branch_log = vec![
+            entry("f1", "rebase (finish): refs/heads/topic onto s1"),
+            entry("t0", "commit: Part 1"),
+        ];
+
+        let sessions = group_rebase_sessions(&head_log, Some("refs/heads/topic"), &branch_log);
+
+        assert_eq!(sessions[0].before_commit, "t0");
+    }
+
+    #[test]
+    fn test_summarize_numstat_skips_binary_line_counts() {
+        assert_eq!(
+            summarize_numstat("3\t1\tsrc/a.rs\n-\t-\timage.png\n"),
+            DiffSummary {
+                files_changed: 2,
+                insertions: 3,
+                deletions: 1,
+            }
+        );
+    }
+}
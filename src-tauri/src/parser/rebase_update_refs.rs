@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+
+use super::rebase::{RebaseCommand, RebaseEntry, RebaseTodoFile};
+use super::rebase_lint::hashes_match;
+use crate::error::AppError;
+
+/// todo のコミットを指すローカルブランチと、rebase 後にどこへ移るか。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StackBranch {
+    pub ref_name: String,
+    /// ブランチが現在指しているコミット。まだ存在しない ref なら空。
+    pub commit_hash: String,
+    /// 現在指しているコミットのエントリ。
+    pub entry_id: Option<String>,
+    /// この ref を更新する update-ref エントリ。
+    pub update_ref_entry_id: Option<String>,
+    /// rebase 後にブランチが指すエントリ（update-ref の直前でコミットを作るエントリ）。
+    pub target_entry_id: Option<String>,
+    /// 別のワークツリーでチェックアウトされている場合、そのパス。
+    pub checked_out_at: Option<String>,
+    /// rebase 中のブランチ（`head-name`）。update-ref がなくても最後に更新される。
+    pub is_rebased_branch: bool,
+}
+
+/// rebase 後にブランチを置けるエントリ。
+fn creates_commit(command: &RebaseCommand) -> bool {
+    matches!(
+        command,
+        RebaseCommand::Pick
+            | RebaseCommand::Reword
+            | RebaseCommand::Edit
+            | RebaseCommand::Squash
+            | RebaseCommand::Fixup
+            | RebaseCommand::Merge { .. }
+    )
+}
+
+fn is_update_ref_of(entry: &RebaseEntry, ref_name: &str) -> bool {
+    matches!(&entry.command, RebaseCommand::UpdateRef(existing) if existing == ref_name)
+}
+
+/// `git worktree list --porcelain` の出力から、(ワークツリーのパス, チェックアウト中の ref) を返す。
+/// detached HEAD のワークツリーは含めない。
+pub fn parse_worktree_branches(output: &str) -> Vec<(String, String)> {
+    let mut branches = Vec::new();
+    let mut path: Option<&str> = None;
+    for line in output.lines() {
+        if let Some(worktree) = line.strip_prefix("worktree ") {
+            path = Some(worktree);
+        } else if let (Some(ref_name), Some(worktree)) = (line.strip_prefix("branch "), path) {
+            branches.push((worktree.to_string(), ref_name.to_string()));
+        }
+    }
+    branches
+}
+
+/// ローカルブランチと todo を照らし合わせ、todo のコミットを指すブランチ、update-ref の対象、
+/// rebase 中のブランチを todo 上の順に返す。
+///
+/// `branches` は (ref, コミット)、`checked_out` は別のワークツリーの (パス, ref)。
+pub fn plan_stack_branches(
+    file: &RebaseTodoFile,
+    branches: &[(String, String)],
+    checked_out: &[(String, String)],
+    head_name: Option<&str>,
+) -> Vec<StackBranch> {
+    let last_commit_before = |index: usize| {
+        file.entries[..index]
+            .iter()
+            .rfind(|entry| creates_commit(&entry.command))
+            .map(|entry| entry.id.clone())
+    };
+    let checked_out_at = |ref_name: &str| {
+        checked_out
+            .iter()
+            .find(|(_, checked_out_ref)| checked_out_ref == ref_name)
+            .map(|(path, _)| path.clone())
+    };
+
+    // (todo 上の位置, ブランチ)。rebase 中のブランチは最後に並べる。
+    let mut planned: Vec<(usize, StackBranch)> = Vec::new();
+    let mut push = |ref_name: &str, commit_hash: &str| {
+        let entry_index = file.entries.iter().position(|entry| {
+            creates_commit(&entry.command) && hashes_match(commit_hash, &entry.commit_hash)
+        });
+        let update_ref_index = file
+            .entries
+            .iter()
+            .position(|entry| is_update_ref_of(entry, ref_name));
+        let is_rebased_branch = head_name == Some(ref_name);
+        if entry_index.is_none() && update_ref_index.is_none() && !is_rebased_branch {
+            return;
+        }
+
+        let target_entry_id = if is_rebased_branch {
+            last_commit_before(file.entries.len())
+        } else {
+            update_ref_index.and_then(last_commit_before)
+        };
+        let position = if is_rebased_branch {
+            usize::MAX
+        } else {
+            entry_index.or(update_ref_index).unwrap_or(usize::MAX)
+        };
+        planned.push((
+            position,
+            StackBranch {
+                ref_name: ref_name.to_string(),
+                commit_hash: commit_hash.to_string(),
+                entry_id: entry_index.map(|index| file.entries[index].id.clone()),
+                update_ref_entry_id: update_ref_index.map(|index| file.entries[index].id.clone()),
+                target_entry_id,
+                checked_out_at: checked_out_at(ref_name),
+                is_rebased_branch,
+            },
+        ));
+    };
+
+    for (ref_name, commit_hash) in branches {
+        push(ref_name, commit_hash);
+    }
+    // update-ref で新しく作られる（まだ存在しない）ref。
+    for entry in &file.entries {
+        if let RebaseCommand::UpdateRef(ref_name) = &entry.command {
+            if !branches.iter().any(|(existing, _)| existing == ref_name) {
+                push(ref_name, "");
+            }
+        }
+    }
+
+    planned.sort_by_key(|(position, _)| *position);
+    planned.into_iter().map(|(_, branch)| branch).collect()
+}
+
+/// `ref_name` を更新する update-ref エントリをすべて取り除いた todo を返す。
+pub fn remove_update_ref(file: &RebaseTodoFile, ref_name: &str) -> RebaseTodoFile {
+    file.without_entries(|entry| is_update_ref_of(entry, ref_name))
+}
+
+/// `entry_id` のコミットの後ろに `update-ref <ref_name>` を置いた todo を返す。
+///
+/// squash/fixup の連鎖の途中には置かず、連鎖の最後の後ろ（既存の update-ref の後ろ）に置く。
+/// 同じ ref の update-ref が既にあれば移動する。
+pub fn add_update_ref(
+    file: &RebaseTodoFile,
+    entry_id: &str,
+    ref_name: &str,
+) -> Result<RebaseTodoFile, AppError> {
+    if !ref_name.starts_with("refs/") || ref_name.contains(char::is_whitespace) {
+        return Err(AppError::CommandError {
+            message: format!("Invalid ref name: {}", ref_name),
+        });
+    }
+
+    let mut base = remove_update_ref(file, ref_name);
+    let index = base
+        .entries
+        .iter()
+        .position(|entry| entry.id == entry_id)
+        .ok_or_else(|| AppError::CommandError {
+            message: format!("Entry {} not found", entry_id),
+        })?;
+    if !creates_commit(&base.entries[index].command) {
+        return Err(AppError::CommandError {
+            message: "update-ref must follow a commit entry".to_string(),
+        });
+    }
+
+    let mut insert_at = index + 1;
+    while base.entries.get(insert_at).is_some_and(|entry| {
+        matches!(
+            entry.command,
+            RebaseCommand::Squash | RebaseCommand::Fixup | RebaseCommand::UpdateRef(_)
+        )
+    }) {
+        insert_at += 1;
+    }
+    base.entries.insert(
+        insert_at,
+        RebaseEntry::new(
+            RebaseCommand::UpdateRef(ref_name.to_string()),
+            String::new(),
+            String::new(),
+        ),
+    );
+
+    Ok(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    fn lines(file: &RebaseTodoFile) -> Vec<String> {
+        file.entries
+            .iter()
+            .map(|entry| match &entry.command {
+                RebaseCommand::UpdateRef(ref_name) => format!("update-ref {}", ref_name),
+                other => format!("{} {}", other.to_long(), entry.commit_hash),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_worktree_branches_skips_detached() {
+        let output = "worktree /repo\nHEAD aaaa\ndetached\n\nworktree /work/part2\nHEAD bbbb\nbranch refs/heads/part2\n";
+        assert_eq!(
+            parse_worktree_branches(output),
+            vec![("/work/part2".to_string(), "refs/heads/part2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_plan_stack_branches_reports_where_branches_move() {
+        let file = parse_rebase_todo(
+            "pick aaa1111 Part 1\nfixup ccc3333 fixup! Part 1\nupdate-ref refs/heads/part1\npick bbb2222 Part 2\nupdate-ref refs/heads/new\n",
+        )
+        .unwrap();
+        let branches = vec![
+            ("refs/heads/part1".to_string(), "aaa1111000".to_string()),
+            ("refs/heads/part2".to_string(), "bbb2222000".to_string()),
+            ("refs/heads/topic".to_string(), "bbb2222000".to_string()),
+            ("refs/heads/main".to_string(), "fff9999000".to_string()),
+        ];
+        let checked_out = vec![("/work/part2".to_string(), "refs/heads/part2".to_string())];
+
+        let planned = plan_stack_branches(&file, &branches, &checked_out, Some("refs/heads/topic"));
+
+        let ids: Vec<&str> = file.entries.iter().map(|e| e.id.as_str()).collect();
+        let names: Vec<&str> = planned.iter().map(|b| b.ref_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "refs/heads/part1",
+                "refs/heads/part2",
+                "refs/heads/new",
+                "refs/heads/topic"
+            ]
+        );
+
+        // fixup の後ろの update-ref は、連鎖の最後のエントリを指す。
+        assert_eq!(planned[0].entry_id.as_deref(), Some(ids[0]));
+        assert_eq!(planned[0].update_ref_entry_id.as_deref(), Some(ids[2]));
+        assert_eq!(planned[0].target_entry_id.as_deref(), Some(ids[1]));
+
+        assert_eq!(planned[1].update_ref_entry_id, None);
+        assert_eq!(planned[1].checked_out_at.as_deref(), Some("/work/part2"));
+
+        assert_eq!(planned[2].commit_hash, "");
+        assert_eq!(planned[2].target_entry_id.as_deref(), Some(ids[3]));
+
+        assert!(planned[3].is_rebased_branch);
+        assert_eq!(planned[3].target_entry_id.as_deref(), Some(ids[3]));
+    }
+
+    #[test]
+    fn test_add_update_ref_goes_after_chain_and_moves_existing() {
+        let file = parse_rebase_todo(
+            "pick aaa1111 Part 1\nfixup ccc3333 fixup! Part 1\nupdate-ref refs/heads/other\npick bbb2222 Part 2\nupdate-ref refs/heads/part1\n",
+        )
+        .unwrap();
+        let first = file.entries[0].id.clone();
+
+        let added = add_update_ref(&file, &first, "refs/heads/part1").unwrap();
+
+        assert_eq!(
+            lines(&added),
+            vec![
+                "pick aaa1111",
+                "fixup ccc3333",
+                "update-ref refs/heads/other",
+                "update-ref refs/heads/part1",
+                "pick bbb2222",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_update_ref_rejects_invalid_input() {
+        let file = parse_rebase_todo("pick aaa1111 Part 1\nexec make\n").unwrap();
+
+        assert!(add_update_ref(&file, &file.entries[0].id, "part1").is_err());
+        assert!(add_update_ref(&file, &file.entries[1].id, "refs/heads/part1").is_err());
+        assert!(add_update_ref(&file, "missing", "refs/heads/part1").is_err());
+    }
+
+    #[test]
+    fn test_remove_update_ref_keeps_comments() {
+        let file = parse_rebase_todo(
+            "pick aaa1111 Part 1\n# stack\nupdate-ref refs/heads/part1\npick bbb2222 Part 2\n",
+        )
+        .unwrap();
+
+        let removed = remove_update_ref(&file, "refs/heads/part1");
+
+        assert_eq!(lines(&removed), vec!["pick aaa1111", "pick bbb2222"]);
+        assert_eq!(
+            removed.entries[1].leading_lines,
+            vec!["# stack".to_string()]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::commit::{dedup_trailers, parse_commit_msg, CommitMessage, Trailer};
+use super::rebase::{
+    amend_message, strip_fixup_prefix, RebaseCommand, RebaseEntry, RebaseTodoFile,
+};
+use crate::error::AppError;
+
+/// squash を含む、1 つのコミットにまとめられるエントリの並び。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SquashChain {
+    /// 統合先（連鎖の先頭）のエントリ。
+    pub root_id: String,
+    /// 先頭を含む、連鎖に属するエントリ。
+    pub entry_ids: Vec<String>,
+    /// 連鎖の最後のコミット。Git はこのコミットを適用した後にエディタを開く。
+    pub final_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposedSquash {
+    #[serde(flatten)]
+    pub chain: SquashChain,
+    /// コミットメッセージファイル形式に整形した合成結果。
+    pub message: String,
+}
+
+/// 連鎖の先頭になれるエントリ。
+fn starts_chain(command: &RebaseCommand) -> bool {
+    matches!(
+        command,
+        RebaseCommand::Pick | RebaseCommand::Reword | RebaseCommand::Edit
+    )
+}
+
+/// todo から squash を含む連鎖を探す。fixup だけの連鎖はメッセージを編集しないため含めない。
+pub fn find_squash_chains(file: &RebaseTodoFile) -> Vec<SquashChain> {
+    let mut chains = Vec::new();
+    let mut index = 0;
+    while index < file.entries.len() {
+        if !starts_chain(&file.entries[index].command) {
+            index += 1;
+            continue;
+        }
+
+        let end = file.entries[index + 1..]
+            .iter()
+            .position(|entry| {
+                !matches!(entry.command, RebaseCommand::Squash | RebaseCommand::Fixup)
+            })
+            .map(|offset| index + 1 + offset)
+            .unwrap_or(file.entries.len());
+        let members = &file.entries[index..end];
+        if members
+            .iter()
+            .any(|entry| entry.command == RebaseCommand::Squash)
+        {
+            chains.push(SquashChain {
+                root_id: members[0].id.clone(),
+                entry_ids: members.iter().map(|entry| entry.id.clone()).collect(),
+                final_hash: members[members.len() - 1].commit_hash.clone(),
+            });
+        }
+        index = end;
+    }
+    chains
+}
+
+/// subject から `fixup! `・`squash! `・`amend! ` を取り除く。
+fn strip_prefixes(subject: &str) -> &str {
+    strip_fixup_prefix(subject)
+        .map(|(_, rest)| rest)
+        .unwrap_or(subject)
+}
+
+/// 連鎖のメッセージを Git の squash と同じ規則で 1 つにまとめる。
+///
+/// 先頭の subject を subject とし、squash したコミットの subject と本文を段落として続ける。
+/// 素の fixup のメッセージは捨てる。`fixup -C`/`-c` は squash より前ならそれまでのメッセージを
+/// 置き換え、squash の後では squash と同じく追記する。`amend! ` コミットは 1 行目を除いた本文を使う。
+/// `squash! X` のような subject は接頭辞を除いた結果が既出なら省き、trailer は重複を取り除く。
+/// `messages` はエントリ id ごとの完全なメッセージで、ない場合は todo 上の subject を使う。
+pub fn compose_squash_message(
+    entries: &[RebaseEntry],
+    messages: &HashMap<String, String>,
+) -> CommitMessage {
+    let mut subject = String::new();
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut trailers: Vec<Trailer> = Vec::new();
+    let mut seen_squash = false;
+
+    for (position, entry) in entries.iter().enumerate() {
+        let raw = messages.get(&entry.id).unwrap_or(&entry.message);
+        let raw = if raw.starts_with("amend! ") {
+            amend_message(raw)
+        } else {
+            raw.as_str()
+        };
+        let Ok(parsed) = parse_commit_msg(raw) else {
+            continue;
+        };
+        let entry_subject = strip_prefixes(&parsed.subject).to_string();
+
+        let fixup_with_message =
+            entry.command == RebaseCommand::Fixup && entry.fixup_option.is_some();
+        if position == 0 || (fixup_with_message && !seen_squash) {
+            subject = entry_subject;
+            paragraphs.clear();
+            trailers.clear();
+        } else if entry.command == RebaseCommand::Squash || fixup_with_message {
+            seen_squash |= entry.command == RebaseCommand::Squash;
+            let repeated = entry_subject == subject || paragraphs.contains(&entry_subject);
+            if !entry_subject.is_empty() && !repeated {
+                paragraphs.push(entry_subject);
+            }
+        } else {
+            continue;
+        }
+
+        if !parsed.body.is_empty() {
+            paragraphs.push(parsed.body);
+        }
+        trailers.extend(parsed.trailers);
+    }
+
+    CommitMessage {
+        subject,
+        body: paragraphs.join("\n\n"),
+        trailers: dedup_trailers(trailers),
+        ..CommitMessage::new()
+    }
+}
+
+/// 連鎖を、合成したメッセージを持つ `reword` と素の `fixup` に置き換えた todo を返す。
+///
+/// 途中の `fixup -C` もメッセージを上書きしないよう素の fixup にする。
+/// `edit` で始まる連鎖は reword にすると停止しなくなるため、エラーにする。
+pub fn replace_chain_with_reword(
+    file: &RebaseTodoFile,
+    root_id: &str,
+    message: &str,
+) -> Result<RebaseTodoFile, AppError> {
+    let chain = find_squash_chains(file)
+        .into_iter()
+        .find(|chain| chain.root_id == root_id)
+        .ok_or_else(|| AppError::CommandError {
+            message: format!("Entry {} does not start a squash chain", root_id),
+        })?;
+
+    let mut result = file.clone();
+    for entry in result
+        .entries
+        .iter_mut()
+        .filter(|entry| chain.entry_ids.contains(&entry.id))
+    {
+        if entry.id == chain.root_id {
+            if entry.command == RebaseCommand::Edit {
+                return Err(AppError::CommandError {
+                    message: "A squash chain starting with 'edit' cannot be turned into a reword"
+                        .to_string(),
+                });
+            }
+            entry.command = RebaseCommand::Reword;
+            entry.message = message.to_string();
+        } else {
+            entry.command = RebaseCommand::Fixup;
+            entry.fixup_option = None;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::commit::serialize_commit_msg;
+    use crate::parser::rebase::parse_rebase_todo;
+
+    const TODO: &str = "pick aaa1111 Add parser\nsquash bbb2222 squash! Add parser\nfixup ccc3333 fixup! Add parser\nsquash ddd4444 Handle errors\npick eee5555 Other\nfixup fff6666 fixup! Other\n";
+
+    fn messages(file: &RebaseTodoFile) -> HashMap<String, String> {
+        let full = [
+            "Add parser\n\nParse the todo file.\n\nSigned-off-by: A <a@example.com>",
+            "squash! Add parser\n\nAlso parse comments.\n\nSigned-off-by: A <a@example.com>",
+            "fixup! Add parser\n\nTypo",
+            "Handle errors\n\nRefs: #12\nsigned-off-by: A <a@example.com>",
+        ];
+        file.entries
+            .iter()
+            .zip(full)
+            .map(|(entry, message)| (entry.id.clone(), message.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_squash_chains_skips_fixup_only_chains() {
+        let file = parse_rebase_todo(TODO).unwrap();
+
+        let chains = find_squash_chains(&file);
+
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].root_id, file.entries[0].id);
+        assert_eq!(chains[0].entry_ids.len(), 4);
+        assert_eq!(chains[0].final_hash, "ddd4444");
+    }
+
+    #[test]
+    fn test_compose_squash_message_merges_bodies_and_trailers() {
+        let file = parse_rebase_todo(TODO).unwrap();
+
+        let message = compose_squash_message(&file.entries[..4], &messages(&file));
+
+        assert_eq!(
+            serialize_commit_msg(&message),
+            "Add parser\n\nParse the todo file.\n\nAlso parse comments.\n\nHandle errors\n\nSigned-off-by: A <a@example.com>\nRefs: #12"
+        );
+    }
+
+    fn amend_messages(file: &RebaseTodoFile) -> HashMap<String, String> {
+        file.entries
+            .iter()
+            .map(|entry| {
+                let message = match entry.commit_hash.as_str() {
+                    "aaa1111" => "One\n\nFirst body",
+                    "bbb2222" => "Two",
+                    _ => "amend! One\n\nOne reworded\n\nRewritten body",
+                };
+                (entry.id.clone(), message.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compose_squash_message_fixup_c_before_squash_replaces_message() {
+        let todo = "pick aaa1111 One\nfixup -C ccc3333 amend! One\nsquash bbb2222 Two\n";
+        let file = parse_rebase_todo(todo).unwrap();
+
+        let message = compose_squash_message(&file.entries, &amend_messages(&file));
+
+        assert_eq!(message.subject, "One reworded");
+        assert_eq!(message.body, "Rewritten body\n\nTwo");
+    }
+
+    #[test]
+    fn test_compose_squash_message_fixup_c_after_squash_appends_message() {
+        let todo = "pick aaa1111 One\nsquash bbb2222 Two\nfixup -C ccc3333 amend! One\n";
+        let file = parse_rebase_todo(todo).unwrap();
+
+        let message = compose_squash_message(&file.entries, &amend_messages(&file));
+
+        assert_eq!(message.subject, "One");
+        assert_eq!(
+            message.body,
+            "First body\n\nTwo\n\nOne reworded\n\nRewritten body"
+        );
+    }
+
+    #[test]
+    fn test_replace_chain_with_reword() {
+        let file = parse_rebase_todo(TODO).unwrap();
+        let root_id = file.entries[0].id.clone();
+
+        let result = replace_chain_with_reword(&file, &root_id, "Add parser\n\nBody").unwrap();
+
+        let commands: Vec<&str> = result
+            .entries
+            .iter()
+            .map(|entry| entry.command.to_long())
+            .collect();
+        assert_eq!(
+            commands,
+            vec!["reword", "fixup", "fixup", "fixup", "pick", "fixup"]
+        );
+        assert_eq!(result.entries[0].message, "Add parser\n\nBody");
+    }
+
+    #[test]
+    fn test_replace_chain_with_reword_rejects_edit_root() {
+        let file = parse_rebase_todo("edit aaa1111 One\nsquash bbb2222 Two\n").unwrap();
+        let root_id = file.entries[0].id.clone();
+
+        let result = replace_chain_with_reword(&file, &root_id, "One");
+
+        assert!(matches!(result, Err(AppError::CommandError { .. })));
+    }
+}
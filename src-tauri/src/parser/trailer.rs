@@ -0,0 +1,739 @@
+//! `git interpret-trailers` と同じ規則で trailer ブロックを解析・編集する。
+//!
+//! trailer ブロックの検出、継続行、`trailer.separators`、`trailer.<token>.key` の別名、
+//! `where`・`ifExists`・`ifMissing` の規則は Git の trailer.c に合わせる。
+//! `trailer.<token>.command` と `cmd` は実行しない。
+
+use serde::{Deserialize, Serialize};
+
+/// Git が生成し、設定がなくても trailer とみなす行の先頭。
+const GIT_GENERATED_PREFIXES: &[&str] = &["Signed-off-by: ", "(cherry picked from commit "];
+
+/// `trailer.separators` の既定値。
+const DEFAULT_SEPARATORS: &str = ":";
+
+/// trailer を追加する位置（`trailer.where`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailerWhere {
+    /// 同じ token の最後の trailer の後ろ。
+    After,
+    /// 同じ token の最初の trailer の前。
+    Before,
+    /// ブロックの末尾。
+    #[default]
+    End,
+    /// ブロックの先頭。
+    Start,
+}
+
+/// 同じ token の trailer があるときの動作（`trailer.ifExists`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IfExists {
+    /// 隣の trailer と token・値が同じでなければ追加する。
+    #[default]
+    AddIfDifferentNeighbor,
+    /// 同じ token・値の trailer がなければ追加する。
+    AddIfDifferent,
+    Add,
+    Replace,
+    DoNothing,
+}
+
+/// 同じ token の trailer がないときの動作（`trailer.ifMissing`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IfMissing {
+    #[default]
+    Add,
+    DoNothing,
+}
+
+impl TrailerWhere {
+    fn from_config(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "after" => Some(TrailerWhere::After),
+            "before" => Some(TrailerWhere::Before),
+            "end" => Some(TrailerWhere::End),
+            "start" => Some(TrailerWhere::Start),
+            _ => None,
+        }
+    }
+
+    fn after_or_end(self) -> bool {
+        matches!(self, TrailerWhere::After | TrailerWhere::End)
+    }
+}
+
+impl IfExists {
+    fn from_config(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "addifdifferentneighbor" => Some(IfExists::AddIfDifferentNeighbor),
+            "addifdifferent" => Some(IfExists::AddIfDifferent),
+            "add" => Some(IfExists::Add),
+            "replace" => Some(IfExists::Replace),
+            "donothing" => Some(IfExists::DoNothing),
+            _ => None,
+        }
+    }
+}
+
+impl IfMissing {
+    fn from_config(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "add" => Some(IfMissing::Add),
+            "donothing" => Some(IfMissing::DoNothing),
+            _ => None,
+        }
+    }
+}
+
+/// `trailer.<token>.*` の設定。未設定の規則は全体の設定に従う。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrailerTokenConfig {
+    /// `<token>` の部分。大文字小文字を区別せず、前方一致で照合する。
+    pub name: String,
+    /// 出力に使う token（`trailer.<token>.key`）。
+    pub key: Option<String>,
+    pub where_: Option<TrailerWhere>,
+    pub if_exists: Option<IfExists>,
+    pub if_missing: Option<IfMissing>,
+}
+
+/// trailer の規則（`trailer.*` の git config）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailerConfig {
+    /// token と値を区切る文字。出力には先頭の文字を使う。
+    pub separators: String,
+    pub where_: TrailerWhere,
+    pub if_exists: IfExists,
+    pub if_missing: IfMissing,
+    /// 設定ファイルに書かれた順の token ごとの設定。
+    pub tokens: Vec<TrailerTokenConfig>,
+}
+
+impl Default for TrailerConfig {
+    fn default() -> Self {
+        Self {
+            separators: DEFAULT_SEPARATORS.to_string(),
+            where_: TrailerWhere::default(),
+            if_exists: IfExists::default(),
+            if_missing: IfMissing::default(),
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl TrailerConfig {
+    /// `git config --get-regexp '^trailer\.'` の出力（`trailer.<name> <value>` の行）を読む。
+    ///
+    /// Git と同じく、解釈できない値は無視する。
+    pub fn from_config_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut config = TrailerConfig::default();
+        for line in lines {
+            let Some((name, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let Some(name) = name.strip_prefix("trailer.") else {
+                continue;
+            };
+            let Some((token, variable)) = name.rsplit_once('.') else {
+                match name {
+                    "separators" if !value.is_empty() => config.separators = value.to_string(),
+                    "where" => {
+                        config.where_ = TrailerWhere::from_config(value).unwrap_or(config.where_)
+                    }
+                    "ifexists" => {
+                        config.if_exists = IfExists::from_config(value).unwrap_or(config.if_exists)
+                    }
+                    "ifmissing" => {
+                        config.if_missing =
+                            IfMissing::from_config(value).unwrap_or(config.if_missing)
+                    }
+                    _ => {}
+                }
+                continue;
+            };
+
+            let index = match config.tokens.iter().position(|item| item.name == token) {
+                Some(index) => index,
+                None => {
+                    config.tokens.push(TrailerTokenConfig {
+                        name: token.to_string(),
+                        ..TrailerTokenConfig::default()
+                    });
+                    config.tokens.len() - 1
+                }
+            };
+            let item = &mut config.tokens[index];
+            match variable {
+                "key" => item.key = Some(value.to_string()),
+                "where" => item.where_ = TrailerWhere::from_config(value).or(item.where_),
+                "ifexists" => item.if_exists = IfExists::from_config(value).or(item.if_exists),
+                "ifmissing" => item.if_missing = IfMissing::from_config(value).or(item.if_missing),
+                _ => {}
+            }
+        }
+        config
+    }
+
+    /// `tok` に一致する最初の token の設定。`tok_len` バイトまでで照合する。
+    fn find_token(&self, tok: &str, tok_len: usize) -> Option<&TrailerTokenConfig> {
+        self.tokens.iter().find(|item| {
+            prefix_matches(tok, &item.name, tok_len)
+                || item
+                    .key
+                    .as_deref()
+                    .is_some_and(|key| prefix_matches(tok, key, tok_len))
+        })
+    }
+
+    /// 設定の別名を解決した token と、その token に適用する規則を返す。
+    fn resolve(&self, tok: &str) -> (String, Rules) {
+        let tok_len = token_len_without_separator(tok);
+        match self.find_token(tok, tok_len) {
+            Some(item) => (
+                item.key.clone().unwrap_or_else(|| tok.to_string()),
+                Rules {
+                    where_: item.where_.unwrap_or(self.where_),
+                    if_exists: item.if_exists.unwrap_or(self.if_exists),
+                    if_missing: item.if_missing.unwrap_or(self.if_missing),
+                },
+            ),
+            None => (
+                tok.to_string(),
+                Rules {
+                    where_: self.where_,
+                    if_exists: self.if_exists,
+                    if_missing: self.if_missing,
+                },
+            ),
+        }
+    }
+}
+
+/// trailer ブロックへの操作。`token` は設定の別名でもよい。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrailerOperation {
+    /// 設定の `where`・`ifExists`・`ifMissing` に従って追加する（`--trailer`）。
+    Add { token: String, value: String },
+    /// 同じ token の trailer を置き換える（`--if-exists replace --trailer`）。
+    Replace { token: String, value: String },
+    /// 同じ token の trailer を取り除く。`value` があれば値も一致するものだけ。
+    Remove {
+        token: String,
+        value: Option<String>,
+    },
+}
+
+/// 1 つの trailer に適用する規則。
+#[derive(Debug, Clone, Copy)]
+struct Rules {
+    where_: TrailerWhere,
+    if_exists: IfExists,
+    if_missing: IfMissing,
+}
+
+/// trailer ブロックの 1 項目。
+#[derive(Debug, Clone, PartialEq)]
+enum BlockItem {
+    /// token と値。値は継続行を改行付きで含む。
+    Trailer { token: String, value: String },
+    /// ブロック内の trailer でない行。
+    Line(String),
+}
+
+/// 追加する trailer と、適用する規則。
+struct NewTrailer {
+    token: String,
+    value: String,
+    rules: Rules,
+}
+
+/// C の isspace と同じ空白。
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0b' | '\x0c')
+}
+
+fn trim_space(text: &str) -> &str {
+    text.trim_matches(is_space)
+}
+
+/// 空白だけの行か（改行を含んでもよい）。
+fn is_blank_line(line: &str) -> bool {
+    line.chars().all(is_space)
+}
+
+/// `tok` の先頭 `len` バイトが `name` の先頭と大文字小文字を区別せず一致するか。
+fn prefix_matches(tok: &str, name: &str, len: usize) -> bool {
+    let (tok, name) = (tok.as_bytes(), name.as_bytes());
+    tok.len() >= len && name.len() >= len && tok[..len].eq_ignore_ascii_case(&name[..len])
+}
+
+/// 末尾の英数字でない文字（区切り文字など）を除いた token の長さ。
+fn token_len_without_separator(token: &str) -> usize {
+    token
+        .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+        .len()
+}
+
+/// token と値の区切り文字の位置。token は英数字と `-`、その後に空白を置ける。
+fn find_separator(line: &str, separators: &str) -> Option<usize> {
+    let mut whitespace_found = false;
+    for (index, c) in line.char_indices() {
+        if separators.contains(c) {
+            return Some(index);
+        }
+        if !whitespace_found && (c.is_ascii_alphanumeric() || c == '-') {
+            continue;
+        }
+        if index != 0 && (c == ' ' || c == '\t') {
+            whitespace_found = true;
+            continue;
+        }
+        break;
+    }
+    None
+}
+
+/// 各行の開始位置と、改行を含む行。
+fn lines_with_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line))
+    })
+}
+
+/// 行頭の `---`（パッチの始まり）の位置。なければ末尾。
+fn find_patch_start(message: &str) -> usize {
+    lines_with_offsets(message)
+        .find(|(_, line)| {
+            line.strip_prefix("---")
+                .is_some_and(|rest| rest.starts_with(is_space))
+        })
+        .map_or(message.len(), |(start, _)| start)
+}
+
+/// scissors 行の位置。なければ `len`。
+fn locate_cut_line(message: &str, len: usize, comment: &str) -> usize {
+    let cut_line = format!(
+        "{} ------------------------ >8 ------------------------\n",
+        comment
+    );
+    if message.starts_with(&cut_line) {
+        return 0;
+    }
+    message
+        .find(&format!("\n{}", cut_line))
+        .map_or(len, |index| (index + 1).min(len))
+}
+
+/// 末尾のコメント行・空行、scissors 行以降、古い `Conflicts:` ブロックを除いた終わりの位置。
+fn find_trailer_end(message: &str, len: usize, comment: &str) -> usize {
+    let cutoff = locate_cut_line(message, len, comment);
+    // Git と同じく 0 を「未設定」として扱う。
+    let mut boc = 0;
+    let mut in_old_conflicts_block = false;
+    for (bol, line) in lines_with_offsets(&message[..cutoff]) {
+        if line.starts_with(comment) || line.starts_with('\n') {
+            if boc == 0 {
+                boc = bol;
+            }
+        } else if line.starts_with("Conflicts:\n") {
+            in_old_conflicts_block = true;
+            if boc == 0 {
+                boc = bol;
+            }
+        } else if in_old_conflicts_block && line.starts_with('\t') {
+            // conflicts ブロックのパス名。
+        } else if boc != 0 {
+            boc = 0;
+            in_old_conflicts_block = false;
+        }
+    }
+    if boc != 0 {
+        boc
+    } else {
+        cutoff
+    }
+}
+
+/// trailer ブロックの始まり。ブロックがなければ `len`。
+///
+/// 最後の段落がすべて trailer（と継続行）か、Git が生成する行か設定の token で始まる
+/// trailer を含み、trailer の行が 25% 以上ならブロックとみなす。
+fn find_trailer_start(message: &str, len: usize, config: &TrailerConfig, comment: &str) -> usize {
+    let lines: Vec<(usize, &str)> = lines_with_offsets(&message[..len]).collect();
+
+    // 先頭の段落は subject なので trailer にしない。
+    let end_of_title = lines
+        .iter()
+        .find(|(_, line)| !line.starts_with(comment) && is_blank_line(line))
+        .map_or(len, |(start, _)| *start);
+
+    let mut only_spaces = true;
+    let mut recognized_prefix = false;
+    let mut trailer_lines = 0;
+    let mut non_trailer_lines = 0;
+    let mut possible_continuation_lines = 0;
+    for &(start, line) in lines.iter().rev() {
+        if start < end_of_title {
+            break;
+        }
+        if line.starts_with(comment) {
+            non_trailer_lines += possible_continuation_lines;
+            possible_continuation_lines = 0;
+            continue;
+        }
+        if is_blank_line(line) {
+            if only_spaces {
+                continue;
+            }
+            non_trailer_lines += possible_continuation_lines;
+            if (recognized_prefix && trailer_lines * 3 >= non_trailer_lines)
+                || (trailer_lines > 0 && non_trailer_lines == 0)
+            {
+                return start + line.len();
+            }
+            return len;
+        }
+        only_spaces = false;
+
+        if GIT_GENERATED_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+            recognized_prefix = true;
+            continue;
+        }
+
+        match find_separator(line, &config.separators) {
+            Some(separator) if separator >= 1 && !line.starts_with(is_space) => {
+                trailer_lines += 1;
+                possible_continuation_lines = 0;
+                if !recognized_prefix && config.find_token(line, separator).is_some() {
+                    recognized_prefix = true;
+                }
+            }
+            _ if line.starts_with(is_space) => possible_continuation_lines += 1,
+            _ => {
+                non_trailer_lines += 1 + possible_continuation_lines;
+                possible_continuation_lines = 0;
+            }
+        }
+    }
+    len
+}
+
+/// trailer ブロックを項目に分ける。継続行は直前の trailer の値に含め、コメント行は落とす。
+fn parse_block(block: &str, config: &TrailerConfig, comment: &str) -> Vec<BlockItem> {
+    let mut raw: Vec<String> = Vec::new();
+    let mut last: Option<usize> = None;
+    for line in block.split_inclusive('\n') {
+        if let Some(index) = last {
+            if line.starts_with(is_space) {
+                raw[index].push_str(line);
+                continue;
+            }
+        }
+        last = find_separator(line, &config.separators)
+            .filter(|&separator| separator >= 1)
+            .map(|_| raw.len());
+        raw.push(line.to_string());
+    }
+
+    raw.into_iter()
+        .filter(|item| !item.starts_with(comment))
+        .map(
+            |item| match find_separator(&item, &config.separators).filter(|&pos| pos >= 1) {
+                Some(separator) => BlockItem::Trailer {
+                    token: config.resolve(trim_space(&item[..separator])).0,
+                    value: trim_space(&item[separator + 1..]).to_string(),
+                },
+                None => BlockItem::Line(item.strip_suffix('\n').unwrap_or(&item).to_string()),
+            },
+        )
+        .collect()
+}
+
+/// 区切りを除いた token が、大文字小文字を区別せず前方一致するか。
+fn same_token(token: &str, other: &str) -> bool {
+    let len = token_len_without_separator(token).min(token_len_without_separator(other));
+    prefix_matches(token, other, len)
+}
+
+fn has_token(item: &BlockItem, other: &str) -> bool {
+    matches!(item, BlockItem::Trailer { token, .. } if same_token(token, other))
+}
+
+fn same_trailer(item: &BlockItem, trailer: &NewTrailer) -> bool {
+    let BlockItem::Trailer { token, value } = item else {
+        return false;
+    };
+    same_token(token, &trailer.token) && value.eq_ignore_ascii_case(&trailer.value)
+}
+
+/// `index` から `where` の方向へ、同じ trailer がないかを調べる。`check_all` でなければ
+/// `index` の項目だけを調べる。
+fn is_different(
+    items: &[BlockItem],
+    mut index: usize,
+    trailer: &NewTrailer,
+    check_all: bool,
+) -> bool {
+    loop {
+        if same_trailer(&items[index], trailer) {
+            return false;
+        }
+        let next = if trailer.rules.where_.after_or_end() {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|&next| next < items.len())
+        };
+        match next {
+            Some(next) if check_all => index = next,
+            _ => return true,
+        }
+    }
+}
+
+/// 同じ token の trailer があれば `ifExists` に従って追加する。なければ `trailer` を返す。
+fn apply_if_exists(items: &mut Vec<BlockItem>, trailer: NewTrailer) -> Result<(), NewTrailer> {
+    let where_ = trailer.rules.where_;
+    let backwards = where_.after_or_end();
+    let is_same_token = |index: &usize| has_token(&items[*index], &trailer.token);
+    // after と end は後ろから、before と start は前から探す。
+    let found = if backwards {
+        (0..items.len()).rev().find(is_same_token)
+    } else {
+        (0..items.len()).find(is_same_token)
+    };
+    let Some(found) = found else {
+        return Err(trailer);
+    };
+    let start = if backwards { items.len() - 1 } else { 0 };
+    let on = if matches!(where_, TrailerWhere::After | TrailerWhere::Before) {
+        found
+    } else {
+        start
+    };
+    let insert_at = if backwards { on + 1 } else { on };
+    let item = BlockItem::Trailer {
+        token: trailer.token.clone(),
+        value: trailer.value.clone(),
+    };
+
+    match trailer.rules.if_exists {
+        IfExists::DoNothing => {}
+        IfExists::Replace => {
+            items.insert(insert_at, item);
+            let removed = if insert_at <= found { found + 1 } else { found };
+            items.remove(removed);
+        }
+        IfExists::Add => items.insert(insert_at, item),
+        IfExists::AddIfDifferent => {
+            if is_different(items, found, &trailer, true) {
+                items.insert(insert_at, item);
+            }
+        }
+        IfExists::AddIfDifferentNeighbor => {
+            if is_different(items, on, &trailer, false) {
+                items.insert(insert_at, item);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 区切りを除いた token が大文字小文字を区別せず一致し、`value` があれば値も一致する trailer か。
+fn is_removed(item: &BlockItem, token: &str, value: Option<&str>) -> bool {
+    let BlockItem::Trailer {
+        token: found,
+        value: found_value,
+    } = item
+    else {
+        return false;
+    };
+    let len = token_len_without_separator(token);
+    token_len_without_separator(found) == len
+        && prefix_matches(found, token, len)
+        && value.is_none_or(|value| found_value.eq_ignore_ascii_case(trim_space(value)))
+}
+
+fn apply_if_missing(items: &mut Vec<BlockItem>, trailer: NewTrailer) {
+    if trailer.rules.if_missing == IfMissing::DoNothing {
+        return;
+    }
+    let item = BlockItem::Trailer {
+        token: trailer.token,
+        value: trailer.value,
+    };
+    if trailer.rules.where_.after_or_end() {
+        items.push(item);
+    } else {
+        items.insert(0, item);
+    }
+}
+
+/// `token` と `value` を 1 行（継続行を含む）に書く。token が区切り文字で終われば空白を入れない。
+fn format_trailer(token: &str, value: &str, separators: &str) -> String {
+    match token.chars().rev().find(|&c| !is_space(c)) {
+        None => String::new(),
+        Some(last) if separators.contains(last) => format!("{}{}\n", token, value),
+        Some(_) => format!(
+            "{}{} {}\n",
+            token,
+            separators.chars().next().unwrap_or(':'),
+            value
+        ),
+    }
+}
+
+/// メッセージの trailer ブロックに操作を順に適用し、`git interpret-trailers` と同じ形で返す。
+///
+/// trailer ブロックの項目は token の別名を解決し、`Key: Value` の形に揃える。
+/// trailer ブロックの後ろのコメントや scissors 行以降はそのまま残す。
+pub fn apply_trailer_operations(
+    message: &str,
+    operations: &[TrailerOperation],
+    config: &TrailerConfig,
+    comment: &str,
+) -> String {
+    let patch_start = find_patch_start(message);
+    let end = find_trailer_end(message, patch_start, comment);
+    let start = find_trailer_start(message, end, config, comment);
+    let mut items = parse_block(&message[start..end], config, comment);
+
+    for operation in operations {
+        let (token, value, replace) = match operation {
+            TrailerOperation::Add { token, value } => (token, value, false),
+            TrailerOperation::Replace { token, value } => (token, value, true),
+            TrailerOperation::Remove { token, value } => {
+                let (token, _) = config.resolve(trim_space(token));
+                items.retain(|item| !is_removed(item, &token, value.as_deref()));
+                continue;
+            }
+        };
+        let (token, mut rules) = config.resolve(trim_space(token));
+        if replace {
+            rules.if_exists = IfExists::Replace;
+        }
+        let trailer = NewTrailer {
+            token,
+            value: trim_space(value).to_string(),
+            rules,
+        };
+        if let Err(trailer) = apply_if_exists(&mut items, trailer) {
+            apply_if_missing(&mut items, trailer);
+        }
+    }
+
+    let mut result = message[..start].to_string();
+    // trailer の前には空行を置く。
+    if !result
+        .split_inclusive('\n')
+        .next_back()
+        .is_some_and(is_blank_line)
+    {
+        result.push('\n');
+    }
+    for item in &items {
+        match item {
+            BlockItem::Trailer { token, value } => {
+                result.push_str(&format_trailer(token, value, &config.separators))
+            }
+            BlockItem::Line(line) => {
+                result.push_str(line);
+                result.push('\n');
+            }
+        }
+    }
+    result.push_str(&message[end..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(token: &str, value: &str) -> TrailerOperation {
+        TrailerOperation::Add {
+            token: token.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_trailer_block_keeps_continuation_lines_and_drops_comments() {
+        let message = "Subject\n\nBody\n\nKey:x\n  continued\nother line\n# note\nSigned-off-by: A\n\n# Please enter\n";
+
+        let result = apply_trailer_operations(message, &[], &TrailerConfig::default(), "#");
+
+        assert_eq!(
+            result,
+            "Subject\n\nBody\n\nKey: x\n  continued\nother line\nSigned-off-by: A\n\n# Please enter\n"
+        );
+    }
+
+    #[test]
+    fn test_paragraph_with_mostly_prose_is_not_a_trailer_block() {
+        let message = "Subject\n\nSee: the docs\nfor details\n";
+
+        let result = apply_trailer_operations(
+            message,
+            &[add("Refs", "#1")],
+            &TrailerConfig::default(),
+            "#",
+        );
+
+        assert_eq!(
+            result,
+            "Subject\n\nSee: the docs\nfor details\n\nRefs: #1\n"
+        );
+    }
+
+    #[test]
+    fn test_config_aliases_separators_and_rules() {
+        let config = TrailerConfig::from_config_lines([
+            "trailer.separators :#",
+            "trailer.sign.key Signed-off-by",
+            "trailer.sign.ifexists addIfDifferent",
+            "trailer.bug.key Bug #",
+            "trailer.bug.where start",
+        ]);
+        let message = "Subject\n\nsign: A\nBug #1\n";
+
+        let result = apply_trailer_operations(
+            message,
+            &[add("sign", "B"), add("s", "a"), add("bug", "2")],
+            &config,
+            "#",
+        );
+
+        assert_eq!(
+            result,
+            "Subject\n\nBug #2\nSigned-off-by: A\nBug #1\nSigned-off-by: B\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_and_remove_operations() {
+        let message = "Subject\n\nRefs: #1\nCc: A\nRefs: #2\nCc: B\n";
+        let operations = [
+            TrailerOperation::Replace {
+                token: "Refs".to_string(),
+                value: "#3".to_string(),
+            },
+            TrailerOperation::Remove {
+                token: "cc".to_string(),
+                value: Some("a".to_string()),
+            },
+        ];
+
+        let result = apply_trailer_operations(message, &operations, &TrailerConfig::default(), "#");
+
+        assert_eq!(result, "Subject\n\nRefs: #1\nCc: B\nRefs: #3\n");
+    }
+}
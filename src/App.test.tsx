@@ -9,6 +9,7 @@ import {
 	useFileStore,
 	useHistoryStore,
 	useRebaseStore,
+	useStandaloneStore,
 } from "./stores";
 
 vi.mock("@tauri-apps/api/window", () => ({
@@ -58,6 +59,7 @@ function setupInvoke(backup: string | null | Promise<string | null>) {
 			case "restore_backup":
 			case "delete_backup":
 			case "exit_app":
+			case "finish_editor_request":
 				return undefined as never;
 			default:
 				throw {
@@ -86,6 +88,7 @@ describe("App", () => {
 		useCommitStore.getState().reset();
 		useRebaseStore.getState().reset();
 		useHistoryStore.getState().clear();
+		useStandaloneStore.getState().reset();
 		vi.clearAllMocks();
 		setupFileLaunch();
 	});
@@ -202,7 +205,13 @@ describe("App", () => {
 						is_valid: false,
 						subject_too_long: false,
 						subject_length: 0,
+						subject_width: 0,
+						length_unit: "chars",
 						long_body_lines: [],
+						conventional: null,
+						conventional_violations: [],
+						diagnostics: [],
+						matches_template: false,
 					} as never;
 				default:
 					throw {
@@ -246,7 +255,13 @@ describe("App", () => {
 						is_valid: true,
 						subject_too_long: false,
 						subject_length: 0,
+						subject_width: 0,
+						length_unit: "chars",
 						long_body_lines: [],
+						conventional: null,
+						conventional_violations: [],
+						diagnostics: [],
+						matches_template: false,
 					} as never;
 				case "serialize_commit_msg":
 					return "" as never;
@@ -302,7 +317,9 @@ describe("App", () => {
 						entries: [],
 						comments: [],
 					} as never;
-				case "serialize_rebase_todo":
+				case "lint_rebase_todo":
+					return [] as never;
+				case "serialize_rebase_todo_lossless":
 					return "" as never;
 				case "write_file":
 				case "delete_backup":
@@ -340,4 +357,35 @@ describe("App", () => {
 			});
 		});
 	});
+
+	it("スタンドアロンの rebase では保存を git へ返し、終了せず元の画面に戻る", async () => {
+		const user = userEvent.setup();
+		mockedGetMatches.mockResolvedValue({
+			args: {},
+		} as unknown as Awaited<ReturnType<typeof getMatches>>);
+		setupInvoke(null);
+
+		render(<App />);
+		expect(
+			await screen.findByLabelText("リポジトリのパス"),
+		).toBeInTheDocument();
+
+		await act(async () => {
+			useStandaloneStore.setState({ editorPath: targetPath });
+			await useFileStore.getState().loadFile(targetPath);
+		});
+		const editor = await screen.findByRole("textbox");
+		await user.type(editor, " changed");
+		await user.click(screen.getByRole("button", { name: "保存" }));
+
+		await waitFor(() => {
+			expect(mockedInvoke).toHaveBeenCalledWith("finish_editor_request", {
+				accepted: true,
+			});
+		});
+		expect(commandCallCount("exit_app")).toBe(0);
+		expect(
+			await screen.findByLabelText("リポジトリのパス"),
+		).toBeInTheDocument();
+	});
 });
@@ -7,16 +7,19 @@ import {
 	BackupRecoveryDialog,
 	ErrorDisplay,
 	Loading,
+	RebaseProgressBanner,
 } from "./components/common";
 import { FallbackEditor } from "./components/fallback";
 import { MergeActionBar, MergeEditor } from "./components/merge";
 import { RebaseEditor } from "./components/rebase";
+import { StandaloneRebase } from "./components/standalone";
 import { useAutoBackup, useKeyboardShortcuts } from "./hooks";
 import {
 	useCommitStore,
 	useFileStore,
 	useHistoryStore,
 	useRebaseStore,
+	useStandaloneStore,
 } from "./stores";
 import type { AppError } from "./types/errors";
 import type { MergeFilePaths } from "./types/git";
@@ -59,6 +62,8 @@ function App() {
 		isDirty: rebaseIsDirty,
 		parseContent,
 		serialize,
+		lint: lintTodo,
+		queueSquashMessages,
 		setEntries,
 		clearError: clearRebaseError,
 		getValidationError,
@@ -69,6 +74,7 @@ function App() {
 		error: commitError,
 		isDirty: commitIsDirty,
 		parseContent: parseCommitContent,
+		applyQueuedReword,
 		serialize: serializeCommit,
 		clearError: clearCommitError,
 	} = useCommitStore();
@@ -82,6 +88,15 @@ function App() {
 		clear: clearHistory,
 	} = useHistoryStore();
 
+	// スタンドアロンで開始した rebase の編集中なら、保存・キャンセルを git へ返す。
+	const {
+		editorPath: standaloneEditorPath,
+		finishEditor,
+		setRepoPath,
+		loadBaseCommits,
+		loadSessions,
+	} = useStandaloneStore();
+
 	const isLoading = fileLoading || rebaseLoading || commitLoading;
 	const error = fileError || rebaseError || commitError || backupError;
 
@@ -142,6 +157,9 @@ function App() {
 				if (args.file && typeof args.file.value === "string") {
 					const targetPath = args.file.value;
 					await loadFile(targetPath);
+				} else if (args.repo && typeof args.repo.value === "string") {
+					setRepoPath(args.repo.value);
+					await Promise.all([loadBaseCommits(), loadSessions()]);
 				}
 			} catch (err) {
 				console.error("CLI 引数の取得に失敗しました:", err);
@@ -149,7 +167,7 @@ function App() {
 		}
 
 		loadFromCli();
-	}, [loadFile]);
+	}, [loadFile, setRepoPath, loadBaseCommits, loadSessions]);
 
 	// ファイル読み込み後に前回セッションのバックアップを確認する。
 	useEffect(() => {
@@ -194,21 +212,37 @@ function App() {
 	}, [fileType, currentContent, parseContent]);
 
 	// ファイル読み込み後にコミットメッセージ内容を解析する。
+	// reword 中の COMMIT_EDITMSG なら、rebase エディタで編集済みのメッセージを補完する。
 	useEffect(() => {
 		if (isCommitType && currentContent !== null) {
-			parseCommitContent(currentContent);
+			parseCommitContent(currentContent, filePath ?? undefined).then((ok) => {
+				if (ok && fileType === "commit_msg" && filePath) {
+					applyQueuedReword(filePath);
+				}
+			});
 		}
-	}, [isCommitType, currentContent, parseCommitContent]);
+	}, [
+		isCommitType,
+		currentContent,
+		parseCommitContent,
+		fileType,
+		filePath,
+		applyQueuedReword,
+	]);
 
 	// 保存処理。
 	const handleSave = useCallback(async () => {
 		let success = false;
 
 		if (fileType === "rebase_todo") {
-			const serialized = await serialize();
-			if (serialized !== null) {
-				setContent(serialized);
-				success = await saveFile();
+			// 保存直前にも検証し、Git が受け付けない todo は書き出さない。
+			if (filePath && (await lintTodo(filePath))) {
+				const serialized = await serialize(filePath);
+				// 合成済みの squash メッセージは、Git が後で開くエディタに備えて先に保存する。
+				if (serialized !== null && (await queueSquashMessages(filePath))) {
+					setContent(serialized);
+					success = await saveFile();
+				}
 			}
 		} else if (isCommitType) {
 			const serialized = await serializeCommit();
@@ -222,23 +256,35 @@ function App() {
 
 		if (success) {
 			await clearBackup(filePath ?? undefined);
-			await exitApp(0);
+			if (standaloneEditorPath) {
+				await finishEditor(true);
+			} else {
+				await exitApp(0);
+			}
 		}
 	}, [
 		clearBackup,
 		filePath,
 		fileType,
+		finishEditor,
 		isCommitType,
+		lintTodo,
+		queueSquashMessages,
 		serialize,
 		serializeCommit,
 		setContent,
 		saveFile,
+		standaloneEditorPath,
 	]);
 
 	// キャンセル処理。
 	const handleCancel = useCallback(async () => {
-		await exitApp(1);
-	}, []);
+		if (standaloneEditorPath) {
+			await finishEditor(false);
+		} else {
+			await exitApp(1);
+		}
+	}, [finishEditor, standaloneEditorPath]);
 
 	const handleRestoreBackup = useCallback(async () => {
 		if (!backupPathToRecover || !filePath) return;
@@ -334,6 +380,7 @@ function App() {
 	if (isMergeMode && mergeFilePaths) {
 		return (
 			<div className="flex h-screen flex-col bg-white dark:bg-gray-900">
+				<RebaseProgressBanner filePath={mergeFilePaths.merged} />
 				<main className="flex-1 overflow-hidden">
 					<MergeEditor filePaths={mergeFilePaths} />
 				</main>
@@ -342,18 +389,9 @@ function App() {
 		);
 	}
 
-	// ファイルが読み込まれていない場合はエラーを表示する。
+	// ファイルが指定されていない場合は、リポジトリを開いて rebase を始める画面を表示する。
 	if (!filePath) {
-		return (
-			<div className="flex h-screen flex-col items-center justify-center bg-white dark:bg-gray-900">
-				<p className="text-gray-500 dark:text-gray-400">
-					ファイルが指定されていません
-				</p>
-				<p className="mt-2 text-sm text-gray-400 dark:text-gray-500">
-					使用方法: gui-git-editor &lt;ファイルパス&gt;
-				</p>
-			</div>
-		);
+		return <StandaloneRebase />;
 	}
 
 	return (
@@ -365,6 +403,8 @@ function App() {
 				/>
 			)}
 
+			{isCommitType && <RebaseProgressBanner filePath={filePath} />}
+
 			{error && (
 				<div className="p-4">
 					<ErrorDisplay error={error} onDismiss={clearError} />
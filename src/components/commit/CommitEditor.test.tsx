@@ -271,5 +271,27 @@ describe("CommitEditor", () => {
 			// StagingArea は filePath が無い場合非表示
 			expect(screen.queryByText(/Staged|Unstaged/)).not.toBeInTheDocument();
 		});
+
+		it("commit.template から未編集なら警告を表示する", () => {
+			useCommitStore.setState({
+				validation: {
+					is_valid: false,
+					subject_too_long: false,
+					subject_length: 0,
+					subject_width: 0,
+					length_unit: "chars",
+					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: true,
+				},
+			});
+			render(<CommitEditor />);
+
+			expect(
+				screen.getByText(/commit\.template から編集されていない/),
+			).toBeInTheDocument();
+		});
 	});
 });
@@ -8,6 +8,8 @@ import {
 } from "../../types/ipc";
 import { getShortcut } from "../../utils/platform";
 import { BodyTextarea } from "./BodyTextarea";
+import { CommitLintList } from "./CommitLintList";
+import { ConventionalCommitStatus } from "./ConventionalCommitStatus";
 import { StagingArea } from "./StagingArea";
 import { SubjectInput } from "./SubjectInput";
 import { TrailersDisplay } from "./TrailersDisplay";
@@ -21,6 +23,7 @@ export function CommitEditor() {
 		diffContent,
 		setSubject,
 		setBody,
+		validation,
 	} = useCommitStore();
 
 	const filePath = useFileStore((s) => s.filePath);
@@ -101,13 +104,32 @@ export function CommitEditor() {
 				</div>
 
 				{/* Subject 入力 */}
-				<SubjectInput value={subject} onChange={setSubject} />
+				<SubjectInput
+					value={subject}
+					onChange={setSubject}
+					displayWidth={validation?.subject_width}
+					lengthUnit={validation?.length_unit}
+				/>
+				{validation && (
+					<ConventionalCommitStatus
+						commit={validation.conventional}
+						violations={validation.conventional_violations}
+					/>
+				)}
+				{validation?.matches_template && (
+					<p className="text-xs text-amber-600 dark:text-amber-400">
+						commit.template から編集されていないため、Git はコミットを中止します
+					</p>
+				)}
 
 				{/* Body 入力 */}
 				<div className="flex-1">
 					<BodyTextarea value={body} onChange={setBody} />
 				</div>
 
+				{/* リポジトリの lint ルールによる診断 */}
+				{validation && <CommitLintList diagnostics={validation.diagnostics} />}
+
 				{/* Trailer とコメント */}
 				<TrailersDisplay
 					trailers={trailers}
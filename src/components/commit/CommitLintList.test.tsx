@@ -0,0 +1,31 @@
+import { render, screen } from "@testing-library/react";
+import { describe, expect, it } from "vitest";
+import { CommitLintList } from "./CommitLintList";
+
+describe("CommitLintList", () => {
+	it("診断がなければ何も表示しない", () => {
+		const { container } = render(<CommitLintList diagnostics={[]} />);
+
+		expect(container).toBeEmptyDOMElement();
+	});
+
+	it("行・列とルール名を表示する", () => {
+		render(
+			<CommitLintList
+				diagnostics={[
+					{
+						rule: "forbidden-words",
+						severity: "error",
+						message: "'WIP' is not allowed",
+						line: 3,
+						column: 5,
+					},
+				]}
+			/>,
+		);
+
+		expect(screen.getByText("3:5")).toBeInTheDocument();
+		expect(screen.getByText("'WIP' is not allowed")).toBeInTheDocument();
+		expect(screen.getByText("forbidden-words")).toBeInTheDocument();
+	});
+});
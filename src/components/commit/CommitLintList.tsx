@@ -0,0 +1,44 @@
+import {
+	ExclamationCircleIcon,
+	ExclamationTriangleIcon,
+} from "@heroicons/react/24/outline";
+import type { CommitLintDiagnostic } from "../../types/git";
+
+interface CommitLintListProps {
+	diagnostics: CommitLintDiagnostic[];
+}
+
+// リポジトリの lint ルールによる診断を、行:列 とルール名付きで一覧表示する。
+export function CommitLintList({ diagnostics }: CommitLintListProps) {
+	if (diagnostics.length === 0) {
+		return null;
+	}
+
+	return (
+		<ul className="space-y-1 text-xs">
+			{diagnostics.map((diagnostic) => (
+				<li
+					key={`${diagnostic.rule}-${diagnostic.line}-${diagnostic.column}`}
+					className={`flex items-center gap-1.5 ${
+						diagnostic.severity === "error"
+							? "text-red-600 dark:text-red-400"
+							: "text-amber-600 dark:text-amber-400"
+					}`}
+				>
+					{diagnostic.severity === "error" ? (
+						<ExclamationCircleIcon className="h-4 w-4 shrink-0" />
+					) : (
+						<ExclamationTriangleIcon className="h-4 w-4 shrink-0" />
+					)}
+					<span className="font-mono">
+						{diagnostic.line}:{diagnostic.column}
+					</span>
+					<span>{diagnostic.message}</span>
+					<span className="text-gray-400 dark:text-gray-500">
+						{diagnostic.rule}
+					</span>
+				</li>
+			))}
+		</ul>
+	);
+}
@@ -0,0 +1,38 @@
+import { render, screen } from "@testing-library/react";
+import { describe, expect, it } from "vitest";
+import { ConventionalCommitStatus } from "./ConventionalCommitStatus";
+
+describe("ConventionalCommitStatus", () => {
+	it("解析結果も違反もなければ何も表示しない", () => {
+		const { container } = render(
+			<ConventionalCommitStatus commit={null} violations={[]} />,
+		);
+
+		expect(container).toBeEmptyDOMElement();
+	});
+
+	it("type・scope と破壊的変更を表示する", () => {
+		render(
+			<ConventionalCommitStatus
+				commit={{
+					commit_type: "feat",
+					scope: "parser",
+					breaking: true,
+					description: "drop the legacy format",
+					breaking_changes: ["the v1 format is no longer read"],
+				}}
+				violations={[{ kind: "scope_not_allowed", scope: "parser" }]}
+			/>,
+		);
+
+		expect(screen.getByText("feat")).toBeInTheDocument();
+		expect(screen.getByText("parser")).toBeInTheDocument();
+		expect(screen.getByText("破壊的変更")).toBeInTheDocument();
+		expect(
+			screen.getByText("BREAKING CHANGE: the v1 format is no longer read"),
+		).toBeInTheDocument();
+		expect(
+			screen.getByText('scope "parser" は許可されていません'),
+		).toBeInTheDocument();
+	});
+});
@@ -0,0 +1,70 @@
+import { ExclamationTriangleIcon } from "@heroicons/react/24/outline";
+import type {
+	ConventionalCommit,
+	ConventionalViolation,
+} from "../../types/git";
+
+interface ConventionalCommitStatusProps {
+	commit: ConventionalCommit | null;
+	violations: ConventionalViolation[];
+}
+
+function describeViolation(violation: ConventionalViolation): string {
+	switch (violation.kind) {
+		case "not_conventional":
+			return "Subject を type(scope): description の形式にしてください";
+		case "type_not_allowed":
+			return `type "${violation.commit_type}" は許可されていません`;
+		case "scope_not_allowed":
+			return `scope "${violation.scope}" は許可されていません`;
+	}
+}
+
+// subject を Conventional Commits として解析した結果と、許可する type・scope の違反を表示する。
+export function ConventionalCommitStatus({
+	commit,
+	violations,
+}: ConventionalCommitStatusProps) {
+	if (!commit && violations.length === 0) {
+		return null;
+	}
+
+	return (
+		<div className="space-y-1 text-xs">
+			{commit && (
+				<div className="flex flex-wrap items-center gap-1.5">
+					<span className="rounded bg-blue-100 px-1.5 py-0.5 font-mono text-blue-700 dark:bg-blue-900/40 dark:text-blue-300">
+						{commit.commit_type}
+					</span>
+					{commit.scope && (
+						<span className="rounded bg-gray-100 px-1.5 py-0.5 font-mono text-gray-700 dark:bg-gray-700 dark:text-gray-300">
+							{commit.scope}
+						</span>
+					)}
+					{commit.breaking && (
+						<span className="rounded bg-red-100 px-1.5 py-0.5 font-medium text-red-700 dark:bg-red-900/40 dark:text-red-300">
+							破壊的変更
+						</span>
+					)}
+				</div>
+			)}
+			{commit?.breaking_changes.map((note) => (
+				<p
+					key={note}
+					className="whitespace-pre-wrap text-red-700 dark:text-red-400"
+				>
+					BREAKING CHANGE: {note}
+				</p>
+			))}
+			{violations.map((violation) => (
+				<div
+					key={describeViolation(violation)}
+					className="flex items-center gap-1.5 text-amber-600 dark:text-amber-400"
+				>
+					<ExclamationTriangleIcon className="h-4 w-4" />
+					<span>{describeViolation(violation)}</span>
+				</div>
+			))}
+		</div>
+	);
+}
@@ -40,6 +40,22 @@ describe("SubjectInput", () => {
 		).toBeInTheDocument();
 	});
 
+	it("counts display width when the unit is display_width", () => {
+		render(
+			<SubjectInput
+				{...defaultProps}
+				value={"修正".repeat(15)}
+				displayWidth={60}
+				lengthUnit="display_width"
+			/>,
+		);
+
+		expect(screen.getByText("60/50 桁（30 文字）")).toBeInTheDocument();
+		expect(
+			screen.getByText("Subject は 50 桁以内を推奨"),
+		).toBeInTheDocument();
+	});
+
 	it("respects custom maxLength", () => {
 		render(<SubjectInput {...defaultProps} value="Hello" maxLength={72} />);
 
@@ -1,18 +1,27 @@
 import { ExclamationTriangleIcon } from "@heroicons/react/24/outline";
+import type { LengthUnit } from "../../types/git";
 
 interface SubjectInputProps {
 	value: string;
 	onChange: (value: string) => void;
 	maxLength?: number;
+	// バックエンドで数えた表示幅。lengthUnit が display_width のときに使う。
+	displayWidth?: number;
+	lengthUnit?: LengthUnit;
 }
 
 export function SubjectInput({
 	value,
 	onChange,
 	maxLength = 50,
+	displayWidth,
+	lengthUnit = "chars",
 }: SubjectInputProps) {
-	const isTooLong = value.length > maxLength;
 	const charCount = value.length;
+	const byWidth = lengthUnit === "display_width" && displayWidth !== undefined;
+	const length = byWidth ? displayWidth : charCount;
+	const isTooLong = length > maxLength;
+	const unitLabel = byWidth ? "桁" : "文字";
 
 	return (
 		<div className="space-y-1">
@@ -30,7 +39,9 @@ export function SubjectInput({
 							: "text-gray-500 dark:text-gray-400"
 					}`}
 				>
-					{charCount}/{maxLength}
+					{byWidth
+						? `${length}/${maxLength} 桁（${charCount} 文字）`
+						: `${charCount}/${maxLength}`}
 				</span>
 			</div>
 
@@ -50,7 +61,7 @@ export function SubjectInput({
 			{isTooLong && (
 				<div className="flex items-center gap-1.5 text-xs text-amber-600 dark:text-amber-400">
 					<ExclamationTriangleIcon className="h-4 w-4" />
-					<span>Subject は {maxLength} 文字以内を推奨</span>
+					<span>Subject は {maxLength} {unitLabel}以内を推奨</span>
 				</div>
 			)}
 		</div>
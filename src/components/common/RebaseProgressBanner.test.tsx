@@ -0,0 +1,62 @@
+import { render, screen } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import type { RebaseProgress } from "../../types/git";
+import { getRebaseProgress } from "../../types/ipc";
+import { RebaseProgressBanner } from "./RebaseProgressBanner";
+
+vi.mock("../../types/ipc", () => ({
+	getRebaseProgress: vi.fn(),
+}));
+
+const progress: RebaseProgress = {
+	step: 4,
+	total: 12,
+	done: [],
+	remaining: [],
+	current: {
+		id: "entry-4",
+		command: "edit",
+		commit_hash: "abc1234def5678",
+		message: "Add parser",
+	},
+	stopped_sha: "abc1234def5678",
+	stop_reason: "edit",
+	onto: "1234567",
+	orig_head: "89abcde",
+	head_name: "feature",
+};
+
+describe("RebaseProgressBanner", () => {
+	beforeEach(() => {
+		vi.mocked(getRebaseProgress).mockReset();
+	});
+
+	it("現在のステップと適用中のコミットを表示する", async () => {
+		vi.mocked(getRebaseProgress).mockResolvedValue({
+			ok: true,
+			data: progress,
+		});
+
+		render(<RebaseProgressBanner filePath="/repo/.git/COMMIT_EDITMSG" />);
+
+		expect(await screen.findByText(/ステップ 4\/12/)).toBeInTheDocument();
+		expect(screen.getByText("abc1234")).toBeInTheDocument();
+		expect(screen.getByText(/'Add parser' を適用中/)).toBeInTheDocument();
+		expect(screen.getByText("edit で停止中")).toBeInTheDocument();
+		expect(screen.getByText("feature")).toBeInTheDocument();
+		expect(getRebaseProgress).toHaveBeenCalledWith(
+			"/repo/.git/COMMIT_EDITMSG",
+		);
+	});
+
+	it("rebase 中でなければ何も描画しない", async () => {
+		vi.mocked(getRebaseProgress).mockResolvedValue({ ok: true, data: null });
+
+		const { container } = render(
+			<RebaseProgressBanner filePath="/repo/file.txt" />,
+		);
+
+		await vi.waitFor(() => expect(getRebaseProgress).toHaveBeenCalled());
+		expect(container).toBeEmptyDOMElement();
+	});
+});
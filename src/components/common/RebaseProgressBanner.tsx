@@ -0,0 +1,67 @@
+import { ArrowPathIcon } from "@heroicons/react/24/outline";
+import { useEffect, useState } from "react";
+import type { RebaseProgress, StopReason } from "../../types/git";
+import { getRebaseProgress } from "../../types/ipc";
+
+interface RebaseProgressBannerProps {
+	filePath: string;
+}
+
+const STOP_REASON_LABELS: Record<StopReason, string> = {
+	edit: "edit で停止中",
+	break: "break で停止中",
+	exec: "exec が失敗",
+	conflict: "競合で停止中",
+};
+
+// rebase の途中で起動されたとき、現在のステップと適用中のコミットを表示する。
+export function RebaseProgressBanner({ filePath }: RebaseProgressBannerProps) {
+	const [progress, setProgress] = useState<RebaseProgress | null>(null);
+
+	useEffect(() => {
+		let cancelled = false;
+		getRebaseProgress(filePath).then((result) => {
+			if (!cancelled && result.ok) {
+				setProgress(result.data);
+			}
+		});
+		return () => {
+			cancelled = true;
+		};
+	}, [filePath]);
+
+	if (!progress) {
+		return null;
+	}
+
+	const current = progress.current;
+	const hash = (current?.commit_hash ?? progress.stopped_sha ?? "").slice(
+		0,
+		7,
+	);
+
+	return (
+		<div className="flex items-center gap-2 border-b border-blue-200 bg-blue-50 px-4 py-2 text-sm text-blue-800 dark:border-blue-800 dark:bg-blue-900/20 dark:text-blue-200">
+			<ArrowPathIcon className="h-4 w-4 flex-shrink-0" />
+			<span className="min-w-0 flex-1 truncate">
+				ステップ {progress.step}/{progress.total}
+				{hash && (
+					<>
+						: <span className="font-mono">{hash}</span>
+						{current?.message && ` '${current.message}'`} を適用中
+					</>
+				)}
+			</span>
+			{progress.stop_reason && (
+				<span className="flex-shrink-0 rounded bg-blue-100 px-1.5 py-0.5 text-xs dark:bg-blue-800/50">
+					{STOP_REASON_LABELS[progress.stop_reason]}
+				</span>
+			)}
+			{progress.head_name && (
+				<span className="flex-shrink-0 font-mono text-xs text-blue-600 dark:text-blue-300">
+					{progress.head_name}
+				</span>
+			)}
+		</div>
+	);
+}
@@ -2,3 +2,4 @@ export { ActionBar } from "./ActionBar";
 export { BackupRecoveryDialog } from "./BackupRecoveryDialog";
 export { ErrorDisplay } from "./ErrorDisplay";
 export { Loading } from "./Loading";
+export { RebaseProgressBanner } from "./RebaseProgressBanner";
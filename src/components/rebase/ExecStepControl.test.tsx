@@ -0,0 +1,45 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import { ExecStepControl } from "./ExecStepControl";
+
+describe("ExecStepControl", () => {
+	it("最初のテンプレートを初期値にして挿入できる", async () => {
+		const user = userEvent.setup();
+		const onInsert = vi.fn();
+		render(
+			<ExecStepControl
+				templates={[{ command: "cargo test", source: "git_config" }]}
+				onInsert={onInsert}
+				onRemove={vi.fn()}
+			/>,
+		);
+
+		expect(screen.getByLabelText("exec コマンド")).toHaveValue("cargo test");
+		await user.click(screen.getByRole("button", { name: "exec を挿入" }));
+
+		expect(onInsert).toHaveBeenCalledWith("cargo test");
+	});
+
+	it("入力したコマンドで削除する", async () => {
+		const user = userEvent.setup();
+		const onRemove = vi.fn();
+		render(
+			<ExecStepControl templates={[]} onInsert={vi.fn()} onRemove={onRemove} />,
+		);
+
+		await user.type(screen.getByLabelText("exec コマンド"), " make lint ");
+		await user.click(screen.getByRole("button", { name: "exec を削除" }));
+
+		expect(onRemove).toHaveBeenCalledWith("make lint");
+	});
+
+	it("空のコマンドではボタンを無効にする", () => {
+		render(
+			<ExecStepControl templates={[]} onInsert={vi.fn()} onRemove={vi.fn()} />,
+		);
+
+		expect(screen.getByRole("button", { name: "exec を挿入" })).toBeDisabled();
+		expect(screen.getByRole("button", { name: "exec を削除" })).toBeDisabled();
+	});
+});
@@ -0,0 +1,66 @@
+import { useId, useState } from "react";
+import type { ExecTemplate } from "../../types/git";
+
+interface ExecStepControlProps {
+	templates: ExecTemplate[];
+	onInsert: (command: string) => void;
+	onRemove: (command: string) => void;
+}
+
+const SOURCE_LABELS: Record<ExecTemplate["source"], string> = {
+	git_config: "git config",
+	project_file: ".gui-git-editor-exec",
+};
+
+// `git rebase --exec` と同様に、各コミットの後ろへ exec を挿入・削除する。
+export function ExecStepControl({
+	templates,
+	onInsert,
+	onRemove,
+}: ExecStepControlProps) {
+	const [command, setCommand] = useState(templates[0]?.command ?? "");
+	const listId = useId();
+	const trimmed = command.trim();
+
+	return (
+		<div className="flex items-center gap-2">
+			<label htmlFor={`${listId}-input`} className="sr-only">
+				exec コマンド
+			</label>
+			<input
+				id={`${listId}-input`}
+				type="text"
+				value={command}
+				onChange={(e) => setCommand(e.target.value)}
+				list={listId}
+				placeholder="cargo test"
+				className="min-w-0 flex-1 rounded-md border border-gray-300 bg-white px-2 py-1 font-mono text-sm text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+			/>
+			<datalist id={listId}>
+				{templates.map((template) => (
+					<option key={template.command} value={template.command}>
+						{SOURCE_LABELS[template.source]}
+					</option>
+				))}
+			</datalist>
+			<button
+				type="button"
+				disabled={!trimmed}
+				onClick={() => onInsert(trimmed)}
+				title="コミットごと（squash/fixup の連鎖は最後）に exec を挿入します"
+				className="rounded-md bg-gray-100 px-3 py-1 text-sm font-medium text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+			>
+				exec を挿入
+			</button>
+			<button
+				type="button"
+				disabled={!trimmed}
+				onClick={() => onRemove(trimmed)}
+				title="このコマンドの exec をすべて削除します"
+				className="rounded-md bg-gray-100 px-3 py-1 text-sm font-medium text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+			>
+				exec を削除
+			</button>
+		</div>
+	);
+}
@@ -0,0 +1,83 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import type { RebaseEntry } from "../../types/git";
+import { MessageReplacePanel } from "./MessageReplacePanel";
+
+const entries: RebaseEntry[] = [
+	{
+		id: "1",
+		command: { type: "pick" },
+		commit_hash: "aaa1111",
+		message: "ABC-1: Add parser",
+	},
+	{
+		id: "2",
+		command: { type: "exec", value: "make" },
+		commit_hash: "",
+		message: "",
+	},
+	{
+		id: "3",
+		command: { type: "reword" },
+		commit_hash: "bbb2222",
+		message: "ABC-2: Fix typo",
+	},
+];
+
+describe("MessageReplacePanel", () => {
+	it("チェックしたコミットだけを対象にプレビューする", async () => {
+		const user = userEvent.setup();
+		const onPreview = vi.fn();
+		render(
+			<MessageReplacePanel
+				entries={entries}
+				replacements={[]}
+				onPreview={onPreview}
+				onApply={vi.fn()}
+				onCancel={vi.fn()}
+			/>,
+		);
+
+		await user.type(screen.getByLabelText("検索"), "ABC-");
+		await user.type(screen.getByLabelText("置換"), "XYZ-");
+		await user.click(screen.getByRole("checkbox", { name: /bbb2222/ }));
+		await user.click(screen.getByRole("button", { name: "プレビュー" }));
+
+		expect(onPreview).toHaveBeenCalledWith(["1"], {
+			find: "ABC-",
+			replace: "XYZ-",
+			regex: false,
+		});
+	});
+
+	it("変わった行を変更前後で表示し、適用できる", async () => {
+		const user = userEvent.setup();
+		const onApply = vi.fn();
+		render(
+			<MessageReplacePanel
+				entries={entries}
+				replacements={[
+					{
+						entry_id: "1",
+						commit_hash: "aaa1111",
+						old_message: "ABC-1: Add parser\n\nBody",
+						new_message: "XYZ-1: Add parser\n\nBody",
+					},
+				]}
+				onPreview={vi.fn()}
+				onApply={onApply}
+				onCancel={vi.fn()}
+			/>,
+		);
+
+		expect(screen.getByText("- ABC-1: Add parser")).toBeInTheDocument();
+		expect(screen.getByText("+ XYZ-1: Add parser")).toBeInTheDocument();
+		expect(screen.queryByText(/Body/)).not.toBeInTheDocument();
+
+		await user.click(
+			screen.getByRole("button", { name: "1 件を reword にする" }),
+		);
+		expect(onApply).toHaveBeenCalled();
+	});
+});
@@ -0,0 +1,180 @@
+import { useId, useState } from "react";
+import type {
+	MessageReplace,
+	MessageReplacement,
+	RebaseEntry,
+} from "../../types/git";
+
+interface MessageReplacePanelProps {
+	entries: RebaseEntry[];
+	replacements: MessageReplacement[];
+	onPreview: (entryIds: string[], spec: MessageReplace) => void;
+	onApply: () => void;
+	onCancel: () => void;
+}
+
+/** メッセージを置換できる（reword にできる）エントリ。 */
+function isReplaceable(entry: RebaseEntry): boolean {
+	return entry.command.type === "pick" || entry.command.type === "reword";
+}
+
+/** 行ごとに比べ、変わった行だけを `- `（変更前）と `+ `（変更後）で並べる。 */
+function changedLines(oldMessage: string, newMessage: string): string[] {
+	const oldLines = oldMessage.split("\n");
+	const newLines = newMessage.split("\n");
+	const lines: string[] = [];
+	for (let i = 0; i < Math.max(oldLines.length, newLines.length); i++) {
+		if (oldLines[i] === newLines[i]) continue;
+		if (oldLines[i] !== undefined) lines.push(`- ${oldLines[i]}`);
+		if (newLines[i] !== undefined) lines.push(`+ ${newLines[i]}`);
+	}
+	return lines;
+}
+
+/** 同じ行が複数あっても一意になる key を付ける。 */
+function withKeys(lines: string[]): { key: string; line: string }[] {
+	const occurrences = new Map<string, number>();
+	return lines.map((line) => {
+		const count = (occurrences.get(line) ?? 0) + 1;
+		occurrences.set(line, count);
+		return { key: `${line}-${count}`, line };
+	});
+}
+
+// 選んだコミットのメッセージを一括で検索・置換する。プレビューを確認してから reword にする。
+export function MessageReplacePanel({
+	entries,
+	replacements,
+	onPreview,
+	onApply,
+	onCancel,
+}: MessageReplacePanelProps) {
+	const candidates = entries.filter(isReplaceable);
+	const [find, setFind] = useState("");
+	const [replace, setReplace] = useState("");
+	const [regex, setRegex] = useState(false);
+	const [excluded, setExcluded] = useState<string[]>([]);
+	const id = useId();
+
+	const selectedIds = candidates
+		.map((entry) => entry.id)
+		.filter((entryId) => !excluded.includes(entryId));
+
+	const toggle = (entryId: string) => {
+		setExcluded((current) =>
+			current.includes(entryId)
+				? current.filter((excludedId) => excludedId !== entryId)
+				: [...current, entryId],
+		);
+	};
+
+	return (
+		<div className="space-y-3 text-sm">
+			<div className="flex items-center gap-2">
+				<label htmlFor={`${id}-find`} className="sr-only">
+					検索
+				</label>
+				<input
+					id={`${id}-find`}
+					type="text"
+					value={find}
+					onChange={(e) => setFind(e.target.value)}
+					placeholder="検索"
+					className="min-w-0 flex-1 rounded-md border border-gray-300 bg-white px-2 py-1 font-mono text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+				/>
+				<label htmlFor={`${id}-replace`} className="sr-only">
+					置換
+				</label>
+				<input
+					id={`${id}-replace`}
+					type="text"
+					value={replace}
+					onChange={(e) => setReplace(e.target.value)}
+					placeholder="置換"
+					className="min-w-0 flex-1 rounded-md border border-gray-300 bg-white px-2 py-1 font-mono text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+				/>
+				<label className="flex items-center gap-1 text-gray-700 dark:text-gray-300">
+					<input
+						type="checkbox"
+						checked={regex}
+						onChange={(e) => setRegex(e.target.checked)}
+					/>
+					正規表現
+				</label>
+				<button
+					type="button"
+					disabled={!find || selectedIds.length === 0}
+					onClick={() => onPreview(selectedIds, { find, replace, regex })}
+					className="rounded-md bg-gray-100 px-3 py-1 font-medium text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+				>
+					プレビュー
+				</button>
+			</div>
+
+			<ul className="max-h-40 space-y-1 overflow-auto">
+				{candidates.map((entry) => (
+					<li key={entry.id}>
+						<label className="flex items-center gap-2 text-gray-700 dark:text-gray-300">
+							<input
+								type="checkbox"
+								checked={!excluded.includes(entry.id)}
+								onChange={() => toggle(entry.id)}
+							/>
+							<span className="font-mono text-xs text-amber-600 dark:text-amber-400">
+								{entry.commit_hash.slice(0, 7)}
+							</span>
+							<span className="truncate">{entry.message.split("\n")[0]}</span>
+						</label>
+					</li>
+				))}
+			</ul>
+
+			{replacements.length > 0 && (
+				<div className="space-y-2 rounded-md border border-gray-200 p-2 dark:border-gray-700">
+					{replacements.map((replacement) => (
+						<div key={replacement.entry_id}>
+							<p className="font-mono text-xs text-gray-500 dark:text-gray-400">
+								{replacement.commit_hash.slice(0, 7)}
+							</p>
+							<pre className="font-mono text-xs whitespace-pre-wrap">
+								{withKeys(
+									changedLines(
+										replacement.old_message,
+										replacement.new_message,
+									),
+								).map(({ key, line }) => (
+									<div
+										key={key}
+										className={
+											line.startsWith("-")
+												? "text-red-700 dark:text-red-400"
+												: "text-green-700 dark:text-green-400"
+										}
+									>
+										{line}
+									</div>
+								))}
+							</pre>
+						</div>
+					))}
+					<div className="flex items-center gap-2">
+						<button
+							type="button"
+							onClick={onApply}
+							className="rounded-md bg-blue-600 px-3 py-1 font-medium text-white hover:bg-blue-700"
+						>
+							{replacements.length} 件を reword にする
+						</button>
+						<button
+							type="button"
+							onClick={onCancel}
+							className="rounded-md bg-gray-100 px-3 py-1 font-medium text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+						>
+							取り消す
+						</button>
+					</div>
+				</div>
+			)}
+		</div>
+	);
+}
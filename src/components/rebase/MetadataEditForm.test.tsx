@@ -0,0 +1,63 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import { MetadataEditForm } from "./MetadataEditForm";
+
+describe("MetadataEditForm", () => {
+	it("入力した内容をこのコミットに適用する", async () => {
+		const user = userEvent.setup();
+		const onApply = vi.fn();
+		render(<MetadataEditForm onApply={onApply} onApplyToAll={vi.fn()} />);
+
+		await user.type(
+			screen.getByLabelText("author"),
+			"A U Thor <a@example.com>",
+		);
+		await user.click(screen.getByLabelText("Signed-off-by を追加"));
+		await user.click(
+			screen.getByRole("button", { name: "このコミットに適用" }),
+		);
+
+		expect(onApply).toHaveBeenCalledWith({
+			author: "A U Thor <a@example.com>",
+			date: null,
+			signoff: true,
+		});
+	});
+
+	it("すべて空で適用すると書き換えを解除する", async () => {
+		const user = userEvent.setup();
+		const onApply = vi.fn();
+		render(
+			<MetadataEditForm
+				edit={{ signoff: true }}
+				onApply={onApply}
+				onApplyToAll={vi.fn()}
+			/>,
+		);
+
+		await user.click(screen.getByLabelText("Signed-off-by を追加"));
+		await user.click(
+			screen.getByRole("button", { name: "このコミットに適用" }),
+		);
+
+		expect(onApply).toHaveBeenCalledWith(null);
+		expect(
+			screen.getByRole("button", { name: "すべてのコミットに適用" }),
+		).toBeDisabled();
+	});
+
+	it("author の形式が不正なら適用できない", async () => {
+		const user = userEvent.setup();
+		render(<MetadataEditForm onApply={vi.fn()} onApplyToAll={vi.fn()} />);
+
+		await user.type(screen.getByLabelText("author"), "no email");
+
+		expect(
+			screen.getByText("author は Name <email> の形式で入力してください"),
+		).toBeInTheDocument();
+		expect(
+			screen.getByRole("button", { name: "このコミットに適用" }),
+		).toBeDisabled();
+	});
+});
@@ -0,0 +1,102 @@
+import { useId, useState } from "react";
+import type { MetadataEdit } from "../../types/git";
+
+interface MetadataEditFormProps {
+	edit?: MetadataEdit | null;
+	onApply: (edit: MetadataEdit | null) => void;
+	onApplyToAll: (edit: MetadataEdit) => void;
+}
+
+const AUTHOR_PATTERN = /^[^<>]+<[^<>]*>$/;
+
+// 選択中のコミットの author・日付・sign-off を、適用後の amend で書き換える。
+export function MetadataEditForm({
+	edit,
+	onApply,
+	onApplyToAll,
+}: MetadataEditFormProps) {
+	const [author, setAuthor] = useState(edit?.author ?? "");
+	const [date, setDate] = useState(edit?.date ?? "");
+	const [signoff, setSignoff] = useState(edit?.signoff ?? false);
+	const id = useId();
+
+	const trimmedAuthor = author.trim();
+	const trimmedDate = date.trim();
+	const authorError =
+		trimmedAuthor && !AUTHOR_PATTERN.test(trimmedAuthor)
+			? "author は Name <email> の形式で入力してください"
+			: null;
+	const current: MetadataEdit = {
+		author: trimmedAuthor || null,
+		date: trimmedDate || null,
+		signoff,
+	};
+	const isEmpty = !current.author && !current.date && !current.signoff;
+
+	return (
+		<div className="flex flex-col gap-2 text-sm">
+			<div className="flex items-center gap-2">
+				<label
+					htmlFor={`${id}-author`}
+					className="w-16 shrink-0 text-gray-600 dark:text-gray-400"
+				>
+					author
+				</label>
+				<input
+					id={`${id}-author`}
+					type="text"
+					value={author}
+					onChange={(e) => setAuthor(e.target.value)}
+					placeholder="Name <email@example.com>"
+					className="min-w-0 flex-1 rounded-md border border-gray-300 bg-white px-2 py-1 text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+				/>
+			</div>
+			<div className="flex items-center gap-2">
+				<label
+					htmlFor={`${id}-date`}
+					className="w-16 shrink-0 text-gray-600 dark:text-gray-400"
+				>
+					日時
+				</label>
+				<input
+					id={`${id}-date`}
+					type="text"
+					value={date}
+					onChange={(e) => setDate(e.target.value)}
+					placeholder="2024-01-02T03:04:05+09:00"
+					className="min-w-0 flex-1 rounded-md border border-gray-300 bg-white px-2 py-1 font-mono text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+				/>
+			</div>
+			<label className="flex items-center gap-2 text-gray-700 dark:text-gray-300">
+				<input
+					type="checkbox"
+					checked={signoff}
+					onChange={(e) => setSignoff(e.target.checked)}
+				/>
+				Signed-off-by を追加
+			</label>
+			{authorError && (
+				<p className="text-xs text-red-600 dark:text-red-400">{authorError}</p>
+			)}
+			<div className="flex items-center gap-2">
+				<button
+					type="button"
+					disabled={authorError !== null}
+					onClick={() => onApply(isEmpty ? null : current)}
+					className="rounded-md bg-blue-600 px-3 py-1 font-medium text-white hover:bg-blue-700 disabled:opacity-50"
+				>
+					このコミットに適用
+				</button>
+				<button
+					type="button"
+					disabled={authorError !== null || isEmpty}
+					onClick={() => onApplyToAll(current)}
+					title="squash/fixup は統合先のコミットで書き換えます"
+					className="rounded-md bg-gray-100 px-3 py-1 font-medium text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+				>
+					すべてのコミットに適用
+				</button>
+			</div>
+		</div>
+	);
+}
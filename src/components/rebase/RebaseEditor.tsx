@@ -1,15 +1,28 @@
 import { useCallback, useEffect, useState } from "react";
 import { useFileStore, useRebaseStore } from "../../stores";
-import type { RebaseEntry, SimpleCommand } from "../../types/git";
+import type {
+	RebaseDiagnostic,
+	RebaseEntry,
+	SimpleCommand,
+} from "../../types/git";
 import { getModifierKey, getShortcut } from "../../utils/platform";
 import {
 	collectSquashedCommitHashes,
 	countSquashableEntries,
+	findDependencyViolations,
+	hasAutosquashCandidates,
 	hasSquashTargetBeforeEntry,
+	isSquashableEntry,
 } from "../../utils/rebase";
 import { CommitChangeViewer } from "./CommitChangeViewer";
+import { ExecStepControl } from "./ExecStepControl";
+import { MessageReplacePanel } from "./MessageReplacePanel";
+import { MetadataEditForm } from "./MetadataEditForm";
 import { RebaseEntryList } from "./RebaseEntryList";
+import { RebasePlanPanel } from "./RebasePlanPanel";
 import { RewordModal } from "./RewordModal";
+import { SquashMessagePanel } from "./SquashMessagePanel";
+import { StackBranchPanel } from "./StackBranchPanel";
 
 /** コミットメッセージ先頭の `#` を表示用に取り除く。 */
 function cleanMessage(message: string): string {
@@ -29,6 +42,8 @@ export function RebaseEditor() {
 	const {
 		entries,
 		comments,
+		header,
+		regenerateHelp,
 		selectedEntryId,
 		selectEntry,
 		moveEntry,
@@ -36,6 +51,45 @@ export function RebaseEditor() {
 		setSimpleCommand,
 		updateEntryMessage,
 		squashAll,
+		autosquash,
+		simulate,
+		simulatedSteps,
+		originalEntries,
+		commitMetadata,
+		loadCommitMetadata,
+		upstreamMatches,
+		detectUpstream,
+		dependencies,
+		analyzeDependencies,
+		stackBranches,
+		loadStackBranches,
+		addUpdateRef,
+		removeUpdateRef,
+		execTemplates,
+		loadExecTemplates,
+		insertExec,
+		removeExec,
+		squashMessages,
+		composeSquash,
+		setSquashMessage,
+		applySquashAsReword,
+		setMetadataEdit,
+		applyMetadataEditToAll,
+		messageReplacements,
+		previewMessageReplace,
+		applyMessageReplace,
+		clearMessageReplacements,
+		savedPlan,
+		savedPlanPath,
+		planReport,
+		loadSavedPlan,
+		savePlan,
+		applySavedPlan,
+		diagnostics,
+		lint,
+		rewordMode,
+		setRewordMode,
+		detectRewordMode,
 	} = useRebaseStore();
 
 	const filePath = useFileStore((s) => s.filePath);
@@ -45,9 +99,72 @@ export function RebaseEditor() {
 	const selectedCommitHash = selectedEntry?.commit_hash || null;
 	const selectedMessage = selectedEntry?.message || "";
 
+	// 読み込み直後に一覧表示用のコミットメタデータと取り込み済み判定をまとめて取得する
+	useEffect(() => {
+		if (filePath && originalEntries.length > 0) {
+			loadCommitMetadata(filePath);
+			detectUpstream(filePath);
+			analyzeDependencies(filePath);
+			loadStackBranches(filePath);
+		}
+	}, [
+		filePath,
+		originalEntries,
+		loadCommitMetadata,
+		detectUpstream,
+		analyzeDependencies,
+		loadStackBranches,
+	]);
+
+	// 編集のたびに todo を検証し、Git が拒否する問題をエントリに表示する
+	// biome-ignore lint/correctness/useExhaustiveDependencies: lint はストアの entries を読むため、変更時に再実行するよう entries を含める
+	useEffect(() => {
+		if (filePath) {
+			lint(filePath);
+		}
+	}, [filePath, entries, lint]);
+
+	useEffect(() => {
+		if (filePath) {
+			loadExecTemplates(filePath);
+			loadSavedPlan(filePath);
+			detectRewordMode(filePath);
+		}
+	}, [filePath, loadExecTemplates, loadSavedPlan, detectRewordMode]);
+
 	// Reword ダイアログの表示状態
 	const [rewordEntry, setRewordEntry] = useState<RebaseEntry | null>(null);
 	const squashableEntryCount = countSquashableEntries(entries);
+	const canAutosquash = hasAutosquashCandidates(entries);
+	const hasSquash = entries.some((entry) => entry.command.type === "squash");
+	const hasReword = entries.some((entry) => entry.command.type === "reword");
+	const brokenDependencies = Object.fromEntries(
+		Object.entries(findDependencyViolations(entries, dependencies)).map(
+			([entryId, dependencyIds]) => [
+				entryId,
+				dependencyIds.map((dependencyId) => {
+					const dependency = originalEntries.find((e) => e.id === dependencyId);
+					return dependency
+						? `${dependency.commit_hash.slice(0, 7)} ${cleanMessage(dependency.message)}`
+						: dependencyId;
+				}),
+			],
+		),
+	);
+
+	// 削除されたコミットなど、エントリに対応しない問題は一覧の上にまとめて表示する
+	const entryDiagnostics: Record<string, RebaseDiagnostic[]> = {};
+	const todoDiagnostics: RebaseDiagnostic[] = [];
+	for (const diagnostic of diagnostics) {
+		if (diagnostic.entry_id === null) {
+			todoDiagnostics.push(diagnostic);
+		} else {
+			entryDiagnostics[diagnostic.entry_id] = [
+				...(entryDiagnostics[diagnostic.entry_id] ?? []),
+				diagnostic,
+			];
+		}
+	}
 
 	// コマンド変更や並べ替えに使うキーボードショートカット
 	const handleKeyDown = useCallback(
@@ -203,6 +320,36 @@ export function RebaseEditor() {
 						Rebase エントリ
 					</h2>
 					<div className="flex items-center gap-2">
+						{filePath && entries.length > 0 && (
+							<button
+								type="button"
+								onClick={() => simulate(filePath)}
+								title="onto から順に適用した場合の競合を、作業ツリーを変更せずに予測します"
+								className="rounded-md bg-gray-100 px-3 py-1 text-sm font-medium text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+							>
+								競合を予測
+							</button>
+						)}
+						{filePath && hasSquash && (
+							<button
+								type="button"
+								onClick={() => composeSquash(filePath)}
+								title="squash の連鎖ごとに、Git が後で開くメッセージを先に作成します"
+								className="rounded-md bg-purple-100 px-3 py-1 text-sm font-medium text-purple-700 hover:bg-purple-200 dark:bg-purple-900/30 dark:text-purple-300 dark:hover:bg-purple-900/50"
+							>
+								squash メッセージを作成
+							</button>
+						)}
+						{filePath && canAutosquash && (
+							<button
+								type="button"
+								onClick={() => autosquash(filePath)}
+								title="fixup! / squash! / amend! のコミットを対象コミットの直後へ移動します"
+								className="rounded-md bg-purple-100 px-3 py-1 text-sm font-medium text-purple-700 hover:bg-purple-200 dark:bg-purple-900/30 dark:text-purple-300 dark:hover:bg-purple-900/50"
+							>
+								autosquash
+							</button>
+						)}
 						{squashableEntryCount >= 2 && (
 							<button
 								type="button"
@@ -221,6 +368,15 @@ export function RebaseEditor() {
 					</div>
 				</div>
 
+				{/* todo のコメントから読み取った rebase の範囲 */}
+				{header?.range && header.onto && (
+					<p className="font-mono text-xs text-gray-500 dark:text-gray-400">
+						{header.range} → {header.onto}
+						{header.command_count !== null &&
+							`（開始時 ${header.command_count} コマンド）`}
+					</p>
+				)}
+
 				{/* 操作ガイド */}
 				<div className="rounded-lg bg-blue-50 p-3 text-sm text-blue-700 dark:bg-blue-900/20 dark:text-blue-300">
 					<p>
@@ -228,6 +384,132 @@ export function RebaseEditor() {
 					</p>
 				</div>
 
+				{/* reword の保存方式。native は core.editor もこのアプリの場合だけ反映される */}
+				{hasReword && (
+					<label
+						title="オフの場合は pick と exec git commit --amend に展開し、エディタを開かずにメッセージを適用します"
+						className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300"
+					>
+						<input
+							type="checkbox"
+							checked={rewordMode === "native"}
+							onChange={(e) =>
+								setRewordMode(e.target.checked ? "native" : "exec_amend")
+							}
+						/>
+						reword 行のまま保存し、Git が開くメッセージ編集で反映する（core.editor
+						にもこのアプリを設定している場合）
+					</label>
+				)}
+
+				{/* exec の一括挿入・削除（git rebase --exec 相当） */}
+				<ExecStepControl
+					key={execTemplates[0]?.command ?? ""}
+					templates={execTemplates}
+					onInsert={insertExec}
+					onRemove={removeExec}
+				/>
+
+				<SquashMessagePanel
+					entries={entries}
+					messages={squashMessages}
+					onChange={setSquashMessage}
+					onApplyAsReword={applySquashAsReword}
+				/>
+
+				{/* スタックしたブランチの update-ref */}
+				{filePath && stackBranches.length > 0 && (
+					<details className="rounded-lg border border-gray-200 dark:border-gray-700">
+						<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
+							ブランチ ({stackBranches.length})
+						</summary>
+						<div className="border-t border-gray-200 p-4 dark:border-gray-700">
+							<StackBranchPanel
+								entries={entries}
+								branches={stackBranches}
+								onAdd={(entryId, refName) =>
+									addUpdateRef(filePath, entryId, refName)
+								}
+								onRemove={(refName) => removeUpdateRef(filePath, refName)}
+							/>
+						</div>
+					</details>
+				)}
+
+				{/* 複数コミットのメッセージの一括検索・置換 */}
+				{filePath && (
+					<details className="rounded-lg border border-gray-200 dark:border-gray-700">
+						<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
+							メッセージを検索・置換
+						</summary>
+						<div className="border-t border-gray-200 p-4 dark:border-gray-700">
+							<MessageReplacePanel
+								entries={entries}
+								replacements={messageReplacements}
+								onPreview={(entryIds, spec) =>
+									previewMessageReplace(filePath, entryIds, spec)
+								}
+								onApply={applyMessageReplace}
+								onCancel={clearMessageReplacements}
+							/>
+						</div>
+					</details>
+				)}
+
+				{/* 並び順・コマンド・メッセージの保存と再利用 */}
+				{filePath && (
+					<details className="rounded-lg border border-gray-200 dark:border-gray-700">
+						<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
+							rebase の計画
+						</summary>
+						<div className="border-t border-gray-200 p-4 dark:border-gray-700">
+							<RebasePlanPanel
+								entries={entries}
+								plan={savedPlan}
+								savedPath={savedPlanPath}
+								report={planReport}
+								onSave={() => savePlan(filePath)}
+								onApply={() => applySavedPlan(filePath)}
+							/>
+						</div>
+					</details>
+				)}
+
+				{/* 選択中コミットの author・日時・sign-off の書き換え */}
+				{selectedEntry && isSquashableEntry(selectedEntry) && (
+					<details className="rounded-lg border border-gray-200 dark:border-gray-700">
+						<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
+							author・日時・sign-off を書き換え
+						</summary>
+						<div className="border-t border-gray-200 p-4 dark:border-gray-700">
+							<MetadataEditForm
+								key={selectedEntry.id}
+								edit={selectedEntry.metadata_edit}
+								onApply={(edit) => setMetadataEdit(selectedEntry.id, edit)}
+								onApplyToAll={applyMetadataEditToAll}
+							/>
+						</div>
+					</details>
+				)}
+
+				{/* todo 全体の検証結果 */}
+				{todoDiagnostics.length > 0 && (
+					<ul className="space-y-1 rounded-lg bg-red-50 p-3 text-sm dark:bg-red-900/20">
+						{todoDiagnostics.map((diagnostic) => (
+							<li
+								key={`${diagnostic.kind}-${diagnostic.message}`}
+								className={
+									diagnostic.severity === "error"
+										? "text-red-700 dark:text-red-300"
+										: "text-amber-700 dark:text-amber-300"
+								}
+							>
+								{diagnostic.message}
+							</li>
+						))}
+					</ul>
+				)}
+
 				{/* エントリ一覧 */}
 				<div className="flex-1 overflow-auto">
 					<RebaseEntryList
@@ -236,6 +518,11 @@ export function RebaseEditor() {
 						onSelectEntry={selectEntry}
 						onReorder={moveEntry}
 						onCommandChange={handleCommandChange}
+						simulatedSteps={simulatedSteps}
+						commitMetadata={commitMetadata}
+						upstreamMatches={upstreamMatches}
+						brokenDependencies={brokenDependencies}
+						diagnostics={entryDiagnostics}
 					/>
 				</div>
 
@@ -245,7 +532,17 @@ export function RebaseEditor() {
 						<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
 							コメント ({comments.length} 行)
 						</summary>
-						<div className="border-t border-gray-200 bg-gray-50 p-4 dark:border-gray-700 dark:bg-gray-800/50">
+						<div className="space-y-2 border-t border-gray-200 bg-gray-50 p-4 dark:border-gray-700 dark:bg-gray-800/50">
+							{filePath && header && header.help_lines > 0 && (
+								<button
+									type="button"
+									onClick={() => regenerateHelp(filePath)}
+									title="末尾の Git の説明を、現在の core.commentChar で作り直します"
+									className="rounded-md bg-gray-100 px-3 py-1 text-sm font-medium text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+								>
+									説明を作り直す
+								</button>
+							)}
 							<pre className="font-mono text-xs whitespace-pre-wrap text-gray-500 dark:text-gray-500">
 								{comments.join("\n")}
 							</pre>
@@ -146,7 +146,7 @@ describe("RebaseEntryItem", () => {
 		const mergeValue = {
 			commit: "abc123",
 			edit_message: false,
-			label: "feature",
+			labels: ["feature"],
 			message: null,
 		};
 		const entry = makeEntry({
@@ -161,6 +161,147 @@ describe("RebaseEntryItem", () => {
 		expect(screen.getByText(JSON.stringify(mergeValue))).toBeInTheDocument();
 	});
 
+	it("解釈できないコマンド（opaque）は元の行を読み取り専用で表示する", () => {
+		const entry = makeEntry({
+			command: { type: "opaque", value: "frobnicate abc1234" },
+			commit_hash: "",
+			message: "",
+		});
+		render(<RebaseEntryItem entry={entry} {...defaultProps} />);
+
+		// コマンドセレクタではなくスパンで表示される
+		const cmdSpan = screen.getByText("opaque");
+		expect(cmdSpan.tagName).toBe("SPAN");
+		expect(cmdSpan).toHaveAttribute(
+			"title",
+			"解釈できないコマンドのため、元の行のまま保存します",
+		);
+
+		// 元の行が表示される
+		expect(screen.getByText("frobnicate abc1234")).toBeInTheDocument();
+	});
+
+	it("競合が予測されたエントリにファイル名付きのバッジを表示する", () => {
+		const entry = makeEntry();
+		render(
+			<RebaseEntryItem
+				entry={entry}
+				{...defaultProps}
+				simulatedStep={{
+					entry_id: entry.id,
+					status: "conflict",
+					conflicts: ["src/a.rs", "b.txt"],
+					after_conflict: false,
+				}}
+			/>,
+		);
+
+		expect(screen.getByText("競合")).toHaveAttribute(
+			"title",
+			"競合が予測されるファイル: src/a.rs, b.txt",
+		);
+	});
+
+	it("満たされなくなった依存先をバッジで示す", () => {
+		render(
+			<RebaseEntryItem
+				entry={makeEntry()}
+				{...defaultProps}
+				brokenDependencies={["0a1b2c3 Add parser"]}
+			/>,
+		);
+
+		expect(screen.getByText("依存").getAttribute("title")).toContain(
+			"0a1b2c3 Add parser",
+		);
+	});
+
+	it("todo 検証の問題を重大度ごとの色で表示する", () => {
+		render(
+			<RebaseEntryItem
+				entry={makeEntry({ command: { type: "fixup" } })}
+				{...defaultProps}
+				diagnostics={[
+					{
+						entry_id: "entry-1",
+						severity: "error",
+						kind: "fixup_without_commit",
+						message: "'fixup' must follow a commit",
+					},
+				]}
+			/>,
+		);
+
+		expect(screen.getByText("'fixup' must follow a commit")).toHaveClass(
+			"text-red-600",
+		);
+	});
+
+	it("author などを書き換えるエントリにバッジを表示する", () => {
+		const entry = makeEntry({
+			metadata_edit: { author: "A U Thor <a@example.com>", signoff: true },
+		});
+		render(<RebaseEntryItem entry={entry} {...defaultProps} />);
+
+		expect(screen.getByText("書き換え")).toHaveAttribute(
+			"title",
+			"Author: A U Thor <a@example.com>\nSigned-off-by を追加",
+		);
+	});
+
+	it("メタデータがあれば作者と変更量を表示する", () => {
+		const entry = makeEntry();
+		render(
+			<RebaseEntryItem
+				entry={entry}
+				{...defaultProps}
+				metadata={{
+					hash: entry.commit_hash,
+					authorName: "Alice",
+					authorEmail: "alice@example.com",
+					authorDate: "2024-01-02T03:04:05+09:00",
+					committerDate: "2024-01-03T00:00:00+00:00",
+					message: entry.message,
+					parentCount: 1,
+					signature: "good",
+					insertions: 12,
+					deletions: 4,
+					filesChanged: 2,
+				}}
+			/>,
+		);
+
+		expect(screen.getByText("+12")).toBeInTheDocument();
+		expect(screen.getByText("-4")).toBeInTheDocument();
+		expect(screen.getByText(/Alice/)).toHaveAttribute(
+			"title",
+			expect.stringContaining("alice@example.com"),
+		);
+	});
+
+	it("取り込み済みのコミットは drop を提案する", async () => {
+		const user = userEvent.setup();
+		const onCommandChange = vi.fn();
+		const entry = makeEntry();
+		render(
+			<RebaseEntryItem
+				entry={entry}
+				{...defaultProps}
+				onCommandChange={onCommandChange}
+				upstreamMatch={{
+					entry_id: entry.id,
+					commit_hash: entry.commit_hash,
+					status: "already_applied",
+					upstream_commit: "def5678abc",
+				}}
+			/>,
+		);
+
+		await user.click(screen.getByRole("button", { name: "取り込み済み" }));
+
+		expect(onCommandChange).toHaveBeenCalledWith({ type: "drop" });
+	});
+
 	it("squashTarget が渡された場合の統合先表示", () => {
 		const entry = makeEntry({ command: { type: "squash" } });
 		const target = makeEntry({
@@ -3,13 +3,40 @@ import { CSS } from "@dnd-kit/utilities";
 import { ArrowUpIcon, Bars3Icon, XMarkIcon } from "@heroicons/react/24/outline";
 import { type KeyboardEvent, useCallback, useEffect, useRef } from "react";
 import type {
+	CommitMetadata,
+	MetadataEdit,
 	RebaseCommandType,
+	RebaseDiagnostic,
 	RebaseEntry,
 	SimpleCommand,
+	SimulatedStep,
+	UpstreamMatch,
 } from "../../types/git";
 import { SIMPLE_COMMANDS } from "../../types/git";
 import { CommandSelector } from "./CommandSelector";
 
+/** メタデータのツールチップ文字列を組み立てる。 */
+function getMetadataTitle(metadata: CommitMetadata): string {
+	return [
+		`${metadata.authorName} <${metadata.authorEmail}>`,
+		`Author date: ${metadata.authorDate}`,
+		`Commit date: ${metadata.committerDate}`,
+		`Signature: ${metadata.signature}`,
+		`${metadata.filesChanged} files, +${metadata.insertions} -${metadata.deletions}`,
+	].join("\n");
+}
+
+/** 書き換え内容のツールチップ文字列を組み立てる。 */
+function getMetadataEditTitle(edit: MetadataEdit): string {
+	return [
+		edit.author && `Author: ${edit.author}`,
+		edit.date && `Date: ${edit.date}`,
+		edit.signoff && "Signed-off-by を追加",
+	]
+		.filter(Boolean)
+		.join("\n");
+}
+
 /** subject 行だけを取り出す（先頭 # を除いた最初の行）。 */
 function getSubject(message: string): string {
 	const firstLine = message.split("\n")[0];
@@ -25,6 +52,16 @@ interface RebaseEntryItemProps {
 	canSquashOrFixup?: boolean;
 	/** squash/fixup の統合先コミット。 */
 	squashTarget?: RebaseEntry | null;
+	/** 競合予測の結果。 */
+	simulatedStep?: SimulatedStep;
+	/** 作者や変更量などのコミットメタデータ。 */
+	metadata?: CommitMetadata;
+	/** onto 側に取り込み済みと判定された場合の結果。 */
+	upstreamMatch?: UpstreamMatch;
+	/** 並べ替えや drop で満たされなくなった依存先の説明。 */
+	brokenDependencies?: string[];
+	/** todo 検証でこのエントリに見つかった問題。 */
+	diagnostics?: RebaseDiagnostic[];
 	onSelect: () => void;
 	onCommandChange: (command: RebaseCommandType) => void;
 }
@@ -41,6 +78,11 @@ export function RebaseEntryItem({
 	isSelected,
 	canSquashOrFixup = true,
 	squashTarget,
+	simulatedStep,
+	metadata,
+	upstreamMatch,
+	brokenDependencies,
+	diagnostics,
 	onSelect,
 	onCommandChange,
 }: RebaseEntryItemProps) {
@@ -176,7 +218,14 @@ export function RebaseEntryItem({
 						disabledCommands={canSquashOrFixup ? [] : ["squash", "fixup"]}
 					/>
 				) : (
-					<span className="w-24 rounded-md bg-gray-500 px-3 py-1.5 text-center text-sm font-medium text-white">
+					<span
+						className="w-24 rounded-md bg-gray-500 px-3 py-1.5 text-center text-sm font-medium text-white"
+						title={
+							commandType === "opaque"
+								? "解釈できないコマンドのため、元の行のまま保存します"
+								: undefined
+						}
+					>
 						{commandType}
 					</span>
 				)}
@@ -218,6 +267,73 @@ export function RebaseEntryItem({
 					{getSubject(entry.message)}
 				</span>
 
+				{/* 作者と変更量 */}
+				{metadata && (
+					<span
+						className="shrink-0 text-xs text-gray-500 dark:text-gray-400"
+						title={getMetadataTitle(metadata)}
+					>
+						{metadata.authorName}{" "}
+						<span className="text-green-600 dark:text-green-400">
+							+{metadata.insertions}
+						</span>{" "}
+						<span className="text-red-600 dark:text-red-400">
+							-{metadata.deletions}
+						</span>
+					</span>
+				)}
+
+				{/* 取り込み済みコミットへの drop 提案 */}
+				{upstreamMatch && !isDropped && (
+					<button
+						type="button"
+						onClick={(event) => {
+							event.stopPropagation();
+							onCommandChange({ type: "drop" });
+						}}
+						className="rounded bg-amber-100 px-1.5 py-0.5 text-xs font-medium text-amber-700 hover:bg-amber-200 dark:bg-amber-900/40 dark:text-amber-300 dark:hover:bg-amber-900/60"
+						title={
+							upstreamMatch.status === "already_applied"
+								? `onto 側の ${upstreamMatch.upstream_commit?.slice(0, 7) ?? ""} と同じ変更です。クリックで drop します`
+								: "onto に適用すると変更が残りません。クリックで drop します"
+						}
+					>
+						{upstreamMatch.status === "already_applied"
+							? "取り込み済み"
+							: "空になる"}
+					</button>
+				)}
+
+				{/* 依存するコミットとの順序の崩れ */}
+				{brokenDependencies && brokenDependencies.length > 0 && (
+					<span
+						className="rounded bg-orange-100 px-1.5 py-0.5 text-xs font-medium text-orange-700 dark:bg-orange-900/40 dark:text-orange-300"
+						title={`次のコミットが変更した行に依存しています。後ろへの移動や drop で競合する可能性があります:\n${brokenDependencies.join("\n")}`}
+					>
+						依存
+					</span>
+				)}
+
+				{/* author・日時・sign-off の書き換え */}
+				{entry.metadata_edit && !isDropped && (
+					<span
+						className="rounded bg-teal-100 px-1.5 py-0.5 text-xs font-medium text-teal-700 dark:bg-teal-900/40 dark:text-teal-300"
+						title={getMetadataEditTitle(entry.metadata_edit)}
+					>
+						書き換え
+					</span>
+				)}
+
+				{/* 競合予測の表示 */}
+				{simulatedStep?.status === "conflict" && (
+					<span
+						className="rounded bg-red-100 px-1.5 py-0.5 text-xs font-medium text-red-700 dark:bg-red-900/40 dark:text-red-300"
+						title={`競合が予測されるファイル: ${(simulatedStep.conflicts ?? []).join(", ")}`}
+					>
+						競合
+					</span>
+				)}
+
 				{/* 特殊コマンド値の表示 */}
 				{isSpecialCommand && "value" in entry.command && (
 					<span className="rounded bg-gray-200 px-2 py-0.5 text-xs text-gray-600 dark:bg-gray-700 dark:text-gray-400">
@@ -241,6 +357,24 @@ export function RebaseEntryItem({
 					<span className="text-purple-400 dark:text-purple-500">に統合</span>
 				</div>
 			)}
+
+			{/* todo 検証で見つかった問題 */}
+			{diagnostics && diagnostics.length > 0 && (
+				<ul className="mt-1 space-y-0.5 pl-8 text-xs">
+					{diagnostics.map((diagnostic) => (
+						<li
+							key={`${diagnostic.kind}-${diagnostic.message}`}
+							className={
+								diagnostic.severity === "error"
+									? "text-red-600 dark:text-red-400"
+									: "text-amber-600 dark:text-amber-400"
+							}
+						>
+							{diagnostic.message}
+						</li>
+					))}
+				</ul>
+			)}
 		</div>
 	);
 }
@@ -17,7 +17,14 @@ import {
 	verticalListSortingStrategy,
 } from "@dnd-kit/sortable";
 import { useCallback } from "react";
-import type { RebaseCommandType, RebaseEntry } from "../../types/git";
+import type {
+	CommitMetadata,
+	RebaseCommandType,
+	RebaseDiagnostic,
+	RebaseEntry,
+	SimulatedStep,
+	UpstreamMatch,
+} from "../../types/git";
 import {
 	findSquashTarget,
 	hasSquashTargetBeforeIndex,
@@ -30,6 +37,13 @@ interface RebaseEntryListProps {
 	onSelectEntry: (id: string | null) => void;
 	onReorder: (fromIndex: number, toIndex: number) => void;
 	onCommandChange: (id: string, command: RebaseCommandType) => void;
+	simulatedSteps?: Record<string, SimulatedStep>;
+	commitMetadata?: Record<string, CommitMetadata>;
+	upstreamMatches?: Record<string, UpstreamMatch>;
+	/** 満たされなくなった依存先（エントリ id ごと、表示用の説明）。 */
+	brokenDependencies?: Record<string, string[]>;
+	/** todo 検証の結果（エントリ id ごと）。 */
+	diagnostics?: Record<string, RebaseDiagnostic[]>;
 }
 
 export function RebaseEntryList({
@@ -38,6 +52,11 @@ export function RebaseEntryList({
 	onSelectEntry,
 	onReorder,
 	onCommandChange,
+	simulatedSteps,
+	commitMetadata,
+	upstreamMatches,
+	brokenDependencies,
+	diagnostics,
 }: RebaseEntryListProps) {
 	const sensors = useSensors(
 		useSensor(PointerSensor, {
@@ -123,6 +142,11 @@ export function RebaseEntryList({
 							isLast={index === entries.length - 1}
 							canSquashOrFixup={hasSquashTargetBeforeIndex(entries, index)}
 							squashTarget={findSquashTarget(entries, index)}
+							simulatedStep={simulatedSteps?.[entry.id]}
+							metadata={commitMetadata?.[entry.id]}
+							upstreamMatch={upstreamMatches?.[entry.id]}
+							brokenDependencies={brokenDependencies?.[entry.id]}
+							diagnostics={diagnostics?.[entry.id]}
 							onSelect={() => onSelectEntry(entry.id)}
 							onCommandChange={(cmd) => onCommandChange(entry.id, cmd)}
 						/>
@@ -0,0 +1,87 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import type { RebaseEntry, RebasePlan } from "../../types/git";
+import { RebasePlanPanel } from "./RebasePlanPanel";
+
+const entries: RebaseEntry[] = [
+	{
+		id: "1",
+		command: { type: "reword" },
+		commit_hash: "ddd4444",
+		message: "Part 1, reworded",
+	},
+	{
+		id: "2",
+		command: { type: "pick" },
+		commit_hash: "fff6666",
+		message: "New commit",
+	},
+];
+
+const plan: RebasePlan = {
+	version: 1,
+	entries: [
+		{
+			command: { type: "reword" },
+			commit_hash: "aaa1111",
+			patch_id: "p1",
+			message: "Part 1, reworded",
+		},
+	],
+};
+
+describe("RebasePlanPanel", () => {
+	it("保存済みの計画がなければ当てはめられない", async () => {
+		const user = userEvent.setup();
+		const onSave = vi.fn();
+		render(
+			<RebasePlanPanel
+				entries={entries}
+				plan={null}
+				savedPath={null}
+				report={null}
+				onSave={onSave}
+				onApply={vi.fn()}
+			/>,
+		);
+
+		await user.click(screen.getByRole("button", { name: "計画を保存" }));
+
+		expect(onSave).toHaveBeenCalled();
+		expect(
+			screen.getByRole("button", { name: "保存済みの計画を当てはめる" }),
+		).toBeDisabled();
+	});
+
+	it("対応付けられなかったコミットを報告する", () => {
+		render(
+			<RebasePlanPanel
+				entries={entries}
+				plan={plan}
+				savedPath="/repo/.git/gui-git-editor-rebase-plan.json"
+				report={{
+					missing: [
+						{
+							command: { type: "pick" },
+							commit_hash: "eee5555",
+							message: "Gone",
+						},
+					],
+					unplanned_entry_ids: ["2"],
+					matched_by_patch_id: ["1"],
+				}}
+				onSave={vi.fn()}
+				onApply={vi.fn()}
+			/>,
+		);
+
+		expect(
+			screen.getByText(/1 件はハッシュが変わったため/),
+		).toBeInTheDocument();
+		expect(screen.getByText("見つからないコミット (1)")).toBeInTheDocument();
+		expect(screen.getByText("eee5555 Gone")).toBeInTheDocument();
+		expect(screen.getByText("計画にないコミット (1)")).toBeInTheDocument();
+		expect(screen.getByText("fff6666 New commit")).toBeInTheDocument();
+	});
+});
@@ -0,0 +1,109 @@
+import type {
+	AppliedRebasePlan,
+	RebaseEntry,
+	RebasePlan,
+} from "../../types/git";
+
+interface RebasePlanPanelProps {
+	entries: RebaseEntry[];
+	plan: RebasePlan | null;
+	savedPath: string | null;
+	report: Omit<AppliedRebasePlan, "file"> | null;
+	onSave: () => void;
+	onApply: () => void;
+}
+
+function describeCommit(commitHash: string, message: string): string {
+	return `${commitHash.slice(0, 7)} ${message.split("\n")[0]}`;
+}
+
+// 並び順・コマンド・メッセージを計画として保存し、やり直した rebase に当てはめる。
+export function RebasePlanPanel({
+	entries,
+	plan,
+	savedPath,
+	report,
+	onSave,
+	onApply,
+}: RebasePlanPanelProps) {
+	const unplanned = report
+		? entries.filter((entry) => report.unplanned_entry_ids.includes(entry.id))
+		: [];
+
+	return (
+		<div className="space-y-3 text-sm">
+			<div className="flex items-center gap-2">
+				<button
+					type="button"
+					onClick={onSave}
+					className="rounded-md bg-gray-100 px-3 py-1 font-medium text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600"
+				>
+					計画を保存
+				</button>
+				<button
+					type="button"
+					disabled={!plan}
+					onClick={onApply}
+					className="rounded-md bg-blue-600 px-3 py-1 font-medium text-white hover:bg-blue-700 disabled:opacity-50"
+				>
+					保存済みの計画を当てはめる
+				</button>
+				{plan && (
+					<span className="text-gray-500 dark:text-gray-400">
+						{plan.entries.length} エントリ
+					</span>
+				)}
+			</div>
+
+			{savedPath && (
+				<p className="font-mono text-xs text-gray-500 dark:text-gray-400">
+					{savedPath} に保存しました
+				</p>
+			)}
+
+			{report && (
+				<div className="space-y-2 rounded-md border border-gray-200 p-2 dark:border-gray-700">
+					{report.matched_by_patch_id.length > 0 && (
+						<p className="text-gray-700 dark:text-gray-300">
+							{report.matched_by_patch_id.length}{" "}
+							件はハッシュが変わったため patch-id で対応付けました
+						</p>
+					)}
+					{report.missing.length > 0 && (
+						<div>
+							<p className="font-medium text-red-700 dark:text-red-400">
+								見つからないコミット ({report.missing.length})
+							</p>
+							<ul className="font-mono text-xs text-gray-700 dark:text-gray-300">
+								{report.missing.map((entry) => (
+									<li key={entry.commit_hash}>
+										{describeCommit(entry.commit_hash ?? "", entry.message)}
+									</li>
+								))}
+							</ul>
+						</div>
+					)}
+					{unplanned.length > 0 && (
+						<div>
+							<p className="font-medium text-amber-700 dark:text-amber-400">
+								計画にないコミット ({unplanned.length})
+							</p>
+							<ul className="font-mono text-xs text-gray-700 dark:text-gray-300">
+								{unplanned.map((entry) => (
+									<li key={entry.id}>
+										{describeCommit(entry.commit_hash, entry.message)}
+									</li>
+								))}
+							</ul>
+						</div>
+					)}
+					{report.missing.length === 0 && unplanned.length === 0 && (
+						<p className="text-green-700 dark:text-green-400">
+							すべてのエントリを計画どおりに対応付けました
+						</p>
+					)}
+				</div>
+			)}
+		</div>
+	);
+}
@@ -0,0 +1,70 @@
+import { fireEvent, render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import type { RebaseEntry } from "../../types/git";
+import { SquashMessagePanel } from "./SquashMessagePanel";
+
+const entries: RebaseEntry[] = [
+	{
+		id: "1",
+		command: { type: "pick" },
+		commit_hash: "abc1234def",
+		message: "Add parser",
+	},
+	{
+		id: "2",
+		command: { type: "squash" },
+		commit_hash: "bcd2345efa",
+		message: "squash! Add parser",
+	},
+];
+
+describe("SquashMessagePanel", () => {
+	it("合成結果がなければ何も描画しない", () => {
+		const { container } = render(
+			<SquashMessagePanel
+				entries={entries}
+				messages={{}}
+				onChange={vi.fn()}
+				onApplyAsReword={vi.fn()}
+			/>,
+		);
+
+		expect(container).toBeEmptyDOMElement();
+	});
+
+	it("連鎖の先頭ごとにメッセージを編集できる", () => {
+		const onChange = vi.fn();
+		render(
+			<SquashMessagePanel
+				entries={entries}
+				messages={{ "1": "Add parser\n\nBody" }}
+				onChange={onChange}
+				onApplyAsReword={vi.fn()}
+			/>,
+		);
+
+		const textarea = screen.getByLabelText(/abc1234 Add parser/);
+		expect(textarea).toHaveValue("Add parser\n\nBody");
+		fireEvent.change(textarea, { target: { value: "Edited" } });
+
+		expect(onChange).toHaveBeenCalledWith("1", "Edited");
+	});
+
+	it("reword への置き換えを要求する", async () => {
+		const user = userEvent.setup();
+		const onApplyAsReword = vi.fn();
+		render(
+			<SquashMessagePanel
+				entries={entries}
+				messages={{ "1": "Add parser" }}
+				onChange={vi.fn()}
+				onApplyAsReword={onApplyAsReword}
+			/>,
+		);
+
+		await user.click(screen.getByRole("button", { name: "reword に置き換え" }));
+
+		expect(onApplyAsReword).toHaveBeenCalledWith("1");
+	});
+});
@@ -0,0 +1,63 @@
+import type { RebaseEntry } from "../../types/git";
+
+interface SquashMessagePanelProps {
+	entries: RebaseEntry[];
+	messages: Record<string, string>;
+	onChange: (rootId: string, message: string) => void;
+	onApplyAsReword: (rootId: string) => void;
+}
+
+// squash の連鎖ごとに合成したメッセージを編集する。保存時にキューへ書き出され、
+// Git が連鎖の最後で開くエディタに補完される。
+export function SquashMessagePanel({
+	entries,
+	messages,
+	onChange,
+	onApplyAsReword,
+}: SquashMessagePanelProps) {
+	const rootIds = entries
+		.map((entry) => entry.id)
+		.filter((id) => messages[id] !== undefined);
+
+	if (rootIds.length === 0) {
+		return null;
+	}
+
+	return (
+		<div className="space-y-3 rounded-lg border border-purple-200 p-3 dark:border-purple-800">
+			<p className="text-sm text-purple-700 dark:text-purple-300">
+				squash 後のメッセージ（保存すると Git が開くエディタに反映されます）
+			</p>
+			{rootIds.map((rootId) => {
+				const root = entries.find((entry) => entry.id === rootId);
+				return (
+					<div key={rootId} className="space-y-1">
+						<div className="flex items-center justify-between gap-2">
+							<label
+								htmlFor={`squash-message-${rootId}`}
+								className="truncate font-mono text-xs text-gray-600 dark:text-gray-400"
+							>
+								{root?.commit_hash.slice(0, 7)} {root?.message}
+							</label>
+							<button
+								type="button"
+								onClick={() => onApplyAsReword(rootId)}
+								title="連鎖を reword と fixup に置き換え、squash のエディタを開かずに適用します"
+								className="flex-shrink-0 rounded-md bg-purple-100 px-2 py-0.5 text-xs font-medium text-purple-700 hover:bg-purple-200 dark:bg-purple-900/30 dark:text-purple-300 dark:hover:bg-purple-900/50"
+							>
+								reword に置き換え
+							</button>
+						</div>
+						<textarea
+							id={`squash-message-${rootId}`}
+							value={messages[rootId]}
+							onChange={(e) => onChange(rootId, e.target.value)}
+							rows={6}
+							className="w-full rounded-md border border-gray-300 bg-white p-2 font-mono text-sm text-gray-800 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-200"
+						/>
+					</div>
+				);
+			})}
+		</div>
+	);
+}
@@ -0,0 +1,113 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import type { RebaseEntry, StackBranch } from "../../types/git";
+import { StackBranchPanel } from "./StackBranchPanel";
+
+const entries: RebaseEntry[] = [
+	{
+		id: "1",
+		command: { type: "pick" },
+		commit_hash: "aaa1111",
+		message: "Part 1",
+	},
+	{
+		id: "2",
+		command: { type: "fixup" },
+		commit_hash: "ccc3333",
+		message: "fixup! Part 1",
+	},
+	{
+		id: "3",
+		command: { type: "update_ref", value: "refs/heads/part1" },
+		commit_hash: "",
+		message: "",
+	},
+	{
+		id: "4",
+		command: { type: "pick" },
+		commit_hash: "bbb2222",
+		message: "Part 2",
+	},
+];
+
+function branch(overrides: Partial<StackBranch>): StackBranch {
+	return {
+		ref_name: "refs/heads/part1",
+		commit_hash: "aaa1111",
+		entry_id: "1",
+		update_ref_entry_id: null,
+		target_entry_id: null,
+		checked_out_at: null,
+		is_rebased_branch: false,
+		...overrides,
+	};
+}
+
+describe("StackBranchPanel", () => {
+	it("update-ref のあるブランチは rebase 後の位置を表示し、外すと取り除く", async () => {
+		const user = userEvent.setup();
+		const onRemove = vi.fn();
+		render(
+			<StackBranchPanel
+				entries={entries}
+				branches={[branch({})]}
+				onAdd={vi.fn()}
+				onRemove={onRemove}
+			/>,
+		);
+
+		expect(
+			screen.getByText("aaa1111 Part 1 → ccc3333 fixup! Part 1"),
+		).toBeInTheDocument();
+
+		await user.click(screen.getByRole("checkbox", { name: "part1" }));
+		expect(onRemove).toHaveBeenCalledWith("refs/heads/part1");
+	});
+
+	it("update-ref のないブランチは現在のコミットの後ろへ追加する", async () => {
+		const user = userEvent.setup();
+		const onAdd = vi.fn();
+		render(
+			<StackBranchPanel
+				entries={entries}
+				branches={[
+					branch({
+						ref_name: "refs/heads/part2",
+						commit_hash: "bbb2222",
+						entry_id: "4",
+					}),
+				]}
+				onAdd={onAdd}
+				onRemove={vi.fn()}
+			/>,
+		);
+
+		const checkbox = screen.getByRole("checkbox", { name: "part2" });
+		expect(checkbox).not.toBeChecked();
+		await user.click(checkbox);
+		expect(onAdd).toHaveBeenCalledWith("4", "refs/heads/part2");
+	});
+
+	it("別のワークツリーでチェックアウト中のブランチは追加できない", () => {
+		render(
+			<StackBranchPanel
+				entries={entries}
+				branches={[
+					branch({
+						ref_name: "refs/heads/part2",
+						entry_id: "4",
+						checked_out_at: "/work/part2",
+					}),
+				]}
+				onAdd={vi.fn()}
+				onRemove={vi.fn()}
+			/>,
+		);
+
+		expect(screen.getByRole("checkbox", { name: "part2" })).toBeDisabled();
+		expect(
+			screen.getByText("/work/part2 でチェックアウト中のため更新できません"),
+		).toBeInTheDocument();
+	});
+});
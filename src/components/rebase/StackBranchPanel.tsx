@@ -0,0 +1,114 @@
+import type { RebaseEntry, StackBranch } from "../../types/git";
+
+interface StackBranchPanelProps {
+	entries: RebaseEntry[];
+	branches: StackBranch[];
+	onAdd: (entryId: string, refName: string) => void;
+	onRemove: (refName: string) => void;
+}
+
+/** rebase 後にブランチを置けるエントリ。 */
+const COMMIT_COMMAND_TYPES: RebaseEntry["command"]["type"][] = [
+	"pick",
+	"reword",
+	"edit",
+	"squash",
+	"fixup",
+	"merge",
+];
+
+function shortRef(refName: string): string {
+	return refName.replace(/^refs\/heads\//, "");
+}
+
+function describeEntry(entry: RebaseEntry): string {
+	return `${entry.commit_hash.slice(0, 7)} ${entry.message.split("\n")[0]}`;
+}
+
+/**
+ * 現在の並びで、ブランチが rebase 後に指すエントリを求める。
+ * update-ref の直前でコミットを作るエントリ（rebase 中のブランチは最後のエントリ）。
+ */
+function findTarget(
+	entries: RebaseEntry[],
+	branch: StackBranch,
+): RebaseEntry | undefined {
+	const end = branch.is_rebased_branch
+		? entries.length
+		: entries.findIndex(
+				(entry) =>
+					entry.command.type === "update_ref" &&
+					entry.command.value === branch.ref_name,
+			);
+	for (let i = end - 1; i >= 0; i--) {
+		if (COMMIT_COMMAND_TYPES.includes(entries[i].command.type)) {
+			return entries[i];
+		}
+	}
+	return undefined;
+}
+
+// スタックしたブランチの update-ref を管理する。rebase 後にどのブランチがどこへ移るかを示す。
+export function StackBranchPanel({
+	entries,
+	branches,
+	onAdd,
+	onRemove,
+}: StackBranchPanelProps) {
+	return (
+		<ul className="space-y-2 text-sm">
+			{branches.map((branch) => {
+				const tracked = entries.some(
+					(entry) =>
+						entry.command.type === "update_ref" &&
+						entry.command.value === branch.ref_name,
+				);
+				const current = entries.find((entry) => entry.id === branch.entry_id);
+				const target =
+					tracked || branch.is_rebased_branch
+						? findTarget(entries, branch)
+						: undefined;
+				const canAdd =
+					!branch.is_rebased_branch &&
+					!branch.checked_out_at &&
+					current !== undefined;
+
+				return (
+					<li key={branch.ref_name} className="space-y-0.5">
+						<label className="flex items-center gap-2 text-gray-700 dark:text-gray-300">
+							<input
+								type="checkbox"
+								checked={tracked || branch.is_rebased_branch}
+								disabled={branch.is_rebased_branch || (!tracked && !canAdd)}
+								onChange={() => {
+									if (tracked) {
+										onRemove(branch.ref_name);
+									} else if (current) {
+										onAdd(current.id, branch.ref_name);
+									}
+								}}
+							/>
+							<span className="font-mono">{shortRef(branch.ref_name)}</span>
+							{branch.is_rebased_branch && (
+								<span className="rounded bg-blue-100 px-1.5 py-0.5 text-xs text-blue-700 dark:bg-blue-900/30 dark:text-blue-300">
+									rebase 中
+								</span>
+							)}
+						</label>
+						<p className="pl-6 font-mono text-xs text-gray-500 dark:text-gray-400">
+							{current ? describeEntry(current) : "新しいブランチ"}
+							{target && target.id !== current?.id && (
+								<> → {describeEntry(target)}</>
+							)}
+						</p>
+						{branch.checked_out_at && (
+							<p className="pl-6 text-xs text-orange-600 dark:text-orange-400">
+								{branch.checked_out_at} でチェックアウト中のため更新できません
+							</p>
+						)}
+					</li>
+				);
+			})}
+		</ul>
+	);
+}
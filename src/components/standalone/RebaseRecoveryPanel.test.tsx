@@ -0,0 +1,90 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+import type { RebaseSession } from "../../types/git";
+import { RebaseRecoveryPanel } from "./RebaseRecoveryPanel";
+
+const session: RebaseSession = {
+	status: "finished",
+	started_at: "2024-01-02T03:04:05+09:00",
+	finished_at: "2024-01-02T03:05:00+09:00",
+	before_commit: "0a1b2c3d4e5f",
+	onto_commit: "abc1234def5678",
+	after_commit: "9f8e7d6c5b4a",
+	head_name: "refs/heads/topic",
+	steps: 2,
+	diff: { files_changed: 3, insertions: 12, deletions: 4 },
+};
+
+describe("RebaseRecoveryPanel", () => {
+	it("rebase 前後のコミットと現在との差分を表示する", () => {
+		render(
+			<RebaseRecoveryPanel
+				sessions={[session]}
+				restored={null}
+				disabled={false}
+				onRestore={vi.fn()}
+			/>,
+		);
+
+		expect(screen.getByText("完了")).toBeInTheDocument();
+		expect(screen.getByText("topic")).toBeInTheDocument();
+		expect(screen.getByText(/0a1b2c3 →/)).toHaveTextContent("9f8e7d6");
+		expect(screen.getByText("+12")).toBeInTheDocument();
+		expect(screen.getByText("(3 ファイル)")).toBeInTheDocument();
+	});
+
+	it("戻すボタンでセッションを渡す", async () => {
+		const user = userEvent.setup();
+		const onRestore = vi.fn();
+		render(
+			<RebaseRecoveryPanel
+				sessions={[session]}
+				restored={null}
+				disabled={false}
+				onRestore={onRestore}
+			/>,
+		);
+
+		await user.click(screen.getByRole("button", { name: "rebase 前に戻す" }));
+
+		expect(onRestore).toHaveBeenCalledWith(session);
+	});
+
+	it("ブランチが分からないセッションは戻せない", () => {
+		render(
+			<RebaseRecoveryPanel
+				sessions={[{ ...session, status: "incomplete", head_name: null }]}
+				restored={null}
+				disabled={false}
+				onRestore={vi.fn()}
+			/>,
+		);
+
+		expect(
+			screen.getByRole("button", { name: "rebase 前に戻す" }),
+		).toBeDisabled();
+	});
+
+	it("戻した後はバックアップ ref を表示する", () => {
+		render(
+			<RebaseRecoveryPanel
+				sessions={[]}
+				restored={{
+					backup_ref: "refs/gui-git-editor/backup/topic/1704132300",
+					previous_commit: "9f8e7d6c5b4a",
+					restored_commit: "0a1b2c3d4e5f",
+				}}
+				disabled={false}
+				onRestore={vi.fn()}
+			/>,
+		);
+
+		expect(
+			screen.getByText("refs/gui-git-editor/backup/topic/1704132300"),
+		).toBeInTheDocument();
+		expect(
+			screen.getByText("reflog に rebase の記録がありません"),
+		).toBeInTheDocument();
+	});
+});
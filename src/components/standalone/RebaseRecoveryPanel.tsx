@@ -0,0 +1,97 @@
+import type {
+	RebaseSession,
+	RebaseSessionStatus,
+	RestoredRebase,
+} from "../../types/git";
+
+interface RebaseRecoveryPanelProps {
+	sessions: RebaseSession[];
+	restored: RestoredRebase | null;
+	disabled: boolean;
+	onRestore: (session: RebaseSession) => void;
+}
+
+const STATUS_LABELS: Record<RebaseSessionStatus, string> = {
+	finished: "完了",
+	aborted: "中止",
+	incomplete: "未完了",
+};
+
+function shortRef(refName: string | null): string {
+	return refName ? refName.replace(/^refs\/heads\//, "") : "detached HEAD";
+}
+
+// reflog から求めた過去の rebase を並べ、rebase 前の状態へ戻せるようにする。
+export function RebaseRecoveryPanel({
+	sessions,
+	restored,
+	disabled,
+	onRestore,
+}: RebaseRecoveryPanelProps) {
+	return (
+		<div className="space-y-2 text-sm">
+			{restored && (
+				<p className="text-green-700 dark:text-green-400">
+					{restored.restored_commit.slice(0, 7)} に戻しました。元の HEAD は{" "}
+					<span className="font-mono">{restored.backup_ref}</span>{" "}
+					に保存しています
+				</p>
+			)}
+			{sessions.length === 0 ? (
+				<p className="text-gray-500 dark:text-gray-400">
+					reflog に rebase の記録がありません
+				</p>
+			) : (
+				<ul className="space-y-1">
+					{sessions.map((session) => (
+						<li
+							key={`${session.started_at}-${session.before_commit}`}
+							className="flex items-center gap-3 rounded-md px-2 py-1 hover:bg-gray-50 dark:hover:bg-gray-800"
+						>
+							<span className="w-12 text-xs text-gray-500 dark:text-gray-400">
+								{STATUS_LABELS[session.status]}
+							</span>
+							<span className="font-mono">{shortRef(session.head_name)}</span>
+							<span className="font-mono text-xs text-gray-500 dark:text-gray-400">
+								{session.before_commit.slice(0, 7)} →{" "}
+								{session.after_commit?.slice(0, 7) ?? "?"}
+							</span>
+							<span className="min-w-0 flex-1 truncate text-xs text-gray-500 dark:text-gray-400">
+								{session.started_at}
+							</span>
+							{session.diff && (
+								<span
+									className="font-mono text-xs"
+									title="rebase 前と現在のブランチとの差分"
+								>
+									<span className="text-green-600 dark:text-green-400">
+										+{session.diff.insertions}
+									</span>{" "}
+									<span className="text-red-600 dark:text-red-400">
+										-{session.diff.deletions}
+									</span>{" "}
+									<span className="text-gray-500 dark:text-gray-400">
+										({session.diff.files_changed} ファイル)
+									</span>
+								</span>
+							)}
+							<button
+								type="button"
+								disabled={disabled || !session.head_name}
+								onClick={() => onRestore(session)}
+								title={
+									session.head_name
+										? "現在の HEAD をバックアップしてから、rebase 前のコミットに戻します"
+										: "ブランチが分からないセッションは戻せません"
+								}
+								className="rounded-md bg-gray-100 px-2 py-0.5 text-xs text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-200 dark:hover:bg-gray-600"
+							>
+								rebase 前に戻す
+							</button>
+						</li>
+					))}
+				</ul>
+			)}
+		</div>
+	);
+}
@@ -0,0 +1,76 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import { useStandaloneStore } from "../../stores";
+import { StandaloneRebase } from "./StandaloneRebase";
+
+const baseCommit = {
+	hash: "abc1234def5678",
+	short_hash: "abc1234",
+	subject: "Add parser",
+	author_name: "Alice",
+	author_date: "2024-01-02T03:04:05+09:00",
+};
+
+describe("StandaloneRebase", () => {
+	beforeEach(() => {
+		useStandaloneStore.getState().reset();
+		useStandaloneStore.setState({
+			loadBaseCommits: vi.fn().mockResolvedValue(true),
+			loadSessions: vi.fn().mockResolvedValue(true),
+			startRebase: vi.fn().mockResolvedValue(true),
+		});
+	});
+
+	it("入力したリポジトリのコミットを読み込む", async () => {
+		const user = userEvent.setup();
+		render(<StandaloneRebase />);
+
+		await user.type(screen.getByLabelText("リポジトリのパス"), "/repo");
+		await user.click(screen.getByRole("button", { name: "開く" }));
+
+		expect(useStandaloneStore.getState().repoPath).toBe("/repo");
+		expect(useStandaloneStore.getState().loadBaseCommits).toHaveBeenCalled();
+		expect(useStandaloneStore.getState().loadSessions).toHaveBeenCalled();
+	});
+
+	it("選んだコミットを起点に rebase を始める", async () => {
+		const user = userEvent.setup();
+		useStandaloneStore.setState({
+			repoPath: "/repo",
+			baseCommits: [baseCommit],
+		});
+		render(<StandaloneRebase />);
+
+		await user.click(screen.getByRole("button", { name: "ここから rebase" }));
+
+		expect(useStandaloneStore.getState().startRebase).toHaveBeenCalledWith(
+			"abc1234def5678",
+		);
+	});
+
+	it("実行中は開始できず、git の出力を表示する", () => {
+		useStandaloneStore.setState({
+			baseCommits: [baseCommit],
+			isRunning: true,
+			output: ["Stopped at abc1234... Add parser"],
+		});
+		render(<StandaloneRebase />);
+
+		expect(
+			screen.getByRole("button", { name: "ここから rebase" }),
+		).toBeDisabled();
+		expect(screen.getByLabelText("git の出力")).toHaveTextContent(
+			"Stopped at abc1234... Add parser",
+		);
+	});
+
+	it("停止した場合は終了コードを表示する", () => {
+		useStandaloneStore.setState({ result: { success: false, code: 1 } });
+		render(<StandaloneRebase />);
+
+		expect(
+			screen.getByText("rebase が停止しました（終了コード 1）"),
+		).toBeInTheDocument();
+	});
+});
@@ -0,0 +1,121 @@
+import { useStandaloneStore } from "../../stores";
+import { ErrorDisplay } from "../common";
+import { RebaseRecoveryPanel } from "./RebaseRecoveryPanel";
+
+// エディタとして起動されていないときの画面。
+// リポジトリのコミットから起点を選んで rebase を始める。
+export function StandaloneRebase() {
+	const {
+		repoPath,
+		baseCommits,
+		isLoadingCommits,
+		isRunning,
+		output,
+		result,
+		sessions,
+		restored,
+		error,
+		setRepoPath,
+		loadBaseCommits,
+		startRebase,
+		loadSessions,
+		restoreSession,
+		clearError,
+	} = useStandaloneStore();
+
+	return (
+		<div className="flex h-screen flex-col gap-4 bg-white p-4 dark:bg-gray-900">
+			<form
+				className="flex gap-2"
+				onSubmit={(event) => {
+					event.preventDefault();
+					loadBaseCommits();
+					loadSessions();
+				}}
+			>
+				<input
+					type="text"
+					value={repoPath}
+					onChange={(event) => setRepoPath(event.target.value)}
+					placeholder="リポジトリのパス"
+					aria-label="リポジトリのパス"
+					className="flex-1 rounded-md border border-gray-300 bg-white px-3 py-1.5 font-mono text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-100"
+				/>
+				<button
+					type="submit"
+					disabled={!repoPath || isLoadingCommits}
+					className="rounded-md bg-gray-100 px-3 py-1.5 text-sm text-gray-700 hover:bg-gray-200 disabled:opacity-50 dark:bg-gray-700 dark:text-gray-200 dark:hover:bg-gray-600"
+				>
+					開く
+				</button>
+			</form>
+
+			{error && <ErrorDisplay error={error} onDismiss={clearError} />}
+
+			{result && (
+				<p
+					className={`text-sm ${result.success ? "text-green-700 dark:text-green-400" : "text-orange-600 dark:text-orange-400"}`}
+				>
+					{result.success
+						? "rebase が完了しました"
+						: `rebase が停止しました（終了コード ${result.code ?? "不明"}）`}
+				</p>
+			)}
+
+			<ul className="flex-1 space-y-1 overflow-auto">
+				{baseCommits.map((commit) => (
+					<li
+						key={commit.hash}
+						className="flex items-center gap-3 rounded-md px-2 py-1 text-sm hover:bg-gray-50 dark:hover:bg-gray-800"
+					>
+						<span className="font-mono text-gray-500 dark:text-gray-400">
+							{commit.short_hash}
+						</span>
+						<span className="min-w-0 flex-1 truncate text-gray-900 dark:text-gray-100">
+							{commit.subject}
+						</span>
+						<span className="text-xs text-gray-500 dark:text-gray-400">
+							{commit.author_name}
+						</span>
+						<button
+							type="button"
+							disabled={isRunning}
+							onClick={() => startRebase(commit.hash)}
+							title={`${commit.short_hash} より後のコミットを rebase します`}
+							className="rounded-md bg-blue-600 px-2 py-0.5 text-xs text-white hover:bg-blue-700 disabled:opacity-50"
+						>
+							ここから rebase
+						</button>
+					</li>
+				))}
+			</ul>
+
+			{repoPath && (
+				<details className="rounded-lg border border-gray-200 dark:border-gray-700">
+					<summary className="cursor-pointer px-4 py-2 text-sm font-medium text-gray-600 hover:bg-gray-50 dark:text-gray-400 dark:hover:bg-gray-800">
+						rebase の履歴 ({sessions.length})
+					</summary>
+					<div className="border-t border-gray-200 p-4 dark:border-gray-700">
+						<RebaseRecoveryPanel
+							sessions={sessions}
+							restored={restored}
+							disabled={isRunning}
+							onRestore={restoreSession}
+						/>
+					</div>
+				</details>
+			)}
+
+			{(isRunning || output.length > 0) && (
+				<pre
+					aria-label="git の出力"
+					className="max-h-48 overflow-auto rounded-md bg-gray-100 p-2 font-mono text-xs text-gray-700 dark:bg-gray-800 dark:text-gray-300"
+				>
+					{isRunning && output.length === 0
+						? "rebase を実行中..."
+						: output.join("\n")}
+				</pre>
+			)}
+		</div>
+	);
+}
@@ -0,0 +1 @@
+export { StandaloneRebase } from "./StandaloneRebase";
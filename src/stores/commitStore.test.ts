@@ -4,7 +4,9 @@ import { useCommitStore } from "./commitStore";
 
 // IPC モジュールをモック化する。
 vi.mock("../types/ipc", () => ({
+	applyTrailerOperations: vi.fn(),
 	parseCommitMsg: vi.fn(),
+	queuedRewordMessage: vi.fn(),
 	serializeCommitMsg: vi.fn(),
 	validateCommitMsg: vi.fn(),
 }));
@@ -42,7 +44,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 5,
+					subject_width: 5,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.getState().setSubject("test subject");
@@ -56,7 +64,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 0,
+					subject_width: 0,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.setState({ originalSubject: "original" });
@@ -71,7 +85,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 8,
+					subject_width: 8,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.setState({
@@ -92,7 +112,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 0,
+					subject_width: 0,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.getState().setBody("test body");
@@ -106,7 +132,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 0,
+					subject_width: 0,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.setState({ originalBody: "original" });
@@ -181,7 +213,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 4,
+					subject_width: 4,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			useCommitStore.setState({
@@ -237,7 +275,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 10,
+					subject_width: 10,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 
@@ -248,6 +292,54 @@ describe("commitStore", () => {
 			expect(useCommitStore.getState().isLoading).toBe(false);
 		});
 
+		it("読み込んだファイルのリポジトリで検証する", async () => {
+			mockedIpc.parseCommitMsg.mockResolvedValue({
+				ok: true,
+				data: {
+					subject: "feat: test",
+					body: "",
+					trailers: [],
+					comments: [],
+					diff_content: null,
+				},
+			});
+			mockedIpc.validateCommitMsg.mockResolvedValue({
+				ok: true,
+				data: {
+					is_valid: false,
+					subject_too_long: false,
+					subject_length: 10,
+					subject_width: 10,
+					length_unit: "chars",
+					long_body_lines: [],
+					conventional: {
+						commit_type: "feat",
+						scope: null,
+						breaking: false,
+						description: "test",
+						breaking_changes: [],
+					},
+					conventional_violations: [
+						{ kind: "type_not_allowed", commit_type: "feat" },
+					],
+					diagnostics: [],
+					matches_template: false,
+				},
+			});
+
+			await useCommitStore
+				.getState()
+				.parseContent("content", "/repo/.git/COMMIT_EDITMSG");
+
+			expect(mockedIpc.validateCommitMsg).toHaveBeenLastCalledWith(
+				expect.objectContaining({ subject: "feat: test" }),
+				"/repo/.git/COMMIT_EDITMSG",
+			);
+			expect(
+				useCommitStore.getState().validation?.conventional_violations,
+			).toEqual([{ kind: "type_not_allowed", commit_type: "feat" }]);
+		});
+
 		it("should handle parse error", async () => {
 			mockedIpc.parseCommitMsg.mockResolvedValue({
 				ok: false,
@@ -261,6 +353,140 @@ describe("commitStore", () => {
 		});
 	});
 
+	describe("applyQueuedReword", () => {
+		it("キュー済みメッセージで subject/body を置き換え dirty にする", async () => {
+			useCommitStore.setState({
+				subject: "old",
+				originalSubject: "old",
+				comments: ["# comment"],
+			});
+			mockedIpc.queuedRewordMessage.mockResolvedValue({
+				ok: true,
+				data: {
+					subject: "new subject",
+					body: "new body",
+					trailers: [],
+					comments: [],
+					diff_content: null,
+				},
+			});
+			mockedIpc.validateCommitMsg.mockResolvedValue({
+				ok: true,
+				data: {
+					is_valid: true,
+					subject_too_long: false,
+					subject_length: 11,
+					subject_width: 11,
+					length_unit: "chars",
+					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
+				},
+			});
+
+			const applied = await useCommitStore
+				.getState()
+				.applyQueuedReword("/repo/.git/COMMIT_EDITMSG");
+
+			expect(applied).toBe(true);
+			expect(mockedIpc.queuedRewordMessage).toHaveBeenCalledWith(
+				"/repo/.git/COMMIT_EDITMSG",
+			);
+			const state = useCommitStore.getState();
+			expect(state.subject).toBe("new subject");
+			expect(state.body).toBe("new body");
+			expect(state.comments).toEqual(["# comment"]);
+			expect(state.isDirty).toBe(true);
+		});
+
+		it("キューがなければ何も変更しない", async () => {
+			useCommitStore.setState({ subject: "old", originalSubject: "old" });
+			mockedIpc.queuedRewordMessage.mockResolvedValue({
+				ok: true,
+				data: null,
+			});
+
+			const applied = await useCommitStore
+				.getState()
+				.applyQueuedReword("/repo/.git/COMMIT_EDITMSG");
+
+			expect(applied).toBe(false);
+			expect(useCommitStore.getState().subject).toBe("old");
+			expect(useCommitStore.getState().isDirty).toBe(false);
+		});
+	});
+
+	describe("applyTrailerOperations", () => {
+		it("適用結果を解析し直して trailer を置き換える", async () => {
+			useCommitStore.setState({
+				subject: "feat: x",
+				originalSubject: "feat: x",
+				filePath: "/repo/.git/COMMIT_EDITMSG",
+			});
+			mockedIpc.serializeCommitMsg.mockResolvedValue({
+				ok: true,
+				data: "feat: x\n",
+			});
+			mockedIpc.applyTrailerOperations.mockResolvedValue({
+				ok: true,
+				data: "feat: x\n\nRefs: #1\n",
+			});
+			mockedIpc.parseCommitMsg.mockResolvedValue({
+				ok: true,
+				data: {
+					subject: "feat: x",
+					body: "",
+					trailers: [{ key: "Refs", value: "#1" }],
+					comments: [],
+					diff_content: null,
+				},
+			});
+			mockedIpc.validateCommitMsg.mockResolvedValue({
+				ok: true,
+				data: {
+					is_valid: true,
+					subject_too_long: false,
+					subject_length: 7,
+					subject_width: 7,
+					length_unit: "chars",
+					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
+				},
+			});
+			const operations = [{ kind: "add" as const, token: "Refs", value: "#1" }];
+
+			const applied = await useCommitStore
+				.getState()
+				.applyTrailerOperations(operations);
+
+			expect(applied).toBe(true);
+			expect(mockedIpc.applyTrailerOperations).toHaveBeenCalledWith(
+				"/repo/.git/COMMIT_EDITMSG",
+				"feat: x\n",
+				operations,
+			);
+			const state = useCommitStore.getState();
+			expect(state.trailers).toEqual([{ key: "Refs", value: "#1" }]);
+			expect(state.isDirty).toBe(true);
+		});
+
+		it("filePath がなければ何もしない", async () => {
+			const applied = await useCommitStore
+				.getState()
+				.applyTrailerOperations([
+					{ kind: "remove", token: "Refs", value: null },
+				]);
+
+			expect(applied).toBe(false);
+			expect(mockedIpc.applyTrailerOperations).not.toHaveBeenCalled();
+		});
+	});
+
 	describe("serialize", () => {
 		it("should serialize successfully", async () => {
 			mockedIpc.serializeCommitMsg.mockResolvedValue({
@@ -272,6 +498,21 @@ describe("commitStore", () => {
 			expect(result).toBe("serialized content");
 		});
 
+		it("リポジトリの書式で書き出すためにファイルパスを渡す", async () => {
+			mockedIpc.serializeCommitMsg.mockResolvedValue({
+				ok: true,
+				data: "serialized content",
+			});
+			useCommitStore.setState({ filePath: "/repo/.git/COMMIT_EDITMSG" });
+
+			await useCommitStore.getState().serialize();
+
+			expect(mockedIpc.serializeCommitMsg).toHaveBeenCalledWith(
+				expect.objectContaining({ subject: "" }),
+				"/repo/.git/COMMIT_EDITMSG",
+			);
+		});
+
 		it("should return null on error", async () => {
 			mockedIpc.serializeCommitMsg.mockResolvedValue({
 				ok: false,
@@ -340,7 +581,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 5,
+					subject_width: 5,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			await p2;
@@ -349,7 +596,13 @@ describe("commitStore", () => {
 				is_valid: true,
 				subject_too_long: false,
 				subject_length: 5,
+				subject_width: 5,
+				length_unit: "chars",
 				long_body_lines: [],
+				conventional: null,
+				conventional_violations: [],
+				diagnostics: [],
+				matches_template: false,
 			});
 
 			// 1回目を後から解決（古いリクエスト → 無視されるべき）
@@ -359,11 +612,17 @@ describe("commitStore", () => {
 					is_valid: false,
 					subject_too_long: true,
 					subject_length: 100,
+					subject_width: 100,
+					length_unit: "chars",
 					long_body_lines: [
 						[1, 80],
 						[2, 90],
 						[3, 85],
 					],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			await p1;
@@ -373,7 +632,13 @@ describe("commitStore", () => {
 				is_valid: true,
 				subject_too_long: false,
 				subject_length: 5,
+				subject_width: 5,
+				length_unit: "chars",
 				long_body_lines: [],
+				conventional: null,
+				conventional_violations: [],
+				diagnostics: [],
+				matches_template: false,
 			});
 		});
 
@@ -384,7 +649,13 @@ describe("commitStore", () => {
 					is_valid: false,
 					subject_too_long: true,
 					subject_length: 80,
+					subject_width: 80,
+					length_unit: "chars",
 					long_body_lines: [[5, 100]],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 
@@ -394,7 +665,13 @@ describe("commitStore", () => {
 				is_valid: false,
 				subject_too_long: true,
 				subject_length: 80,
+				subject_width: 80,
+				length_unit: "chars",
 				long_body_lines: [[5, 100]],
+				conventional: null,
+				conventional_violations: [],
+				diagnostics: [],
+				matches_template: false,
 			});
 		});
 
@@ -424,7 +701,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 3,
+					subject_width: 3,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			// 最新の応答が反映されるのを待つ
@@ -433,7 +716,13 @@ describe("commitStore", () => {
 					is_valid: true,
 					subject_too_long: false,
 					subject_length: 3,
+					subject_width: 3,
+					length_unit: "chars",
 					long_body_lines: [],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				});
 			});
 
@@ -444,7 +733,13 @@ describe("commitStore", () => {
 					is_valid: false,
 					subject_too_long: true,
 					subject_length: 1,
+					subject_width: 1,
+					length_unit: "chars",
 					long_body_lines: [[99, 120]],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			deferreds[1].resolve({
@@ -453,7 +748,13 @@ describe("commitStore", () => {
 					is_valid: false,
 					subject_too_long: true,
 					subject_length: 2,
+					subject_width: 2,
+					length_unit: "chars",
 					long_body_lines: [[50, 90]],
+					conventional: null,
+					conventional_violations: [],
+					diagnostics: [],
+					matches_template: false,
 				},
 			});
 			// microtask を消化
@@ -464,7 +765,13 @@ describe("commitStore", () => {
 				is_valid: true,
 				subject_too_long: false,
 				subject_length: 3,
+				subject_width: 3,
+				length_unit: "chars",
 				long_body_lines: [],
+				conventional: null,
+				conventional_violations: [],
+				diagnostics: [],
+				matches_template: false,
 			});
 		});
 	});
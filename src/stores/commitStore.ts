@@ -1,6 +1,11 @@
 import { create } from "zustand";
 import type { AppError } from "../types/errors";
-import type { CommitMessage, CommitValidation, Trailer } from "../types/git";
+import type {
+	CommitMessage,
+	CommitValidation,
+	Trailer,
+	TrailerOperation,
+} from "../types/git";
 import * as ipc from "../types/ipc";
 
 interface CommitState {
@@ -17,18 +22,22 @@ interface CommitState {
 	originalSubject: string;
 	originalBody: string;
 	originalTrailers: Trailer[];
+	/** 検証で git config を読むリポジトリのファイル。 */
+	filePath: string | null;
 
 	// 派生値。
 	getMessage: () => CommitMessage;
 
 	// 操作。
-	parseContent: (content: string) => Promise<boolean>;
+	parseContent: (content: string, filePath?: string) => Promise<boolean>;
+	applyQueuedReword: (filePath: string) => Promise<boolean>;
 	serialize: () => Promise<string | null>;
 	setSubject: (subject: string) => void;
 	setBody: (body: string) => void;
 	addTrailer: (trailer: Trailer) => void;
 	removeTrailer: (index: number) => void;
 	updateTrailer: (index: number, trailer: Trailer) => void;
+	applyTrailerOperations: (operations: TrailerOperation[]) => Promise<boolean>;
 	validate: () => Promise<void>;
 	clearError: () => void;
 	reset: () => void;
@@ -65,6 +74,7 @@ const initialState = {
 	originalSubject: "",
 	originalBody: "",
 	originalTrailers: [] as Trailer[],
+	filePath: null as string | null,
 };
 
 export const useCommitStore = create<CommitState>((set, get) => {
@@ -82,10 +92,10 @@ export const useCommitStore = create<CommitState>((set, get) => {
 			diff_content: get().diffContent,
 		}),
 
-		parseContent: async (content: string) => {
-			set({ isLoading: true, error: null });
+		parseContent: async (content: string, filePath?: string) => {
+			set({ isLoading: true, error: null, filePath: filePath ?? null });
 
-			const result = await ipc.parseCommitMsg(content);
+			const result = await ipc.parseCommitMsg(content, filePath);
 
 			if (result.ok) {
 				const msg = result.data;
@@ -113,9 +123,36 @@ export const useCommitStore = create<CommitState>((set, get) => {
 			}
 		},
 
+		// reword 用にキューへ保存済みのメッセージがあれば、subject/body/trailer を置き換える。
+		// コメントと diff は Git が書いた COMMIT_EDITMSG のものを維持する。
+		applyQueuedReword: async (filePath: string) => {
+			const result = await ipc.queuedRewordMessage(filePath);
+			if (!result.ok || result.data === null) {
+				return false;
+			}
+
+			const queued = result.data;
+			set((state) => ({
+				subject: queued.subject,
+				body: queued.body,
+				trailers: queued.trailers,
+				isDirty: computeDirty({
+					...state,
+					subject: queued.subject,
+					body: queued.body,
+					trailers: queued.trailers,
+				}),
+			}));
+			await get().validate();
+			return true;
+		},
+
 		serialize: async () => {
 			const message = get().getMessage();
-			const result = await ipc.serializeCommitMsg(message);
+			const result = await ipc.serializeCommitMsg(
+				message,
+				get().filePath ?? undefined,
+			);
 
 			if (result.ok) {
 				return result.data;
@@ -165,10 +202,57 @@ export const useCommitStore = create<CommitState>((set, get) => {
 			});
 		},
 
+		// リポジトリの trailer.* 設定に従って trailer を編集し、結果を解析し直す。
+		applyTrailerOperations: async (operations: TrailerOperation[]) => {
+			const filePath = get().filePath;
+			if (filePath === null) {
+				return false;
+			}
+			const content = await get().serialize();
+			if (content === null) {
+				return false;
+			}
+
+			const applied = await ipc.applyTrailerOperations(
+				filePath,
+				content,
+				operations,
+			);
+			if (!applied.ok) {
+				set({ error: applied.error });
+				return false;
+			}
+			const parsed = await ipc.parseCommitMsg(applied.data, filePath);
+			if (!parsed.ok) {
+				set({ error: parsed.error });
+				return false;
+			}
+
+			const msg = parsed.data;
+			set((state) => ({
+				subject: msg.subject,
+				body: msg.body,
+				trailers: msg.trailers,
+				comments: msg.comments,
+				diffContent: msg.diff_content,
+				isDirty: computeDirty({
+					...state,
+					subject: msg.subject,
+					body: msg.body,
+					trailers: msg.trailers,
+				}),
+			}));
+			await get().validate();
+			return true;
+		},
+
 		validate: async () => {
 			const requestId = ++validateRequestId;
 			const message = get().getMessage();
-			const result = await ipc.validateCommitMsg(message);
+			const result = await ipc.validateCommitMsg(
+				message,
+				get().filePath ?? undefined,
+			);
 
 			// 古いリクエストの応答は無視する
 			if (requestId !== validateRequestId) return;
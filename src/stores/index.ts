@@ -5,4 +5,5 @@ export { useHistoryStore } from "./historyStore";
 export { useMergeStore } from "./mergeStore";
 export { useRebaseStore } from "./rebaseStore";
 export { useStagingStore } from "./stagingStore";
+export { useStandaloneStore } from "./standaloneStore";
 export { useThemeStore } from "./themeStore";
@@ -4,8 +4,31 @@ import { useRebaseStore } from "./rebaseStore";
 
 // IPC をモック化する
 vi.mock("../types/ipc", () => ({
+	analyzeRebaseDependencies: vi.fn(),
+	addUpdateRef: vi.fn(),
+	applyMessageReplace: vi.fn(),
+	applyRebasePlan: vi.fn(),
+	autosquashRebaseTodo: vi.fn(),
+	composeSquashMessages: vi.fn(),
+	detectRewordMode: vi.fn(),
+	detectUpstreamCommits: vi.fn(),
+	getExecTemplates: vi.fn(),
+	insertExecSteps: vi.fn(),
+	lintRebaseTodo: vi.fn(),
+	listStackBranches: vi.fn(),
+	loadRebasePlan: vi.fn(),
+	moveRebaseCommit: vi.fn(),
+	gitCommitMetadata: vi.fn(),
 	parseRebaseTodo: vi.fn(),
-	serializeRebaseTodo: vi.fn(),
+	previewMessageReplace: vi.fn(),
+	queueSquashMessages: vi.fn(),
+	regenerateTodoHelp: vi.fn(),
+	replaceSquashWithReword: vi.fn(),
+	removeExecSteps: vi.fn(),
+	removeUpdateRef: vi.fn(),
+	saveRebasePlan: vi.fn(),
+	serializeRebaseTodoLossless: vi.fn(),
+	simulateRebase: vi.fn(),
 }));
 
 function makeEntry(
@@ -257,6 +280,42 @@ describe("rebaseStore", () => {
 		});
 	});
 
+	describe("メタデータの書き換え", () => {
+		it("指定したエントリにだけ設定し、null で解除する", () => {
+			useRebaseStore.getState().setEntries([makeEntry("1"), makeEntry("2")]);
+			useRebaseStore.getState().setMetadataEdit("1", { signoff: true });
+
+			let entries = useRebaseStore.getState().entries;
+			expect(entries[0].metadata_edit).toEqual({ signoff: true });
+			expect(entries[1].metadata_edit).toBeUndefined();
+
+			useRebaseStore.getState().setMetadataEdit("1", null);
+			entries = useRebaseStore.getState().entries;
+			expect(entries[0].metadata_edit).toBeNull();
+		});
+
+		it("まとめて設定するときは squash/fixup と特殊コマンドを除く", () => {
+			useRebaseStore
+				.getState()
+				.setEntries([
+					makeEntry("1"),
+					makeEntry("2", { type: "fixup" }),
+					makeEntry("exec", { type: "exec", value: "make" }),
+					makeEntry("3", { type: "reword" }),
+					makeEntry("drop", { type: "drop" }),
+				]);
+			const edit = { author: "A U Thor <author@example.com>" };
+			useRebaseStore.getState().applyMetadataEditToAll(edit);
+
+			const edited = useRebaseStore
+				.getState()
+				.entries.filter((entry) => entry.metadata_edit)
+				.map((entry) => entry.id);
+			expect(edited).toEqual(["1", "3"]);
+			expect(useRebaseStore.getState().isDirty).toBe(true);
+		});
+	});
+
 	describe("clearError", () => {
 		it("should clear the error state", () => {
 			// 強制的にエラー状態を作る
@@ -279,6 +338,614 @@ describe("rebaseStore", () => {
 		});
 	});
 
+	describe("autosquash", () => {
+		it("移動があれば entries を置き換えて dirty にする", async () => {
+			const { autosquashRebaseTodo } = await import("../types/ipc");
+			const target = makeEntry("1", { type: "pick" }, "Add parser");
+			const other = makeEntry("2", { type: "pick" }, "Add editor");
+			const fixup = makeEntry("3", { type: "pick" }, "fixup! Add parser");
+			useRebaseStore.getState().setEntries([target, other, fixup]);
+			useRebaseStore.setState({ isDirty: false });
+
+			const moved = { ...fixup, command: { type: "fixup" as const } };
+			const move = {
+				entry_id: "3",
+				commit_hash: "abc3",
+				target_id: "1",
+				target_hash: "abc1",
+				command: { type: "fixup" as const },
+				from_index: 2,
+				to_index: 1,
+			};
+			vi.mocked(autosquashRebaseTodo).mockResolvedValue({
+				ok: true,
+				data: {
+					file: { entries: [target, moved, other], comments: [] },
+					moves: [move],
+					unmatched: [],
+				},
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.autosquash("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toEqual([move]);
+			const state = useRebaseStore.getState();
+			expect(state.entries.map((e) => e.id)).toEqual(["1", "3", "2"]);
+			expect(state.entries[1].command).toEqual({ type: "fixup" });
+			expect(state.isDirty).toBe(true);
+		});
+
+		it("移動がなければ entries を変更しない", async () => {
+			const { autosquashRebaseTodo } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ isDirty: false });
+			vi.mocked(autosquashRebaseTodo).mockResolvedValue({
+				ok: true,
+				data: {
+					file: { entries, comments: [] },
+					moves: [],
+					unmatched: [],
+				},
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.autosquash("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toEqual([]);
+			expect(useRebaseStore.getState().isDirty).toBe(false);
+		});
+	});
+
+	describe("simulate", () => {
+		it("予測結果をエントリ id ごとに保持し、並べ替えで破棄する", async () => {
+			const { simulateRebase } = await import("../types/ipc");
+			useRebaseStore.getState().setEntries([makeEntry("1"), makeEntry("2")]);
+			const step = {
+				entry_id: "1",
+				status: "conflict" as const,
+				conflicts: ["a.txt"],
+				after_conflict: false,
+			};
+			vi.mocked(simulateRebase).mockResolvedValue({
+				ok: true,
+				data: { onto: "abc0", steps: [step] },
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.simulate("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().simulatedSteps).toEqual({ "1": step });
+
+			useRebaseStore.getState().moveEntry(0, 1);
+			expect(useRebaseStore.getState().simulatedSteps).toEqual({});
+		});
+	});
+
+	describe("loadCommitMetadata", () => {
+		it("エントリ id ごとのメタデータを保持する", async () => {
+			const { gitCommitMetadata } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			const metadata = {
+				hash: "abc1",
+				authorName: "Alice",
+				authorEmail: "alice@example.com",
+				authorDate: "2024-01-02T03:04:05+09:00",
+				committerDate: "2024-01-02T03:04:05+09:00",
+				message: "commit 1",
+				parentCount: 1,
+				signature: "none" as const,
+				insertions: 3,
+				deletions: 1,
+				filesChanged: 1,
+			};
+			vi.mocked(gitCommitMetadata).mockResolvedValue({
+				ok: true,
+				data: { "1": metadata },
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.loadCommitMetadata("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(gitCommitMetadata).toHaveBeenCalledWith(
+				"/repo/.git/rebase-merge/git-rebase-todo",
+				{ entries, comments: [] },
+			);
+			expect(useRebaseStore.getState().commitMetadata).toEqual({
+				"1": metadata,
+			});
+		});
+	});
+
+	describe("detectUpstream", () => {
+		it("取り込み済みのコミットをエントリ id ごとに保持する", async () => {
+			const { detectUpstreamCommits } = await import("../types/ipc");
+			useRebaseStore.getState().setEntries([makeEntry("1"), makeEntry("2")]);
+			const match = {
+				entry_id: "2",
+				commit_hash: "abc2",
+				status: "already_applied" as const,
+				upstream_commit: "def9",
+			};
+			vi.mocked(detectUpstreamCommits).mockResolvedValue({
+				ok: true,
+				data: [match],
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.detectUpstream("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().upstreamMatches).toEqual({
+				"2": match,
+			});
+		});
+	});
+
+	describe("analyzeDependencies", () => {
+		it("読み込んだときの並びで解析し、依存のあるエントリだけ保持する", async () => {
+			const { analyzeRebaseDependencies } = await import("../types/ipc");
+			const original = [makeEntry("1"), makeEntry("2")];
+			useRebaseStore.setState({
+				originalEntries: original,
+				entries: [original[1], original[0]],
+			});
+			vi.mocked(analyzeRebaseDependencies).mockResolvedValue({
+				ok: true,
+				data: [
+					{ entry_id: "1", depends_on: [] },
+					{ entry_id: "2", depends_on: ["1"] },
+				],
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.analyzeDependencies("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(analyzeRebaseDependencies).toHaveBeenCalledWith(
+				"/repo/.git/rebase-merge/git-rebase-todo",
+				{ entries: original, comments: [] },
+			);
+			expect(useRebaseStore.getState().dependencies).toEqual({ "2": ["1"] });
+		});
+	});
+
+	describe("moveToSection", () => {
+		it("検証済みの並びで entries を置き換える", async () => {
+			const { moveRebaseCommit } = await import("../types/ipc");
+			const entries = [makeEntry("1"), makeEntry("2")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ isDirty: false });
+			vi.mocked(moveRebaseCommit).mockResolvedValue({
+				ok: true,
+				data: { entries: [entries[1], entries[0]], comments: [] },
+			});
+
+			const result = await useRebaseStore.getState().moveToSection("1", 1);
+
+			expect(result).toBe(true);
+			expect(moveRebaseCommit).toHaveBeenCalledWith(
+				{ entries, comments: [] },
+				"1",
+				1,
+			);
+			expect(useRebaseStore.getState().entries.map((e) => e.id)).toEqual([
+				"2",
+				"1",
+			]);
+			expect(useRebaseStore.getState().isDirty).toBe(true);
+		});
+
+		it("検証エラー時は entries を変更せず error を設定する", async () => {
+			const { moveRebaseCommit } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			const error = {
+				code: "CommandError" as const,
+				details: { message: "no commit to squash into" },
+			};
+			vi.mocked(moveRebaseCommit).mockResolvedValue({ ok: false, error });
+
+			const result = await useRebaseStore.getState().moveToSection("1", 0);
+
+			expect(result).toBe(false);
+			expect(useRebaseStore.getState().entries).toEqual(entries);
+			expect(useRebaseStore.getState().error).toEqual(error);
+		});
+	});
+
+	describe("insertExec", () => {
+		it("exec を挿入した並びで entries を置き換える", async () => {
+			const { insertExecSteps } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ isDirty: false });
+			const exec = makeEntry("x", { type: "exec", value: "cargo test" }, "");
+			vi.mocked(insertExecSteps).mockResolvedValue({
+				ok: true,
+				data: { entries: [entries[0], exec], comments: [] },
+			});
+
+			const result = await useRebaseStore.getState().insertExec("cargo test");
+
+			expect(result).toBe(true);
+			expect(insertExecSteps).toHaveBeenCalledWith(
+				{ entries, comments: [] },
+				"cargo test",
+			);
+			expect(useRebaseStore.getState().entries).toEqual([entries[0], exec]);
+			expect(useRebaseStore.getState().isDirty).toBe(true);
+		});
+	});
+
+	describe("removeExec", () => {
+		it("取り除く exec がなければ isDirty を変更しない", async () => {
+			const { removeExecSteps } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ isDirty: false });
+			vi.mocked(removeExecSteps).mockResolvedValue({
+				ok: true,
+				data: { entries, comments: [] },
+			});
+
+			const result = await useRebaseStore.getState().removeExec("cargo test");
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().isDirty).toBe(false);
+		});
+	});
+
+	describe("update-ref", () => {
+		it("追加した update-ref を反映し、ブランチ一覧を取り直す", async () => {
+			const { addUpdateRef, listStackBranches } = await import(
+				"../types/ipc"
+			);
+			const updated = [
+				makeEntry("1"),
+				makeEntry("2", { type: "update_ref", value: "refs/heads/part1" }, ""),
+			];
+			const branches = [
+				{
+					ref_name: "refs/heads/part1",
+					commit_hash: "abc1",
+					entry_id: "1",
+					update_ref_entry_id: "2",
+					target_entry_id: "1",
+					checked_out_at: null,
+					is_rebased_branch: false,
+				},
+			];
+			useRebaseStore.setState({ entries: [makeEntry("1")] });
+			vi.mocked(addUpdateRef).mockResolvedValue({
+				ok: true,
+				data: { entries: updated, comments: [] },
+			});
+			vi.mocked(listStackBranches).mockResolvedValue({
+				ok: true,
+				data: branches,
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.addUpdateRef(
+					"/repo/.git/rebase-merge/git-rebase-todo",
+					"1",
+					"refs/heads/part1",
+				);
+
+			expect(result).toBe(true);
+			const state = useRebaseStore.getState();
+			expect(state.entries).toEqual(updated);
+			expect(state.isDirty).toBe(true);
+			expect(state.stackBranches).toEqual(branches);
+		});
+
+		it("チェックアウト中のブランチはエラーを保持する", async () => {
+			const { addUpdateRef } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.setState({ entries });
+			vi.mocked(addUpdateRef).mockResolvedValue({
+				ok: false,
+				error: {
+					code: "CommandError",
+					details: {
+						message: "refs/heads/part1 is checked out at '/work/part1'",
+					},
+				},
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.addUpdateRef(
+					"/repo/.git/rebase-merge/git-rebase-todo",
+					"1",
+					"refs/heads/part1",
+				);
+
+			expect(result).toBe(false);
+			expect(useRebaseStore.getState().entries).toBe(entries);
+			expect(useRebaseStore.getState().error?.code).toBe("CommandError");
+		});
+	});
+
+	describe("regenerateHelp", () => {
+		it("作り直したコメントとコメント文字を反映する", async () => {
+			const { regenerateTodoHelp } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			const header = {
+				comment_char: ";",
+				range: "1a2b3c4..5e6f7a8",
+				onto: "1a2b3c4",
+				command_count: 1,
+				checked_out_refs: [],
+				help_lines: 1,
+			};
+			useRebaseStore.setState({ entries, comments: ["# Befehle:"] });
+			vi.mocked(regenerateTodoHelp).mockResolvedValue({
+				ok: true,
+				data: { entries, comments: ["; Commands:"], header },
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.regenerateHelp("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			const state = useRebaseStore.getState();
+			expect(state.comments).toEqual(["; Commands:"]);
+			expect(state.header?.comment_char).toBe(";");
+			expect(state.isDirty).toBe(true);
+		});
+	});
+
+	describe("loadExecTemplates", () => {
+		it("取得したテンプレートを保持する", async () => {
+			const { getExecTemplates } = await import("../types/ipc");
+			const templates = [
+				{ command: "cargo test", source: "git_config" as const },
+			];
+			vi.mocked(getExecTemplates).mockResolvedValue({
+				ok: true,
+				data: templates,
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.loadExecTemplates("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().execTemplates).toEqual(templates);
+		});
+	});
+
+	describe("squash メッセージ", () => {
+		it("合成したメッセージを先頭エントリ id ごとに保持する", async () => {
+			const { composeSquashMessages } = await import("../types/ipc");
+			const entries = [makeEntry("1"), makeEntry("2", { type: "squash" })];
+			useRebaseStore.getState().setEntries(entries);
+			vi.mocked(composeSquashMessages).mockResolvedValue({
+				ok: true,
+				data: [
+					{
+						root_id: "1",
+						entry_ids: ["1", "2"],
+						final_hash: "abc2",
+						message: "commit 1\n\nbody",
+					},
+				],
+			});
+
+			const result = await useRebaseStore
+				.getState()
+				.composeSquash("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().squashMessages).toEqual({
+				"1": "commit 1\n\nbody",
+			});
+		});
+
+		it("reword に置き換えると entries を更新し、合成結果を破棄する", async () => {
+			const { replaceSquashWithReword } = await import("../types/ipc");
+			const entries = [makeEntry("1"), makeEntry("2", { type: "squash" })];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ squashMessages: { "1": "Edited" } });
+			const replaced = [
+				{ ...entries[0], command: { type: "reword" as const } },
+				{ ...entries[1], command: { type: "fixup" as const } },
+			];
+			vi.mocked(replaceSquashWithReword).mockResolvedValue({
+				ok: true,
+				data: { entries: replaced, comments: [] },
+			});
+
+			const result = await useRebaseStore.getState().applySquashAsReword("1");
+
+			expect(result).toBe(true);
+			expect(replaceSquashWithReword).toHaveBeenCalledWith(
+				{ entries, comments: [] },
+				"1",
+				"Edited",
+			);
+			expect(useRebaseStore.getState().entries).toEqual(replaced);
+			expect(useRebaseStore.getState().squashMessages).toEqual({});
+		});
+
+		it("合成結果がなければキューへ保存しない", async () => {
+			const { queueSquashMessages } = await import("../types/ipc");
+			vi.mocked(queueSquashMessages).mockClear();
+
+			const result = await useRebaseStore
+				.getState()
+				.queueSquashMessages("/repo/.git/rebase-merge/git-rebase-todo");
+
+			expect(result).toBe(true);
+			expect(queueSquashMessages).not.toHaveBeenCalled();
+		});
+	});
+
+	describe("メッセージの検索・置換", () => {
+		const replacement = {
+			entry_id: "1",
+			commit_hash: "abc1",
+			old_message: "ABC-1: commit 1",
+			new_message: "XYZ-1: commit 1",
+		};
+
+		it("プレビューは entries を変更しない", async () => {
+			const { previewMessageReplace } = await import("../types/ipc");
+			const entries = [makeEntry("1"), makeEntry("2")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ isDirty: false });
+			vi.mocked(previewMessageReplace).mockResolvedValue({
+				ok: true,
+				data: [replacement],
+			});
+			const spec = { find: "ABC-", replace: "XYZ-", regex: false };
+
+			const result = await useRebaseStore
+				.getState()
+				.previewMessageReplace(
+					"/repo/.git/rebase-merge/git-rebase-todo",
+					["1"],
+					spec,
+				);
+
+			expect(result).toBe(true);
+			expect(previewMessageReplace).toHaveBeenCalledWith(
+				"/repo/.git/rebase-merge/git-rebase-todo",
+				{ entries, comments: [] },
+				["1"],
+				spec,
+			);
+			const state = useRebaseStore.getState();
+			expect(state.messageReplacements).toEqual([replacement]);
+			expect(state.entries).toEqual(entries);
+			expect(state.isDirty).toBe(false);
+		});
+
+		it("適用すると reword になった entries に置き換え、プレビューを破棄する", async () => {
+			const { applyMessageReplace } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			useRebaseStore.setState({ messageReplacements: [replacement] });
+			const replaced = [
+				{
+					...entries[0],
+					command: { type: "reword" as const },
+					message: "XYZ-1: commit 1",
+				},
+			];
+			vi.mocked(applyMessageReplace).mockResolvedValue({
+				ok: true,
+				data: { entries: replaced, comments: [] },
+			});
+
+			const result = await useRebaseStore.getState().applyMessageReplace();
+
+			expect(result).toBe(true);
+			expect(useRebaseStore.getState().entries).toEqual(replaced);
+			expect(useRebaseStore.getState().messageReplacements).toEqual([]);
+		});
+
+		it("プレビューがなければ適用しない", async () => {
+			const { applyMessageReplace } = await import("../types/ipc");
+			vi.mocked(applyMessageReplace).mockClear();
+
+			const result = await useRebaseStore.getState().applyMessageReplace();
+
+			expect(result).toBe(false);
+			expect(applyMessageReplace).not.toHaveBeenCalled();
+		});
+	});
+
+	describe("rebase 計画", () => {
+		const todoPath = "/repo/.git/rebase-merge/git-rebase-todo";
+		const plan = {
+			version: 1,
+			entries: [
+				{
+					command: { type: "reword" as const },
+					commit_hash: "abc1",
+					patch_id: "p1",
+					message: "Reworded",
+				},
+			],
+		};
+
+		it("保存すると保存先と計画を読み込む", async () => {
+			const { saveRebasePlan, loadRebasePlan } = await import("../types/ipc");
+			const entries = [makeEntry("1")];
+			useRebaseStore.getState().setEntries(entries);
+			vi.mocked(saveRebasePlan).mockResolvedValue({
+				ok: true,
+				data: "/repo/.git/gui-git-editor-rebase-plan.json",
+			});
+			vi.mocked(loadRebasePlan).mockResolvedValue({ ok: true, data: plan });
+
+			const result = await useRebaseStore.getState().savePlan(todoPath);
+
+			expect(result).toBe(true);
+			expect(saveRebasePlan).toHaveBeenCalledWith(todoPath, {
+				entries,
+				comments: [],
+			});
+			const state = useRebaseStore.getState();
+			expect(state.savedPlanPath).toBe(
+				"/repo/.git/gui-git-editor-rebase-plan.json",
+			);
+			expect(state.savedPlan).toEqual(plan);
+		});
+
+		it("当てはめると entries を置き換え、対応付けの結果を残す", async () => {
+			const { applyRebasePlan } = await import("../types/ipc");
+			useRebaseStore.getState().setEntries([makeEntry("1"), makeEntry("2")]);
+			useRebaseStore.setState({ isDirty: false, savedPlan: plan });
+			const applied = [
+				{ ...makeEntry("1"), command: { type: "reword" as const } },
+				makeEntry("2"),
+			];
+			const report = {
+				missing: [],
+				unplanned_entry_ids: ["2"],
+				matched_by_patch_id: ["1"],
+			};
+			vi.mocked(applyRebasePlan).mockResolvedValue({
+				ok: true,
+				data: { file: { entries: applied, comments: [] }, ...report },
+			});
+
+			const result = await useRebaseStore.getState().applySavedPlan(todoPath);
+
+			expect(result).toBe(true);
+			const state = useRebaseStore.getState();
+			expect(state.entries).toEqual(applied);
+			expect(state.isDirty).toBe(true);
+			expect(state.planReport).toEqual(report);
+		});
+
+		it("保存済みの計画がなければ当てはめない", async () => {
+			const { applyRebasePlan } = await import("../types/ipc");
+			vi.mocked(applyRebasePlan).mockClear();
+
+			const result = await useRebaseStore.getState().applySavedPlan(todoPath);
+
+			expect(result).toBe(false);
+			expect(applyRebasePlan).not.toHaveBeenCalled();
+		});
+	});
+
 	describe("parseContent", () => {
 		it("IPC成功時に entries, comments, isLoading, isDirty, selectedEntryId が正しく設定される", async () => {
 			const { parseRebaseTodo } = await import("../types/ipc");
@@ -340,35 +1007,210 @@ describe("rebaseStore", () => {
 		});
 	});
 
+	describe("lint", () => {
+		const filePath = "/repo/.git/rebase-merge/git-rebase-todo";
+
+		it("エラーがあれば保存不可とし、getValidationError で返す", async () => {
+			const { lintRebaseTodo } = await import("../types/ipc");
+			const entries = [
+				makeEntry("1"),
+				makeEntry("2", { type: "label", value: "" }, ""),
+			];
+			useRebaseStore.setState({ entries, comments: [] });
+			vi.mocked(lintRebaseTodo).mockResolvedValue({
+				ok: true,
+				data: [
+					{
+						entry_id: "2",
+						severity: "error",
+						kind: "undefined_label",
+						message: "Label is empty",
+					},
+				],
+			});
+
+			const canSave = await useRebaseStore.getState().lint(filePath);
+
+			expect(canSave).toBe(false);
+			expect(lintRebaseTodo).toHaveBeenCalledWith(filePath, {
+				entries,
+				comments: [],
+			});
+			expect(useRebaseStore.getState().diagnostics).toHaveLength(1);
+			expect(useRebaseStore.getState().getValidationError()).toBe(
+				"Label is empty",
+			);
+		});
+
+		it("警告だけなら保存できる", async () => {
+			const { lintRebaseTodo } = await import("../types/ipc");
+			useRebaseStore.setState({ entries: [makeEntry("1")], comments: [] });
+			vi.mocked(lintRebaseTodo).mockResolvedValue({
+				ok: true,
+				data: [
+					{
+						entry_id: null,
+						commit_hash: "abc9",
+						severity: "warning",
+						kind: "missing_commit",
+						message: "Commit abc9 'x' was removed",
+					},
+				],
+			});
+
+			const canSave = await useRebaseStore.getState().lint(filePath);
+
+			expect(canSave).toBe(true);
+			expect(useRebaseStore.getState().getValidationError()).toBeNull();
+		});
+
+		it("検証に失敗したらエラーを表示して保存しない", async () => {
+			const { lintRebaseTodo } = await import("../types/ipc");
+			useRebaseStore.setState({ entries: [makeEntry("1")], comments: [] });
+			const error = {
+				code: "CommandError",
+				details: { message: "failed to read git-rebase-todo.backup" },
+			};
+			vi.mocked(lintRebaseTodo).mockResolvedValue({
+				ok: false,
+				error: error as never,
+			});
+
+			const canSave = await useRebaseStore.getState().lint(filePath);
+
+			expect(canSave).toBe(false);
+			expect(useRebaseStore.getState().error).toEqual(error);
+		});
+	});
+
+	describe("detectRewordMode", () => {
+		const filePath = "/repo/.git/rebase-merge/git-rebase-todo";
+
+		it("core.editor がこのアプリなら native に切り替える", async () => {
+			const { detectRewordMode } = await import("../types/ipc");
+			vi.mocked(detectRewordMode).mockResolvedValue({
+				ok: true,
+				data: "native",
+			});
+
+			const ok = await useRebaseStore.getState().detectRewordMode(filePath);
+
+			expect(ok).toBe(true);
+			expect(detectRewordMode).toHaveBeenCalledWith(filePath);
+			expect(useRebaseStore.getState().rewordMode).toBe("native");
+		});
+
+		it("確認できない場合は exec_amend のままにする", async () => {
+			const { detectRewordMode } = await import("../types/ipc");
+			vi.mocked(detectRewordMode).mockResolvedValue({
+				ok: false,
+				error: { code: "CommandError", details: { message: "x" } } as never,
+			});
+
+			const ok = await useRebaseStore.getState().detectRewordMode(filePath);
+
+			expect(ok).toBe(false);
+			expect(useRebaseStore.getState().rewordMode).toBe("exec_amend");
+		});
+	});
+
 	describe("serialize", () => {
-		it("IPC成功時にシリアライズされたデータを返す", async () => {
-			const { serializeRebaseTodo } = await import("../types/ipc");
-			const entries = [makeEntry("1")];
-			const comments = ["# comment"];
-			useRebaseStore.getState().setEntries(entries);
-			useRebaseStore.setState({ comments });
+		const filePath = "/repo/.git/rebase-merge/git-rebase-todo";
 
-			vi.mocked(serializeRebaseTodo).mockResolvedValue({
+		it("解析した todo の行・空行・コメントを保ったまま出力する", async () => {
+			const { parseRebaseTodo, serializeRebaseTodoLossless } = await import(
+				"../types/ipc"
+			);
+			const entries: RebaseEntry[] = [
+				{
+					...makeEntry("1"),
+					raw_line: "p abc1 commit 1",
+					leading_lines: ["# first"],
+				},
+				makeEntry("2"),
+			];
+			const header = {
+				comment_char: "#",
+				range: null,
+				onto: null,
+				command_count: null,
+				checked_out_refs: [],
+				help_lines: 1,
+			};
+			vi.mocked(parseRebaseTodo).mockResolvedValue({
+				ok: true,
+				data: {
+					entries,
+					comments: ["# first", "# help"],
+					trailing_lines: ["", "# help"],
+					header,
+				},
+			});
+			vi.mocked(serializeRebaseTodoLossless).mockResolvedValue({
+				ok: true,
+				data: "# first\np abc1 commit 1\npick abc2 commit 2\n\n# help\n",
+			});
+
+			await useRebaseStore.getState().parseContent("ignored");
+			const result = await useRebaseStore.getState().serialize(filePath);
+
+			expect(result).toBe(
+				"# first\np abc1 commit 1\npick abc2 commit 2\n\n# help\n",
+			);
+			expect(serializeRebaseTodoLossless).toHaveBeenCalledWith(
+				filePath,
+				{
+					entries,
+					comments: ["# first", "# help"],
+					trailing_lines: ["", "# help"],
+					header,
+				},
+				"exec_amend",
+			);
+		});
+
+		it("reword は exec_amend 方式を既定とし、設定で native に切り替える", async () => {
+			const { serializeRebaseTodoLossless } = await import("../types/ipc");
+			const entries = [makeEntry("1", { type: "reword" }, "new message")];
+			useRebaseStore.getState().setEntries(entries);
+			vi.mocked(serializeRebaseTodoLossless).mockResolvedValue({
 				ok: true,
-				data: "pick abc1 commit 1\n# comment\n",
+				data: "reword abc1 new message\n",
 			});
 
-			const result = await useRebaseStore.getState().serialize();
+			await useRebaseStore.getState().serialize(filePath);
+			useRebaseStore.getState().setRewordMode("native");
+			await useRebaseStore.getState().serialize(filePath);
 
-			expect(result).toBe("pick abc1 commit 1\n# comment\n");
-			// serializeRebaseTodo に正しい引数が渡されたことを確認
-			expect(serializeRebaseTodo).toHaveBeenCalledWith({ entries, comments });
+			const file = {
+				entries,
+				comments: [],
+				trailing_lines: [],
+				header: undefined,
+			};
+			expect(serializeRebaseTodoLossless).toHaveBeenNthCalledWith(
+				1,
+				filePath,
+				file,
+				"exec_amend",
+			);
+			expect(serializeRebaseTodoLossless).toHaveBeenNthCalledWith(
+				2,
+				filePath,
+				file,
+				"native",
+			);
 		});
 
 		it("IPC失敗時に null を返し、error が設定される", async () => {
-			const { serializeRebaseTodo } = await import("../types/ipc");
+			const { serializeRebaseTodoLossless } = await import("../types/ipc");
 			const error = { message: "serialize failed", code: "SERIALIZE_ERROR" };
-			vi.mocked(serializeRebaseTodo).mockResolvedValue({
+			vi.mocked(serializeRebaseTodoLossless).mockResolvedValue({
 				ok: false,
 				error: error as never,
 			});
 
-			const result = await useRebaseStore.getState().serialize();
+			const result = await useRebaseStore.getState().serialize(filePath);
 
 			expect(result).toBeNull();
 			expect(useRebaseStore.getState().error).toEqual(error);
@@ -1,10 +1,24 @@
 import { create } from "zustand";
 import type { AppError } from "../types/errors";
 import type {
+	AppliedRebasePlan,
+	AutosquashMove,
+	CommitMetadata,
+	ExecTemplate,
+	MessageReplace,
+	MessageReplacement,
+	MetadataEdit,
 	RebaseCommandType,
 	RebaseEntry,
+	RebaseDiagnostic,
+	RebasePlan,
 	RebaseTodoFile,
+	RewordMode,
 	SimpleCommand,
+	SimulatedStep,
+	StackBranch,
+	TodoHeader,
+	UpstreamMatch,
 } from "../types/git";
 import * as ipc from "../types/ipc";
 import { getRebaseValidationError, squashAllEntries } from "../utils/rebase";
@@ -14,10 +28,42 @@ interface RebaseState {
 	entries: RebaseEntry[];
 	originalEntries: RebaseEntry[];
 	comments: string[];
+	/** 最後のエントリより後ろの空行・コメント行。ロスレス出力で書き戻す。 */
+	trailingLines: string[];
+	/** コメントから読み取った範囲・onto・コメント文字。 */
+	header: TodoHeader | null;
 	selectedEntryId: string | null;
 	isLoading: boolean;
 	error: AppError | null;
 	isDirty: boolean;
+	/**
+	 * 保存時の reword の出力方式。native はメッセージをキューで渡すため、
+	 * core.editor もこのアプリの場合だけ使える。
+	 */
+	rewordMode: RewordMode;
+	/** 直近の todo 検証の結果。エラーがあれば保存できない。 */
+	diagnostics: RebaseDiagnostic[];
+	/** 直近の競合予測。エントリ id ごとの結果で、並びやコマンドを変えると破棄する。 */
+	simulatedSteps: Record<string, SimulatedStep>;
+	/** エントリ id ごとのコミットメタデータ。 */
+	commitMetadata: Record<string, CommitMetadata>;
+	/** 行単位で依存する先行エントリ（エントリ id ごと）。元の並びで解析する。 */
+	dependencies: Record<string, string[]>;
+	/** todo のコミットを指すローカルブランチと、rebase 後に移動するブランチ。 */
+	stackBranches: StackBranch[];
+	/** onto 側に取り込み済みのコミット（エントリ id ごと）。 */
+	upstreamMatches: Record<string, UpstreamMatch>;
+	/** git config とプロジェクトファイルで定義された exec テンプレート。 */
+	execTemplates: ExecTemplate[];
+	/** squash の連鎖ごとに合成したメッセージ（先頭エントリ id ごと）。 */
+	squashMessages: Record<string, string>;
+	/** 検索・置換のプレビュー。適用するまで entries は変更しない。 */
+	messageReplacements: MessageReplacement[];
+	/** 保存済みの rebase 計画と、その保存先。 */
+	savedPlan: RebasePlan | null;
+	savedPlanPath: string | null;
+	/** 直近に計画を当てはめたときに対応付けられなかったエントリ。 */
+	planReport: Omit<AppliedRebasePlan, "file"> | null;
 
 	// 派生状態ヘルパー
 	getEntry: (id: string) => RebaseEntry | undefined;
@@ -27,7 +73,13 @@ interface RebaseState {
 
 	// 操作
 	parseContent: (content: string) => Promise<boolean>;
-	serialize: () => Promise<string | null>;
+	/** 解析元の行・空行・コメントの位置を保ったまま todo を書き出す。 */
+	serialize: (filePath: string) => Promise<string | null>;
+	setRewordMode: (mode: RewordMode) => void;
+	/** core.editor がこのアプリを指すか確認し、使える reword の方式を選ぶ。 */
+	detectRewordMode: (filePath: string) => Promise<boolean>;
+	/** todo を検証し、エラーがなく保存してよければ true を返す。 */
+	lint: (filePath: string) => Promise<boolean>;
 	setEntries: (entries: RebaseEntry[]) => void;
 	updateEntryCommand: (id: string, command: RebaseCommandType) => void;
 	updateEntryMessage: (id: string, message: string) => void;
@@ -37,20 +89,85 @@ interface RebaseState {
 	dropEntry: (id: string) => void;
 	undropEntry: (id: string) => void;
 	squashAll: () => void;
+	/** エントリの author・日時・sign-off の書き換えを設定する。null で解除する。 */
+	setMetadataEdit: (id: string, edit: MetadataEdit | null) => void;
+	/** 書き換えを、コミットを作るすべてのエントリへまとめて設定する。 */
+	applyMetadataEditToAll: (edit: MetadataEdit) => void;
+	autosquash: (filePath: string) => Promise<AutosquashMove[] | null>;
+	simulate: (filePath: string) => Promise<boolean>;
+	loadCommitMetadata: (filePath: string) => Promise<boolean>;
+	detectUpstream: (filePath: string) => Promise<boolean>;
+	analyzeDependencies: (filePath: string) => Promise<boolean>;
+	loadStackBranches: (filePath: string) => Promise<boolean>;
+	/** エントリのコミットの後ろに update-ref を追加し、ブランチ一覧を取り直す。 */
+	addUpdateRef: (
+		filePath: string,
+		entryId: string,
+		refName: string,
+	) => Promise<boolean>;
+	removeUpdateRef: (filePath: string, refName: string) => Promise<boolean>;
+	moveToSection: (entryId: string, targetSection: number) => Promise<boolean>;
+	insertExec: (command: string) => Promise<boolean>;
+	removeExec: (command: string) => Promise<boolean>;
+	/** 末尾の Git の説明を core.commentChar で作り直す。 */
+	regenerateHelp: (filePath: string) => Promise<boolean>;
+	loadExecTemplates: (filePath: string) => Promise<boolean>;
+	composeSquash: (filePath: string) => Promise<boolean>;
+	setSquashMessage: (rootId: string, message: string) => void;
+	applySquashAsReword: (rootId: string) => Promise<boolean>;
+	queueSquashMessages: (filePath: string) => Promise<boolean>;
+	previewMessageReplace: (
+		filePath: string,
+		entryIds: string[],
+		spec: MessageReplace,
+	) => Promise<boolean>;
+	applyMessageReplace: () => Promise<boolean>;
+	clearMessageReplacements: () => void;
+	loadSavedPlan: (filePath: string) => Promise<boolean>;
+	/** 現在の todo を計画として保存する。 */
+	savePlan: (filePath: string) => Promise<boolean>;
+	/** 保存済みの計画を todo に当てはめ、対応付けの結果を planReport に残す。 */
+	applySavedPlan: (filePath: string) => Promise<boolean>;
 	clearError: () => void;
 	reset: () => void;
 }
 
+/** まとめて書き換えるときの対象。新しいコミットを作るエントリに限る。 */
+const METADATA_EDIT_COMMAND_TYPES: RebaseCommandType["type"][] = [
+	"pick",
+	"reword",
+	"edit",
+	"merge",
+];
+
 const initialState = {
 	entries: [] as RebaseEntry[],
 	originalEntries: [] as RebaseEntry[],
 	comments: [] as string[],
+	trailingLines: [] as string[],
+	header: null as TodoHeader | null,
 	selectedEntryId: null as string | null,
 	isLoading: false,
 	error: null as AppError | null,
 	isDirty: false,
+	rewordMode: "exec_amend" as RewordMode,
+	diagnostics: [] as RebaseDiagnostic[],
+	simulatedSteps: {} as Record<string, SimulatedStep>,
+	commitMetadata: {} as Record<string, CommitMetadata>,
+	dependencies: {} as Record<string, string[]>,
+	stackBranches: [] as StackBranch[],
+	upstreamMatches: {} as Record<string, UpstreamMatch>,
+	execTemplates: [] as ExecTemplate[],
+	squashMessages: {} as Record<string, string>,
+	messageReplacements: [] as MessageReplacement[],
+	savedPlan: null as RebasePlan | null,
+	savedPlanPath: null as string | null,
+	planReport: null as Omit<AppliedRebasePlan, "file"> | null,
 };
 
+// lint の非同期応答が古い結果で上書きされないよう request-ID で突き合わせる
+let lintRequestId = 0;
+
 export const useRebaseStore = create<RebaseState>((set, get) => ({
 	...initialState,
 
@@ -63,7 +180,10 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 			: undefined;
 	},
 
-	getValidationError: () => getRebaseValidationError(get().entries),
+	getValidationError: () =>
+		getRebaseValidationError(get().entries) ??
+		get().diagnostics.find((d) => d.severity === "error")?.message ??
+		null,
 
 	parseContent: async (content: string) => {
 		set({ isLoading: true, error: null });
@@ -76,6 +196,8 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 				entries: file.entries,
 				originalEntries: file.entries,
 				comments: file.comments,
+				trailingLines: file.trailing_lines ?? [],
+				header: file.header ?? null,
 				isLoading: false,
 				isDirty: false,
 				// 読み込み直後は先頭エントリを選択する
@@ -91,11 +213,20 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 		}
 	},
 
-	serialize: async () => {
-		const { entries, comments } = get();
-		const file: RebaseTodoFile = { entries, comments };
+	serialize: async (filePath: string) => {
+		const { entries, comments, trailingLines, header, rewordMode } = get();
+		const file: RebaseTodoFile = {
+			entries,
+			comments,
+			trailing_lines: trailingLines,
+			header: header ?? undefined,
+		};
 
-		const result = await ipc.serializeRebaseTodo(file);
+		const result = await ipc.serializeRebaseTodoLossless(
+			filePath,
+			file,
+			rewordMode,
+		);
 
 		if (result.ok) {
 			return result.data;
@@ -105,7 +236,38 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 		}
 	},
 
-	setEntries: (entries: RebaseEntry[]) => set({ entries, isDirty: true }),
+	setRewordMode: (mode: RewordMode) => set({ rewordMode: mode }),
+
+	// 確認できない場合は、エディタに依存しない exec_amend のままにする。
+	detectRewordMode: async (filePath: string) => {
+		const result = await ipc.detectRewordMode(filePath);
+		if (result.ok) {
+			set({ rewordMode: result.data });
+			return true;
+		}
+		return false;
+	},
+
+	// 検証自体に失敗した場合はエラーを表示し、検証されていない todo を保存しない。
+	lint: async (filePath: string) => {
+		const requestId = ++lintRequestId;
+		const { entries, comments } = get();
+		const result = await ipc.lintRebaseTodo(filePath, { entries, comments });
+
+		if (!result.ok) {
+			if (requestId === lintRequestId) {
+				set({ error: result.error });
+			}
+			return false;
+		}
+		if (requestId === lintRequestId) {
+			set({ diagnostics: result.data });
+		}
+		return !result.data.some((d) => d.severity === "error");
+	},
+
+	setEntries: (entries: RebaseEntry[]) =>
+		set({ entries, isDirty: true, simulatedSteps: {} }),
 
 	updateEntryCommand: (id: string, command: RebaseCommandType) => {
 		set((state) => ({
@@ -115,6 +277,7 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 					: entry,
 			),
 			isDirty: true,
+			simulatedSteps: {},
 		}));
 	},
 
@@ -132,7 +295,7 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 			const newEntries = [...state.entries];
 			const [removed] = newEntries.splice(fromIndex, 1);
 			newEntries.splice(toIndex, 0, removed);
-			return { entries: newEntries, isDirty: true };
+			return { entries: newEntries, isDirty: true, simulatedSteps: {} };
 		});
 	},
 
@@ -155,10 +318,420 @@ export const useRebaseStore = create<RebaseState>((set, get) => ({
 		set((state) => ({
 			entries: squashAllEntries(state.entries),
 			isDirty: true,
+			simulatedSteps: {},
+		}));
+	},
+
+	setMetadataEdit: (id: string, edit: MetadataEdit | null) => {
+		set((state) => ({
+			entries: state.entries.map((entry) =>
+				entry.id === id ? { ...entry, metadata_edit: edit } : entry,
+			),
+			isDirty: true,
 		}));
 	},
 
+	// squash/fixup は統合先のコミットを書き換えれば足りるため、連鎖の先頭にだけ設定する。
+	applyMetadataEditToAll: (edit: MetadataEdit) => {
+		set((state) => ({
+			entries: state.entries.map((entry) =>
+				METADATA_EDIT_COMMAND_TYPES.includes(entry.command.type)
+					? { ...entry, metadata_edit: edit }
+					: entry,
+			),
+			isDirty: true,
+		}));
+	},
+
+	// fixup!/squash!/amend! のコミットを対象の直後へ移し、適用した変更を返す。
+	autosquash: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.autosquashRebaseTodo(filePath, {
+			entries,
+			comments,
+		});
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return null;
+		}
+
+		const { file, moves } = result.data;
+		if (moves.length > 0) {
+			set({ entries: file.entries, isDirty: true, simulatedSteps: {} });
+		}
+		return moves;
+	},
+
+	simulate: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.simulateRebase(filePath, { entries, comments });
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({
+			simulatedSteps: Object.fromEntries(
+				result.data.steps.map((step) => [step.entry_id, step]),
+			),
+		});
+		return true;
+	},
+
+	// 一覧表示用のメタデータは補助情報のため、失敗してもエラー表示にはしない。
+	loadCommitMetadata: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.gitCommitMetadata(filePath, { entries, comments });
+
+		if (!result.ok) {
+			return false;
+		}
+
+		set({ commitMetadata: result.data });
+		return true;
+	},
+
+	// 取り込み済みの判定も drop の提案に使う補助情報のため、失敗は無視する。
+	detectUpstream: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.detectUpstreamCommits(filePath, {
+			entries,
+			comments,
+		});
+
+		if (!result.ok) {
+			return false;
+		}
+
+		set({
+			upstreamMatches: Object.fromEntries(
+				result.data.map((match) => [match.entry_id, match]),
+			),
+		});
+		return true;
+	},
+
+	// 差分は元の親を基準にしているため、並べ替え後ではなく読み込んだときの並びで解析する。
+	analyzeDependencies: async (filePath: string) => {
+		const { originalEntries, comments } = get();
+		const result = await ipc.analyzeRebaseDependencies(filePath, {
+			entries: originalEntries,
+			comments,
+		});
+
+		if (!result.ok) {
+			return false;
+		}
+
+		set({
+			dependencies: Object.fromEntries(
+				result.data
+					.filter((entry) => entry.depends_on.length > 0)
+					.map((entry) => [entry.entry_id, entry.depends_on]),
+			),
+		});
+		return true;
+	},
+
+	loadStackBranches: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.listStackBranches(filePath, {
+			entries,
+			comments,
+		});
+
+		if (!result.ok) {
+			return false;
+		}
+
+		set({ stackBranches: result.data });
+		return true;
+	},
+
+	addUpdateRef: async (filePath: string, entryId: string, refName: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.addUpdateRef(
+			filePath,
+			{ entries, comments },
+			entryId,
+			refName,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ entries: result.data.entries, isDirty: true, simulatedSteps: {} });
+		return get().loadStackBranches(filePath);
+	},
+
+	removeUpdateRef: async (filePath: string, refName: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.removeUpdateRef({ entries, comments }, refName);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ entries: result.data.entries, isDirty: true, simulatedSteps: {} });
+		return get().loadStackBranches(filePath);
+	},
+
+	// --rebase-merges の別ブランチへの移動は、ラベルとの整合をバックエンドで検証する。
+	moveToSection: async (entryId: string, targetSection: number) => {
+		const { entries, comments } = get();
+		const result = await ipc.moveRebaseCommit(
+			{ entries, comments },
+			entryId,
+			targetSection,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ entries: result.data.entries, isDirty: true, simulatedSteps: {} });
+		return true;
+	},
+
+	insertExec: async (command: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.insertExecSteps({ entries, comments }, command);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ entries: result.data.entries, isDirty: true, simulatedSteps: {} });
+		return true;
+	},
+
+	removeExec: async (command: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.removeExecSteps({ entries, comments }, command);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		if (result.data.entries.length !== entries.length) {
+			set({ entries: result.data.entries, isDirty: true, simulatedSteps: {} });
+		}
+		return true;
+	},
+
+	regenerateHelp: async (filePath: string) => {
+		const { entries, comments, trailingLines, header } = get();
+		const result = await ipc.regenerateTodoHelp(filePath, {
+			entries,
+			comments,
+			trailing_lines: trailingLines,
+			header: header ?? undefined,
+		});
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({
+			entries: result.data.entries,
+			comments: result.data.comments,
+			trailingLines: result.data.trailing_lines ?? [],
+			header: result.data.header ?? null,
+			isDirty: true,
+		});
+		return true;
+	},
+
+	loadExecTemplates: async (filePath: string) => {
+		const result = await ipc.getExecTemplates(filePath);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ execTemplates: result.data });
+		return true;
+	},
+
+	composeSquash: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.composeSquashMessages(filePath, {
+			entries,
+			comments,
+		});
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({
+			squashMessages: Object.fromEntries(
+				result.data.map((composed) => [composed.root_id, composed.message]),
+			),
+		});
+		return true;
+	},
+
+	setSquashMessage: (rootId: string, message: string) => {
+		set((state) => ({
+			squashMessages: { ...state.squashMessages, [rootId]: message },
+		}));
+	},
+
+	// 連鎖を reword + fixup に置き換えると、Git は SQUASH_MSG のエディタを開かなくなる。
+	applySquashAsReword: async (rootId: string) => {
+		const { entries, comments, squashMessages } = get();
+		const message = squashMessages[rootId];
+		if (message === undefined) return false;
+
+		const result = await ipc.replaceSquashWithReword(
+			{ entries, comments },
+			rootId,
+			message,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		const { [rootId]: _, ...rest } = squashMessages;
+		set({
+			entries: result.data.entries,
+			isDirty: true,
+			simulatedSteps: {},
+			squashMessages: rest,
+		});
+		return true;
+	},
+
+	queueSquashMessages: async (filePath: string) => {
+		const { entries, comments, squashMessages } = get();
+		if (Object.keys(squashMessages).length === 0) return true;
+
+		const result = await ipc.queueSquashMessages(
+			filePath,
+			{ entries, comments },
+			squashMessages,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+		return true;
+	},
+
 	clearError: () => set({ error: null }),
 
+	previewMessageReplace: async (
+		filePath: string,
+		entryIds: string[],
+		spec: MessageReplace,
+	) => {
+		const { entries, comments } = get();
+		const result = await ipc.previewMessageReplace(
+			filePath,
+			{ entries, comments },
+			entryIds,
+			spec,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ messageReplacements: result.data });
+		return true;
+	},
+
+	applyMessageReplace: async () => {
+		const { entries, comments, messageReplacements } = get();
+		if (messageReplacements.length === 0) return false;
+
+		const result = await ipc.applyMessageReplace(
+			{ entries, comments },
+			messageReplacements,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({
+			entries: result.data.entries,
+			isDirty: true,
+			simulatedSteps: {},
+			messageReplacements: [],
+		});
+		return true;
+	},
+
+	clearMessageReplacements: () => {
+		set({ messageReplacements: [] });
+	},
+
+	loadSavedPlan: async (filePath: string) => {
+		const result = await ipc.loadRebasePlan(filePath);
+
+		if (!result.ok) {
+			return false;
+		}
+
+		set({ savedPlan: result.data });
+		return true;
+	},
+
+	savePlan: async (filePath: string) => {
+		const { entries, comments } = get();
+		const result = await ipc.saveRebasePlan(filePath, { entries, comments });
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		set({ savedPlanPath: result.data });
+		return get().loadSavedPlan(filePath);
+	},
+
+	applySavedPlan: async (filePath: string) => {
+		const { entries, comments, savedPlan } = get();
+		if (!savedPlan) return false;
+
+		const result = await ipc.applyRebasePlan(
+			filePath,
+			{ entries, comments },
+			savedPlan,
+		);
+
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+
+		const { file, ...report } = result.data;
+		set({
+			entries: file.entries,
+			isDirty: true,
+			simulatedSteps: {},
+			planReport: report,
+		});
+		return true;
+	},
+
 	reset: () => set(initialState),
 }));
@@ -0,0 +1,181 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import type { StandaloneRebaseEvent } from "../types/git";
+import { useFileStore } from "./fileStore";
+import { useStandaloneStore } from "./standaloneStore";
+
+// IPC モジュールをモック化する
+vi.mock("../types/ipc", () => ({
+	listBaseCommits: vi.fn(),
+	startStandaloneRebase: vi.fn(),
+	finishEditorRequest: vi.fn(),
+	listRebaseSessions: vi.fn(),
+	restoreRebaseSession: vi.fn(),
+	readFile: vi.fn(),
+}));
+
+import * as ipc from "../types/ipc";
+
+const mockedIpc = vi.mocked(ipc);
+
+const todoPath = "/repo/.git/rebase-merge/git-rebase-todo";
+
+const baseCommit = {
+	hash: "abc1234def5678",
+	short_hash: "abc1234",
+	subject: "Add parser",
+	author_name: "Alice",
+	author_date: "2024-01-02T03:04:05+09:00",
+};
+
+const session = {
+	status: "finished" as const,
+	started_at: "2024-01-02T03:04:05+09:00",
+	finished_at: "2024-01-02T03:05:00+09:00",
+	before_commit: "0a1b2c3d4e5f",
+	onto_commit: "abc1234def5678",
+	after_commit: "9f8e7d6c5b4a",
+	head_name: "refs/heads/topic",
+	steps: 2,
+	diff: { files_changed: 1, insertions: 2, deletions: 0 },
+};
+
+// 開始した rebase のイベントを受け取る関数を取り出す。
+async function startWithEvents() {
+	let emit!: (event: StandaloneRebaseEvent) => void;
+	mockedIpc.startStandaloneRebase.mockImplementation(
+		async (_repoPath, _base, onEvent) => {
+			emit = onEvent;
+			return { ok: true, data: undefined };
+		},
+	);
+	await useStandaloneStore.getState().startRebase("abc1234");
+	return emit;
+}
+
+describe("standaloneStore", () => {
+	beforeEach(() => {
+		useStandaloneStore.getState().reset();
+		useFileStore.getState().reset();
+		vi.clearAllMocks();
+		useStandaloneStore.getState().setRepoPath("/repo");
+		mockedIpc.listBaseCommits.mockResolvedValue({
+			ok: true,
+			data: [baseCommit],
+		});
+		mockedIpc.listRebaseSessions.mockResolvedValue({
+			ok: true,
+			data: [session],
+		});
+		mockedIpc.readFile.mockResolvedValue({
+			ok: true,
+			data: { path: todoPath, content: "", file_type: "rebase_todo" },
+		});
+	});
+
+	it("起点の候補を読み込む", async () => {
+		const ok = await useStandaloneStore.getState().loadBaseCommits();
+
+		expect(ok).toBe(true);
+		expect(mockedIpc.listBaseCommits).toHaveBeenCalledWith("/repo");
+		expect(useStandaloneStore.getState().baseCommits).toEqual([baseCommit]);
+	});
+
+	it("エディタ要求でファイルを開き、応答すると閉じる", async () => {
+		const emit = await startWithEvents();
+		expect(useStandaloneStore.getState().isRunning).toBe(true);
+
+		emit({ type: "editor_request", path: todoPath });
+		await vi.waitFor(() => {
+			expect(useFileStore.getState().filePath).toBe(todoPath);
+		});
+		expect(useStandaloneStore.getState().editorPath).toBe(todoPath);
+
+		mockedIpc.finishEditorRequest.mockResolvedValue({
+			ok: true,
+			data: undefined,
+		});
+		const ok = await useStandaloneStore.getState().finishEditor(true);
+
+		expect(ok).toBe(true);
+		expect(mockedIpc.finishEditorRequest).toHaveBeenCalledWith(true);
+		expect(useStandaloneStore.getState().editorPath).toBeNull();
+		expect(useFileStore.getState().filePath).toBeNull();
+	});
+
+	it("出力と終了を記録し、起点の候補を読み直す", async () => {
+		const emit = await startWithEvents();
+
+		emit({ type: "output", line: "Successfully rebased and updated HEAD." });
+		emit({ type: "finished", success: true, code: 0 });
+
+		const state = useStandaloneStore.getState();
+		expect(state.output).toEqual(["Successfully rebased and updated HEAD."]);
+		expect(state.result).toEqual({ success: true, code: 0 });
+		expect(state.isRunning).toBe(false);
+		expect(mockedIpc.listBaseCommits).toHaveBeenCalledTimes(1);
+		expect(mockedIpc.listRebaseSessions).toHaveBeenCalledTimes(1);
+	});
+
+	it("rebase 前に戻し、コミットと履歴を読み直す", async () => {
+		const restored = {
+			backup_ref: "refs/gui-git-editor/backup/topic/1704132300",
+			previous_commit: "9f8e7d6c5b4a",
+			restored_commit: "0a1b2c3d4e5f",
+		};
+		mockedIpc.restoreRebaseSession.mockResolvedValue({
+			ok: true,
+			data: restored,
+		});
+
+		const ok = await useStandaloneStore.getState().restoreSession(session);
+
+		expect(ok).toBe(true);
+		expect(mockedIpc.restoreRebaseSession).toHaveBeenCalledWith(
+			"/repo",
+			"0a1b2c3d4e5f",
+			"refs/heads/topic",
+		);
+		expect(useStandaloneStore.getState().restored).toEqual(restored);
+		expect(useStandaloneStore.getState().sessions).toEqual([session]);
+		expect(mockedIpc.listBaseCommits).toHaveBeenCalled();
+	});
+
+	it("戻せなければエラーを設定する", async () => {
+		mockedIpc.restoreRebaseSession.mockResolvedValue({
+			ok: false,
+			error: {
+				code: "CommandError",
+				details: { message: "The working tree has uncommitted changes" },
+			},
+		});
+
+		const ok = await useStandaloneStore.getState().restoreSession(session);
+
+		expect(ok).toBe(false);
+		expect(useStandaloneStore.getState().restored).toBeNull();
+		expect(useStandaloneStore.getState().error).not.toBeNull();
+	});
+
+	it("開始に失敗したらエラーを設定する", async () => {
+		mockedIpc.startStandaloneRebase.mockResolvedValue({
+			ok: false,
+			error: {
+				code: "CommandError",
+				details: { message: "A rebase is already running" },
+			},
+		});
+
+		const ok = await useStandaloneStore.getState().startRebase("abc1234");
+
+		expect(ok).toBe(false);
+		expect(useStandaloneStore.getState().isRunning).toBe(false);
+		expect(useStandaloneStore.getState().error).not.toBeNull();
+	});
+
+	it("エディタ要求がなければ応答しない", async () => {
+		const ok = await useStandaloneStore.getState().finishEditor(false);
+
+		expect(ok).toBe(false);
+		expect(mockedIpc.finishEditorRequest).not.toHaveBeenCalled();
+	});
+});
@@ -0,0 +1,159 @@
+import { create } from "zustand";
+import type { AppError } from "../types/errors";
+import type {
+	BaseCommit,
+	RebaseSession,
+	RestoredRebase,
+	StandaloneRebaseEvent,
+} from "../types/git";
+import * as ipc from "../types/ipc";
+import { useFileStore } from "./fileStore";
+
+type RebaseResult = {
+	success: boolean;
+	code: number | null;
+};
+
+interface StandaloneState {
+	// 状態
+	repoPath: string;
+	baseCommits: BaseCommit[];
+	isLoadingCommits: boolean;
+	isRunning: boolean;
+	// git が編集を待っているファイル。
+	editorPath: string | null;
+	output: string[];
+	result: RebaseResult | null;
+	// reflog から求めた過去の rebase。
+	sessions: RebaseSession[];
+	restored: RestoredRebase | null;
+	error: AppError | null;
+
+	// 操作
+	setRepoPath: (repoPath: string) => void;
+	loadBaseCommits: () => Promise<boolean>;
+	startRebase: (base: string) => Promise<boolean>;
+	finishEditor: (accepted: boolean) => Promise<boolean>;
+	loadSessions: () => Promise<boolean>;
+	restoreSession: (session: RebaseSession) => Promise<boolean>;
+	clearError: () => void;
+	reset: () => void;
+}
+
+const initialState = {
+	repoPath: "",
+	baseCommits: [] as BaseCommit[],
+	isLoadingCommits: false,
+	isRunning: false,
+	editorPath: null as string | null,
+	output: [] as string[],
+	result: null as RebaseResult | null,
+	sessions: [] as RebaseSession[],
+	restored: null as RestoredRebase | null,
+	error: null as AppError | null,
+};
+
+export const useStandaloneStore = create<StandaloneState>((set, get) => ({
+	...initialState,
+
+	setRepoPath: (repoPath: string) => set({ repoPath }),
+
+	loadBaseCommits: async () => {
+		const { repoPath } = get();
+		if (!repoPath) return false;
+
+		set({ isLoadingCommits: true, error: null });
+		const result = await ipc.listBaseCommits(repoPath);
+		if (result.ok) {
+			set({ baseCommits: result.data, isLoadingCommits: false });
+			return true;
+		}
+		set({ baseCommits: [], isLoadingCommits: false, error: result.error });
+		return false;
+	},
+
+	startRebase: async (base: string) => {
+		const { repoPath, isRunning } = get();
+		if (!repoPath || isRunning) return false;
+
+		const handleEvent = (event: StandaloneRebaseEvent) => {
+			switch (event.type) {
+				case "editor_request":
+					// 既存の編集画面で開き、保存・キャンセルで finishEditor を呼ぶ。
+					set({ editorPath: event.path });
+					useFileStore.getState().loadFile(event.path);
+					break;
+				case "output":
+					set((state) => ({ output: [...state.output, event.line] }));
+					break;
+				case "finished":
+					set({
+						isRunning: false,
+						editorPath: null,
+						result: { success: event.success, code: event.code },
+					});
+					get().loadBaseCommits();
+					get().loadSessions();
+					break;
+			}
+		};
+
+		set({ isRunning: true, output: [], result: null, error: null });
+		const result = await ipc.startStandaloneRebase(repoPath, base, handleEvent);
+		if (!result.ok) {
+			set({ isRunning: false, error: result.error });
+			return false;
+		}
+		return true;
+	},
+
+	finishEditor: async (accepted: boolean) => {
+		if (get().editorPath === null) return false;
+
+		const result = await ipc.finishEditorRequest(accepted);
+		set({ editorPath: null });
+		// 次のエディタ要求まで、rebase の画面に戻す。
+		useFileStore.getState().reset();
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+		return true;
+	},
+
+	loadSessions: async () => {
+		const { repoPath } = get();
+		if (!repoPath) return false;
+
+		const result = await ipc.listRebaseSessions(repoPath);
+		if (result.ok) {
+			set({ sessions: result.data });
+			return true;
+		}
+		set({ sessions: [], error: result.error });
+		return false;
+	},
+
+	restoreSession: async (session: RebaseSession) => {
+		const { repoPath, isRunning } = get();
+		if (!repoPath || isRunning) return false;
+
+		set({ restored: null, error: null });
+		const result = await ipc.restoreRebaseSession(
+			repoPath,
+			session.before_commit,
+			session.head_name,
+		);
+		if (!result.ok) {
+			set({ error: result.error });
+			return false;
+		}
+		set({ restored: result.data });
+		await Promise.all([get().loadBaseCommits(), get().loadSessions()]);
+		return true;
+	},
+
+	clearError: () => set({ error: null }),
+
+	reset: () => set(initialState),
+}));
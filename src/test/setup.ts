@@ -4,6 +4,9 @@ import { vi } from "vitest";
 // Tauri API をモック化する。
 vi.mock("@tauri-apps/api/core", () => ({
 	invoke: vi.fn(),
+	Channel: class<T> {
+		onmessage: (message: T) => void = () => {};
+	},
 }));
 
 vi.mock("@tauri-apps/plugin-cli", () => ({
@@ -34,10 +34,13 @@ export type RebaseCommandType =
 			value: {
 				commit: string | null;
 				edit_message: boolean;
-				label: string;
+				// オクトパスマージでは複数になる。
+				labels: string[];
 				message: string | null;
 			};
-	  };
+	  }
+	// エディタが解釈できないコマンド。元の行を読み取り専用で保持する。
+	| { type: "opaque"; value: string };
 
 // Rebase エントリ。
 export interface RebaseEntry {
@@ -46,12 +49,294 @@ export interface RebaseEntry {
 	fixup_option?: "-C" | "-c" | null;
 	commit_hash: string;
 	message: string;
+	// 解析元の行。ロスレス出力で未変更エントリをそのまま書き戻すために使う。
+	raw_line?: string | null;
+	// エントリ直前の空行・コメント行。
+	leading_lines?: string[];
+	// 適用後に書き換える author・日付・sign-off。
+	metadata_edit?: MetadataEdit | null;
+}
+
+// rebase エントリのコミットに対するメタデータの書き換え。
+export interface MetadataEdit {
+	// `Name <email>` 形式。
+	author?: string | null;
+	date?: string | null;
+	signoff?: boolean;
+}
+
+// --update-refs で、別のワークツリーにチェックアウト中のため更新されないブランチ。
+export interface CheckedOutRef {
+	ref_name: string;
+	worktree: string;
+}
+
+// todo のコメントから読み取った rebase の情報。
+export interface TodoHeader {
+	// コメント行の先頭の文字（core.commentChar）。
+	comment_char: string;
+	// `Rebase <range> onto <onto>` の範囲。
+	range: string | null;
+	onto: string | null;
+	command_count: number | null;
+	checked_out_refs: CheckedOutRef[];
+	// 末尾にある、Git が生成した説明の行数（作り直せる）。
+	help_lines: number;
 }
 
 // Rebase todo ファイル。
 export interface RebaseTodoFile {
 	entries: RebaseEntry[];
 	comments: string[];
+	// 最後のエントリより後ろの空行・コメント行。
+	trailing_lines?: string[];
+	header?: TodoHeader;
+}
+
+// autosquash で移動またはコマンドを変更したエントリ。
+export interface AutosquashMove {
+	entry_id: string;
+	commit_hash: string;
+	target_id: string;
+	target_hash: string;
+	command: RebaseCommandType;
+	fixup_option?: string;
+	from_index: number;
+	to_index: number;
+}
+
+// autosquash を適用した todo と変更内容。
+export interface AutosquashPlan {
+	file: RebaseTodoFile;
+	moves: AutosquashMove[];
+	unmatched: string[];
+}
+
+// rebase シミュレーションでのエントリごとの予測結果。
+export type SimulationStatus = "clean" | "conflict" | "skipped";
+
+export interface SimulatedStep {
+	entry_id: string;
+	status: SimulationStatus;
+	conflicts?: string[];
+	after_conflict: boolean;
+}
+
+export interface RebaseSimulation {
+	onto: string;
+	steps: SimulatedStep[];
+}
+
+// onto 側に取り込み済みのコミットの判定結果。
+export type UpstreamStatus = "already_applied" | "becomes_empty";
+
+export interface UpstreamMatch {
+	entry_id: string;
+	commit_hash: string;
+	status: UpstreamStatus;
+	upstream_commit?: string;
+}
+
+// todo のコミットが行単位で依存する、先行するエントリ。
+export interface EntryDependencies {
+	entry_id: string;
+	depends_on: string[];
+}
+
+// todo のコミットを指すローカルブランチと、rebase 後に移る先。
+export interface StackBranch {
+	ref_name: string;
+	// まだ存在しない ref なら空。
+	commit_hash: string;
+	// 現在指しているコミットのエントリ。
+	entry_id: string | null;
+	update_ref_entry_id: string | null;
+	target_entry_id: string | null;
+	// 別のワークツリーでチェックアウトされている場合、そのパス。
+	checked_out_at: string | null;
+	// rebase 中のブランチ。update-ref がなくても最後に更新される。
+	is_rebased_branch: boolean;
+}
+
+// 保存する rebase 計画の 1 エントリ。
+export interface RebasePlanEntry {
+	command: RebaseCommandType;
+	fixup_option?: "-C" | "-c" | null;
+	commit_hash?: string;
+	// ハッシュが変わったコミットの対応付けに使う patch-id。
+	patch_id?: string | null;
+	message: string;
+	metadata_edit?: MetadataEdit | null;
+}
+
+// 並び順・コマンド・メッセージの決定を別の rebase で使い回すための計画。
+export interface RebasePlan {
+	version: number;
+	entries: RebasePlanEntry[];
+}
+
+// 計画を todo に当てはめた結果。
+export interface AppliedRebasePlan {
+	file: RebaseTodoFile;
+	// 計画にあるが todo に見つからなかったエントリ。
+	missing: RebasePlanEntry[];
+	// todo にあるが計画になかったコミットのエントリ。
+	unplanned_entry_ids: string[];
+	// ハッシュではなく patch-id で対応付けたエントリ。
+	matched_by_patch_id: string[];
+}
+
+// rebase が停止している理由。
+export type StopReason = "edit" | "break" | "exec" | "conflict";
+
+// 進行中の rebase の進み具合（.git/rebase-merge の状態ファイルから読む）。
+export interface RebaseProgress {
+	step: number;
+	total: number;
+	done: RebaseEntry[];
+	remaining: RebaseEntry[];
+	current: RebaseEntry | null;
+	stopped_sha: string | null;
+	stop_reason: StopReason | null;
+	onto: string;
+	orig_head: string;
+	head_name: string | null;
+}
+
+// スタンドアロンで rebase を始めるときの起点の候補。
+export interface BaseCommit {
+	hash: string;
+	short_hash: string;
+	subject: string;
+	author_name: string;
+	// ISO 8601 形式の author date。
+	author_date: string;
+}
+
+// スタンドアロンで開始した rebase から届くイベント。
+export type StandaloneRebaseEvent =
+	// git がエディタでファイルを開こうとしている。
+	| { type: "editor_request"; path: string }
+	// git の標準出力・標準エラーの 1 行。
+	| { type: "output"; line: string }
+	// git rebase が終了した。edit や競合で停止した場合も含む。
+	| { type: "finished"; success: boolean; code: number | null };
+
+// reflog から求めた rebase の結末。incomplete は finish も abort も記録されていない。
+export type RebaseSessionStatus = "finished" | "aborted" | "incomplete";
+
+// rebase 前のコミットと現在の状態との差分の規模。
+export interface DiffSummary {
+	files_changed: number;
+	insertions: number;
+	deletions: number;
+}
+
+// HEAD の reflog の rebase (start) から finish / abort までのまとまり。
+export interface RebaseSession {
+	status: RebaseSessionStatus;
+	started_at: string;
+	finished_at: string | null;
+	// rebase を始める前にブランチが指していたコミット。
+	before_commit: string;
+	onto_commit: string;
+	after_commit: string | null;
+	// rebase したブランチ（refs/heads/...）。
+	head_name: string | null;
+	steps: number;
+	diff: DiffSummary | null;
+}
+
+// rebase 前の状態へ戻した結果。
+export interface RestoredRebase {
+	// 戻す前の HEAD を保存した ref。
+	backup_ref: string;
+	previous_commit: string;
+	restored_commit: string;
+}
+
+// merge が取り込むラベルと、それを定義したセクション。
+export interface MergeLink {
+	entry_id: string;
+	label: string;
+	source_section: number | null;
+}
+
+// --rebase-merges の todo における reset から次の reset までのまとまり。
+export interface RebaseSection {
+	index: number;
+	base: string | null;
+	base_section: number | null;
+	entries: RebaseEntry[];
+	labels: string[];
+	merges: MergeLink[];
+	update_refs: string[];
+}
+
+export interface RebaseGraph {
+	sections: RebaseSection[];
+	comments: string[];
+	trailing_lines?: string[];
+}
+
+// squash を含む連鎖と、先に合成したコミットメッセージ。
+export interface ComposedSquash {
+	root_id: string;
+	entry_ids: string[];
+	final_hash: string;
+	message: string;
+}
+
+// コミットメッセージに対する検索・置換。
+export interface MessageReplace {
+	find: string;
+	replace: string;
+	// find を正規表現として扱う。replace では $1 などで参照できる。
+	regex: boolean;
+}
+
+// 置換で変わるエントリの、変更前後のメッセージ。
+export interface MessageReplacement {
+	entry_id: string;
+	commit_hash: string;
+	old_message: string;
+	new_message: string;
+}
+
+// exec テンプレートの定義元。
+export type ExecTemplateSource = "git_config" | "project_file";
+
+// 保存済みの exec コマンド。
+export interface ExecTemplate {
+	command: string;
+	source: ExecTemplateSource;
+}
+
+// reword の出力方式。native は reword 行のまま出力し、メッセージをキューで渡す。
+export type RewordMode = "exec_amend" | "native";
+
+// todo 検証で見つかった問題の重大度。
+export type DiagnosticSeverity = "error" | "warning";
+
+// todo 検証で見つかった問題の種別。
+export type DiagnosticKind =
+	| "fixup_without_commit"
+	| "undefined_label"
+	| "duplicate_label"
+	| "duplicate_update_ref"
+	| "empty_exec"
+	| "missing_commit"
+	| "unknown_command"
+	| "unsupported_command"
+	| "invalid_metadata_edit";
+
+// todo 検証結果 1 件。削除されたコミットは entry_id が null になる。
+export interface RebaseDiagnostic {
+	entry_id: string | null;
+	commit_hash?: string;
+	severity: DiagnosticSeverity;
+	kind: DiagnosticKind;
+	message: string;
 }
 
 // Signed-off-by などの Git trailer。
@@ -69,6 +354,12 @@ export interface CommitMessage {
 	diff_content: string | null;
 }
 
+// trailer ブロックへの編集操作。remove の value が null なら同じ token をすべて削除する。
+export type TrailerOperation =
+	| { kind: "add"; token: string; value: string }
+	| { kind: "replace"; token: string; value: string }
+	| { kind: "remove"; token: string; value: string | null };
+
 // バックエンドから返るマージ用ファイル内容。
 export interface MergeFileContent {
 	path: string;
@@ -150,14 +441,90 @@ export interface CommitFileInfo {
 	status: string;
 }
 
+// %G? による署名の検証結果。
+export type SignatureStatus =
+	| "good"
+	| "bad"
+	| "untrusted"
+	| "expired"
+	| "expired_key"
+	| "revoked"
+	| "unverifiable"
+	| "none";
+
+// rebase 一覧に表示するコミットのメタデータ。
+export interface CommitMetadata {
+	hash: string;
+	authorName: string;
+	authorEmail: string;
+	authorDate: string;
+	committerDate: string;
+	message: string;
+	parentCount: number;
+	signature: SignatureStatus;
+	insertions: number;
+	deletions: number;
+	filesChanged: number;
+}
+
 // コミットメッセージ検証結果。
 export interface CommitValidation {
 	is_valid: boolean;
 	subject_too_long: boolean;
 	subject_length: number;
-	long_body_lines: [number, number][]; // [行番号, 文字数]
+	// subject の表示幅（全角を 2 桁とする桁数）。
+	subject_width: number;
+	// subject_too_long と long_body_lines の長さの数え方。
+	length_unit: LengthUnit;
+	long_body_lines: [number, number][]; // [行番号, 長さ]
+	// subject を Conventional Commits として解析した結果。形式が違えば null。
+	conventional: ConventionalCommit | null;
+	conventional_violations: ConventionalViolation[];
+	// リポジトリの lint ルールによる診断。
+	diagnostics: CommitLintDiagnostic[];
+	// commit.template から編集されていない（Git はコミットを中止する）。
+	matches_template: boolean;
 }
 
+// 行の長さの数え方。display_width は全角を 2 桁として数える。
+export type LengthUnit = "chars" | "display_width";
+
+// コミットメッセージの lint ルール（.gitmessage-lint.toml / .commitlintrc.json）。
+export type LintRuleName =
+	| "subject-max-length"
+	| "subject-min-length"
+	| "body-max-line-length"
+	| "subject-full-stop"
+	| "subject-case"
+	| "body-leading-blank"
+	| "trailer-exists"
+	| "forbidden-words";
+
+// lint の診断。行・列は 1 始まりで、コメントを除いたメッセージでの位置。
+export interface CommitLintDiagnostic {
+	rule: LintRuleName;
+	severity: DiagnosticSeverity;
+	message: string;
+	line: number;
+	column: number;
+}
+
+// `type(scope)!: description` 形式の subject と、破壊的変更のフッター。
+export interface ConventionalCommit {
+	commit_type: string;
+	scope: string | null;
+	// subject の `!`、または BREAKING CHANGE フッターがある。
+	breaking: boolean;
+	description: string;
+	breaking_changes: string[];
+}
+
+// 許可する type・scope（git config で設定）に対する違反。
+export type ConventionalViolation =
+	| { kind: "not_conventional" }
+	| { kind: "type_not_allowed"; commit_type: string }
+	| { kind: "scope_not_allowed"; scope: string };
+
 // バックエンドから返るファイル内容。
 export interface FileContent {
 	path: string;
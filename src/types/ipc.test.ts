@@ -4,36 +4,68 @@ import { beforeEach, describe, expect, it, vi } from "vitest";
 // invoke をモックして、IPC呼び出しの引数キーがcamelCaseであることを検証する
 vi.mock("@tauri-apps/api/core", () => ({
 	invoke: vi.fn(),
+	Channel: class<T> {
+		onmessage: (message: T) => void = () => {};
+	},
 }));
 
 const mockedInvoke = vi.mocked(invoke);
 
 import {
+	addUpdateRef,
+	analyzeRebaseDependencies,
+	applyMessageReplace,
+	applyRebasePlan,
+	applyTrailerOperations,
+	autosquashRebaseTodo,
+	buildRebaseGraph,
 	checkBackupExists,
 	checkCodexAvailable,
 	checkGitScAvailable,
 	createBackup,
 	deleteBackup,
+	detectRewordMode,
+	detectUpstreamCommits,
 	exitApp,
+	finishEditorRequest,
 	generateCommitMessage,
 	generateCommitMessageFromStaged,
+	getExecTemplates,
+	getRebaseProgress,
 	gitBlameForMerge,
 	gitCommitDiff,
 	gitCommitFiles,
+	gitCommitMetadata,
 	gitDiffFile,
 	gitStageAll,
 	gitStageFile,
 	gitStatus,
 	gitUnstageFile,
+	lintRebaseTodo,
+	listBaseCommits,
+	listRebaseSessions,
+	listStackBranches,
+	moveRebaseCommit,
 	openCodexTerminal,
 	parseCommitMsg,
 	parseConflicts,
 	parseRebaseTodo,
+	previewMessageReplace,
+	queuedRewordMessage,
+	queueSquashMessages,
 	readFile,
 	readMergeFiles,
+	regenerateTodoHelp,
+	removeUpdateRef,
+	replaceSquashWithReword,
 	restoreBackup,
+	restoreRebaseSession,
+	saveRebasePlan,
 	serializeCommitMsg,
 	serializeRebaseTodo,
+	serializeRebaseTodoLossless,
+	simulateRebase,
+	startStandaloneRebase,
 	validateCommitMsg,
 	writeFile,
 } from "./ipc";
@@ -176,29 +208,370 @@ describe("IPC引数キーのcamelCase検証", () => {
 		});
 	});
 
+	it("lintRebaseTodo はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		const todoPath = "/repo/.git/rebase-merge/git-rebase-todo";
+		await lintRebaseTodo(todoPath, file as never);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("lint_rebase_todo", {
+			filePath: todoPath,
+			file,
+		});
+	});
+
+	it("serializeRebaseTodoLossless はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		const todoPath = "/repo/.git/rebase-merge/git-rebase-todo";
+		await serializeRebaseTodoLossless(todoPath, file as never);
+
+		expect(mockedInvoke).toHaveBeenCalledWith(
+			"serialize_rebase_todo_lossless",
+			{ filePath: todoPath, file, rewordMode: "exec_amend" },
+		);
+	});
+
+	it("detectRewordMode はcamelCaseキーを渡す", async () => {
+		const todoPath = "/repo/.git/rebase-merge/git-rebase-todo";
+		await detectRewordMode(todoPath);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("detect_reword_mode", {
+			filePath: todoPath,
+		});
+	});
+
+	it("queuedRewordMessage はcamelCaseキーを渡す", async () => {
+		await queuedRewordMessage("/repo/.git/COMMIT_EDITMSG");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("queued_reword_message", {
+			filePath: "/repo/.git/COMMIT_EDITMSG",
+		});
+	});
+
+	it("autosquashRebaseTodo はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await autosquashRebaseTodo("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("autosquash_rebase_todo", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("simulateRebase はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await simulateRebase("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("simulate_rebase", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("gitCommitMetadata はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await gitCommitMetadata("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("git_commit_metadata", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("detectUpstreamCommits はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await detectUpstreamCommits(
+			"/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("detect_upstream_commits", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("buildRebaseGraph はfileを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await buildRebaseGraph(file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("build_rebase_graph", { file });
+	});
+
+	it("moveRebaseCommit はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await moveRebaseCommit(file, "entry-1", 2);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("move_rebase_commit", {
+			file,
+			entryId: "entry-1",
+			targetSection: 2,
+		});
+	});
+
+	it("getRebaseProgress はcamelCaseキーを渡す", async () => {
+		await getRebaseProgress("/repo/.git/COMMIT_EDITMSG");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("get_rebase_progress", {
+			filePath: "/repo/.git/COMMIT_EDITMSG",
+		});
+	});
+
+	it("getExecTemplates はcamelCaseキーを渡す", async () => {
+		await getExecTemplates("/repo/.git/rebase-merge/git-rebase-todo");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("get_exec_templates", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+		});
+	});
+
+	it("replaceSquashWithReword はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await replaceSquashWithReword(file, "root-1", "Subject");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("replace_squash_with_reword", {
+			file,
+			rootId: "root-1",
+			message: "Subject",
+		});
+	});
+
+	it("queueSquashMessages はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await queueSquashMessages("/repo/.git/rebase-merge/git-rebase-todo", file, {
+			"root-1": "Subject",
+		});
+
+		expect(mockedInvoke).toHaveBeenCalledWith("queue_squash_messages", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			messages: { "root-1": "Subject" },
+		});
+	});
+
+	it("previewMessageReplace はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		const spec = { find: "ABC-", replace: "XYZ-", regex: false };
+		await previewMessageReplace(
+			"/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			["entry-1"],
+			spec,
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("preview_message_replace", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			entryIds: ["entry-1"],
+			spec,
+		});
+	});
+
+	it("applyMessageReplace は置換結果を渡す", async () => {
+		const file = { entries: [], comments: [] };
+		const replacements = [
+			{
+				entry_id: "entry-1",
+				commit_hash: "abc1234",
+				old_message: "ABC-1: Fix",
+				new_message: "XYZ-1: Fix",
+			},
+		];
+		await applyMessageReplace(file, replacements);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("apply_message_replace", {
+			file,
+			replacements,
+		});
+	});
+
+	it("analyzeRebaseDependencies はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await analyzeRebaseDependencies(
+			"/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("analyze_rebase_dependencies", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("regenerateTodoHelp はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await regenerateTodoHelp("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("regenerate_todo_help", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("listStackBranches はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await listStackBranches("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("list_stack_branches", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("addUpdateRef はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await addUpdateRef(
+			"/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			"entry-1",
+			"refs/heads/part1",
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("add_update_ref", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			entryId: "entry-1",
+			refName: "refs/heads/part1",
+		});
+	});
+
+	it("removeUpdateRef はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await removeUpdateRef(file, "refs/heads/part1");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("remove_update_ref", {
+			file,
+			refName: "refs/heads/part1",
+		});
+	});
+
+	it("listBaseCommits はcamelCaseキーを渡す", async () => {
+		await listBaseCommits("/repo", 50);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("list_base_commits", {
+			repoPath: "/repo",
+			limit: 50,
+		});
+	});
+
+	it("startStandaloneRebase はイベントをチャンネルで受け取る", async () => {
+		const onEvent = vi.fn();
+		await startStandaloneRebase("/repo", "abc1234", onEvent);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("start_standalone_rebase", {
+			repoPath: "/repo",
+			base: "abc1234",
+			onEvent: expect.anything(),
+		});
+		const { onEvent: channel } = mockedInvoke.mock.calls[0][1] as {
+			onEvent: { onmessage: (event: unknown) => void };
+		};
+		channel.onmessage({ type: "output", line: "Successfully rebased" });
+		expect(onEvent).toHaveBeenCalledWith({
+			type: "output",
+			line: "Successfully rebased",
+		});
+	});
+
+	it("finishEditorRequest はcamelCaseキーを渡す", async () => {
+		await finishEditorRequest(false);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("finish_editor_request", {
+			accepted: false,
+		});
+	});
+
+	it("listRebaseSessions はcamelCaseキーを渡す", async () => {
+		await listRebaseSessions("/repo", 10);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("list_rebase_sessions", {
+			repoPath: "/repo",
+			limit: 10,
+		});
+	});
+
+	it("restoreRebaseSession はcamelCaseキーを渡す", async () => {
+		await restoreRebaseSession("/repo", "abc1234", "refs/heads/topic");
+
+		expect(mockedInvoke).toHaveBeenCalledWith("restore_rebase_session", {
+			repoPath: "/repo",
+			beforeCommit: "abc1234",
+			headName: "refs/heads/topic",
+		});
+	});
+
+	it("saveRebasePlan はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		await saveRebasePlan("/repo/.git/rebase-merge/git-rebase-todo", file);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("save_rebase_plan", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+		});
+	});
+
+	it("applyRebasePlan はcamelCaseキーを渡す", async () => {
+		const file = { entries: [], comments: [] };
+		const plan = { version: 1, entries: [] };
+		await applyRebasePlan(
+			"/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			plan,
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("apply_rebase_plan", {
+			filePath: "/repo/.git/rebase-merge/git-rebase-todo",
+			file,
+			plan,
+		});
+	});
+
 	it("parseCommitMsg はcamelCaseキーを渡す", async () => {
-		await parseCommitMsg("feat: add feature\n\nbody text");
+		await parseCommitMsg(
+			"feat: add feature\n\nbody text",
+			"/repo/.git/COMMIT_EDITMSG",
+		);
 
 		expect(mockedInvoke).toHaveBeenCalledWith("parse_commit_msg", {
 			content: "feat: add feature\n\nbody text",
+			filePath: "/repo/.git/COMMIT_EDITMSG",
 		});
 	});
 
 	it("serializeCommitMsg はcamelCaseキーを渡す", async () => {
 		const message = { subject: "feat: add feature", body: "", trailers: [] };
-		await serializeCommitMsg(message as never);
+		await serializeCommitMsg(message as never, "/repo/.git/COMMIT_EDITMSG");
 
 		expect(mockedInvoke).toHaveBeenCalledWith("serialize_commit_msg", {
 			message,
+			filePath: "/repo/.git/COMMIT_EDITMSG",
+		});
+	});
+
+	it("applyTrailerOperations はcamelCaseキーを渡す", async () => {
+		const operations = [
+			{ kind: "add" as const, token: "Refs", value: "#1" },
+			{ kind: "remove" as const, token: "Cc", value: null },
+		];
+		await applyTrailerOperations(
+			"/repo/.git/COMMIT_EDITMSG",
+			"feat: add feature\n",
+			operations,
+		);
+
+		expect(mockedInvoke).toHaveBeenCalledWith("apply_trailer_operations", {
+			filePath: "/repo/.git/COMMIT_EDITMSG",
+			content: "feat: add feature\n",
+			operations,
 		});
 	});
 
 	it("validateCommitMsg はcamelCaseキーを渡す", async () => {
 		const message = { subject: "feat: add feature", body: "", trailers: [] };
-		await validateCommitMsg(message as never);
+		await validateCommitMsg(message as never, "/repo/.git/COMMIT_EDITMSG");
 
 		expect(mockedInvoke).toHaveBeenCalledWith("validate_commit_msg", {
 			message,
+			filePath: "/repo/.git/COMMIT_EDITMSG",
 		});
 	});
 });
@@ -1,15 +1,36 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 import type { AppError } from "./errors";
 import type {
+	AppliedRebasePlan,
+	AutosquashPlan,
+	BaseCommit,
 	BlameLine,
 	CommitFileInfo,
+	CommitMetadata,
 	CommitMessage,
 	CommitValidation,
+	ComposedSquash,
+	EntryDependencies,
+	ExecTemplate,
 	FileContent,
 	GitStatusResult,
 	MergeFiles,
+	MessageReplace,
+	MessageReplacement,
 	ParseConflictsResult,
+	RebaseDiagnostic,
+	RebaseGraph,
+	RebasePlan,
+	RebaseProgress,
+	RebaseSession,
+	RebaseSimulation,
 	RebaseTodoFile,
+	RestoredRebase,
+	RewordMode,
+	StackBranch,
+	StandaloneRebaseEvent,
+	TrailerOperation,
+	UpstreamMatch,
 } from "./git";
 
 // IPC 呼び出し結果。
@@ -83,6 +104,321 @@ export async function serializeRebaseTodo(
 	return safeInvoke<string>("serialize_rebase_todo", { file });
 }
 
+// todo の各エントリが参照するコミットのメタデータをエントリ id ごとに取得する。
+export async function gitCommitMetadata(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<Record<string, CommitMetadata>>> {
+	return safeInvoke<Record<string, CommitMetadata>>("git_commit_metadata", {
+		filePath,
+		file,
+	});
+}
+
+// onto 側に取り込み済み、または適用すると空になる todo のコミットを検出する。
+export async function detectUpstreamCommits(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<UpstreamMatch[]>> {
+	return safeInvoke<UpstreamMatch[]>("detect_upstream_commits", {
+		filePath,
+		file,
+	});
+}
+
+// 並べ替える前の todo から、コミット間の行単位の依存関係を求める。
+export async function analyzeRebaseDependencies(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<EntryDependencies[]>> {
+	return safeInvoke<EntryDependencies[]>("analyze_rebase_dependencies", {
+		filePath,
+		file,
+	});
+}
+
+// todo のコミットを指すローカルブランチと、rebase 後に移動するブランチを取得する。
+export async function listStackBranches(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<StackBranch[]>> {
+	return safeInvoke<StackBranch[]>("list_stack_branches", { filePath, file });
+}
+
+// エントリのコミットの後ろに update-ref を追加する。
+export async function addUpdateRef(
+	filePath: string,
+	file: RebaseTodoFile,
+	entryId: string,
+	refName: string,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("add_update_ref", {
+		filePath,
+		file,
+		entryId,
+		refName,
+	});
+}
+
+// ref の update-ref 行を取り除く。
+export async function removeUpdateRef(
+	file: RebaseTodoFile,
+	refName: string,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("remove_update_ref", { file, refName });
+}
+
+// todo を reword のメッセージとともに JSON の計画として保存し、保存先を返す。
+export async function saveRebasePlan(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<string>> {
+	return safeInvoke<string>("save_rebase_plan", { filePath, file });
+}
+
+// 保存済みの計画を読む。保存されていなければ null。
+export async function loadRebasePlan(
+	filePath: string,
+): Promise<IpcResult<RebasePlan | null>> {
+	return safeInvoke<RebasePlan | null>("load_rebase_plan", { filePath });
+}
+
+// 計画を todo に当てはめ、対応付けられなかったエントリを報告する。
+export async function applyRebasePlan(
+	filePath: string,
+	file: RebaseTodoFile,
+	plan: RebasePlan,
+): Promise<IpcResult<AppliedRebasePlan>> {
+	return safeInvoke<AppliedRebasePlan>("apply_rebase_plan", {
+		filePath,
+		file,
+		plan,
+	});
+}
+
+// 末尾にある Git の説明を、リポジトリの core.commentChar で作り直す。
+export async function regenerateTodoHelp(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("regenerate_todo_help", { filePath, file });
+}
+
+// コミットを作る各エントリの後ろに exec を挿入する（squash/fixup の連鎖は最後）。
+export async function insertExecSteps(
+	file: RebaseTodoFile,
+	command: string,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("insert_exec_steps", { file, command });
+}
+
+// 指定したコマンドの exec エントリをすべて取り除く。
+export async function removeExecSteps(
+	file: RebaseTodoFile,
+	command: string,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("remove_exec_steps", { file, command });
+}
+
+// git config とプロジェクトファイルから exec テンプレートを取得する。
+export async function getExecTemplates(
+	filePath: string,
+): Promise<IpcResult<ExecTemplate[]>> {
+	return safeInvoke<ExecTemplate[]>("get_exec_templates", { filePath });
+}
+
+// squash を含む連鎖ごとに、Git が後で開くメッセージを先に合成する。
+export async function composeSquashMessages(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<ComposedSquash[]>> {
+	return safeInvoke<ComposedSquash[]>("compose_squash_messages", {
+		filePath,
+		file,
+	});
+}
+
+// 合成したメッセージ（連鎖の先頭エントリ id ごと）を SQUASH_MSG 用のキューへ保存する。
+export async function queueSquashMessages(
+	filePath: string,
+	file: RebaseTodoFile,
+	messages: Record<string, string>,
+): Promise<IpcResult<void>> {
+	return safeInvoke<void>("queue_squash_messages", {
+		filePath,
+		file,
+		messages,
+	});
+}
+
+// squash の連鎖を、合成したメッセージの reword と fixup に置き換える。
+export async function replaceSquashWithReword(
+	file: RebaseTodoFile,
+	rootId: string,
+	message: string,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("replace_squash_with_reword", {
+		file,
+		rootId,
+		message,
+	});
+}
+
+// 選んだエントリのメッセージに置換を適用した結果をプレビューする。todo は変更しない。
+export async function previewMessageReplace(
+	filePath: string,
+	file: RebaseTodoFile,
+	entryIds: string[],
+	spec: MessageReplace,
+): Promise<IpcResult<MessageReplacement[]>> {
+	return safeInvoke<MessageReplacement[]>("preview_message_replace", {
+		filePath,
+		file,
+		entryIds,
+		spec,
+	});
+}
+
+// プレビューした置換を適用し、対象エントリを reword にした todo を返す。
+export async function applyMessageReplace(
+	file: RebaseTodoFile,
+	replacements: MessageReplacement[],
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("apply_message_replace", {
+		file,
+		replacements,
+	});
+}
+
+// 進行中の rebase の進み具合を取得する。rebase 中でなければ null。
+export async function getRebaseProgress(
+	filePath: string,
+): Promise<IpcResult<RebaseProgress | null>> {
+	return safeInvoke<RebaseProgress | null>("get_rebase_progress", {
+		filePath,
+	});
+}
+
+// rebase の起点の候補として、HEAD からのコミットを新しい順に取得する。
+export async function listBaseCommits(
+	repoPath: string,
+	limit?: number,
+): Promise<IpcResult<BaseCommit[]>> {
+	return safeInvoke<BaseCommit[]>("list_base_commits", { repoPath, limit });
+}
+
+// リポジトリで git rebase -i を開始する。エディタ要求や出力は onEvent に届く。
+export async function startStandaloneRebase(
+	repoPath: string,
+	base: string,
+	onEvent: (event: StandaloneRebaseEvent) => void,
+): Promise<IpcResult<void>> {
+	const channel = new Channel<StandaloneRebaseEvent>();
+	channel.onmessage = onEvent;
+	return safeInvoke<void>("start_standalone_rebase", {
+		repoPath,
+		base,
+		onEvent: channel,
+	});
+}
+
+// reflog から rebase のセッションを新しい順に取得する。
+export async function listRebaseSessions(
+	repoPath: string,
+	limit?: number,
+): Promise<IpcResult<RebaseSession[]>> {
+	return safeInvoke<RebaseSession[]>("list_rebase_sessions", {
+		repoPath,
+		limit,
+	});
+}
+
+// バックアップ ref を作ってから、現在のブランチを rebase 前のコミットに戻す。
+export async function restoreRebaseSession(
+	repoPath: string,
+	beforeCommit: string,
+	headName: string | null,
+): Promise<IpcResult<RestoredRebase>> {
+	return safeInvoke<RestoredRebase>("restore_rebase_session", {
+		repoPath,
+		beforeCommit,
+		headName,
+	});
+}
+
+// 編集を待っている git へ応答する。false なら git に中止させる。
+export async function finishEditorRequest(
+	accepted: boolean,
+): Promise<IpcResult<void>> {
+	return safeInvoke<void>("finish_editor_request", { accepted });
+}
+
+// --rebase-merges の todo をセクションとラベルの関係に分解する。
+export async function buildRebaseGraph(
+	file: RebaseTodoFile,
+): Promise<IpcResult<RebaseGraph>> {
+	return safeInvoke<RebaseGraph>("build_rebase_graph", { file });
+}
+
+// コミットを別のセクション（ブランチ）の先端へ移動する。
+export async function moveRebaseCommit(
+	file: RebaseTodoFile,
+	entryId: string,
+	targetSection: number,
+): Promise<IpcResult<RebaseTodoFile>> {
+	return safeInvoke<RebaseTodoFile>("move_rebase_commit", {
+		file,
+		entryId,
+		targetSection,
+	});
+}
+
+// fixup!/squash!/amend! のコミットを対象コミットの直後へ並べ替える。
+// fixup -C に対応しない Git では、amend! を対象の reword として適用する。
+export async function autosquashRebaseTodo(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<AutosquashPlan>> {
+	return safeInvoke<AutosquashPlan>("autosquash_rebase_todo", {
+		filePath,
+		file,
+	});
+}
+
+// 編集中の todo を onto から適用した場合の競合を予測する（作業ツリーや ref は変更しない）。
+export async function simulateRebase(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<RebaseSimulation>> {
+	return safeInvoke<RebaseSimulation>("simulate_rebase", { filePath, file });
+}
+
+export async function serializeRebaseTodoLossless(
+	filePath: string,
+	file: RebaseTodoFile,
+	rewordMode: RewordMode = "exec_amend",
+): Promise<IpcResult<string>> {
+	return safeInvoke<string>("serialize_rebase_todo_lossless", {
+		filePath,
+		file,
+		rewordMode,
+	});
+}
+
+// core.editor（GIT_EDITOR）がこのアプリを指す場合だけ "native" を返す。
+export async function detectRewordMode(
+	filePath: string,
+): Promise<IpcResult<RewordMode>> {
+	return safeInvoke<RewordMode>("detect_reword_mode", { filePath });
+}
+
+export async function lintRebaseTodo(
+	filePath: string,
+	file: RebaseTodoFile,
+): Promise<IpcResult<RebaseDiagnostic[]>> {
+	return safeInvoke<RebaseDiagnostic[]>("lint_rebase_todo", { filePath, file });
+}
+
 export async function generateCommitMessage(
 	hashes: string[],
 	withBody: boolean = false,
@@ -106,23 +442,54 @@ export async function generateCommitMessageFromStaged(
 	return result;
 }
 
-// コミットメッセージ操作。
+// コミットメッセージ操作。filePath を渡すと、そのリポジトリの
+// commit.cleanup と core.commentChar に従って解析・シリアライズする。
 export async function parseCommitMsg(
 	content: string,
+	filePath?: string,
 ): Promise<IpcResult<CommitMessage>> {
-	return safeInvoke<CommitMessage>("parse_commit_msg", { content });
+	return safeInvoke<CommitMessage>("parse_commit_msg", { content, filePath });
 }
 
 export async function serializeCommitMsg(
 	message: CommitMessage,
+	filePath?: string,
+): Promise<IpcResult<string>> {
+	return safeInvoke<string>("serialize_commit_msg", { message, filePath });
+}
+
+// リポジトリの trailer.* 設定に従い、git interpret-trailers と同じ規則で
+// trailer を追加・置換・削除したメッセージ全文を返す。
+export async function applyTrailerOperations(
+	filePath: string,
+	content: string,
+	operations: TrailerOperation[],
 ): Promise<IpcResult<string>> {
-	return safeInvoke<string>("serialize_commit_msg", { message });
+	return safeInvoke<string>("apply_trailer_operations", {
+		filePath,
+		content,
+		operations,
+	});
 }
 
+// filePath を渡すと、そのリポジトリの git config で Conventional Commits も検証する。
 export async function validateCommitMsg(
 	message: CommitMessage,
+	filePath?: string,
 ): Promise<IpcResult<CommitValidation>> {
-	return safeInvoke<CommitValidation>("validate_commit_msg", { message });
+	return safeInvoke<CommitValidation>("validate_commit_msg", {
+		message,
+		filePath,
+	});
+}
+
+// reword 中の COMMIT_EDITMSG に対してキュー済みメッセージを取得する。
+export async function queuedRewordMessage(
+	filePath: string,
+): Promise<IpcResult<CommitMessage | null>> {
+	return safeInvoke<CommitMessage | null>("queued_reword_message", {
+		filePath,
+	});
 }
 
 // マージ操作。
@@ -3,7 +3,9 @@ import type { RebaseEntry } from "../types/git";
 import {
 	collectSquashedCommitHashes,
 	countSquashableEntries,
+	findDependencyViolations,
 	findSquashTarget,
+	hasAutosquashCandidates,
 	hasSquashTargetBeforeEntry,
 	squashAllEntries,
 } from "./rebase";
@@ -114,6 +116,19 @@ describe("rebase utils", () => {
 		});
 	});
 
+	describe("hasAutosquashCandidates", () => {
+		it("fixup!/squash!/amend! で始まる pick 行だけを候補にする", () => {
+			const fixup = { ...makeEntry("1"), message: "fixup! commit 0" };
+			const done = {
+				...makeEntry("2", { type: "fixup" }),
+				message: "amend! commit 0",
+			};
+
+			expect(hasAutosquashCandidates([makeEntry("0"), fixup])).toBe(true);
+			expect(hasAutosquashCandidates([makeEntry("0"), done])).toBe(false);
+		});
+	});
+
 	describe("squashAllEntries", () => {
 		it("特殊コマンドと drop を維持しつつ後続コミットだけを fixup にする", () => {
 			const entries = [
@@ -149,4 +164,34 @@ describe("rebase utils", () => {
 			expect(squashed[1].fixup_option).toBeNull();
 		});
 	});
+
+	describe("findDependencyViolations", () => {
+		const dependencies = { "2": ["1"], "3": ["1", "2"] };
+
+		it("依存先が前にあれば違反なし", () => {
+			const entries = [makeEntry("1"), makeEntry("2"), makeEntry("3")];
+
+			expect(findDependencyViolations(entries, dependencies)).toEqual({});
+		});
+
+		it("依存先より前へ移動したエントリを報告する", () => {
+			const entries = [makeEntry("2"), makeEntry("1"), makeEntry("3")];
+
+			expect(findDependencyViolations(entries, dependencies)).toEqual({
+				"2": ["1"],
+			});
+		});
+
+		it("drop された依存先を報告し、drop したエントリ自体は報告しない", () => {
+			const entries = [
+				makeEntry("1", { type: "drop" }),
+				makeEntry("2", { type: "drop" }),
+				makeEntry("3"),
+			];
+
+			expect(findDependencyViolations(entries, dependencies)).toEqual({
+				"3": ["1", "2"],
+			});
+		});
+	});
 });
@@ -119,6 +119,20 @@ export function countSquashableEntries(
 	return entries.filter((entry) => isSquashableEntry(entry)).length;
 }
 
+/**
+ * `git commit --fixup` 系で作られた subject を持つ pick 行があるかを返す。
+ * autosquash ボタンの表示判定に使い、実際の対象探索はバックエンドで行う。
+ */
+export function hasAutosquashCandidates(
+	entries: Pick<RebaseEntry, "command" | "message">[],
+): boolean {
+	return entries.some(
+		(entry) =>
+			entry.command.type === "pick" &&
+			/^(fixup|squash|amend)! /.test(entry.message),
+	);
+}
+
 /**
  * 最初のコミット系エントリを残し、それ以降のコミット系エントリだけを fixup 化する。
  * drop や特殊コマンドはそのまま残す。
@@ -165,3 +179,33 @@ export function getRebaseValidationError(
 
 	return null;
 }
+
+/**
+ * 並べ替えや drop で満たされなくなった依存関係を、エントリ id ごとに返す。
+ * 依存先が drop された、todo から消えた、または後ろへ移動した場合に違反とみなす。
+ */
+export function findDependencyViolations(
+	entries: RebaseEntry[],
+	dependencies: Record<string, string[]>,
+): Record<string, string[]> {
+	const positions = new Map(entries.map((entry, index) => [entry.id, index]));
+	const violations: Record<string, string[]> = {};
+
+	entries.forEach((entry, index) => {
+		if (entry.command.type === "drop") return;
+
+		const broken = (dependencies[entry.id] ?? []).filter((dependencyId) => {
+			const position = positions.get(dependencyId);
+			return (
+				position === undefined ||
+				position > index ||
+				entries[position].command.type === "drop"
+			);
+		});
+		if (broken.length > 0) {
+			violations[entry.id] = broken;
+		}
+	});
+
+	return violations;
+}